@@ -1,4 +0,0 @@
-pub mod game;
-pub mod games;
-
-pub mod tinyvec_util;
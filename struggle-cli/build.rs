@@ -0,0 +1,5 @@
+// Writes the gRPC bindings for the `grpc` feature. Without it there is nothing to do.
+fn main() {
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/struggle.proto").expect("Failed to compile the protos");
+}
@@ -0,0 +1,106 @@
+use clap::Parser;
+use struggle_core::{
+    best_response::{best_response, BestResponse, BestResponseOptions},
+    games::struggle::players::HeuristicWeights,
+    registry::struggle_player,
+    session::run_match_seeded,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Tunes the weights of `default_heuristic` to beat one target player, producing a counter
+/// player that shows how exploitable the target is, see `best_response`.
+#[derive(Parser)]
+struct Args {
+    /// The player to beat
+    #[arg(long, default_value = "score_move")]
+    target: String,
+
+    /// Search depth of the counter player
+    #[arg(long, default_value_t = 1)]
+    depth: u8,
+
+    #[arg(long, default_value_t = 20)]
+    rounds: usize,
+
+    /// Perturbed weights tried per round
+    #[arg(long, default_value_t = 8)]
+    candidates: usize,
+
+    /// Games per candidate and round
+    #[arg(long, default_value_t = 2_000)]
+    games: u32,
+
+    /// The largest relative change of a weight in a perturbation
+    #[arg(long, default_value_t = 0.3)]
+    step: f64,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Fresh games to compare the counter player with the default weights on
+    #[arg(long, default_value_t = 20_000)]
+    eval_games: u32,
+
+    /// Writes the weights as JSON to this file
+    #[arg(long)]
+    out: Option<String>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+    let target = struggle_player(&args.target)?;
+
+    let options = BestResponseOptions {
+        rounds: args.rounds,
+        candidates: args.candidates,
+        games: args.games,
+        step: args.step,
+        depth: args.depth,
+        seed: args.seed,
+        ..Default::default()
+    };
+
+    let result = best_response(&target, HeuristicWeights::DEFAULT, &options, |round| {
+        println!(
+            "Round {:>3}: {:.1}% against {}{} (step {:.3})",
+            round.round + 1,
+            round.win_rate * 100.0,
+            args.target,
+            if round.improved { ", improved" } else { "" },
+            round.step
+        );
+    });
+
+    // The games of the search picked the winners, so they're compared on games they haven't seen
+    let eval_seed = args.seed.wrapping_add(1);
+    let default = BestResponse {
+        weights: HeuristicWeights::DEFAULT,
+        win_rate: 0.0,
+    };
+
+    for (name, response) in [("Default weights", &default), ("Counter player", &result)] {
+        let player = response.player(args.depth);
+        let match_result = run_match_seeded(&player, &target, args.eval_games, eval_seed, |_| {});
+
+        println!(
+            "{}: {:.1}% against {} over {} fresh games",
+            name,
+            match_result.a_win_rate() * 100.0,
+            args.target,
+            args.eval_games
+        );
+    }
+
+    println!("{:#?}", result.weights);
+
+    if let Some(out) = &args.out {
+        std::fs::write(out, serde_json::to_string_pretty(&result.weights)?)?;
+        println!("Wrote the weights to {}", out);
+    }
+
+    Ok(())
+}
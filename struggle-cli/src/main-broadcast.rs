@@ -0,0 +1,207 @@
+use std::{
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use clap::Parser;
+use rand::prelude::*;
+use serde::Serialize;
+use struggle_core::{
+    game::{NamedPlayer, RaceGame, TurnResult},
+    games::struggle::{
+        board::Board, players::default_heuristic, AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+    json::{color_name, BoardJson, MoveJson},
+    registry::struggle_player,
+};
+use tungstenite::{Message, WebSocket};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays games forever and streams every turn as JSON to WebSocket clients.
+/// web/spectate.html is a minimal page for watching.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:9001")]
+    address: String,
+
+    #[arg(long, default_value = "expectiminimax:1")]
+    red: String,
+
+    #[arg(long, default_value = "random")]
+    yellow: String,
+
+    /// Pause between turns, so that spectators can follow along.
+    #[arg(long, default_value_t = 250)]
+    delay_ms: u64,
+
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Serves Prometheus metrics on this address, e.g. 127.0.0.1:9090
+    #[cfg(feature = "metrics")]
+    #[arg(long)]
+    metrics: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event {
+    GameStart {
+        game: u64,
+        red: String,
+        yellow: String,
+    },
+    Turn {
+        game: u64,
+        turn: u32,
+        player: &'static str,
+        dice: u8,
+        #[serde(rename = "move")]
+        mov: MoveJson,
+        board: BoardJson,
+        /// The default heuristic from Red's point of view after the move
+        eval: f64,
+    },
+    GameEnd {
+        game: u64,
+        winner: &'static str,
+        turns: u32,
+    },
+}
+
+type Clients = Arc<Mutex<Vec<WebSocket<TcpStream>>>>;
+
+fn broadcast(clients: &Clients, event: &Event) {
+    let text = serde_json::to_string(event).unwrap();
+
+    // Clients that have gone away are dropped on the first failed send
+    clients
+        .lock()
+        .unwrap()
+        .retain_mut(|client| client.send(Message::text(text.clone())).is_ok());
+}
+
+fn accept_clients(listener: TcpListener, clients: Clients) {
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+
+        match tungstenite::accept(stream) {
+            Ok(socket) => clients.lock().unwrap().push(socket),
+            Err(err) => eprintln!("WebSocket handshake failed: {}", err),
+        }
+    }
+}
+
+fn eval(board: &Board) -> f64 {
+    default_heuristic(board, PlayerColor::Red, PlayerColor::Yellow)
+}
+
+pub fn main() {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let (red, yellow) = match (struggle_player(&args.red), struggle_player(&args.yellow)) {
+        (Ok(red), Ok(yellow)) => (red, yellow),
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+
+    let mut rng = match args.seed {
+        Some(seed) => SmallRng::seed_from_u64(seed),
+        None => SmallRng::from_rng(rand::thread_rng()).unwrap(),
+    };
+
+    let listener = TcpListener::bind(&args.address).expect("Failed to bind the address");
+    let clients = Clients::default();
+
+    {
+        let clients = clients.clone();
+        std::thread::spawn(move || accept_clients(listener, clients));
+    }
+
+    println!("Broadcasting on ws://{}", args.address);
+
+    #[cfg(feature = "metrics")]
+    if let Some(address) = &args.metrics {
+        struggle_core::metrics::serve(address).expect("Failed to serve metrics");
+        println!("Serving metrics on http://{}/metrics", address);
+    }
+
+    let delay = Duration::from_millis(args.delay_ms);
+
+    for game_id in 0.. {
+        let mut game = StruggleGame::new(
+            AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+            AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+            false,
+        );
+
+        broadcast(
+            &clients,
+            &Event::GameStart {
+                game: game_id,
+                red: red.name().into_owned(),
+                yellow: yellow.name().into_owned(),
+            },
+        );
+
+        for turn in 1.. {
+            let player = game.current_player();
+            let dice = game.throw_dice(&mut rng);
+            let ctx = game.create_turn_context(dice);
+            let moves = game.get_moves(&ctx);
+            let mov = game.select_move(&ctx, &moves, &mut rng).clone();
+            let result = game.apply_move(&ctx, &mov);
+
+            broadcast(
+                &clients,
+                &Event::Turn {
+                    game: game_id,
+                    turn,
+                    player: color_name(player),
+                    dice,
+                    mov: MoveJson::from(&mov),
+                    board: BoardJson::new(game.board()),
+                    eval: eval(game.board()),
+                },
+            );
+
+            std::thread::sleep(delay);
+
+            match result {
+                TurnResult::PlayAgain => {}
+                TurnResult::PassTo(player) => game.set_current_player(player),
+                TurnResult::EndGame { winner } => {
+                    #[cfg(feature = "metrics")]
+                    {
+                        let (winner_name, loser_name) = if winner == PlayerColor::Red {
+                            (red.name(), yellow.name())
+                        } else {
+                            (yellow.name(), red.name())
+                        };
+
+                        struggle_core::metrics::metrics().record_game(&winner_name, &loser_name);
+                    }
+
+                    broadcast(
+                        &clients,
+                        &Event::GameEnd {
+                            game: game_id,
+                            winner: color_name(winner),
+                            turns: turn,
+                        },
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use clap::Parser;
+use struggle_core::games::struggle::{
+    curriculum::{run_curriculum, stages, CurriculumOptions, DEFAULT_STAGES},
+    linear::TrainingOptions,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Trains the learned linear evaluation against stronger and stronger opponents, see
+/// `games::struggle::curriculum`.
+#[derive(Parser)]
+struct Args {
+    /// The opponents in order, defaults to random, random_eater, score_move and
+    /// expectiminimax 1 to 3
+    stages: Vec<String>,
+
+    /// The win rate needed to move on to the next opponent
+    #[arg(long, default_value_t = 0.6)]
+    target: f64,
+
+    /// Training games per round
+    #[arg(long, default_value_t = 2_000)]
+    games: u64,
+
+    /// Evaluation games per round
+    #[arg(long, default_value_t = 2_000)]
+    eval_games: u32,
+
+    /// Rounds per stage before giving up
+    #[arg(long, default_value_t = 5)]
+    max_rounds: usize,
+
+    /// Search depth of the learner
+    #[arg(long, default_value_t = 1)]
+    depth: u8,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Writes the weights as JSON to this file
+    #[arg(long)]
+    out: Option<String>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let specs = if args.stages.is_empty() {
+        DEFAULT_STAGES.to_vec()
+    } else {
+        args.stages.iter().map(String::as_str).collect()
+    };
+
+    let opponents = stages(&specs)?;
+
+    let options = CurriculumOptions {
+        target_win_rate: args.target,
+        games_per_round: args.games,
+        eval_games: args.eval_games,
+        max_rounds: args.max_rounds,
+        depth: args.depth,
+        training: TrainingOptions {
+            seed: args.seed,
+            ..Default::default()
+        },
+    };
+
+    let result = run_curriculum(&opponents, &options, |report| {
+        println!(
+            "Stage {} ({}), round {}: {:.1}% wins after {} positions{}",
+            report.stage + 1,
+            report.opponent,
+            report.round + 1,
+            report.win_rate * 100.0,
+            report.examples,
+            if report.promoted { ", promoted" } else { "" }
+        );
+    });
+
+    println!(
+        "Passed {} of {} stages",
+        result.stages_passed,
+        opponents.len()
+    );
+
+    for (name, weight) in result.heuristic.named_weights() {
+        println!("{:>20}: {:.4}", name, weight);
+    }
+
+    if let Some(out) = &args.out {
+        std::fs::write(out, serde_json::to_string_pretty(&result.heuristic)?)?;
+        println!("Wrote the weights to {}", out);
+    }
+
+    Ok(())
+}
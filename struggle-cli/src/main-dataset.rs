@@ -0,0 +1,94 @@
+use std::{path::Path, time::Instant};
+
+use clap::Parser;
+use indicatif::ProgressBar;
+use struggle_core::{
+    dataset::{generate, DatasetOptions, Sampling},
+    game::NamedPlayer,
+    manifest::RunManifest,
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays self-play games and writes every decision to a dataset for training models, see
+/// `dataset` for the format.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "expectiminimax:1")]
+    red: String,
+
+    #[arg(long, default_value = "expectiminimax:1")]
+    yellow: String,
+
+    #[arg(long, default_value_t = 100_000)]
+    games: u64,
+
+    /// Master seed, from which every game's seed is derived
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Directory to write the shards to
+    #[arg(long)]
+    out: String,
+
+    /// Keeps this many random decisions from each game
+    #[arg(long, conflicts_with = "reservoir")]
+    per_game: Option<usize>,
+
+    /// Keeps a uniform random sample of this many decisions over the whole run
+    #[arg(long)]
+    reservoir: Option<usize>,
+
+    #[arg(long, default_value_t = 1_000_000)]
+    records_per_shard: usize,
+
+    /// Leaves out decisions with only one legal move
+    #[arg(long)]
+    skip_forced: bool,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let red = struggle_player(&args.red)?;
+    let yellow = struggle_player(&args.yellow)?;
+
+    let sampling = match (args.per_game, args.reservoir) {
+        (Some(count), _) => Sampling::PerGame(count),
+        (_, Some(capacity)) => Sampling::Reservoir(capacity),
+        _ => Sampling::All,
+    };
+
+    let options = DatasetOptions {
+        games: args.games,
+        seed: args.seed,
+        sampling,
+        records_per_shard: args.records_per_shard,
+        skip_forced: args.skip_forced,
+    };
+
+    let manifest = RunManifest::new("struggle-dataset", args.seed)
+        .with_players([args.red.clone(), args.yellow.clone()])
+        .with_player_configs([red.describe(), yellow.describe()])
+        .with_games(args.games);
+    let started = Instant::now();
+
+    let progress = ProgressBar::new(args.games);
+    let written = generate(&red, &yellow, &args.out, &options, |games| {
+        progress.inc(games)
+    })?;
+    progress.finish();
+
+    // The manifest goes next to the shards, so that the dataset says how it was made
+    manifest
+        .with_elapsed(started.elapsed())
+        .write(Path::new(&args.out).join("manifest.json"))?;
+
+    println!("Wrote {} samples to {}", written, args.out);
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use clap::Parser;
+use plotters::prelude::*;
+use struggle_core::{
+    palette::Palette,
+    registry::struggle_player,
+    render::to_plotters_color,
+    scaling::{depth_scaling, DepthPoint},
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays expectiminimax at every depth up to `--max-depth` against a fixed baseline, and plots its
+/// win rate and its time per move against the depth, see `scaling`.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value_t = 3)]
+    max_depth: u8,
+
+    /// The opponent of every depth
+    #[arg(long, default_value = "random")]
+    baseline: String,
+
+    /// Games per depth
+    #[arg(long, default_value_t = 10_000)]
+    games: u32,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    #[arg(long, default_value = "out/depth_scaling.svg")]
+    out: String,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+    let baseline = struggle_player(&args.baseline)?;
+
+    println!("Depth   Win %            95% CI   μs per move");
+
+    let points = depth_scaling(
+        &baseline,
+        0..=args.max_depth,
+        args.games,
+        args.seed,
+        |point| {
+            println!(
+                "{:>5} {:>7.2} [{:>6.2}, {:>6.2}] {:>13.1}",
+                point.depth,
+                point.win_rate() * 100.0,
+                point.interval.0 * 100.0,
+                point.interval.1 * 100.0,
+                point.move_time.as_secs_f64() * 1e6
+            );
+        },
+    );
+
+    if let Some(parent) = std::path::Path::new(&args.out).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    draw_scaling(&points, &args.baseline, &args.out);
+    println!("Wrote the plot to {}", args.out);
+
+    Ok(())
+}
+
+fn draw_scaling(points: &[DepthPoint], baseline: &str, path: &str) {
+    let drawing_area = SVGBackend::new(path, (1200, 500)).into_drawing_area();
+    drawing_area.fill(&WHITE).unwrap();
+
+    let (left, right) = drawing_area.split_horizontally(600);
+    let color = to_plotters_color(Palette::from_env().chart);
+    let depths = -0.5..(points.len() as f64 - 0.5);
+
+    let mut win_rate = ChartBuilder::on(&left)
+        .set_label_area_size(LabelAreaPosition::Left, 50)
+        .set_label_area_size(LabelAreaPosition::Bottom, 40)
+        .margin(8)
+        .caption(
+            format!("Win rate against {}", baseline),
+            ("Source Sans Pro, sans-serif", 20),
+        )
+        .build_cartesian_2d(depths.clone(), 0.0..100.0)
+        .unwrap();
+
+    win_rate
+        .configure_mesh()
+        .x_desc("Depth")
+        .y_desc("Win %")
+        .draw()
+        .unwrap();
+
+    win_rate
+        .draw_series(points.iter().map(|point| {
+            let x = point.depth as f64;
+            let (low, high) = point.interval;
+            PathElement::new(vec![(x, low * 100.0), (x, high * 100.0)], color)
+        }))
+        .unwrap();
+
+    win_rate
+        .draw_series(LineSeries::new(
+            points
+                .iter()
+                .map(|point| (point.depth as f64, point.win_rate() * 100.0)),
+            color,
+        ))
+        .unwrap();
+
+    win_rate
+        .draw_series(points.iter().map(|point| {
+            Circle::new(
+                (point.depth as f64, point.win_rate() * 100.0),
+                3,
+                color.filled(),
+            )
+        }))
+        .unwrap();
+
+    let micros = points
+        .iter()
+        .map(|point| (point.depth as f64, point.move_time.as_secs_f64() * 1e6))
+        .collect::<Vec<_>>();
+    let (min, max) = micros
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), point| {
+            (min.min(point.1), max.max(point.1))
+        });
+
+    // The time grows exponentially with the depth, so it's drawn on a log scale
+    let mut move_time = ChartBuilder::on(&right)
+        .set_label_area_size(LabelAreaPosition::Left, 60)
+        .set_label_area_size(LabelAreaPosition::Bottom, 40)
+        .margin(8)
+        .caption("Time per move", ("Source Sans Pro, sans-serif", 20))
+        .build_cartesian_2d(
+            depths,
+            ((min * 0.5).max(1e-3)..(max * 2.0).max(1e-2)).log_scale(),
+        )
+        .unwrap();
+
+    move_time
+        .configure_mesh()
+        .x_desc("Depth")
+        .y_desc("μs")
+        .draw()
+        .unwrap();
+
+    move_time
+        .draw_series(LineSeries::new(micros.iter().copied(), color))
+        .unwrap();
+
+    move_time
+        .draw_series(
+            micros
+                .iter()
+                .map(|&point| Circle::new(point, 3, color.filled())),
+        )
+        .unwrap();
+
+    drawing_area.present().unwrap();
+}
@@ -0,0 +1,116 @@
+use clap::Parser;
+use struggle_core::{
+    games::struggle::distill::{
+        label_positions, micros_per_move, teacher, DistillOptions, DistilledPolicy,
+    },
+    registry::{struggle_player, BoxedStrugglePlayer},
+    session::run_match,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Distills a deep expectiminimax search into a linear policy, and measures how much of its
+/// strength survives, see `games::struggle::distill`.
+#[derive(Parser)]
+struct Args {
+    /// Search depth of the teacher
+    #[arg(long, default_value_t = 3)]
+    depth: u8,
+
+    /// The player whose games provide the positions to label
+    #[arg(long, default_value = "expectiminimax:1")]
+    explorer: String,
+
+    /// Games to label. A tenth as many games are held out for validation.
+    #[arg(long, default_value_t = 2_000)]
+    games: u64,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    #[arg(long, default_value_t = 10)]
+    epochs: usize,
+
+    /// The opponent both the teacher and the student play against
+    #[arg(long, default_value = "random")]
+    opponent: String,
+
+    /// Games against the opponent per player
+    #[arg(long, default_value_t = 10_000)]
+    eval_games: u32,
+
+    /// Writes the weights as JSON to this file
+    #[arg(long)]
+    out: Option<String>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let explorer = struggle_player(&args.explorer)?;
+    let opponent = struggle_player(&args.opponent)?;
+    let teacher = teacher(args.depth);
+
+    let training = label_positions(&explorer, &teacher, args.games, args.seed);
+    let validation = label_positions(
+        &explorer,
+        &teacher,
+        (args.games / 10).max(1),
+        args.seed.wrapping_add(args.games),
+    );
+
+    println!(
+        "Labeled {} training and {} validation positions",
+        training.len(),
+        validation.len()
+    );
+
+    let options = DistillOptions {
+        epochs: args.epochs,
+        seed: args.seed,
+        ..Default::default()
+    };
+
+    let policy = DistilledPolicy::train(&training, &options);
+
+    println!(
+        "Agreement with the teacher: {:.1}% training, {:.1}% validation",
+        policy.agreement(&training) * 100.0,
+        policy.agreement(&validation) * 100.0
+    );
+
+    let teacher_micros = micros_per_move(&mut teacher.clone(), &validation);
+    let student_micros = micros_per_move(&mut policy.clone().player(), &validation);
+
+    println!(
+        "Time per move: {:.1} μs for the teacher, {:.2} μs for the student ({:.0}× faster)",
+        teacher_micros,
+        student_micros,
+        teacher_micros / student_micros
+    );
+
+    let players: [(&str, BoxedStrugglePlayer); 2] = [
+        ("teacher", Box::new(teacher)),
+        ("student", Box::new(policy.clone().player())),
+    ];
+
+    for (name, player) in players {
+        let result = run_match(&player, &opponent, args.eval_games, |_| {});
+        println!(
+            "The {} wins {:.1}% against {}",
+            name,
+            result.a_win_rate() * 100.0,
+            args.opponent
+        );
+    }
+
+    if let Some(out) = &args.out {
+        std::fs::write(out, serde_json::to_string_pretty(&policy)?)?;
+        println!("Wrote the weights to {}", out);
+    }
+
+    Ok(())
+}
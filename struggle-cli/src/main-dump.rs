@@ -0,0 +1,63 @@
+use std::{fs::File, io};
+
+use clap::Parser;
+use struggle_core::{
+    games::struggle::{
+        game_notation::NotatedGame, players::default_heuristic, turn_table::turn_rows,
+    },
+    registry::struggle_player,
+};
+
+/// Plays a single game, or replays one from a game notation file, and writes one CSV row per
+/// turn: the roll, the move, the position after it and the default heuristic's evaluation of the
+/// position for both players.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "expectiminimax:1")]
+    red: String,
+
+    #[arg(long, default_value = "random")]
+    yellow: String,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Replays this game instead of playing a new one, see `game_notation`
+    #[arg(long, conflicts_with_all = ["red", "yellow", "seed"])]
+    replay: Option<String>,
+
+    /// CSV file to write, stdout by default
+    #[arg(long)]
+    out: Option<String>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let game = match &args.replay {
+        Some(path) => NotatedGame::parse(&std::fs::read_to_string(path)?)?,
+        None => {
+            let red = struggle_player(&args.red)?;
+            let yellow = struggle_player(&args.yellow)?;
+            NotatedGame::play(&red, &yellow, args.seed)
+        }
+    };
+
+    let rows = turn_rows(&game, default_heuristic)?;
+
+    let out: Box<dyn io::Write> = match &args.out {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(out);
+
+    for row in &rows {
+        writer.serialize(row)?;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
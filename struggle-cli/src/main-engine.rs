@@ -0,0 +1,276 @@
+//! A line based engine protocol on stdin and stdout, in the spirit of UCI, so that GUIs and
+//! tournament scripts can drive any registered player.
+//!
+//! ```text
+//! > struggle
+//! < id name struggle-engine
+//! < id player expectiminimax:1
+//! < struggleok
+//! > position startpos
+//! > go dice 6 depth 2
+//! < bestmove n
+//! ```
+//!
+//! Commands:
+//!
+//! - `struggle`: identifies the engine, answered with `struggleok`
+//! - `isready`: answered with `readyok`
+//! - `player <spec>`: switches the player, e.g. `player score_move`
+//! - `position startpos` or `position <notation>`: sets the position, see `games::struggle::notation`
+//! - `go [dice <n>] [depth <d>]`: answers with `bestmove <move>`. Without a die the engine rolls
+//!   one itself and reports it with `info dice <n>`. The depth overrides the depth of the player.
+//! - `moves [dice <n>]`: lists the legal moves as `info moves <move> ...`
+//! - `rollout [games <n>]`: plays the position out with the player on both sides, 1000 times by
+//!   default, and answers with `info winprob <p> turns <mean> median <turns>` for the player to
+//!   move
+//! - `quit`
+//!
+//! Problems are reported as `info string <message>`, and the engine keeps going.
+
+use std::io::BufRead;
+
+use clap::Parser;
+use rand::prelude::*;
+use struggle_core::{
+    game::{GamePlayer, NamedPlayer},
+    games::struggle::{
+        board::{Board, MoveVec},
+        notation::{format_move, parse_position, START_POSITION},
+        players::GameContext,
+        rollout::rollouts,
+        PlayerColor,
+    },
+    registry::{struggle_player, BoxedStrugglePlayer},
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+#[derive(Parser)]
+struct Args {
+    /// The player that picks the moves, can be changed with the `player` command
+    #[arg(long, default_value = "expectiminimax:1")]
+    player: String,
+
+    /// Seeds the rolls and any randomness in the players
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+struct Engine {
+    spec: String,
+    player: BoxedStrugglePlayer,
+    board: Board,
+    to_move: PlayerColor,
+    rng: SmallRng,
+}
+
+impl Engine {
+    fn other_player(&self) -> PlayerColor {
+        let (a, b) = self.board.players();
+        if a == self.to_move {
+            b
+        } else {
+            a
+        }
+    }
+
+    // Parses `dice <n>` and `depth <d>` in any order
+    fn parse_options<'a>(
+        mut args: impl Iterator<Item = &'a str>,
+    ) -> Result<(Option<u8>, Option<u8>), String> {
+        let (mut dice, mut depth) = (None, None);
+
+        while let Some(option) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("Missing value for '{}'", option))?;
+
+            match option {
+                "dice" => match value.parse() {
+                    Ok(value @ 1..=6) => dice = Some(value),
+                    _ => return Err(format!("Invalid die '{}'", value)),
+                },
+                "depth" => {
+                    depth = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid depth '{}'", value))?,
+                    )
+                }
+                _ => return Err(format!("Unknown option '{}'", option)),
+            }
+        }
+
+        Ok((dice, depth))
+    }
+
+    fn context(&mut self, dice: Option<u8>) -> GameContext {
+        let dice = dice.unwrap_or_else(|| {
+            let dice = self.rng.gen_range(1..=6);
+            println!("info dice {}", dice);
+            dice
+        });
+
+        GameContext {
+            current_player: self.to_move,
+            other_player: self.other_player(),
+            dice,
+        }
+    }
+
+    fn moves(&self, ctx: &GameContext) -> MoveVec {
+        self.board
+            .get_moves(ctx.dice, ctx.current_player, ctx.other_player)
+    }
+
+    fn go<'a>(&mut self, args: impl Iterator<Item = &'a str>) -> Result<(), String> {
+        let (dice, depth) = Self::parse_options(args)?;
+
+        let mut player = match depth {
+            Some(depth) => {
+                let name = self.spec.split(':').next().unwrap_or_default();
+                struggle_player(&format!("{}:{}", name, depth))?
+            }
+            None => self.player.clone(),
+        };
+
+        let ctx = self.context(dice);
+        let moves = self.moves(&ctx);
+        let mov = player.select_move(&ctx, &self.board, &moves, &mut self.rng);
+
+        println!("bestmove {}", format_move(mov));
+
+        Ok(())
+    }
+
+    fn list_moves<'a>(&mut self, args: impl Iterator<Item = &'a str>) -> Result<(), String> {
+        let (dice, _) = Self::parse_options(args)?;
+
+        let ctx = self.context(dice);
+        let moves: Vec<String> = self.moves(&ctx).iter().map(format_move).collect();
+
+        println!("info moves {}", moves.join(" "));
+
+        Ok(())
+    }
+
+    fn rollout<'a>(&mut self, mut args: impl Iterator<Item = &'a str>) -> Result<(), String> {
+        let games = match (args.next(), args.next()) {
+            (None, _) => 1000,
+            (Some("games"), Some(value)) => value
+                .parse()
+                .ok()
+                .filter(|&games| games > 0)
+                .ok_or_else(|| format!("Invalid number of games '{}'", value))?,
+            (Some("games"), None) => return Err("Missing value for 'games'".to_string()),
+            (Some(option), _) => return Err(format!("Unknown option '{}'", option)),
+        };
+
+        let rollouts = rollouts(
+            &self.board,
+            self.to_move,
+            &self.player,
+            &self.player,
+            games,
+            self.rng.gen(),
+        );
+
+        if rollouts.games() == 0 {
+            return Err("The game is already over".to_string());
+        }
+
+        println!(
+            "info winprob {:.3} turns {:.1} median {}",
+            rollouts.win_probability(self.to_move),
+            rollouts.mean_turns(),
+            rollouts.turn_quantile(0.5).unwrap_or_default()
+        );
+
+        Ok(())
+    }
+
+    fn set_position(&mut self, notation: &str) -> Result<(), String> {
+        let notation = match notation.trim() {
+            "startpos" => START_POSITION,
+            notation => notation,
+        };
+
+        (self.board, self.to_move) = parse_position(notation)?;
+
+        Ok(())
+    }
+
+    fn set_player(&mut self, spec: &str) -> Result<(), String> {
+        self.player = struggle_player(spec)?;
+        self.spec = spec.to_string();
+
+        Ok(())
+    }
+
+    /// Runs one command. Returns false on `quit`.
+    fn handle(&mut self, line: &str) -> Result<bool, String> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let args = rest.split_whitespace();
+
+        match command {
+            "" => {}
+            "struggle" => {
+                println!("id name struggle-engine");
+                println!("id player {}", self.spec);
+                println!("struggleok");
+            }
+            "isready" => println!("readyok"),
+            "player" => self.set_player(rest.trim())?,
+            "position" => self.set_position(rest)?,
+            "go" => self.go(args)?,
+            "moves" => self.list_moves(args)?,
+            "rollout" => self.rollout(args)?,
+            "quit" => return Ok(false),
+            _ => return Err(format!("Unknown command '{}'", command)),
+        }
+
+        Ok(true)
+    }
+}
+
+pub fn main() {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let player = struggle_player(&args.player).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+
+    let rng = match args.seed {
+        Some(seed) => SmallRng::seed_from_u64(seed),
+        None => SmallRng::from_rng(rand::thread_rng()).unwrap(),
+    };
+
+    let (board, to_move) = parse_position(START_POSITION).unwrap();
+
+    let mut engine = Engine {
+        spec: args.player,
+        player,
+        board,
+        to_move,
+        rng,
+    };
+
+    eprintln!("struggle-engine playing as {}", engine.player.name());
+
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+
+        match engine.handle(&line) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => println!("info string {}", err),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use std::time::Instant;
+
+use clap::Parser;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+#[cfg(feature = "parquet")]
+use struggle_core::export::ParquetExporter;
+#[cfg(feature = "sqlite")]
+use struggle_core::results_db::ResultsDb;
+use struggle_core::{
+    analysis::DiceTally,
+    game::{GameId, NamedPlayer},
+    games::struggle::{players::default_heuristic, turn_log::TurnLogger},
+    manifest::RunManifest,
+    records::play_recorded_game,
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+// Games are simulated and written a batch at a time, so memory use stays flat for huge runs
+const BATCH_SIZE: u64 = 1 << 16;
+
+/// Simulates games between two players and records one row per game, to a Parquet file with the
+/// `parquet` feature and to a SQLite database with the `sqlite` feature.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "expectiminimax:1")]
+    red: String,
+
+    #[arg(long, default_value = "random")]
+    yellow: String,
+
+    #[arg(long, default_value_t = 1_000_000)]
+    games: u64,
+
+    /// Master seed, from which every game's seed is derived
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Parquet file to write, e.g. out/games.parquet
+    #[cfg(feature = "parquet")]
+    #[arg(long)]
+    out: Option<String>,
+
+    /// SQLite database to append the match to, e.g. out/results.db
+    #[cfg(feature = "sqlite")]
+    #[arg(long)]
+    db: Option<String>,
+
+    /// Logs every turn as a JSON line to this file, or to stdout with `-`
+    #[arg(long)]
+    log: Option<String>,
+
+    /// Adds the default heuristic's evaluation to every logged turn
+    #[arg(long, requires = "log")]
+    log_eval: bool,
+
+    /// Writes the seed and the command line of the run to this JSON file
+    #[arg(long)]
+    manifest: Option<String>,
+
+    /// Tests the dice of both players for fairness, and adds the tests to the manifest
+    #[arg(long)]
+    check_dice: bool,
+}
+
+#[cfg(any(feature = "parquet", feature = "sqlite"))]
+fn create_parent_dir(path: &str) -> std::io::Result<()> {
+    match std::path::Path::new(path).parent() {
+        Some(parent) => std::fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let red = struggle_player(&args.red)?;
+    let yellow = struggle_player(&args.yellow)?;
+
+    let manifest = RunManifest::new("struggle-export", args.seed)
+        .with_players([args.red.clone(), args.yellow.clone()])
+        .with_player_configs([red.describe(), yellow.describe()])
+        .with_games(args.games);
+    let started = Instant::now();
+
+    #[cfg(feature = "parquet")]
+    let mut exporter = match &args.out {
+        Some(out) => {
+            create_parent_dir(out)?;
+            Some(ParquetExporter::create(out)?)
+        }
+        None => None,
+    };
+
+    #[cfg(feature = "sqlite")]
+    let mut db = match &args.db {
+        Some(path) => {
+            create_parent_dir(path)?;
+            let mut db = ResultsDb::open(path)?;
+            let match_id = db.start_match(&red.name(), &yellow.name())?;
+            Some((db, match_id))
+        }
+        None => None,
+    };
+
+    let logger = match args.log.as_deref() {
+        Some("-") => Some(TurnLogger::stdout()),
+        Some(path) => Some(TurnLogger::create(path)?),
+        None => None,
+    }
+    .map(|logger| {
+        if args.log_eval {
+            logger.with_eval(default_heuristic)
+        } else {
+            logger
+        }
+    });
+
+    let outputs = [
+        #[cfg(feature = "parquet")]
+        exporter.is_some(),
+        #[cfg(feature = "sqlite")]
+        db.is_some(),
+        logger.is_some(),
+    ];
+
+    if !outputs.contains(&true) {
+        return Err(concat!(
+            "Nothing to record to, pass --out (requires the parquet feature) ",
+            "or --db (requires the sqlite feature) or --log"
+        )
+        .into());
+    }
+
+    let progress = ProgressBar::new(args.games);
+    let mut dice = DiceTally::default();
+
+    for start in (0..args.games).step_by(BATCH_SIZE as usize) {
+        let end = (start + BATCH_SIZE).min(args.games);
+
+        let records = (start..end)
+            .into_par_iter()
+            .map(|game| {
+                play_recorded_game(&red, &yellow, GameId::new(args.seed, game), logger.as_ref())
+            })
+            .collect::<Vec<_>>();
+
+        if args.check_dice {
+            for record in &records {
+                for (totals, counts) in dice.numbers.iter_mut().zip(record.dice) {
+                    for (total, count) in totals.iter_mut().zip(counts) {
+                        *total += count as u64;
+                    }
+                }
+            }
+        }
+
+        #[cfg(feature = "parquet")]
+        if let Some(exporter) = &mut exporter {
+            exporter.write_batch(&records)?;
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some((db, match_id)) = &mut db {
+            db.add_games(*match_id, &records)?;
+        }
+
+        progress.inc(records.len() as u64);
+    }
+
+    #[cfg(feature = "parquet")]
+    if let Some(exporter) = exporter {
+        exporter.finish()?;
+    }
+
+    if let Some(logger) = &logger {
+        logger.flush()?;
+    }
+
+    progress.finish();
+
+    let dice_checks = if args.check_dice {
+        dice.checks()
+    } else {
+        Vec::new()
+    };
+
+    for check in &dice_checks {
+        eprintln!(
+            "{} die of {}: {:?}, chi-square {:.2}, p = {:.4}{}",
+            check.die,
+            if check.player == 0 { "red" } else { "yellow" },
+            check.counts,
+            check.chi_square,
+            check.p_value,
+            if check.passed() { "" } else { " (UNFAIR)" }
+        );
+    }
+
+    if let Some(path) = &args.manifest {
+        manifest
+            .with_elapsed(started.elapsed())
+            .with_dice_checks(dice_checks)
+            .write(path)?;
+    }
+
+    // stdout may be carrying the turn log
+    eprintln!("Recorded {} games", args.games);
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use struggle_core::{
+    game::NamedPlayer,
+    games::struggle::{
+        board::{Board, StruggleMove},
+        PlayerColor,
+    },
+    registry::struggle_player,
+    session::{run_match, GameSession, SessionError},
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{transport::Server, Request, Response, Status};
+
+use proto::{
+    r#move::Kind,
+    struggle_server::{Struggle, StruggleServer},
+};
+
+mod proto {
+    tonic::include_proto!("struggle");
+}
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:50051";
+
+// Same limit as the JSON server, matches run on the blocking pool
+const MAX_MATCH_GAMES: u32 = 100_000;
+
+// Roughly how many progress messages a match sends before the final one
+const PROGRESS_UPDATES: u32 = 100;
+
+fn to_proto_player(color: PlayerColor) -> proto::Player {
+    match color {
+        PlayerColor::Red => proto::Player::Red,
+        PlayerColor::Blue => proto::Player::Blue,
+        PlayerColor::Yellow => proto::Player::Yellow,
+        PlayerColor::Green => proto::Player::Green,
+    }
+}
+
+fn to_proto_move(mov: &StruggleMove) -> proto::Move {
+    let (kind, from, to, eats) = match *mov {
+        StruggleMove::AddNewPiece { eats } => (Kind::AddNewPiece, 0, 0, eats),
+        StruggleMove::MovePiece { from, to, eats } => (Kind::MovePiece, from, to, eats),
+        StruggleMove::MoveToGoal {
+            from_board,
+            to_goal,
+        } => (Kind::MoveToGoal, from_board, to_goal, false),
+        StruggleMove::MoveInGoal { from_goal, to_goal } => {
+            (Kind::MoveInGoal, from_goal, to_goal, false)
+        }
+        StruggleMove::SkipTurn => (Kind::SkipTurn, 0, 0, false),
+    };
+
+    proto::Move {
+        kind: kind as i32,
+        from: from as u32,
+        to: to as u32,
+        eats,
+    }
+}
+
+fn to_proto_board(board: &Board) -> proto::Board {
+    let (a, b) = board.players();
+
+    proto::Board {
+        tiles: board
+            .tiles
+            .iter()
+            .map(|cell| cell.map_or(proto::Player::None, to_proto_player) as i32)
+            .collect(),
+        players: [a, b]
+            .iter()
+            .map(|&color| proto::PlayerState {
+                color: to_proto_player(color) as i32,
+                waiting: board.home_bases[color as usize].pieces_waiting as u32,
+                goal: board.goals[color as usize]
+                    .iter()
+                    .map(Option::is_some)
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn game_state(session: &GameSession, id: u64) -> proto::GameState {
+    proto::GameState {
+        id,
+        current_player: to_proto_player(session.current_player()) as i32,
+        winner: session
+            .winner()
+            .map_or(proto::Player::None, to_proto_player) as i32,
+        turns: session.turns(),
+        dice: session.dice().unwrap_or(0) as u32,
+        moves: session.moves().iter().map(to_proto_move).collect(),
+        board: Some(to_proto_board(session.board())),
+    }
+}
+
+fn session_status(err: SessionError) -> Status {
+    match err {
+        SessionError::GameOver | SessionError::NotRolled => {
+            Status::failed_precondition(err.to_string())
+        }
+        SessionError::InvalidMove(_) | SessionError::InvalidPlayer(_) => {
+            Status::invalid_argument(err.to_string())
+        }
+    }
+}
+
+#[derive(Default)]
+struct AppState {
+    games: HashMap<u64, GameSession>,
+    next_id: u64,
+}
+
+#[derive(Default)]
+struct StruggleService {
+    state: Arc<Mutex<AppState>>,
+}
+
+impl StruggleService {
+    fn with_game<T>(
+        &self,
+        id: u64,
+        f: impl FnOnce(&mut GameSession) -> Result<T, SessionError>,
+    ) -> Result<T, Status> {
+        let mut state = self.state.lock().unwrap();
+        let game = state
+            .games
+            .get_mut(&id)
+            .ok_or_else(|| Status::not_found(format!("No game with id {}", id)))?;
+
+        f(game).map_err(session_status)
+    }
+}
+
+#[tonic::async_trait]
+impl Struggle for StruggleService {
+    async fn new_game(
+        &self,
+        request: Request<proto::NewGameRequest>,
+    ) -> Result<Response<proto::GameState>, Status> {
+        let request = request.into_inner();
+        let session = GameSession::new(
+            request.red.as_deref().unwrap_or("random"),
+            request.yellow.as_deref().unwrap_or("random"),
+            request.seed,
+        )
+        .map_err(session_status)?;
+
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+
+        let response = game_state(&session, id);
+        state.games.insert(id, session);
+
+        Ok(Response::new(response))
+    }
+
+    async fn get_moves(
+        &self,
+        request: Request<proto::GameId>,
+    ) -> Result<Response<proto::GameState>, Status> {
+        let id = request.into_inner().id;
+
+        self.with_game(id, |game| {
+            if game.dice().is_none() {
+                game.roll()?;
+            }
+
+            Ok(game_state(game, id))
+        })
+        .map(Response::new)
+    }
+
+    async fn apply_move(
+        &self,
+        request: Request<proto::ApplyMoveRequest>,
+    ) -> Result<Response<proto::GameState>, Status> {
+        let request = request.into_inner();
+
+        self.with_game(request.id, |game| {
+            game.apply(request.index as usize)?;
+            Ok(game_state(game, request.id))
+        })
+        .map(Response::new)
+    }
+
+    async fn suggest_move(
+        &self,
+        request: Request<proto::SuggestMoveRequest>,
+    ) -> Result<Response<proto::Suggestion>, Status> {
+        let request = request.into_inner();
+
+        self.with_game(request.id, |game| {
+            let (index, name) = match request.player {
+                Some(spec) => {
+                    let mut player = struggle_player(&spec).map_err(SessionError::InvalidPlayer)?;
+                    (game.suggestion(&mut player)?, player.name().into_owned())
+                }
+                None => (game.seated_choice()?, "seated player".to_string()),
+            };
+
+            Ok(proto::Suggestion {
+                index: index as u32,
+                r#move: Some(to_proto_move(&game.moves()[index])),
+                player: name,
+            })
+        })
+        .map(Response::new)
+    }
+
+    type RunMatchStream = ReceiverStream<Result<proto::MatchProgress, Status>>;
+
+    async fn run_match(
+        &self,
+        request: Request<proto::RunMatchRequest>,
+    ) -> Result<Response<Self::RunMatchStream>, Status> {
+        let request = request.into_inner();
+        let a = struggle_player(&request.a).map_err(Status::invalid_argument)?;
+        let b = struggle_player(&request.b).map_err(Status::invalid_argument)?;
+
+        if request.games == 0 || request.games > MAX_MATCH_GAMES {
+            return Err(Status::invalid_argument(format!(
+                "games must be between 1 and {}",
+                MAX_MATCH_GAMES
+            )));
+        }
+
+        let (tx, rx) = mpsc::channel(16);
+        let every = (request.games / PROGRESS_UPDATES).max(1);
+
+        tokio::task::spawn_blocking(move || {
+            let progress = |games_played, a_wins, done| proto::MatchProgress {
+                a: a.name().into_owned(),
+                b: b.name().into_owned(),
+                games_played,
+                games: request.games,
+                a_wins,
+                done,
+            };
+
+            let result = run_match(&a, &b, request.games, |result| {
+                // A client that has gone away just stops receiving, the match still finishes
+                if result.games % every == 0 && result.games < request.games {
+                    let _ = tx.blocking_send(Ok(progress(result.games, result.a_wins, false)));
+                }
+            });
+
+            let _ = tx.blocking_send(Ok(progress(result.games, result.a_wins, true)));
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
+
+#[tokio::main]
+pub async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    println!("Serving gRPC on {}", address);
+
+    Server::builder()
+        .add_service(StruggleServer::new(StruggleService::default()))
+        .serve(address.parse()?)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use clap::Parser;
+use struggle_core::{
+    handicap::{calibrate_handicap, CalibrationOptions, Handicap},
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Finds how much a strong player has to be handicapped to win half of its games against a weak
+/// one, see `handicap`.
+#[derive(Parser)]
+struct Args {
+    /// The player to handicap, with a depth for the depth handicap
+    #[arg(long, default_value = "expectiminimax:2")]
+    strong: String,
+
+    #[arg(long, default_value = "score_move")]
+    weak: String,
+
+    /// The handicap to search: dilution (the share of random moves) or depth (plies less)
+    #[arg(long, default_value = "dilution")]
+    handicap: Handicap,
+
+    /// Games per step
+    #[arg(long, default_value_t = 4_000)]
+    games: u32,
+
+    /// How close to 50% the win rate has to get
+    #[arg(long, default_value_t = 0.02)]
+    tolerance: f64,
+
+    #[arg(long, default_value_t = 12)]
+    max_steps: usize,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+    let weak = struggle_player(&args.weak)?;
+
+    let options = CalibrationOptions {
+        games: args.games,
+        tolerance: args.tolerance,
+        max_steps: args.max_steps,
+        seed: args.seed,
+    };
+
+    let calibration = calibrate_handicap(&args.strong, &weak, args.handicap, &options, |step| {
+        println!(
+            "{} {:.3}: {:.1}% (p95 [{:.1}, {:.1}]) against {}",
+            args.handicap,
+            step.handicap,
+            step.result.a_win_rate() * 100.0,
+            step.interval.0 * 100.0,
+            step.interval.1 * 100.0,
+            args.weak
+        );
+    })?;
+
+    let best = calibration.best();
+
+    println!(
+        "{} plays {} evenly with a {} handicap of {:.3}: {:.1}% (p95 [{:.1}, {:.1}]) over {} games{}",
+        args.strong,
+        args.weak,
+        args.handicap,
+        best.handicap,
+        best.result.a_win_rate() * 100.0,
+        best.interval.0 * 100.0,
+        best.interval.1 * 100.0,
+        best.result.games,
+        if calibration.converged {
+            ""
+        } else {
+            ", which is outside the tolerance"
+        }
+    );
+
+    Ok(())
+}
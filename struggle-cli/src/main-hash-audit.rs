@@ -0,0 +1,54 @@
+use clap::Parser;
+use struggle_core::games::struggle::{
+    hash_audit::{audit_board_hash, sample_boards},
+    transposition_table::DEFAULT_CAPACITY,
+};
+
+/// Hashes the positions of many random games with `get_board_hash`, and reports how many distinct
+/// positions share a hash and how evenly the hashes spread over their bits and a table's slots.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value_t = 10_000)]
+    games: u64,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Slots of the table to spread the hashes over, a power of two
+    #[arg(long, default_value_t = DEFAULT_CAPACITY)]
+    slots: usize,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    if !args.slots.is_power_of_two() {
+        return Err(format!("{} slots isn't a power of two", args.slots).into());
+    }
+
+    let audit = audit_board_hash(sample_boards(args.games, args.seed), args.slots);
+
+    println!(
+        "{} positions, {} distinct, {} collisions ({:.6}%)",
+        audit.positions,
+        audit.distinct,
+        audit.collisions,
+        audit.collision_rate() * 100.0
+    );
+    println!("{} of 64 bits never change", audit.constant_bits());
+    println!(
+        "Spread over {} slots: chi-squared z-score {:.2}",
+        audit.slots, audit.slot_z_score
+    );
+
+    println!();
+    println!("Bit  Set");
+
+    for (bit, frequency) in audit.bit_frequencies.iter().enumerate() {
+        println!("{:>3}  {:.4}", bit, frequency);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use clap::Parser;
+use struggle_core::{
+    mirror::{mirror_match, DEFAULT_Z_LIMIT},
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays every player against itself and checks that Red wins half of the games, see `mirror`.
+/// Exits with an error if any player shows a color bias.
+#[derive(Parser)]
+struct Args {
+    #[arg(default_values_t = [
+        "random".to_string(),
+        "score_move".to_string(),
+        "expectiminimax:1".to_string(),
+    ])]
+    players: Vec<String>,
+
+    /// Games per player
+    #[arg(long, default_value_t = 100_000)]
+    games: u32,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Standard errors from 50% that count as a bias
+    #[arg(long, default_value_t = DEFAULT_Z_LIMIT)]
+    z_limit: f64,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+    let mut biased = Vec::new();
+
+    println!(
+        "{:<30} {:>7} {:>18} {:>7}",
+        "Player", "Red %", "95% CI", "z"
+    );
+
+    for spec in &args.players {
+        let player = struggle_player(spec)?;
+        let check = mirror_match(&player, args.games, args.seed);
+        let flag = if check.biased(args.z_limit) {
+            biased.push(spec.clone());
+            "  biased"
+        } else {
+            ""
+        };
+
+        println!(
+            "{:<30} {:>7.2} [{:>6.2}, {:>6.2}]  {:>6.2}{}",
+            spec,
+            check.red_win_rate() * 100.0,
+            check.interval.0 * 100.0,
+            check.interval.1 * 100.0,
+            check.z,
+            flag
+        );
+    }
+
+    if !biased.is_empty() {
+        return Err(format!("Color bias in {}", biased.join(", ")).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use clap::Parser;
+use rand::{rngs::SmallRng, SeedableRng};
+use rayon::prelude::*;
+use struggle_core::{
+    analysis::win_rate_interval,
+    game::GameId,
+    games::struggle::{multi::MultiStruggleGame, AiStrugglePlayer, COLORS},
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays games between 2 to 4 players on one board, e.g.
+/// `struggle-multi random score_move expectiminimax:1 random`, see `multi`. The players are
+/// seated as Red, Blue, Yellow and Green, in turn order.
+#[derive(Parser)]
+struct Args {
+    #[arg(num_args = 2..=4, required = true)]
+    players: Vec<String>,
+
+    #[arg(long, default_value_t = 10_000)]
+    games: u64,
+
+    /// Master seed, from which every game's seed is derived
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let seats = args
+        .players
+        .iter()
+        .zip(COLORS)
+        .map(|(spec, color)| Ok(AiStrugglePlayer::new(color, struggle_player(spec)?)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let game = MultiStruggleGame::new(seats, true);
+
+    // Per seat: wins, and the turns of the games won
+    let (wins, turns) = (0..args.games)
+        .into_par_iter()
+        .map(|index| {
+            let mut game = game.clone();
+            let mut rng = SmallRng::seed_from_u64(GameId::new(args.seed, index).game_seed());
+
+            let winner = game.play(&mut rng);
+            let seat = game.seat_of(winner).unwrap();
+            let turns = game.stats().unwrap().turns as u64;
+
+            let mut wins = [0u64; 4];
+            let mut won_turns = [0u64; 4];
+            wins[seat] = 1;
+            won_turns[seat] = turns;
+
+            (wins, won_turns)
+        })
+        .reduce(
+            || ([0; 4], [0; 4]),
+            |(a_wins, a_turns), (b_wins, b_turns)| {
+                (
+                    core::array::from_fn(|i| a_wins[i] + b_wins[i]),
+                    core::array::from_fn(|i| a_turns[i] + b_turns[i]),
+                )
+            },
+        );
+
+    println!(
+        "{:<8} {:<30} {:>7} {:>18} {:>10}",
+        "Seat", "Player", "Wins %", "95% CI", "Turns/win"
+    );
+
+    for (index, (seat, spec)) in game.seats().iter().zip(&args.players).enumerate() {
+        let (rate, (low, high)) = win_rate_interval(wins[index], args.games);
+
+        println!(
+            "{:<8} {:<30} {:>7.2} [{:>6.2}, {:>6.2}] {:>10.1}",
+            format!("{:?}", seat.color),
+            spec,
+            rate * 100.0,
+            low * 100.0,
+            high * 100.0,
+            turns[index] as f64 / wins[index].max(1) as f64
+        );
+    }
+
+    Ok(())
+}
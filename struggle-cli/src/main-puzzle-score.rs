@@ -0,0 +1,50 @@
+use std::{fs::File, io::BufReader};
+
+use clap::Parser;
+use struggle_core::{
+    games::struggle::puzzles::{read_puzzles, score_suite},
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Runs players over a puzzle suite from `struggle-puzzles`, and reports how often they find the
+/// reference move and how much they lose on average by their choices.
+#[derive(Parser)]
+struct Args {
+    /// Player specs, e.g. `expectiminimax:2`
+    #[arg(required = true)]
+    players: Vec<String>,
+
+    #[arg(long, default_value = "out/puzzles.jsonl")]
+    puzzles: String,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let puzzles = read_puzzles(BufReader::new(File::open(&args.puzzles)?))?;
+    println!("{} puzzles from {}", puzzles.len(), args.puzzles);
+    println!();
+    println!("{:<30} {:>9} {:>12}", "Player", "Accuracy", "Average loss");
+
+    for spec in &args.players {
+        let player = struggle_player(spec)?;
+        let score = score_suite(&player, &puzzles, args.seed)?;
+
+        println!(
+            "{:<30} {:>8.1}% {:>12.4}",
+            spec,
+            score.accuracy() * 100.0,
+            score.average_loss()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use std::{fs::File, io::BufWriter};
+
+use clap::Parser;
+use struggle_core::{
+    games::struggle::puzzles::{find_puzzles, write_puzzles},
+    registry::struggle_player,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays games and saves the positions where a shallow and a deep expectiminimax search choose
+/// different moves, as a corpus of puzzles for heuristic development, see
+/// `games::struggle::puzzles`.
+#[derive(Parser)]
+struct Args {
+    /// The player whose games provide the positions
+    #[arg(long, default_value = "expectiminimax:1")]
+    explorer: String,
+
+    #[arg(long, default_value_t = 1)]
+    shallow: u8,
+
+    #[arg(long, default_value_t = 3)]
+    deep: u8,
+
+    #[arg(long, default_value_t = 1_000)]
+    games: u64,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// The puzzles are written to this file as JSON lines, the costliest mistakes first
+    #[arg(long, default_value = "out/puzzles.jsonl")]
+    out: String,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    if args.shallow >= args.deep {
+        return Err("The shallow depth must be less than the deep depth".into());
+    }
+
+    let explorer = struggle_player(&args.explorer)?;
+
+    let mut puzzles = find_puzzles(&explorer, args.shallow, args.deep, args.games, args.seed);
+    puzzles.sort_by(|a, b| b.regret().total_cmp(&a.regret()));
+
+    if let Some(parent) = std::path::Path::new(&args.out).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut writer = BufWriter::new(File::create(&args.out)?);
+    write_puzzles(&mut writer, &puzzles)?;
+
+    println!(
+        "Found {} puzzles in {} games, wrote them to {}",
+        puzzles.len(),
+        args.games,
+        args.out
+    );
+
+    Ok(())
+}
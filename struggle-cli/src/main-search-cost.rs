@@ -0,0 +1,171 @@
+use std::{fs::OpenOptions, path::Path};
+
+use clap::Parser;
+use plotters::prelude::*;
+use serde::Serialize;
+use struggle_core::{
+    games::struggle::distill::teacher,
+    manifest::RunManifest,
+    palette::Palette,
+    render::to_plotters_color,
+    scaling::{sample_positions, search_cost, CostPoint, Phase},
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Measures the time and the heuristic evaluations expectiminimax takes per move at every depth
+/// and game phase, on the same sampled positions on every run, see `scaling::search_cost`. The
+/// results are appended to a CSV file with the commit, to track the cost curve over time.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value_t = 3)]
+    max_depth: u8,
+
+    /// Positions per game phase
+    #[arg(long, default_value_t = 200)]
+    positions: usize,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// The CSV file the results are appended to
+    #[arg(long, default_value = "out/search_cost.csv")]
+    csv: String,
+
+    #[arg(long, default_value = "out/search_cost.svg")]
+    out: String,
+}
+
+#[derive(Serialize)]
+struct CostRow<'a> {
+    commit: &'a str,
+    timestamp: u64,
+    depth: u8,
+    phase: String,
+    positions: usize,
+    micros_per_move: f64,
+    nodes_per_move: f64,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+    let manifest = RunManifest::new("search-cost", args.seed);
+
+    let positions = sample_positions(args.positions, args.seed);
+    let points = search_cost(&positions, 0..=args.max_depth, teacher);
+
+    println!("Depth  Phase    μs per move  Nodes per move");
+
+    for point in &points {
+        println!(
+            "{:>5}  {:<8} {:>11.1} {:>15.1}",
+            point.depth, point.phase, point.micros_per_move, point.nodes_per_move
+        );
+    }
+
+    for path in [&args.csv, &args.out] {
+        if let Some(parent) = Path::new(path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let exists = Path::new(&args.csv).exists();
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&args.csv)?;
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(!exists)
+        .from_writer(file);
+
+    let commit = manifest.commit.as_deref().unwrap_or("unknown");
+
+    for point in &points {
+        writer.serialize(CostRow {
+            commit,
+            timestamp: manifest.started_at,
+            depth: point.depth,
+            phase: point.phase.to_string(),
+            positions: point.positions,
+            micros_per_move: point.micros_per_move,
+            nodes_per_move: point.nodes_per_move,
+        })?;
+    }
+
+    writer.flush()?;
+
+    draw_cost(&points, &args.out);
+    println!(
+        "Appended the results to {} and wrote the plot to {}",
+        args.csv, args.out
+    );
+
+    Ok(())
+}
+
+/// Reads the plotted cost off a measurement.
+type CostAccessor = fn(&CostPoint) -> f64;
+
+fn draw_cost(points: &[CostPoint], path: &str) {
+    let drawing_area = SVGBackend::new(path, (1200, 500)).into_drawing_area();
+    drawing_area.fill(&WHITE).unwrap();
+
+    let (left, right) = drawing_area.split_horizontally(600);
+    let palette = Palette::from_env();
+    let max_depth = points.iter().map(|point| point.depth).max().unwrap_or(0);
+
+    let charts: [(_, &str, CostAccessor); 2] = [
+        (left, "Time per move (μs)", |point| point.micros_per_move),
+        (right, "Evaluations per move", |point| point.nodes_per_move),
+    ];
+
+    for (area, caption, value) in charts {
+        let (min, max) = points
+            .iter()
+            .map(value)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                (min.min(value), max.max(value))
+            });
+
+        // The cost grows exponentially with the depth, so it's drawn on a log scale
+        let mut chart = ChartBuilder::on(&area)
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 40)
+            .margin(8)
+            .caption(caption, ("Source Sans Pro, sans-serif", 20))
+            .build_cartesian_2d(
+                -0.5..(max_depth as f64 + 0.5),
+                ((min * 0.5).max(1e-3)..(max * 2.0).max(1e-2)).log_scale(),
+            )
+            .unwrap();
+
+        chart.configure_mesh().x_desc("Depth").draw().unwrap();
+
+        for (phase, color) in Phase::ALL.into_iter().zip(palette.players) {
+            let color = to_plotters_color(color);
+            let series = points
+                .iter()
+                .filter(|point| point.phase == phase)
+                .map(|point| (point.depth as f64, value(point)))
+                .collect::<Vec<_>>();
+
+            chart
+                .draw_series(LineSeries::new(series, color))
+                .unwrap()
+                .label(phase.to_string())
+                .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 16, y)], color));
+        }
+
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()
+            .unwrap();
+    }
+
+    drawing_area.present().unwrap();
+}
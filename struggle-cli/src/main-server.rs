@@ -0,0 +1,318 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use struggle_core::{
+    game::NamedPlayer,
+    json::{color_name, BoardJson, MoveJson},
+    registry::struggle_player,
+    session::{run_match, GameSession, SessionError},
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:8080";
+
+// Every batch comparison gets a thread of its own, so keep them from hogging the machine
+const MAX_COMPARISON_GAMES: u32 = 100_000;
+
+fn game_state(session: &GameSession, id: u64) -> GameState {
+    GameState {
+        id,
+        current_player: color_name(session.current_player()),
+        winner: session.winner().map(color_name),
+        turns: session.turns(),
+        dice: session.dice(),
+        moves: session.moves().iter().map(MoveJson::from).collect(),
+        board: BoardJson::new(session.board()),
+    }
+}
+
+#[derive(Serialize)]
+struct GameState {
+    id: u64,
+    current_player: &'static str,
+    winner: Option<&'static str>,
+    turns: u32,
+    /// The latest roll, while the move for it hasn't been made yet
+    dice: Option<u8>,
+    moves: Vec<MoveJson>,
+    board: BoardJson,
+}
+
+#[derive(Deserialize)]
+struct CreateGameRequest {
+    red: Option<String>,
+    yellow: Option<String>,
+    seed: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct MoveRequest {
+    index: usize,
+}
+
+#[derive(Deserialize)]
+struct SuggestionRequest {
+    /// Player spec to ask, defaults to the player in the current seat
+    player: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CompareRequest {
+    a: String,
+    b: String,
+    games: u32,
+}
+
+struct ApiError {
+    status: u16,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: 400,
+            message: message.into(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self {
+            status: 404,
+            message: "Not found".to_string(),
+        }
+    }
+
+    fn conflict(message: impl Into<String>) -> Self {
+        Self {
+            status: 409,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<SessionError> for ApiError {
+    fn from(err: SessionError) -> Self {
+        match err {
+            SessionError::GameOver | SessionError::NotRolled => Self::conflict(err.to_string()),
+            SessionError::InvalidMove(_) | SessionError::InvalidPlayer(_) => {
+                Self::bad_request(err.to_string())
+            }
+        }
+    }
+}
+
+/// The sessions have locks of their own, so that a long search in one game only holds up
+/// requests to that game. The state's lock is only held to look them up.
+#[derive(Default)]
+struct AppState {
+    games: HashMap<u64, Arc<Mutex<GameSession>>>,
+    next_id: u64,
+}
+
+// Looks up a session, releasing the state's lock before the caller locks the session
+fn session(state: &Mutex<AppState>, id: &str) -> Result<(u64, Arc<Mutex<GameSession>>), ApiError> {
+    let state = state.lock().unwrap();
+
+    id.parse()
+        .ok()
+        .and_then(|id| Some((id, state.games.get(&id)?.clone())))
+        .ok_or_else(ApiError::not_found)
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(request: &mut Request) -> Result<T, ApiError> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .read_to_string(&mut body)
+        .map_err(|err| ApiError::bad_request(err.to_string()))?;
+
+    // Allow an empty body for requests where every field is optional
+    let body = if body.trim().is_empty() { "{}" } else { &body };
+
+    serde_json::from_str(body).map_err(|err| ApiError::bad_request(err.to_string()))
+}
+
+fn compare(request: CompareRequest) -> Result<serde_json::Value, ApiError> {
+    let a = struggle_player(&request.a).map_err(ApiError::bad_request)?;
+    let b = struggle_player(&request.b).map_err(ApiError::bad_request)?;
+
+    if request.games == 0 || request.games > MAX_COMPARISON_GAMES {
+        return Err(ApiError::bad_request(format!(
+            "games must be between 1 and {}",
+            MAX_COMPARISON_GAMES
+        )));
+    }
+
+    let result = run_match(&a, &b, request.games, |_| {});
+
+    Ok(json!({
+        "a": a.name(),
+        "b": b.name(),
+        "games": result.games,
+        "a_wins": result.a_wins,
+        "b_wins": result.b_wins(),
+        "a_win_rate": result.a_win_rate(),
+    }))
+}
+
+fn path(request: &Request) -> String {
+    let path = request.url().split('?').next().unwrap_or("");
+    path.trim_matches('/').to_string()
+}
+
+// Every route but `compare`, which runs on a thread of its own, see `main`
+fn route(state: &Mutex<AppState>, request: &mut Request) -> Result<serde_json::Value, ApiError> {
+    let path = path(request);
+    let segments: Vec<&str> = path.split('/').collect();
+    let method = request.method().clone();
+
+    match (method, segments.as_slice()) {
+        (Method::Post, ["games"]) => {
+            let body: CreateGameRequest = parse_body(request)?;
+            let session = GameSession::new(
+                body.red.as_deref().unwrap_or("random"),
+                body.yellow.as_deref().unwrap_or("random"),
+                body.seed,
+            )?;
+
+            let mut state = state.lock().unwrap();
+            let id = state.next_id;
+            state.next_id += 1;
+
+            let response = game_state(&session, id);
+            state.games.insert(id, Arc::new(Mutex::new(session)));
+
+            Ok(json!(response))
+        }
+        (Method::Get, ["games", id]) => {
+            let (id, game) = session(state, id)?;
+            let game = game.lock().unwrap();
+            Ok(json!(game_state(&game, id)))
+        }
+        (Method::Delete, ["games", id]) => {
+            let (id, _) = session(state, id)?;
+            state.lock().unwrap().games.remove(&id);
+            Ok(json!({ "deleted": id }))
+        }
+        (Method::Post, ["games", id, "roll"]) => {
+            let (id, game) = session(state, id)?;
+            let mut game = game.lock().unwrap();
+
+            game.roll()?;
+            Ok(json!(game_state(&game, id)))
+        }
+        (Method::Post, ["games", id, "moves"]) => {
+            let body: MoveRequest = parse_body(request)?;
+            let (id, game) = session(state, id)?;
+            let mut game = game.lock().unwrap();
+
+            game.apply(body.index)?;
+            Ok(json!(game_state(&game, id)))
+        }
+        // Rolls if needed, and lets the seated player make the move
+        (Method::Post, ["games", id, "ai-turn"]) => {
+            let (id, game) = session(state, id)?;
+            let mut game = game.lock().unwrap();
+
+            game.play_ai_turn()?;
+            Ok(json!(game_state(&game, id)))
+        }
+        (Method::Post, ["games", id, "suggestion"]) => {
+            let body: SuggestionRequest = parse_body(request)?;
+            let (_, game) = session(state, id)?;
+            let mut game = game.lock().unwrap();
+
+            let (index, name) = match body.player {
+                Some(spec) => {
+                    let mut player = struggle_player(&spec).map_err(ApiError::bad_request)?;
+                    (game.suggestion(&mut player)?, player.name().into_owned())
+                }
+                None => (game.seated_choice()?, "seated player".to_string()),
+            };
+
+            Ok(json!({
+                "index": index,
+                "move": MoveJson::from(&game.moves()[index]),
+                "player": name,
+            }))
+        }
+        _ => Err(ApiError::not_found()),
+    }
+}
+
+fn respond(request: Request, status: u16, body: serde_json::Value) {
+    let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(header);
+
+    if let Err(err) = request.respond(response) {
+        eprintln!("Failed to send a response: {}", err);
+    }
+}
+
+fn respond_with(request: Request, result: Result<serde_json::Value, ApiError>) {
+    match result {
+        Ok(body) => respond(request, 200, body),
+        Err(err) => respond(request, err.status, json!({ "error": err.message })),
+    }
+}
+
+pub fn main() {
+    struggle_core::logging::init();
+
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+
+    let server = Arc::new(Server::http(&address).expect("Failed to start the server"));
+    let state = Arc::new(Mutex::new(AppState::default()));
+
+    println!("Listening on http://{}", address);
+
+    // A handful of workers, so that a long search doesn't block everything else
+    let workers = (0..4)
+        .map(|_| {
+            let server = server.clone();
+            let state = state.clone();
+
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    // Prometheus scrapes expect the text format rather than JSON
+                    #[cfg(feature = "metrics")]
+                    if request.method() == &Method::Get && request.url() == "/metrics" {
+                        if let Err(err) = request.respond(struggle_core::metrics::response()) {
+                            eprintln!("Failed to send a response: {}", err);
+                        }
+                        continue;
+                    }
+
+                    // Comparisons don't touch the sessions, and can take long enough to
+                    // tie up every worker, so they run on threads of their own
+                    if request.method() == &Method::Post && path(&request) == "compare" {
+                        std::thread::spawn(move || {
+                            let result = parse_body(&mut request).and_then(compare);
+                            respond_with(request, result);
+                        });
+                        continue;
+                    }
+
+                    let result = route(&state, &mut request);
+                    respond_with(request, result);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for worker in workers {
+        worker.join().unwrap();
+    }
+}
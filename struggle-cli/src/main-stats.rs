@@ -1,16 +1,16 @@
 use std::collections::HashMap;
 
-use rayon::prelude::*;
 use struggle_core::{
-    game::{play_game, NamedPlayer},
+    game::{simulate_batch, BatchConfig, NamedPlayer, WinCounts},
     games::struggle::{
         players::{
             expectiminimax, maximize_options, minimize_options, participation_trophy,
-            worst_expectiminimax, DilutedPlayer, RandomDietPlayer, RandomEaterPlayer, RandomPlayer,
+            worst_expectiminimax, RandomDietPlayer, RandomEaterPlayer, RandomPlayer,
             StrugglePlayer,
         },
         AiStrugglePlayer, PlayerColor, StruggleGame,
     },
+    players::DilutedPlayer,
 };
 
 #[global_allocator]
@@ -24,21 +24,17 @@ pub fn compare_players<A: StrugglePlayer, B: StrugglePlayer>(
     let a_color = a.0;
     let b_color = b.0;
 
-    let games_won_by_a = (0..rounds)
-        .into_par_iter()
-        .map(|_| {
-            let player_a = a.1.clone();
-            let player_b = b.1.clone();
-            let player_a = AiStrugglePlayer::new(a_color, player_a);
-            let player_b = AiStrugglePlayer::new(b_color, player_b);
-            let mut game = StruggleGame::new(player_a, player_b, false);
-            let winner = play_game(&mut game);
-            winner
-        })
-        .filter(|winner| *winner == a_color)
-        .count();
-
-    games_won_by_a as f64 / rounds as f64
+    let wins: WinCounts<PlayerColor> = simulate_batch(
+        || {
+            let player_a = AiStrugglePlayer::new(a_color, a.1.clone());
+            let player_b = AiStrugglePlayer::new(b_color, b.1.clone());
+            StruggleGame::new(player_a, player_b, false)
+        },
+        rounds as u64,
+        &BatchConfig::default(),
+    );
+
+    wins.wins(&a_color) as f64 / rounds as f64
 }
 
 const TOTAL_GAMES: u32 = 500_000;
@@ -66,6 +62,8 @@ macro_rules! run_games {
 }
 
 pub fn main() {
+    struggle_core::logging::init();
+
     let mut results = HashMap::new();
     let mut writer = csv::Writer::from_path("./results.csv").unwrap();
 
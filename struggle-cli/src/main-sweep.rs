@@ -0,0 +1,203 @@
+use std::io::Write;
+
+use clap::Parser;
+use indicatif::ProgressBar;
+use plotters::prelude::*;
+use rand::{rngs::SmallRng, SeedableRng};
+use struggle_core::{
+    palette::Palette,
+    registry::struggle_player,
+    render::to_plotters_color,
+    sweep::{run_sweep, Dimension, SearchSpace, SweepResult},
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Searches for the strongest expectiminimax parameters against a gauntlet of opponents, see
+/// `sweep` for the parameters.
+#[derive(Parser)]
+struct Args {
+    /// Dimensions of the search space, e.g. `depth=0,1,2` or `base_piece_score=300..800`
+    #[arg(required = true)]
+    dimensions: Vec<String>,
+
+    /// Samples this many random configurations instead of searching the whole grid
+    #[arg(long)]
+    random: Option<usize>,
+
+    /// Values per range in grid search
+    #[arg(long, default_value_t = 5)]
+    steps: usize,
+
+    /// The opponents every configuration plays against
+    #[arg(long, default_values_t = ["random".to_string(), "expectiminimax:1".to_string()])]
+    gauntlet: Vec<String>,
+
+    /// Games against each opponent per configuration
+    #[arg(long, default_value_t = 1_000)]
+    games: u32,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Writes the results as CSV to this file
+    #[arg(long)]
+    csv: Option<String>,
+
+    /// Writes a plot of win rate against each parameter to this directory
+    #[arg(long)]
+    plots: Option<String>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let space = SearchSpace {
+        dimensions: args
+            .dimensions
+            .iter()
+            .map(|spec| Dimension::parse(spec))
+            .collect::<Result<_, _>>()?,
+    };
+
+    let gauntlet = args
+        .gauntlet
+        .iter()
+        .map(|spec| struggle_player(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let configurations = match args.random {
+        Some(count) => space.random(count, &mut SmallRng::seed_from_u64(args.seed)),
+        None => space.grid(args.steps),
+    };
+
+    let progress = ProgressBar::new((configurations.len() * gauntlet.len()) as u64);
+
+    let results = run_sweep(configurations, &gauntlet, args.games, || progress.inc(1));
+    progress.finish_and_clear();
+
+    println!(
+        "{:>4}  {:>6}  {:>15}  Configuration",
+        "Rank", "Win %", "95% CI"
+    );
+
+    for (rank, result) in results.iter().enumerate() {
+        println!(
+            "{:>4}  {:>6.1}  {:>6.1} – {:>6.1}  {}",
+            rank + 1,
+            result.win_rate * 100.0,
+            result.interval.0 * 100.0,
+            result.interval.1 * 100.0,
+            result.configuration
+        );
+    }
+
+    if let Some(path) = &args.csv {
+        write_csv(path, &space, &args.gauntlet, &results)?;
+        println!("Wrote the results to {}", path);
+    }
+
+    if let Some(dir) = &args.plots {
+        std::fs::create_dir_all(dir)?;
+
+        for dimension in &space.dimensions {
+            let path = format!("{}/{}.svg", dir, dimension.name);
+            plot_dimension(&path, &dimension.name, &results);
+            println!("Wrote {}", path);
+        }
+    }
+
+    Ok(())
+}
+
+fn write_csv(
+    path: &str,
+    space: &SearchSpace,
+    gauntlet: &[String],
+    results: &[SweepResult],
+) -> std::io::Result<()> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+
+    let header = space
+        .dimensions
+        .iter()
+        .map(|dimension| dimension.name.clone())
+        .chain(gauntlet.iter().cloned())
+        .chain(["win_rate".to_string()])
+        .collect::<Vec<_>>();
+    writeln!(file, "{}", header.join(","))?;
+
+    for result in results {
+        let row = result
+            .configuration
+            .0
+            .iter()
+            .map(|(_, value)| *value)
+            .chain(result.win_rates.iter().copied())
+            .chain([result.win_rate])
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+        writeln!(file, "{}", row.join(","))?;
+    }
+
+    file.flush()
+}
+
+fn plot_dimension(path: &str, name: &str, results: &[SweepResult]) {
+    let points = results
+        .iter()
+        .filter_map(|result| {
+            let value = result.configuration.get(name)?;
+            Some((value, result.win_rate * 100.0, result.interval))
+        })
+        .collect::<Vec<_>>();
+
+    let (min, max) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), point| {
+            (min.min(point.0), max.max(point.0))
+        });
+    let margin = ((max - min) * 0.05).max(0.5);
+
+    let drawing_area = SVGBackend::new(path, (800, 600)).into_drawing_area();
+    drawing_area.fill(&WHITE).unwrap();
+
+    let mut chart = ChartBuilder::on(&drawing_area)
+        .set_label_area_size(LabelAreaPosition::Left, 50)
+        .set_label_area_size(LabelAreaPosition::Bottom, 40)
+        .margin(8)
+        .caption(
+            format!("Win rate by {}", name),
+            ("Source Sans Pro, sans-serif", 20),
+        )
+        .build_cartesian_2d((min - margin)..(max + margin), 0.0..100.0)
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_desc(name)
+        .y_desc("Win %")
+        .draw()
+        .unwrap();
+
+    let color = to_plotters_color(Palette::from_env().chart);
+
+    chart
+        .draw_series(points.iter().map(|(x, _, (low, high))| {
+            PathElement::new(vec![(*x, low * 100.0), (*x, high * 100.0)], color)
+        }))
+        .unwrap();
+
+    chart
+        .draw_series(
+            points
+                .iter()
+                .map(|(x, y, _)| Circle::new((*x, *y), 3, color.filled())),
+        )
+        .unwrap();
+
+    drawing_area.present().unwrap();
+}
@@ -0,0 +1,221 @@
+use std::time::Duration;
+
+use clap::Parser;
+use indicatif::ProgressBar;
+use struggle_core::{
+    analysis::{wilson_score, EloAnchor},
+    best_response::{exploitability, BestResponseOptions},
+    manifest::RunManifest,
+    registry::struggle_player,
+    tournament::Tournament,
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Plays a round-robin tournament between players, e.g.
+/// `struggle-tournament random score_move expectiminimax:1`, or re-runs one from its manifest
+/// with `struggle-tournament --from-manifest out/manifest.json`.
+#[derive(Parser)]
+struct Args {
+    /// Player specs, see `registry`
+    #[arg(num_args = 2.., required_unless_present = "from_manifest")]
+    players: Vec<String>,
+
+    /// Games between each pair of players
+    #[arg(long, default_value_t = 10_000)]
+    games: u32,
+
+    /// Abandons games after this many turns and counts them as draws
+    #[arg(long)]
+    max_turns: Option<u32>,
+
+    /// Seed of the games of every matchup, random by default
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Pins the Elo rating of this player, e.g. `random`, so that ratings are comparable between
+    /// runs with different players
+    #[arg(long)]
+    anchor: Option<String>,
+
+    /// The rating the anchor is pinned to
+    #[arg(long, default_value_t = 0.0, requires = "anchor")]
+    anchor_rating: f64,
+
+    /// Also tunes a counter player against every player and reports how far beyond 50% it wins,
+    /// see `best_response::exploitability`. This takes far longer than the tournament.
+    #[arg(long)]
+    exploitability: bool,
+
+    /// Writes the seed, the players and the command line of the run to this JSON file
+    #[arg(long)]
+    manifest: Option<String>,
+
+    /// Plays the same tournament as the manifest of an earlier run, with its players, games, turn
+    /// cap and seed
+    #[arg(long, conflicts_with_all = ["players", "games", "max_turns", "seed"])]
+    from_manifest: Option<String>,
+
+    /// Shows a live dashboard instead of a progress bar
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    tui: bool,
+}
+
+fn show_progress(tournament: &Tournament) {
+    let progress = ProgressBar::new(tournament.total_games() as u64);
+
+    while !tournament.is_finished() {
+        progress.set_position(tournament.games_played() as u64);
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    progress.finish();
+}
+
+fn print_results(tournament: &Tournament, anchor: Option<EloAnchor>, exploitability: &[f64]) {
+    let names = tournament.names();
+    let ratings = tournament.ratings(anchor);
+    let mixture = tournament.nash_mixture();
+
+    for matchup in tournament.matchups() {
+        let result = matchup.result();
+        let (low, high) = wilson_score(result.a_win_rate(), result.games as u64);
+
+        println!(
+            "{} vs {}: {:.3} (p95 [{:.4}, {:.4}]) over {} games",
+            names[matchup.a],
+            names[matchup.b],
+            result.a_win_rate(),
+            low,
+            high,
+            result.games
+        );
+    }
+
+    println!();
+
+    if tournament.truncated() > 0 {
+        println!(
+            "{} games were abandoned at the turn cap and counted as draws",
+            tournament.truncated()
+        );
+        println!();
+    }
+
+    for (rank, standing) in tournament.standings().iter().enumerate() {
+        let exploitability = exploitability
+            .get(standing.player)
+            .map(|e| format!(", exploitability {:.1}%", e * 100.0))
+            .unwrap_or_default();
+
+        println!(
+            "{}. {}: {:.3}, Elo {:.0}, Nash weight {:.3}{} ({} wins and {} draws in {} games)",
+            rank + 1,
+            names[standing.player],
+            standing.win_rate(),
+            ratings[standing.player],
+            mixture[standing.player],
+            exploitability,
+            standing.wins,
+            standing.draws,
+            standing.games
+        );
+    }
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let mut args = Args::parse();
+
+    if let Some(path) = &args.from_manifest {
+        let manifest = RunManifest::read(path)?;
+
+        if manifest.tool != "struggle-tournament" {
+            return Err(format!("{} is a manifest of {}", path, manifest.tool).into());
+        }
+
+        args.players = manifest.players;
+        args.games = manifest.games as u32;
+        args.max_turns = manifest.max_turns;
+        args.seed = Some(manifest.seed);
+    }
+
+    let anchor = match &args.anchor {
+        Some(spec) => {
+            let player = args
+                .players
+                .iter()
+                .position(|player| player == spec)
+                .ok_or_else(|| format!("The anchor {} isn't one of the players", spec))?;
+
+            Some(EloAnchor {
+                player,
+                rating: args.anchor_rating,
+            })
+        }
+        None => None,
+    };
+
+    let players = args
+        .players
+        .iter()
+        .map(|spec| struggle_player(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut tournament = Tournament::new(players, args.games);
+
+    if let Some(seed) = args.seed {
+        tournament = tournament.with_seed(seed);
+    }
+
+    if let Some(max_turns) = args.max_turns {
+        tournament = tournament.with_max_turns(max_turns);
+    }
+
+    let manifest = RunManifest::new("struggle-tournament", tournament.seed())
+        .with_players(args.players.iter().cloned())
+        .with_player_configs(tournament.player_configs())
+        .with_games(args.games as u64)
+        .with_max_turns(args.max_turns);
+
+    std::thread::scope(|scope| -> std::io::Result<()> {
+        scope.spawn(|| tournament.run());
+
+        #[cfg(feature = "tui")]
+        if args.tui {
+            return struggle_core::dashboard::run(&tournament);
+        }
+
+        show_progress(&tournament);
+        Ok(())
+    })?;
+
+    let exploitability = if args.exploitability {
+        let options = BestResponseOptions {
+            seed: tournament.seed(),
+            ..Default::default()
+        };
+
+        args.players
+            .iter()
+            .map(|spec| {
+                let player = struggle_player(spec)?;
+                Ok(exploitability(&player, &options, args.games).exploitability())
+            })
+            .collect::<Result<Vec<_>, String>>()?
+    } else {
+        Vec::new()
+    };
+
+    print_results(&tournament, anchor, &exploitability);
+
+    // Written at the end, so that it has the running time
+    if let Some(path) = &args.manifest {
+        manifest.with_elapsed(tournament.elapsed()).write(path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use clap::Parser;
+use struggle_core::{
+    games::struggle::{
+        features::FEATURE_NAMES,
+        linear::{self_play_examples, LearnedLinearHeuristic, TrainingOptions},
+        win_probability::{simulate_examples, WinProbabilityModel},
+    },
+    registry::{struggle_player, BoxedStrugglePlayer},
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+/// Fits a linear evaluation to the outcomes of self-play games, see `games::struggle::linear`.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, default_value = "expectiminimax:1")]
+    red: String,
+
+    #[arg(long, default_value = "expectiminimax:1")]
+    yellow: String,
+
+    /// Games to train on. A tenth as many games are held out for validation.
+    #[arg(long, default_value_t = 10_000)]
+    games: u64,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    #[arg(long, default_value_t = 10)]
+    epochs: usize,
+
+    #[arg(long, default_value_t = 0.01)]
+    learning_rate: f64,
+
+    /// Fits the calibrated win-probability model instead, see `games::struggle::win_probability`
+    #[arg(long)]
+    win_probability: bool,
+
+    /// Writes the weights as JSON to this file
+    #[arg(long)]
+    out: Option<String>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let red = struggle_player(&args.red)?;
+    let yellow = struggle_player(&args.yellow)?;
+
+    let options = TrainingOptions {
+        epochs: args.epochs,
+        learning_rate: args.learning_rate,
+        seed: args.seed,
+        ..Default::default()
+    };
+
+    if args.win_probability {
+        return train_win_probability(&args, &red, &yellow, &options);
+    }
+
+    let training = self_play_examples(&red, &yellow, args.games, args.seed);
+    let validation = self_play_examples(
+        &red,
+        &yellow,
+        (args.games / 10).max(1),
+        args.seed.wrapping_add(args.games),
+    );
+
+    println!(
+        "{} training and {} validation positions",
+        training.len(),
+        validation.len()
+    );
+
+    let heuristic = LearnedLinearHeuristic::train(&training, &options);
+
+    for (name, weight) in heuristic.named_weights() {
+        println!("{:>20}: {:.4}", name, weight);
+    }
+
+    println!(
+        "log loss: {:.4} training, {:.4} validation",
+        heuristic.log_loss(&training),
+        heuristic.log_loss(&validation)
+    );
+
+    if let Some(out) = &args.out {
+        std::fs::write(out, serde_json::to_string_pretty(&heuristic)?)?;
+        println!("Wrote the weights to {}", out);
+    }
+
+    Ok(())
+}
+
+fn train_win_probability(
+    args: &Args,
+    red: &BoxedStrugglePlayer,
+    yellow: &BoxedStrugglePlayer,
+    options: &TrainingOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let training = simulate_examples(red, yellow, args.games, args.seed);
+    let validation = simulate_examples(
+        red,
+        yellow,
+        (args.games / 10).max(1),
+        args.seed.wrapping_add(args.games),
+    );
+
+    println!(
+        "{} training and {} validation positions",
+        training.len(),
+        validation.len()
+    );
+
+    let model = WinProbabilityModel::train(&training, options);
+
+    for (name, weight) in FEATURE_NAMES.into_iter().zip(model.weights) {
+        println!("{:>20}: {:.4}", name, weight);
+    }
+    println!("{:>20}: {:.4}", "tempo", model.tempo);
+
+    println!(
+        "log loss: {:.4} training, {:.4} validation",
+        model.log_loss(&training),
+        model.log_loss(&validation)
+    );
+    println!(
+        "Brier score: {:.4} validation",
+        model.brier_score(&validation)
+    );
+
+    println!("Calibration on the validation positions:");
+    for bin in model.calibration(&validation, 10) {
+        println!(
+            "  predicted {:>5.1}%, observed {:>5.1}% ({} positions)",
+            bin.predicted * 100.0,
+            bin.observed * 100.0,
+            bin.count
+        );
+    }
+
+    if let Some(out) = &args.out {
+        std::fs::write(out, serde_json::to_string_pretty(&model)?)?;
+        println!("Wrote the model to {}", out);
+    }
+
+    Ok(())
+}
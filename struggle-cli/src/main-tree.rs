@@ -0,0 +1,71 @@
+use clap::Parser;
+use rand::{rngs::SmallRng, SeedableRng};
+use struggle_core::games::struggle::{
+    distill::teacher,
+    notation::{parse_position, START_POSITION},
+    players::GameContext,
+};
+
+/// Writes the tree expectiminimax explores for a single decision as Graphviz DOT, to see why it
+/// prefers the move it does. Render it with e.g. `dot -Tsvg out/tree.dot -o out/tree.svg`.
+#[derive(Parser)]
+struct Args {
+    /// The position in `notation` form
+    #[arg(long, default_value = START_POSITION)]
+    position: String,
+
+    #[arg(long, default_value_t = 6)]
+    dice: u8,
+
+    #[arg(long, default_value_t = 1)]
+    depth: u8,
+
+    /// Nodes to record before the rest of the tree is left out
+    #[arg(long, default_value_t = 2_000)]
+    limit: usize,
+
+    #[arg(long, default_value = "out/tree.dot")]
+    out: String,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    if !(1..=6).contains(&args.dice) {
+        return Err(format!("Invalid roll {}", args.dice).into());
+    }
+
+    let (board, to_move) = parse_position(&args.position)?;
+    let (a, b) = board.players();
+    let ctx = GameContext {
+        current_player: to_move,
+        other_player: if to_move == a { b } else { a },
+        dice: args.dice,
+    };
+    let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+    let tree = teacher(args.depth).search_tree(
+        &ctx,
+        &board,
+        &moves,
+        args.limit,
+        &mut SmallRng::seed_from_u64(0),
+    );
+
+    if let Some(parent) = std::path::Path::new(&args.out).parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::write(&args.out, tree.to_dot())?;
+
+    println!(
+        "Wrote {} nodes to {} ({} omitted)",
+        tree.nodes.len(),
+        args.out,
+        tree.omitted
+    );
+
+    Ok(())
+}
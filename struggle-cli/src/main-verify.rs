@@ -0,0 +1,41 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use struggle_core::games::struggle::game_notation::NotatedGame;
+
+/// Replays games in the text notation and checks them against their recorded hash chain, to find
+/// stored games that no longer play out the same way under the current rules.
+#[derive(Parser)]
+struct Args {
+    /// Game files to verify
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
+}
+
+pub fn main() -> Result<(), Box<dyn std::error::Error>> {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+    let mut failed = 0;
+
+    for path in &args.files {
+        let result = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| NotatedGame::parse(&text))
+            .and_then(|game| game.verify());
+
+        match result {
+            Ok(_) => println!("{}: ok", path.display()),
+            Err(err) => {
+                println!("{}: {}", path.display(), err);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} games failed to verify", failed, args.files.len()).into());
+    }
+
+    Ok(())
+}
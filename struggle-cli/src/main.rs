@@ -0,0 +1,590 @@
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use indicatif::ProgressBar;
+use itertools::Itertools;
+use plotters::prelude::*;
+use rayon::prelude::*;
+use struggle_core::{
+    analysis::{
+        average_eats, average_trajectory, goal_filling, luck_breakdown, move_distribution,
+        piece_survival, share_with_choice, share_with_moves, wilson_score, Luck, MeanPieceCounts,
+        Summary,
+    },
+    game::{
+        simulate_batch_with_progress, BatchConfig, CreateGame, GameStats, IntoGameStats,
+        NamedPlayer, ResetGame, WinCounts,
+    },
+    games::{
+        struggle::{notation::parse_position, players::StrugglePlayer, PlayerColor, StruggleGame},
+        twist::{players::TwistPlayer, TwistGame},
+    },
+    palette::Palette,
+    registry::{struggle_player, twist_player},
+    render::{save_png, to_plotters_color, to_svg},
+};
+
+#[global_allocator]
+static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
+
+pub fn compare_players_detailed<
+    const MAX_MOVES: usize,
+    G: CreateGame + IntoGameStats<MAX_MOVES> + ResetGame + Send,
+>(
+    a: (G::PlayerId, G::PlayerA),
+    b: (G::PlayerId, G::PlayerB),
+    rounds: u32,
+    config: &BatchConfig,
+    svg_path: &Path,
+) {
+    println!("{} ({:?}) vs {} ({:?})", a.1.name(), a.0, b.1.name(), b.0);
+
+    let start_time = std::time::Instant::now();
+
+    let progress = ProgressBar::new(rounds as u64);
+
+    // The piece survival and the trajectory chart need them
+    let config = BatchConfig {
+        timelines: true,
+        ..config.clone()
+    };
+
+    let results: Vec<(G::PlayerId, GameStats<MAX_MOVES>)> = simulate_batch_with_progress(
+        || G::create_game(a.clone(), b.clone(), true),
+        rounds as u64,
+        &config,
+        |games| progress.inc(games),
+    );
+
+    progress.finish();
+    print_elapsed(start_time, rounds);
+
+    let drawing_area = SVGBackend::new(svg_path, (1500, 2250)).into_drawing_area();
+    drawing_area.fill(&WHITE).unwrap();
+
+    let (upper, rest) = drawing_area.split_vertically(750);
+    let (lower, bottom) = rest.split_vertically(750);
+
+    let (lower_left, lower_right) = lower.split_horizontally(750);
+    let (bottom_left, bottom_right) = bottom.split_horizontally(750);
+
+    let total_games = results.len();
+    let (winners, stats): (Vec<_>, Vec<_>) = results.into_iter().unzip();
+
+    let turns = stats.iter().map(|stats| stats.turns as u32).collect_vec();
+    let (&min_turns, &max_turns) = turns.iter().minmax().into_option().unwrap();
+    let turn_counts = turns.iter().counts();
+    let most_common_turn = turn_counts.values().copied().max().unwrap() as u32;
+
+    let average_eats_per_player = average_eats(&stats);
+
+    println!(
+        "average pieces eaten: {} vs {}",
+        average_eats_per_player[0], average_eats_per_player[1]
+    );
+
+    let mut ctx = ChartBuilder::on(&upper)
+        .set_label_area_size(LabelAreaPosition::Left, 40)
+        .set_label_area_size(LabelAreaPosition::Bottom, 40)
+        .caption(
+            format!(
+                "Game length distribution: {} vs {} (n={})",
+                a.1.name(),
+                b.1.name(),
+                total_games
+            ),
+            ("Source Sans Pro, sans-serif", 20),
+        )
+        .build_cartesian_2d(
+            ((min_turns - 2)..(max_turns + 2)).into_segmented(),
+            0..(most_common_turn + 5),
+        )
+        .unwrap();
+
+    ctx.configure_mesh().draw().unwrap();
+
+    let bar_color = to_plotters_color(Palette::from_env().chart);
+
+    ctx.draw_series((min_turns..=max_turns).map(|i| {
+        let count = *turn_counts.get(&i).unwrap_or(&0);
+        let x0 = SegmentValue::Exact(i);
+        let x1 = SegmentValue::Exact(i + 1);
+        Rectangle::new([(x0, 0), (x1, count as u32)], bar_color.filled())
+    }))
+    .unwrap();
+
+    let total_a_wins: usize = winners
+        .par_iter()
+        .fold(
+            || 0,
+            |acc, winner| {
+                if *winner == a.0 {
+                    acc + 1
+                } else {
+                    acc
+                }
+            },
+        )
+        .sum();
+
+    let total_b_wins = total_games - total_a_wins;
+
+    let a_b_win_ratio = total_a_wins as f64 / total_games as f64;
+
+    println!(
+        "{} games, player A won {}, player B won {}",
+        total_games, total_a_wins, total_b_wins
+    );
+
+    let confidence_interval = wilson_score(a_b_win_ratio, total_games as u64);
+    println!(
+        "p(a_wins) = {:.3} (p95 [{:.4}, {:.4}])",
+        a_b_win_ratio, confidence_interval.0, confidence_interval.1
+    );
+
+    for luck in Luck::ALL {
+        let a_won = winners.iter().map(|winner| *winner == a.0);
+
+        for bucket in luck_breakdown(a_won.zip(&stats), luck) {
+            if bucket.games == 0 {
+                continue;
+            }
+
+            let (win_rate, (low, high)) = bucket.win_rate();
+            let comparison = match bucket.luck {
+                Ordering::Less => "fewer",
+                Ordering::Equal => "as many",
+                Ordering::Greater => "more",
+            };
+
+            println!(
+                "{} wins {:.1}% (p95 [{:.1}, {:.1}]) of the {} games with {} {}",
+                a.1.name(),
+                win_rate * 100.0,
+                low * 100.0,
+                high * 100.0,
+                bucket.games,
+                comparison,
+                luck
+            );
+        }
+    }
+
+    for (player, name) in [a.1.name(), b.1.name()].iter().enumerate() {
+        let survival = piece_survival(stats.iter().flat_map(|stats| &stats.pieces[player]));
+
+        if survival.pieces == 0 {
+            continue;
+        }
+
+        println!(
+            "{}: pieces last {:.1} turns on the board, {:.1}% are eaten and {:.1}% reach the goal, {:.1}% survive 10 turns and {:.1}% 30 turns",
+            name,
+            survival.mean_lifetime,
+            survival.eaten as f64 / survival.pieces as f64 * 100.0,
+            survival.reached_goal as f64 / survival.pieces as f64 * 100.0,
+            survival.survival_at(10) * 100.0,
+            survival.survival_at(30) * 100.0
+        );
+    }
+
+    for (player, name) in [a.1.name(), b.1.name()].iter().enumerate() {
+        let filling = goal_filling(&stats, player);
+
+        println!("{}'s goal slots, from the entrance inwards:", name);
+
+        for (slot, (filled, mean_turn)) in filling.filled.iter().zip(filling.mean_turn).enumerate()
+        {
+            println!(
+                "  slot {}: filled in {:.1}% of games on turn {:.1} on average, {:.1}% / {:.1}% / {:.1}% / {:.1}% of the time as the 1st / 2nd / 3rd / 4th",
+                slot + 1,
+                *filled as f64 / filling.games as f64 * 100.0,
+                mean_turn,
+                filling.share_filled_as(slot, 0) * 100.0,
+                filling.share_filled_as(slot, 1) * 100.0,
+                filling.share_filled_as(slot, 2) * 100.0,
+                filling.share_filled_as(slot, 3) * 100.0
+            );
+        }
+    }
+
+    let length = Summary::of(turns.iter().map(|&turns| turns as f64)).unwrap();
+
+    println!(
+        "average game length: {:.1} ± {:.1} ({}..{})",
+        length.mean, length.std_dev, length.min, length.max
+    );
+
+    let move_distribution = move_distribution(&stats);
+
+    draw_move_distribution_histogram(&move_distribution[0], lower_left, "A", &a.1.name());
+    draw_move_distribution_histogram(&move_distribution[1], lower_right, "B", &b.1.name());
+
+    // Most games are over by then, and the rest would only make the chart harder to read
+    let horizon = turns
+        .iter()
+        .copied()
+        .sorted()
+        .nth(turns.len() * 19 / 20)
+        .unwrap() as usize;
+    let trajectory = average_trajectory(&stats, horizon);
+
+    draw_trajectory(&trajectory, 0, bottom_left, "A", &a.1.name());
+    draw_trajectory(&trajectory, 1, bottom_right, "B", &b.1.name());
+
+    let choice_percentage_a = share_with_choice(&move_distribution[0]) * 100.0;
+    let choice_percentage_b = share_with_choice(&move_distribution[1]) * 100.0;
+
+    println!(
+        "{}: {:.1}% of turns had more than 1 option",
+        a.1.name(),
+        choice_percentage_a
+    );
+
+    let four_choices_percentage_a = share_with_moves(&move_distribution[0], 4) * 100.0;
+
+    println!(
+        "{}: {:.1}% of turns had more than 1 option",
+        b.1.name(),
+        choice_percentage_b
+    );
+
+    println!(
+        "{}: {:.1}% of turns had 4 options",
+        a.1.name(),
+        four_choices_percentage_a
+    );
+
+    let evals_per_turn: f64 = stats
+        .iter()
+        .map(|s| s.expectiminimax_evals[0] as f64 / s.turns_per_player[0] as f64)
+        .sum();
+
+    println!(
+        "A's average expectiminimax evaluations per turn: {:.1}",
+        evals_per_turn / total_games as f64
+    );
+}
+
+fn draw_move_distribution_histogram<const MAX_MOVES: usize>(
+    distribution: &[u64; MAX_MOVES],
+    drawing_area: DrawingArea<SVGBackend, plotters::coord::Shift>,
+    player_id: &'static str,
+    player_name: &str,
+) {
+    let total_moves = distribution.iter().copied().sum::<u64>();
+    let most_common_number_of_moves = distribution.iter().copied().max().unwrap();
+
+    let mut chart = ChartBuilder::on(&drawing_area)
+        .set_label_area_size(LabelAreaPosition::Left, 40)
+        .set_label_area_size(LabelAreaPosition::Bottom, 40)
+        .margin(4)
+        .caption(
+            format!("Player {} ({}) number of choices", player_name, player_id,),
+            ("Source Sans Pro, sans-serif", 20),
+        )
+        .build_cartesian_2d(
+            (0..MAX_MOVES - 1).into_segmented(),
+            0..((most_common_number_of_moves as f32 * 1.05) as u64),
+        )
+        .unwrap();
+
+    chart
+        .configure_mesh()
+        .x_label_formatter(&|coord| match coord {
+            SegmentValue::Exact(_) => todo!(),
+            SegmentValue::CenterOf(n) => format!("{}", n + 1),
+            SegmentValue::Last => todo!(),
+        })
+        .y_label_formatter(&|coord| format!("{:.1}%", (*coord as f32 / total_moves as f32) * 100.0))
+        .draw()
+        .unwrap();
+
+    chart
+        .draw_series((0..MAX_MOVES).map(|i| {
+            let count = distribution[i];
+            let x0 = SegmentValue::Exact(i);
+            let x1 = SegmentValue::Exact(i + 1);
+            let mut bar = Rectangle::new([(x0, 0), (x1, count)], MAGENTA.filled());
+            bar.set_margin(0, 0, 1, 1);
+            bar
+        }))
+        .unwrap();
+}
+
+/// Reads one of the means off a turn of the trajectory.
+type PieceCountAccessor = fn(&MeanPieceCounts) -> f64;
+
+fn draw_trajectory(
+    trajectory: &[[MeanPieceCounts; 2]],
+    player: usize,
+    drawing_area: DrawingArea<SVGBackend, plotters::coord::Shift>,
+    player_id: &'static str,
+    player_name: &str,
+) {
+    let mut chart = ChartBuilder::on(&drawing_area)
+        .set_label_area_size(LabelAreaPosition::Left, 40)
+        .set_label_area_size(LabelAreaPosition::Bottom, 40)
+        .margin(4)
+        .caption(
+            format!("Player {} ({}) pieces by turn", player_name, player_id),
+            ("Source Sans Pro, sans-serif", 20),
+        )
+        .build_cartesian_2d(0..trajectory.len().max(1), 0.0..4.0)
+        .unwrap();
+
+    chart.configure_mesh().draw().unwrap();
+
+    let series: [(&str, PieceCountAccessor, RGBColor); 3] = [
+        ("home", |counts| counts.home, BLUE),
+        ("board", |counts| counts.board, MAGENTA),
+        ("goal", |counts| counts.goal, GREEN),
+    ];
+
+    for (label, value, color) in series {
+        chart
+            .draw_series(LineSeries::new(
+                trajectory
+                    .iter()
+                    .enumerate()
+                    .map(|(turn, counts)| (turn, value(&counts[player]))),
+                color.stroke_width(2),
+            ))
+            .unwrap()
+            .label(label)
+            .legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(2)));
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.filled())
+        .border_style(BLACK)
+        .draw()
+        .unwrap();
+}
+
+/// Like `compare_players_detailed`, but only counts the wins, which is faster and leaves out the
+/// charts.
+pub fn compare_players_wins<G: CreateGame + ResetGame + Send>(
+    a: (G::PlayerId, G::PlayerA),
+    b: (G::PlayerId, G::PlayerB),
+    rounds: u32,
+    config: &BatchConfig,
+) {
+    println!("{} ({:?}) vs {} ({:?})", a.1.name(), a.0, b.1.name(), b.0);
+
+    let start_time = std::time::Instant::now();
+
+    let progress = ProgressBar::new(rounds as u64);
+
+    let wins: WinCounts<G::PlayerId> = simulate_batch_with_progress(
+        || G::create_game(a.clone(), b.clone(), false),
+        rounds as u64,
+        config,
+        |games| progress.inc(games),
+    );
+
+    progress.finish();
+    print_elapsed(start_time, rounds);
+
+    let a_wins = wins.wins(&a.0);
+    let a_b_win_ratio = a_wins as f64 / wins.games as f64;
+    let confidence_interval = wilson_score(a_b_win_ratio, wins.games);
+
+    println!(
+        "{} games, player A won {}, player B won {}",
+        wins.games,
+        a_wins,
+        wins.wins(&b.0)
+    );
+    println!(
+        "p(a_wins) = {:.3} (p95 [{:.4}, {:.4}])",
+        a_b_win_ratio, confidence_interval.0, confidence_interval.1
+    );
+}
+
+fn print_elapsed(start_time: std::time::Instant, rounds: u32) {
+    let elapsed = start_time.elapsed();
+
+    println!(
+        "Finished {} rounds in {}.{:03}s ({} μs per round)",
+        rounds,
+        elapsed.as_secs(),
+        elapsed.subsec_millis(),
+        elapsed.as_micros() / rounds.max(1) as u128
+    );
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GameKind {
+    Struggle,
+    Twist,
+}
+
+/// Simulates games between AI players.
+#[derive(Parser)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Plays player A against player B and reports how they did, e.g.
+    /// `struggle-sim compare --a expectiminimax:2 --b random --rounds 100000`.
+    Compare(CompareArgs),
+    /// Draws a position to an image, e.g.
+    /// `struggle-sim render "r6y20 r:3:---- y:2:x--- y" --out position.png`.
+    Render(RenderArgs),
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    #[arg(long, value_enum, default_value = "struggle")]
+    game: GameKind,
+
+    /// Player A, who plays Red, e.g. `expectiminimax:2`. See `registry` for the names.
+    #[arg(long)]
+    a: String,
+
+    /// Player B, who plays Yellow, e.g. `random`.
+    #[arg(long)]
+    b: String,
+
+    #[arg(long, default_value_t = 100_000)]
+    rounds: u32,
+
+    /// Where to draw the charts. Defaults to out/struggle.svg or out/twist.svg.
+    #[arg(long)]
+    out: Option<PathBuf>,
+
+    /// Only count the wins, without collecting the stats of every game or drawing the charts.
+    #[arg(long)]
+    no_stats: bool,
+
+    /// Game `n` is played with the seed of `GameId::new(seed, n)`, for reproducible runs.
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// The position in the engine notation, see `struggle_core::games::struggle::notation`.
+    notation: String,
+
+    /// Where to write the image. A `.png` path is drawn as PNG, anything else as SVG.
+    #[arg(long, default_value = "out/position.svg")]
+    out: PathBuf,
+}
+
+fn compare_struggle_players(
+    a: impl StrugglePlayer + 'static,
+    b: impl StrugglePlayer + 'static,
+    args: &CompareArgs,
+    config: &BatchConfig,
+    svg_path: &Path,
+) {
+    let a = (PlayerColor::Red, a);
+    let b = (PlayerColor::Yellow, b);
+
+    if args.no_stats {
+        compare_players_wins::<StruggleGame<_, _>>(a, b, args.rounds, config);
+    } else {
+        // It is a current unfortunate limitation of associated consts / const generics that we have to provde MAX_MOVES here :(
+        compare_players_detailed::<4, StruggleGame<_, _>>(a, b, args.rounds, config, svg_path);
+    }
+}
+
+fn compare_twist_players(
+    a: impl TwistPlayer + 'static,
+    b: impl TwistPlayer + 'static,
+    args: &CompareArgs,
+    config: &BatchConfig,
+    svg_path: &Path,
+) {
+    let a = (PlayerColor::Red, a);
+    let b = (PlayerColor::Yellow, b);
+
+    if args.no_stats {
+        compare_players_wins::<TwistGame<_, _>>(a, b, args.rounds, config);
+    } else {
+        // It is a current unfortunate limitation of associated consts / const generics that we have to provde MAX_MOVES here :(
+        compare_players_detailed::<25, TwistGame<_, _>>(a, b, args.rounds, config, svg_path);
+    }
+}
+
+fn compare(args: &CompareArgs) -> Result<(), String> {
+    let config = BatchConfig {
+        seed: args.seed,
+        ..BatchConfig::default()
+    };
+
+    let svg_path = args.out.clone().unwrap_or_else(|| match args.game {
+        GameKind::Struggle => PathBuf::from("out/struggle.svg"),
+        GameKind::Twist => PathBuf::from("out/twist.svg"),
+    });
+
+    if let Some(directory) = svg_path.parent() {
+        std::fs::create_dir_all(directory)
+            .map_err(|err| format!("Could not create {}: {}", directory.display(), err))?;
+    }
+
+    match args.game {
+        GameKind::Struggle => compare_struggle_players(
+            struggle_player(&args.a)?,
+            struggle_player(&args.b)?,
+            args,
+            &config,
+            &svg_path,
+        ),
+        GameKind::Twist => compare_twist_players(
+            twist_player(&args.a)?,
+            twist_player(&args.b)?,
+            args,
+            &config,
+            &svg_path,
+        ),
+    }
+
+    Ok(())
+}
+
+fn render(args: &RenderArgs) -> Result<(), String> {
+    let (board, _) = parse_position(&args.notation)?;
+    let palette = Palette::from_env();
+
+    if let Some(directory) = args.out.parent() {
+        std::fs::create_dir_all(directory)
+            .map_err(|err| format!("Could not create {}: {}", directory.display(), err))?;
+    }
+
+    if args
+        .out
+        .extension()
+        .is_some_and(|extension| extension == "png")
+    {
+        save_png(&board, &palette, &args.out)
+    } else {
+        std::fs::write(&args.out, to_svg(&board, &palette)).map_err(Into::into)
+    }
+    .map_err(|err| format!("Could not write {}: {}", args.out.display(), err))
+}
+
+pub fn main() {
+    struggle_core::logging::init();
+
+    let cli = Cli::parse();
+
+    let result = match &cli.command {
+        Command::Compare(args) => compare(args),
+        Command::Render(args) => render(args),
+    };
+
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    }
+}
@@ -0,0 +1,156 @@
+//! Benchmarks of the board, the bitboard, the heuristic and the search on fixed positions. Run
+//! with `cargo bench --bench struggle`, and compare against a baseline with
+//! `cargo bench -- --save-baseline before` and `cargo bench -- --baseline before`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::SmallRng, SeedableRng};
+use struggle_core::{
+    game::play_game_with_rng,
+    games::struggle::{
+        bitboard::BitBoard,
+        board::{Board, StruggleMove},
+        players::{default_heuristic, GameContext, GameTreePlayer, RandomPlayer},
+        zobrist, AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+};
+
+const RED: PlayerColor = PlayerColor::Red;
+const YELLOW: PlayerColor = PlayerColor::Yellow;
+
+/// A midgame position where both players have pieces on the board, in their goal and at home.
+fn midgame() -> Board {
+    let mut board = Board::new(RED, YELLOW);
+
+    board.tiles[2] = Some(RED);
+    board.tiles[9] = Some(YELLOW);
+    board.tiles[17] = Some(RED);
+    board.tiles[24] = Some(YELLOW);
+    board.goals[RED as usize][3] = Some(RED);
+    board.goals[YELLOW as usize][2] = Some(YELLOW);
+    board.home_bases[RED as usize].pieces_waiting = 1;
+    board.home_bases[YELLOW as usize].pieces_waiting = 1;
+    board.update_piece_cache();
+
+    board
+}
+
+fn board(c: &mut Criterion) {
+    let board = midgame();
+
+    c.bench_function("get_moves", |b| {
+        b.iter(|| {
+            for dice in 1..=6 {
+                black_box(black_box(&board).get_moves(dice, RED, YELLOW));
+            }
+        })
+    });
+
+    let mov = StruggleMove::MovePiece {
+        from: 17,
+        to: 20,
+        eats: false,
+    };
+
+    c.bench_function("perform_move", |b| {
+        b.iter_batched_ref(
+            || board.clone(),
+            |board| board.perform_move(RED, black_box(&mov)),
+            criterion::BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("make_move and unmake_move", |b| {
+        let mut board = board.clone();
+
+        b.iter(|| {
+            let undo = board.make_move(RED, black_box(&mov));
+            board.unmake_move(undo);
+        })
+    });
+
+    // The search keys its table with the incrementally updated hash, whose upkeep is part of
+    // `perform_move`. Hashing from scratch is what every node would cost without it.
+    c.bench_function("zobrist_hash", |b| {
+        b.iter(|| black_box(&board).zobrist_hash())
+    });
+
+    c.bench_function("zobrist hash from scratch", |b| {
+        b.iter(|| zobrist::hash(black_box(&board)))
+    });
+
+    let bitboard = BitBoard::from(&board);
+
+    c.bench_function("bitboard get_moves", |b| {
+        b.iter(|| {
+            for dice in 1..=6 {
+                black_box(black_box(&bitboard).get_moves(dice, RED, YELLOW));
+            }
+        })
+    });
+
+    c.bench_function("bitboard perform_move", |b| {
+        b.iter(|| black_box(&bitboard).with_move(RED, black_box(&mov)))
+    });
+
+    c.bench_function("default_heuristic", |b| {
+        b.iter(|| default_heuristic(black_box(&board), RED, YELLOW))
+    });
+}
+
+fn search(c: &mut Criterion) {
+    let board = midgame();
+    let ctx = GameContext {
+        current_player: RED,
+        other_player: YELLOW,
+        dice: 3,
+    };
+    let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+    for depth in 1..=3 {
+        c.bench_function(&format!("expectiminimax depth {}", depth), |b| {
+            let mut player = GameTreePlayer::new(default_heuristic, depth, "Bench");
+            let mut rng = SmallRng::seed_from_u64(0);
+
+            b.iter(|| player.evaluate_moves(&ctx, &board, &moves, &mut rng))
+        });
+
+        c.bench_function(&format!("expectiminimax depth {} with undo", depth), |b| {
+            let mut player = GameTreePlayer::builder(default_heuristic, "Bench")
+                .depth(depth)
+                .undo()
+                .build();
+            let mut rng = SmallRng::seed_from_u64(0);
+
+            b.iter(|| player.evaluate_moves(&ctx, &board, &moves, &mut rng))
+        });
+
+        c.bench_function(&format!("expectiminimax depth {} with arena", depth), |b| {
+            let mut player = GameTreePlayer::builder(default_heuristic, "Bench")
+                .depth(depth)
+                .arena()
+                .build();
+            let mut rng = SmallRng::seed_from_u64(0);
+
+            b.iter(|| player.evaluate_moves(&ctx, &board, &moves, &mut rng))
+        });
+    }
+}
+
+fn self_play(c: &mut Criterion) {
+    c.bench_function("random self-play", |b| {
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        b.iter(|| {
+            let mut game = StruggleGame::new(
+                AiStrugglePlayer::new(RED, RandomPlayer),
+                AiStrugglePlayer::new(YELLOW, RandomPlayer),
+                false,
+            );
+
+            play_game_with_rng(&mut game, &mut rng)
+        })
+    });
+}
+
+criterion_group!(benches, board, search, self_play);
+criterion_main!(benches);
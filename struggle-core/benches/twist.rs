@@ -0,0 +1,91 @@
+//! Benchmarks of Twist's move generation and board checks on a fixed position. Run with
+//! `cargo bench --bench twist`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use struggle_core::games::{
+    struggle::PlayerColor,
+    twist::{
+        board::{ActionDie, DieResult, SpinSection, TwistBoard},
+        get_moves::get_twist_moves,
+    },
+};
+
+const RED: PlayerColor = PlayerColor::Red;
+const YELLOW: PlayerColor = PlayerColor::Yellow;
+
+/// A midgame position where both players have pieces on the board, in their goal and at home.
+fn midgame() -> TwistBoard {
+    let mut board = TwistBoard::new((RED, YELLOW));
+
+    board.set_tile(3, Some(RED));
+    board.set_tile(12, Some(YELLOW));
+    board.set_tile(20, Some(RED));
+    board.set_tile(27, Some(YELLOW));
+    board.set_goal(RED, 2, Some(RED));
+    board.set_goal(YELLOW, 1, Some(YELLOW));
+    board.home_bases[RED as usize].pieces_waiting = 1;
+    board.home_bases[YELLOW as usize].pieces_waiting = 1;
+
+    board
+}
+
+fn moves(c: &mut Criterion) {
+    let board = midgame();
+
+    for action in [
+        ActionDie::DoNothing,
+        ActionDie::SpinSection,
+        ActionDie::RotateBoard,
+    ] {
+        c.bench_function(&format!("get_twist_moves {:?}", action), |b| {
+            b.iter(|| {
+                for number in 1..=6 {
+                    let dice = DieResult { number, action };
+                    black_box(get_twist_moves(black_box(&board), dice, RED));
+                }
+            })
+        });
+    }
+}
+
+// Equality, the winner and spin checks compare packed nibbles. The per-cell versions are here
+// for comparison, so that the packing can be judged on the same machine.
+fn checks(c: &mut Criterion) {
+    let board = midgame();
+    let other = board.clone();
+
+    c.bench_function("twist eq", |b| {
+        b.iter(|| black_box(&board) == black_box(&other))
+    });
+
+    c.bench_function("twist eq per cell", |b| {
+        b.iter(|| {
+            let (board, other) = (black_box(&board), black_box(&other));
+
+            board.tiles() == other.tiles()
+                && board.goals() == other.goals()
+                && board.home_bases == other.home_bases
+                && board.rotation == other.rotation
+        })
+    });
+
+    c.bench_function("twist get_winner", |b| {
+        b.iter(|| black_box(&board).get_winner())
+    });
+
+    c.bench_function("twist spin_is_nop", |b| {
+        b.iter(|| SpinSection::ALL.map(|section| black_box(&board).spin_is_nop(section)))
+    });
+
+    c.bench_function("twist spin_is_nop per cell", |b| {
+        b.iter(|| {
+            SpinSection::ALL.map(|section| {
+                let cells = black_box(&board).get_spin_section(section);
+                cells[0] == cells[4] && cells[1] == cells[3]
+            })
+        })
+    });
+}
+
+criterion_group!(benches, moves, checks);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+// Records the git commit for run manifests, and writes the C header for the `ffi` feature.
+fn main() {
+    // Builds from a source archive have no commit to record, see `manifest`
+    let commit = std::process::Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok());
+
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=STRUGGLE_GIT_COMMIT={}", commit.trim());
+    }
+
+    println!("cargo:rerun-if-changed=../.git/HEAD");
+    println!("cargo:rerun-if-changed=../.git/refs/heads");
+
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+
+        // Only the FFI module is parsed, everything else stays opaque to C
+        cbindgen::Builder::new()
+            .with_language(cbindgen::Language::C)
+            .with_include_guard("STRUGGLE_H")
+            .with_src(format!("{}/src/ffi.rs", crate_dir))
+            .generate()
+            .expect("Failed to generate the C header")
+            .write_to_file(format!("{}/include/struggle.h", crate_dir));
+    }
+}
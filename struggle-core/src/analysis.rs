@@ -0,0 +1,1036 @@
+//! Statistics over the results of many games, for the tools and for anyone analysing their own
+//! simulations.
+//!
+//! The intervals are 95% confidence intervals of a win rate, i.e. of the success probability of a
+//! binomial distribution. `wilson_score` is the one to use; `normal_interval` is the textbook
+//! approximation, which is only reasonable for many games and win rates far from 0 and 1.
+//!
+//! `elo_ratings` fits ratings to the results between many players. Ratings are only meaningful
+//! relative to each other, so to compare them between runs, pin one player that takes part in
+//! all of them (usually `RandomPlayer`) to a fixed rating with an `EloAnchor`.
+//!
+//! `nash_mixture` summarizes the same results as the equilibrium of the meta-game of picking a
+//! player: players that aren't worth picking get no weight, however well they do against the
+//! weak ones, and a cycle of counters shares the weight between its members.
+//!
+//! `luck_breakdown` separates skill from dice luck, by splitting the games by whether player A
+//! had less, as much or more of some luck than B, e.g. "expectiminimax(2) wins 71% even when it
+//! rolls fewer sixes".
+//!
+//! `piece_survival` turns the `PieceLife`s of many games into a survival curve of the pieces on
+//! the board, with being eaten as the only way to die: a piece that reaches its goal, or is still
+//! on the board when the game ends, only tells that it survived that long.
+//!
+//! `goal_filling` shows when and in which order the players fill their goal slots, to check
+//! whether e.g. `advance_piece_in_goal_multiplier` pushes the pieces deep into the goal early.
+//! `average_trajectory` shows the shape of a whole game: how many pieces each player has at
+//! home, on the board and in the goal as the game goes on.
+//!
+//! `DiceTally` is a self-test of the engine rather than of the players: it collects the dice of
+//! a run and checks them against the fair dice with chi-square tests, to catch a refactor that
+//! reuses an RNG or samples a die wrong.
+
+use core::{cmp::Ordering, fmt};
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{GameStats, PieceFate, PieceLife},
+    session::MatchResult,
+};
+
+const Z_95: f64 = 1.96;
+
+/// The 95% confidence interval of a win rate `p_hat` measured over `samples` games.
+pub fn wilson_score(p_hat: f64, samples: u64) -> (f64, f64) {
+    let z: f64 = Z_95;
+
+    let a = p_hat + z * z / (2.0 * samples as f64);
+    let b =
+        z * ((p_hat * (1.0 - p_hat) + z.powi(2) / (4.0 * samples as f64)) / samples as f64).sqrt();
+    let c = 1.0 + z * z / samples as f64;
+
+    ((a - b) / c, (a + b) / c)
+}
+
+/// The 95% confidence interval of a win rate from the normal approximation of the binomial
+/// distribution, clamped to [0, 1].
+pub fn normal_interval(p_hat: f64, samples: u64) -> (f64, f64) {
+    let margin = Z_95 * (p_hat * (1.0 - p_hat) / samples as f64).sqrt();
+
+    ((p_hat - margin).max(0.0), (p_hat + margin).min(1.0))
+}
+
+/// The win rate of `wins` out of `games`, and its `wilson_score` interval.
+pub fn win_rate_interval(wins: u64, games: u64) -> (f64, (f64, f64)) {
+    let p_hat = wins as f64 / games as f64;
+    (p_hat, wilson_score(p_hat, games))
+}
+
+/// The mean, standard deviation and range of some samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    /// The sample standard deviation, 0 for fewer than two samples
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    /// Summarises `samples`, or returns `None` if there aren't any.
+    pub fn of(samples: impl IntoIterator<Item = f64>) -> Option<Self> {
+        // Welford's algorithm, so that the samples don't have to be collected
+        let mut count = 0;
+        let mut mean = 0.0;
+        let mut squares = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+
+        for sample in samples {
+            count += 1;
+
+            let delta = sample - mean;
+            mean += delta / count as f64;
+            squares += delta * (sample - mean);
+
+            min = min.min(sample);
+            max = max.max(sample);
+        }
+
+        if count == 0 {
+            return None;
+        }
+
+        let std_dev = if count > 1 {
+            (squares / (count - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            count,
+            mean,
+            std_dev,
+            min,
+            max,
+        })
+    }
+
+    /// The 95% confidence interval of the mean.
+    pub fn mean_interval(&self) -> (f64, f64) {
+        let margin = Z_95 * self.std_dev / (self.count as f64).sqrt();
+        (self.mean - margin, self.mean + margin)
+    }
+}
+
+/// The rating of the average player when the ratings aren't anchored.
+pub const DEFAULT_RATING: f64 = 1500.0;
+
+/// Pins the rating of `player` to `rating`, see `elo_ratings`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EloAnchor {
+    pub player: usize,
+    pub rating: f64,
+}
+
+/// Elo ratings of `players` players fitted to the results between them, given as
+/// `(a, b, result)` where `a` and `b` are indices of the players. Games abandoned at a turn cap
+/// count as draws.
+///
+/// The ratings are the maximum likelihood Bradley-Terry strengths on the Elo scale, with a
+/// virtual draw between every pair that played, so that a player that never won still gets a
+/// finite rating. Without an anchor the ratings average to `DEFAULT_RATING`.
+pub fn elo_ratings(
+    players: usize,
+    results: &[(usize, usize, MatchResult)],
+    anchor: Option<EloAnchor>,
+) -> Vec<f64> {
+    let mut scores = vec![0.0; players];
+    let mut games = vec![vec![0.0; players]; players];
+
+    for &(a, b, result) in results {
+        let draws = result.truncated as f64 + 1.0;
+        scores[a] += result.a_wins as f64 + draws / 2.0;
+        scores[b] += result.b_wins() as f64 + draws / 2.0;
+        games[a][b] += result.games as f64 + 1.0;
+        games[b][a] += result.games as f64 + 1.0;
+    }
+
+    // Hunter's MM algorithm, which converges for any connected set of results
+    let mut strengths = vec![1.0; players];
+
+    for _ in 0..1000 {
+        let next = (0..players)
+            .map(|i| {
+                let expected = (0..players)
+                    .filter(|&j| games[i][j] > 0.0)
+                    .map(|j| games[i][j] / (strengths[i] + strengths[j]))
+                    .sum::<f64>();
+
+                if expected > 0.0 {
+                    scores[i] / expected
+                } else {
+                    strengths[i]
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let change = next
+            .iter()
+            .zip(&strengths)
+            .map(|(next, old)| (next / old).ln().abs())
+            .fold(0.0, f64::max);
+
+        strengths = next;
+
+        if change < 1e-10 {
+            break;
+        }
+    }
+
+    let ratings = strengths
+        .iter()
+        .map(|strength| 400.0 * strength.log10())
+        .collect::<Vec<_>>();
+
+    let offset = match anchor {
+        Some(anchor) => anchor.rating - ratings[anchor.player],
+        None => DEFAULT_RATING - ratings.iter().sum::<f64>() / players.max(1) as f64,
+    };
+
+    ratings.into_iter().map(|rating| rating + offset).collect()
+}
+
+/// The share of the points player `i` scored against player `j` in `results`, counting draws as
+/// half a point, given as `(a, b, result)` like in `elo_ratings`. Pairs that didn't play, and
+/// every player against itself, get 0.5.
+pub fn win_rate_matrix(players: usize, results: &[(usize, usize, MatchResult)]) -> Vec<Vec<f64>> {
+    let mut points = vec![vec![0.0; players]; players];
+    let mut games = vec![vec![0.0; players]; players];
+
+    for &(a, b, result) in results {
+        let draws = result.truncated as f64 / 2.0;
+        points[a][b] += result.a_wins as f64 + draws;
+        points[b][a] += result.b_wins() as f64 + draws;
+        games[a][b] += result.games as f64;
+        games[b][a] += result.games as f64;
+    }
+
+    (0..players)
+        .map(|i| {
+            (0..players)
+                .map(|j| {
+                    if games[i][j] > 0.0 {
+                        points[i][j] / games[i][j]
+                    } else {
+                        0.5
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The Nash equilibrium mixture over the players of the symmetric zero-sum meta-game where both
+/// sides pick a player, and `matrix[i][j]` is the share of the points `i` scores against `j`, as
+/// from `win_rate_matrix`. The weights sum to 1.
+///
+/// The equilibrium is the average strategy of multiplicative weights in self-play, which
+/// approaches it to within about a thousandth of a point for a handful of players. Weights below
+/// that are rounded to 0.
+pub fn nash_mixture(matrix: &[Vec<f64>]) -> Vec<f64> {
+    const ITERATIONS: usize = 100_000;
+    const THRESHOLD: f64 = 1e-3;
+
+    let players = matrix.len();
+
+    if players == 0 {
+        return Vec::new();
+    }
+
+    let rate = (8.0 * (players as f64).ln().max(1.0) / ITERATIONS as f64).sqrt();
+    let mut payoffs = vec![0.0; players];
+    let mut average = vec![0.0; players];
+
+    for _ in 0..ITERATIONS {
+        // Softmax of the cumulative payoffs, shifted for stability
+        let max = payoffs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let weights = payoffs
+            .iter()
+            .map(|payoff| ((payoff - max) * rate).exp())
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f64>();
+
+        for (average, weight) in average.iter_mut().zip(&weights) {
+            *average += weight / total;
+        }
+
+        for (i, payoff) in payoffs.iter_mut().enumerate() {
+            *payoff += (0..players)
+                .map(|j| (matrix[i][j] - 0.5) * weights[j] / total)
+                .sum::<f64>();
+        }
+    }
+
+    for weight in &mut average {
+        *weight /= ITERATIONS as f64;
+
+        if *weight < THRESHOLD {
+            *weight = 0.0;
+        }
+    }
+
+    let total = average.iter().sum::<f64>();
+    average.into_iter().map(|weight| weight / total).collect()
+}
+
+/// The number of turns each player had with 1, 2, … moves to choose from, over all of `stats`.
+pub fn move_distribution<'a, const MAX_MOVES: usize>(
+    stats: impl IntoIterator<Item = &'a GameStats<MAX_MOVES>>,
+) -> [[u64; MAX_MOVES]; 2] {
+    let mut distribution = [[0; MAX_MOVES]; 2];
+
+    for stats in stats {
+        for (totals, counts) in distribution.iter_mut().zip(&stats.move_distribution) {
+            for (total, &turns) in totals.iter_mut().zip(counts) {
+                *total += turns as u64;
+            }
+        }
+    }
+
+    distribution
+}
+
+/// The share of the turns in a player's `move_distribution` with `moves` moves to choose from.
+pub fn share_with_moves(distribution: &[u64], moves: usize) -> f64 {
+    let total = distribution.iter().sum::<u64>();
+    distribution[moves - 1] as f64 / total as f64
+}
+
+/// The share of the turns in a player's `move_distribution` where there was a choice to make.
+pub fn share_with_choice(distribution: &[u64]) -> f64 {
+    1.0 - share_with_moves(distribution, 1)
+}
+
+/// The average number of pieces each player ate per game over `stats`.
+pub fn average_eats<'a, const MAX_MOVES: usize>(
+    stats: impl IntoIterator<Item = &'a GameStats<MAX_MOVES>>,
+) -> [f64; 2] {
+    let mut games = 0;
+    let mut eats = [0u64; 2];
+
+    for stats in stats {
+        games += 1;
+        eats[0] += stats.pieces_eaten_by[0] as u64;
+        eats[1] += stats.pieces_eaten_by[1] as u64;
+    }
+
+    [eats[0] as f64 / games as f64, eats[1] as f64 / games as f64]
+}
+
+/// A measure of how lucky a player was in a game, from its `GameStats`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Luck {
+    /// Sixes rolled, each of which is an extra turn
+    Sixes,
+    /// Turns with a chance to eat a piece
+    EatOpportunities,
+}
+
+impl Luck {
+    pub const ALL: [Luck; 2] = [Luck::Sixes, Luck::EatOpportunities];
+
+    pub fn of<const MAX_MOVES: usize>(self, stats: &GameStats<MAX_MOVES>) -> [u16; 2] {
+        match self {
+            Luck::Sixes => stats.sixes,
+            Luck::EatOpportunities => stats.eat_opportunities,
+        }
+    }
+}
+
+impl fmt::Display for Luck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Luck::Sixes => "sixes",
+            Luck::EatOpportunities => "eat opportunities",
+        })
+    }
+}
+
+/// Player A's results in the games where it had less, as much or more of some `Luck` than B.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LuckBucket {
+    /// How A's luck compares to B's
+    pub luck: Ordering,
+    pub games: u64,
+    pub a_wins: u64,
+}
+
+impl LuckBucket {
+    /// A's win rate in these games, and its `wilson_score` interval.
+    pub fn win_rate(&self) -> (f64, (f64, f64)) {
+        win_rate_interval(self.a_wins, self.games)
+    }
+}
+
+/// Splits `games`, given as whether A won and the stats of the game, by how A's `luck` compared
+/// to B's. The buckets are for less, as much and more luck, in that order.
+pub fn luck_breakdown<'a, const MAX_MOVES: usize>(
+    games: impl IntoIterator<Item = (bool, &'a GameStats<MAX_MOVES>)>,
+    luck: Luck,
+) -> [LuckBucket; 3] {
+    let mut buckets = [Ordering::Less, Ordering::Equal, Ordering::Greater].map(|luck| LuckBucket {
+        luck,
+        games: 0,
+        a_wins: 0,
+    });
+
+    for (a_won, stats) in games {
+        let [a, b] = luck.of(stats);
+        let bucket = &mut buckets[(a.cmp(&b) as i8 + 1) as usize];
+
+        bucket.games += 1;
+        bucket.a_wins += a_won as u64;
+    }
+
+    buckets
+}
+
+/// How long pieces last on the board, see `piece_survival`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Survival {
+    pub pieces: usize,
+    pub eaten: usize,
+    pub reached_goal: usize,
+    /// The mean number of turns the pieces spent on the board, however they left it
+    pub mean_lifetime: f64,
+    /// The Kaplan-Meier estimate of the share of pieces that aren't eaten within 0, 1, 2, …
+    /// turns of entering the board
+    pub curve: Vec<f64>,
+}
+
+impl Survival {
+    /// The estimated share of pieces that aren't eaten within `turns` turns of entering the board.
+    pub fn survival_at(&self, turns: usize) -> f64 {
+        self.curve
+            .get(turns)
+            .or(self.curve.last())
+            .copied()
+            .unwrap_or(1.0)
+    }
+}
+
+/// The survival of `pieces`, e.g. those of one player over many games with
+/// `stats.iter().flat_map(|stats| &stats.pieces[0])`. The games need to be played with
+/// `BatchConfig::timelines`.
+pub fn piece_survival<'a>(pieces: impl IntoIterator<Item = &'a PieceLife>) -> Survival {
+    // The number of pieces that left the board, and that were eaten, after each number of turns
+    let mut left = Vec::<usize>::new();
+    let mut eaten = Vec::<usize>::new();
+    let mut reached_goal = 0;
+    let mut total_lifetime = 0u64;
+
+    for piece in pieces {
+        let lifetime = piece.lifetime() as usize;
+
+        if left.len() <= lifetime {
+            left.resize(lifetime + 1, 0);
+            eaten.resize(lifetime + 1, 0);
+        }
+
+        left[lifetime] += 1;
+        total_lifetime += lifetime as u64;
+
+        match piece.fate {
+            PieceFate::Eaten => eaten[lifetime] += 1,
+            PieceFate::ReachedGoal => reached_goal += 1,
+            PieceFate::OnBoard => {}
+        }
+    }
+
+    let pieces = left.iter().sum::<usize>();
+    let mut at_risk = pieces;
+    let mut survival = 1.0;
+
+    let curve = left
+        .iter()
+        .zip(&eaten)
+        .map(|(&left, &eaten)| {
+            survival *= 1.0 - eaten as f64 / at_risk as f64;
+            at_risk -= left;
+            survival
+        })
+        .collect();
+
+    Survival {
+        pieces,
+        eaten: eaten.iter().sum(),
+        reached_goal,
+        mean_lifetime: total_lifetime as f64 / pieces.max(1) as f64,
+        curve,
+    }
+}
+
+/// When and in which order a player filled its goal slots over many games, see `goal_filling`.
+/// The slots are numbered from the entrance inwards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalFilling {
+    pub games: u64,
+    /// The number of games each slot was filled by the end of
+    pub filled: [u64; 4],
+    /// The mean turn each slot was filled on, over the games it was filled in
+    pub mean_turn: [f64; 4],
+    /// `order[slot][n]` is the number of games `slot` was the `n + 1`th slot to be filled in
+    pub order: [[u64; 4]; 4],
+}
+
+impl GoalFilling {
+    /// The share of the games that `slot` was the `n + 1`th slot to be filled in.
+    pub fn share_filled_as(&self, slot: usize, n: usize) -> f64 {
+        self.order[slot][n] as f64 / self.games.max(1) as f64
+    }
+}
+
+/// The `GoalFilling` of `player` (0 for A, 1 for B) over `stats`.
+pub fn goal_filling<'a, const MAX_MOVES: usize>(
+    stats: impl IntoIterator<Item = &'a GameStats<MAX_MOVES>>,
+    player: usize,
+) -> GoalFilling {
+    let mut games = 0;
+    let mut filled = [0; 4];
+    let mut total_turns = [0u64; 4];
+    let mut order = [[0; 4]; 4];
+
+    for stats in stats {
+        games += 1;
+
+        let mut slots = stats.goal_slots[player]
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, turn)| turn.map(|turn| (turn, slot)))
+            .collect::<Vec<_>>();
+        slots.sort_unstable();
+
+        for (n, (turn, slot)) in slots.into_iter().enumerate() {
+            filled[slot] += 1;
+            total_turns[slot] += turn as u64;
+            order[slot][n] += 1;
+        }
+    }
+
+    GoalFilling {
+        games,
+        filled,
+        mean_turn: core::array::from_fn(|slot| {
+            total_turns[slot] as f64 / filled[slot].max(1) as f64
+        }),
+        order,
+    }
+}
+
+/// The mean of some `PieceCounts`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MeanPieceCounts {
+    pub home: f64,
+    pub board: f64,
+    pub goal: f64,
+}
+
+/// Where the pieces of A and B are on average after each of the first `turns` turns of the
+/// games in `stats`. Games that have already ended count with their final positions, so that
+/// the series show the course of all of the games rather than of the longest ones. The games
+/// need to be played with `BatchConfig::timelines`, others are left out.
+pub fn average_trajectory<'a, const MAX_MOVES: usize>(
+    stats: impl IntoIterator<Item = &'a GameStats<MAX_MOVES>>,
+    turns: usize,
+) -> Vec<[MeanPieceCounts; 2]> {
+    let mut games = 0;
+    let mut totals = vec![[[0u64; 3]; 2]; turns];
+
+    for stats in stats {
+        let Some(last) = stats.trajectory.last() else {
+            continue;
+        };
+
+        games += 1;
+
+        for (turn, total) in totals.iter_mut().enumerate() {
+            let counts = stats.trajectory.get(turn).unwrap_or(last);
+
+            for (total, counts) in total.iter_mut().zip(counts) {
+                total[0] += counts.home as u64;
+                total[1] += counts.board as u64;
+                total[2] += counts.goal as u64;
+            }
+        }
+    }
+
+    if games == 0 {
+        return Vec::new();
+    }
+
+    let mean = |total: [u64; 3]| MeanPieceCounts {
+        home: total[0] as f64 / games as f64,
+        board: total[1] as f64 / games as f64,
+        goal: total[2] as f64 / games as f64,
+    };
+
+    totals
+        .into_iter()
+        .map(|[a, b]| [mean(a), mean(b)])
+        .collect()
+}
+
+/// The p-value below which a `DiceCheck` fails. Runs make a few checks each, and a fair die still
+/// fails one in a thousand, so a failure is worth a rerun with another seed before a bug hunt.
+pub const DICE_SIGNIFICANCE: f64 = 0.001;
+
+const ACTION_DIE_PROBABILITIES: [f64; 3] = [3.0 / 6.0, 2.0 / 6.0, 1.0 / 6.0];
+
+/// The dice both players rolled over a run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiceTally {
+    /// How many times A and B rolled each number, from 1 to 6
+    pub numbers: [[u64; 6]; 2],
+    /// How many times A and B rolled each face of Twist's action die, see `GameStats`
+    pub actions: [[u64; 3]; 2],
+}
+
+impl DiceTally {
+    pub fn add<const MAX_MOVES: usize>(&mut self, stats: &GameStats<MAX_MOVES>) {
+        for player in 0..2 {
+            for (total, count) in self.numbers[player].iter_mut().zip(stats.dice[player]) {
+                *total += count as u64;
+            }
+
+            for (total, count) in self.actions[player]
+                .iter_mut()
+                .zip(stats.action_dice[player])
+            {
+                *total += count as u64;
+            }
+        }
+    }
+
+    /// Chi-square tests of each player's dice. The action die is only tested if it was rolled,
+    /// i.e. for Twist.
+    pub fn checks(&self) -> Vec<DiceCheck> {
+        let mut checks = Vec::new();
+
+        for player in 0..2 {
+            checks.push(DiceCheck::new(
+                "number",
+                player,
+                &self.numbers[player],
+                &[1.0 / 6.0; 6],
+            ));
+
+            if self.actions[player].iter().sum::<u64>() > 0 {
+                checks.push(DiceCheck::new(
+                    "action",
+                    player,
+                    &self.actions[player],
+                    &ACTION_DIE_PROBABILITIES,
+                ));
+            }
+        }
+
+        checks
+    }
+}
+
+/// A chi-square goodness of fit test of the rolls of one die.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DiceCheck {
+    /// `number` or `action`
+    pub die: String,
+    /// 0 for A, 1 for B
+    pub player: usize,
+    pub counts: Vec<u64>,
+    pub chi_square: f64,
+    /// The probability of a fair die rolling counts at least this far from the expected ones
+    pub p_value: f64,
+}
+
+impl DiceCheck {
+    fn new(die: &str, player: usize, counts: &[u64], probabilities: &[f64]) -> Self {
+        let (chi_square, p_value) = chi_square_test(counts, probabilities);
+
+        Self {
+            die: die.to_string(),
+            player,
+            counts: counts.to_vec(),
+            chi_square,
+            p_value,
+        }
+    }
+
+    pub fn passed(&self) -> bool {
+        self.p_value >= DICE_SIGNIFICANCE
+    }
+}
+
+/// The chi-square statistic of `counts` against the outcome `probabilities`, and its p-value.
+/// Without any counts there is nothing to reject, so the p-value is 1.
+pub fn chi_square_test(counts: &[u64], probabilities: &[f64]) -> (f64, f64) {
+    let total = counts.iter().sum::<u64>() as f64;
+
+    if total == 0.0 {
+        return (0.0, 1.0);
+    }
+
+    let chi_square = counts
+        .iter()
+        .zip(probabilities)
+        .map(|(&count, &probability)| {
+            let expected = total * probability;
+            (count as f64 - expected).powi(2) / expected
+        })
+        .sum::<f64>();
+
+    let degrees_of_freedom = (counts.len() - 1) as f64;
+
+    (
+        chi_square,
+        upper_incomplete_gamma(degrees_of_freedom / 2.0, chi_square / 2.0),
+    )
+}
+
+// The regularized upper incomplete gamma function Q(a, x), from its series below a + 1 and from
+// its continued fraction above, as in Numerical Recipes
+fn upper_incomplete_gamma(a: f64, x: f64) -> f64 {
+    const ITERATIONS: usize = 200;
+    const EPSILON: f64 = 1e-14;
+
+    if x <= 0.0 {
+        return 1.0;
+    }
+
+    let log_prefactor = a * x.ln() - x - ln_gamma(a);
+
+    if x < a + 1.0 {
+        let mut term = 1.0 / a;
+        let mut sum = term;
+
+        for n in 1..ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+
+            if term.abs() < sum.abs() * EPSILON {
+                break;
+            }
+        }
+
+        1.0 - sum * log_prefactor.exp()
+    } else {
+        // Lentz's method
+        let tiny = f64::MIN_POSITIVE / EPSILON;
+        let mut b = x + 1.0 - a;
+        let mut c = 1.0 / tiny;
+        let mut d = 1.0 / b;
+        let mut fraction = d;
+
+        for n in 1..ITERATIONS {
+            let an = -(n as f64) * (n as f64 - a);
+            b += 2.0;
+            d = an * d + b;
+            if d.abs() < tiny {
+                d = tiny;
+            }
+            c = b + an / c;
+            if c.abs() < tiny {
+                c = tiny;
+            }
+            d = 1.0 / d;
+            let delta = d * c;
+            fraction *= delta;
+
+            if (delta - 1.0).abs() < EPSILON {
+                break;
+            }
+        }
+
+        fraction * log_prefactor.exp()
+    }
+}
+
+// Lanczos' approximation, good to about 15 digits for positive x
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .fold(1.000_000_000_190_015, |series, (i, coefficient)| {
+            series + coefficient / (x + 1.0 + i as f64)
+        });
+
+    -tmp + (2.506_628_274_631 * series / x).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::PieceCounts;
+
+    #[test]
+    fn intervals_contain_the_estimate() {
+        for (p_hat, samples) in [(0.5, 100), (0.9, 1000), (0.0, 10)] {
+            let (low, high) = wilson_score(p_hat, samples);
+            assert!(low <= p_hat && p_hat <= high);
+            assert!(low >= 0.0 && high <= 1.0);
+
+            let (low, high) = normal_interval(p_hat, samples);
+            assert!(low <= p_hat && p_hat <= high);
+        }
+
+        // A fair coin over 100 tosses: the textbook ±9.8%
+        let (low, high) = normal_interval(0.5, 100);
+        assert!((high - low - 0.196).abs() < 1e-9);
+
+        let (p_hat, interval) = win_rate_interval(30, 60);
+        assert_eq!(p_hat, 0.5);
+        assert_eq!(interval, wilson_score(0.5, 60));
+    }
+
+    #[test]
+    fn summarises_samples() {
+        let summary = Summary::of([2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+
+        assert_eq!(summary.count, 8);
+        assert_eq!(summary.mean, 5.0);
+        assert!((summary.std_dev - (32.0f64 / 7.0).sqrt()).abs() < 1e-12);
+        assert_eq!((summary.min, summary.max), (2.0, 9.0));
+
+        assert_eq!(Summary::of([]), None);
+        assert_eq!(Summary::of([3.0]).unwrap().std_dev, 0.0);
+    }
+
+    #[test]
+    fn anchors_elo_ratings() {
+        let result = |games, a_wins| MatchResult {
+            games,
+            a_wins,
+            truncated: 0,
+        };
+        let results = [
+            (0, 1, result(100, 90)),
+            (1, 2, result(100, 75)),
+            (0, 2, result(100, 95)),
+        ];
+
+        let ratings = elo_ratings(3, &results, None);
+        assert!(ratings[0] > ratings[1] && ratings[1] > ratings[2]);
+        assert!((ratings.iter().sum::<f64>() / 3.0 - DEFAULT_RATING).abs() < 1e-6);
+
+        let anchored = elo_ratings(
+            3,
+            &results,
+            Some(EloAnchor {
+                player: 2,
+                rating: 0.0,
+            }),
+        );
+        assert!(anchored[2].abs() < 1e-9);
+
+        // Anchoring only moves the scale, the differences stay the same
+        assert!((anchored[0] - anchored[1] - (ratings[0] - ratings[1])).abs() < 1e-9);
+    }
+
+    #[test]
+    fn nash_mixture_of_a_cycle_and_a_dominated_player() {
+        let result = |games, a_wins| MatchResult {
+            games,
+            a_wins,
+            truncated: 0,
+        };
+
+        // 0 beats 1, 1 beats 2, 2 beats 0, and everyone beats 3
+        let results = [
+            (0, 1, result(100, 70)),
+            (1, 2, result(100, 70)),
+            (2, 0, result(100, 70)),
+            (0, 3, result(100, 90)),
+            (1, 3, result(100, 90)),
+            (2, 3, result(100, 90)),
+        ];
+
+        let matrix = win_rate_matrix(4, &results);
+        assert_eq!(matrix[0][1], 0.7);
+        assert!((matrix[1][0] - 0.3).abs() < 1e-9);
+        assert_eq!(matrix[2][2], 0.5);
+
+        let mixture = nash_mixture(&matrix);
+        assert!((mixture.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert_eq!(mixture[3], 0.0);
+
+        for &weight in &mixture[..3] {
+            assert!((weight - 1.0 / 3.0).abs() < 0.02, "{:?}", mixture);
+        }
+    }
+
+    #[test]
+    fn splits_games_by_luck() {
+        let game = |sixes| GameStats::<4> {
+            sixes,
+            ..GameStats::new()
+        };
+        let (fewer, same, more) = (game([1, 3]), game([2, 2]), game([4, 0]));
+
+        let buckets = luck_breakdown(
+            [
+                (true, &fewer),
+                (false, &fewer),
+                (true, &same),
+                (true, &more),
+            ],
+            Luck::Sixes,
+        );
+
+        assert_eq!(buckets[0].luck, Ordering::Less);
+        assert_eq!((buckets[0].games, buckets[0].a_wins), (2, 1));
+        assert_eq!((buckets[1].games, buckets[1].a_wins), (1, 1));
+        assert_eq!((buckets[2].games, buckets[2].a_wins), (1, 1));
+        assert_eq!(buckets[0].win_rate().0, 0.5);
+    }
+
+    #[test]
+    fn aggregates_game_stats() {
+        let mut a = GameStats::<4>::new();
+        a.move_distribution = [[3, 1, 0, 0], [2, 2, 0, 0]];
+        a.pieces_eaten_by = [2, 0];
+
+        let mut b = GameStats::<4>::new();
+        b.move_distribution = [[1, 1, 1, 1], [0, 0, 0, 4]];
+        b.pieces_eaten_by = [1, 3];
+
+        let distribution = move_distribution([&a, &b]);
+        assert_eq!(distribution, [[4, 2, 1, 1], [2, 2, 0, 4]]);
+
+        assert_eq!(share_with_moves(&distribution[1], 4), 0.5);
+        assert_eq!(share_with_choice(&distribution[0]), 0.5);
+
+        assert_eq!(average_eats([&a, &b]), [1.5, 1.5]);
+    }
+
+    #[test]
+    fn survival_treats_pieces_that_were_not_eaten_as_censored() {
+        let piece = |entered, left, fate| PieceLife {
+            entered,
+            left,
+            fate,
+        };
+        let pieces = [
+            piece(0, 2, PieceFate::Eaten),
+            piece(5, 9, PieceFate::ReachedGoal),
+            piece(1, 5, PieceFate::Eaten),
+            piece(3, 6, PieceFate::OnBoard),
+        ];
+
+        let survival = piece_survival(&pieces);
+
+        assert_eq!(
+            (survival.pieces, survival.eaten, survival.reached_goal),
+            (4, 2, 1)
+        );
+        assert_eq!(survival.mean_lifetime, 13.0 / 4.0);
+
+        // One of four is eaten after 2 turns, then one of the two left after 4
+        assert_eq!(survival.survival_at(1), 1.0);
+        assert_eq!(survival.survival_at(2), 0.75);
+        assert_eq!(survival.survival_at(4), 0.375);
+        assert_eq!(survival.survival_at(100), 0.375);
+
+        assert_eq!(piece_survival([]).survival_at(10), 1.0);
+    }
+
+    #[test]
+    fn orders_goal_slots_by_the_turn_they_were_filled_on() {
+        let mut a = GameStats::<4>::new();
+        a.goal_slots[0] = [Some(40), Some(30), Some(20), Some(10)];
+        let mut b = GameStats::<4>::new();
+        b.goal_slots[0] = [None, Some(60), None, Some(50)];
+
+        let filling = goal_filling([&a, &b], 0);
+
+        assert_eq!(filling.games, 2);
+        assert_eq!(filling.filled, [1, 2, 1, 2]);
+        assert_eq!(filling.mean_turn, [40.0, 45.0, 20.0, 30.0]);
+
+        // The deepest slot was filled first in both games
+        assert_eq!(filling.order[3], [2, 0, 0, 0]);
+        assert_eq!(filling.order[1], [0, 1, 1, 0]);
+        assert_eq!(filling.share_filled_as(0, 3), 0.5);
+    }
+
+    #[test]
+    fn finished_games_keep_their_final_positions_in_the_trajectory() {
+        let counts = |home, board, goal| PieceCounts { home, board, goal };
+
+        let mut short = GameStats::<4>::new();
+        short.trajectory = vec![[counts(3, 1, 0), counts(4, 0, 0)]];
+        let mut long = GameStats::<4>::new();
+        long.trajectory = vec![
+            [counts(3, 1, 0), counts(4, 0, 0)],
+            [counts(2, 1, 1), counts(3, 1, 0)],
+        ];
+
+        let trajectory = average_trajectory([&short, &long, &GameStats::new()], 3);
+
+        assert_eq!(trajectory.len(), 3);
+        assert_eq!(trajectory[0][0].home, 3.0);
+        assert_eq!(trajectory[1][0].home, 2.5);
+        assert_eq!(trajectory[2][0].goal, 0.5);
+        assert_eq!(trajectory[2][1].board, 0.5);
+
+        assert!(average_trajectory(Vec::<&GameStats<4>>::new(), 3).is_empty());
+    }
+
+    #[test]
+    fn chi_square_p_values_match_the_tables() {
+        let (_, p) = chi_square_test(&[100, 100, 100, 100, 100, 100], &[1.0 / 6.0; 6]);
+        assert!((p - 1.0).abs() < 1e-9);
+
+        // The 5% critical values of 5 and 2 degrees of freedom
+        assert!((upper_incomplete_gamma(2.5, 11.0705 / 2.0) - 0.05).abs() < 1e-4);
+        assert!((upper_incomplete_gamma(1.0, 5.9915 / 2.0) - 0.05).abs() < 1e-4);
+        assert!((upper_incomplete_gamma(2.5, 1.0) - 0.8491).abs() < 1e-4);
+    }
+
+    #[test]
+    fn dice_checks_catch_a_biased_die() {
+        let mut stats = GameStats::<4>::new();
+        stats.dice = [
+            [100, 100, 100, 100, 100, 100],
+            [100, 100, 100, 100, 100, 160],
+        ];
+        stats.action_dice = [[300, 200, 100], [0, 0, 0]];
+
+        let mut tally = DiceTally::default();
+        tally.add(&stats);
+        tally.add(&stats);
+
+        let checks = tally.checks();
+
+        // B never rolled the action die, so it isn't tested
+        assert_eq!(checks.len(), 3);
+        assert!(checks[0].passed() && checks[1].passed());
+        assert_eq!((checks[1].die.as_str(), checks[1].player), ("action", 0));
+        assert!(!checks[2].passed());
+        assert_eq!(checks[2].counts[5], 320);
+    }
+}
@@ -0,0 +1,231 @@
+//! Best-response tuning: weights of `default_heuristic` that beat one fixed target player.
+//!
+//! Unlike `sweep`, which ranks configurations by their mean win rate against a gauntlet, this
+//! climbs towards a counter player for a single target, which shows how exploitable the target
+//! is. Every round perturbs the current weights at random, and plays the current weights and the
+//! candidates against the target with the same seeds, so that they are compared on the same dice.
+//! The best candidate replaces the current weights if it wins more games, and the perturbations
+//! shrink when no candidate does.
+//!
+//! `exploitability` turns this into a robustness metric to go with a player's Elo: how far beyond
+//! 50% a counter player tuned against it wins, on games the tuning didn't see.
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    analysis::wilson_score,
+    games::struggle::players::{weighted_expectiminimax, HeuristicWeights},
+    registry::BoxedStrugglePlayer,
+    session::{run_match_seeded, MatchResult},
+};
+
+#[derive(Clone, Debug)]
+pub struct BestResponseOptions {
+    pub rounds: usize,
+    /// Perturbed weights tried per round
+    pub candidates: usize,
+    /// Games against the target per candidate and round
+    pub games: u32,
+    /// The largest relative change of a weight in a perturbation, e.g. 0.2 for ±20%
+    pub step: f64,
+    /// The step is multiplied by this after a round without improvement
+    pub step_decay: f64,
+    /// Search depth of the counter player
+    pub depth: u8,
+    pub seed: u64,
+}
+
+impl Default for BestResponseOptions {
+    fn default() -> Self {
+        Self {
+            rounds: 20,
+            candidates: 8,
+            games: 2_000,
+            step: 0.3,
+            step_decay: 0.7,
+            depth: 1,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BestResponseRound {
+    pub round: usize,
+    /// The win rate of the weights kept after the round, on the round's games
+    pub win_rate: f64,
+    pub improved: bool,
+    pub step: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct BestResponse {
+    pub weights: HeuristicWeights,
+    /// The win rate of `weights` in the last round. It's biased upwards by the selection, so
+    /// measure it again on fresh games to compare with other players.
+    pub win_rate: f64,
+}
+
+impl BestResponse {
+    pub fn player(&self, depth: u8) -> BoxedStrugglePlayer {
+        Box::new(weighted_expectiminimax(depth, self.weights))
+    }
+}
+
+/// Tunes weights against `target` starting from `start`, calling `log` after every round.
+pub fn best_response(
+    target: &BoxedStrugglePlayer,
+    start: HeuristicWeights,
+    options: &BestResponseOptions,
+    mut log: impl FnMut(&BestResponseRound),
+) -> BestResponse {
+    let mut rng = SmallRng::seed_from_u64(options.seed);
+    let mut best = BestResponse {
+        weights: start,
+        win_rate: 0.0,
+    };
+    let mut step = options.step;
+
+    let win_rate = |weights: HeuristicWeights, seed: u64| {
+        let player: BoxedStrugglePlayer = Box::new(weighted_expectiminimax(options.depth, weights));
+        run_match_seeded(&player, target, options.games, seed, |_| {}).a_win_rate()
+    };
+
+    for round in 0..options.rounds {
+        let seed = rng.gen();
+        best.win_rate = win_rate(best.weights, seed);
+
+        let mut improved = false;
+
+        for _ in 0..options.candidates {
+            let candidate = perturb(&best.weights, step, &mut rng);
+            let candidate_win_rate = win_rate(candidate, seed);
+
+            if candidate_win_rate > best.win_rate {
+                best = BestResponse {
+                    weights: candidate,
+                    win_rate: candidate_win_rate,
+                };
+                improved = true;
+            }
+        }
+
+        if !improved {
+            step *= options.step_decay;
+        }
+
+        log(&BestResponseRound {
+            round,
+            win_rate: best.win_rate,
+            improved,
+            step,
+        });
+    }
+
+    best
+}
+
+#[derive(Clone, Debug)]
+pub struct Exploitability {
+    pub counter: BestResponse,
+    /// The counter player as A against the player, on fresh games
+    pub result: MatchResult,
+    /// The 95% Wilson interval of the counter player's win rate
+    pub interval: (f64, f64),
+}
+
+impl Exploitability {
+    /// How far beyond 50% the counter player wins, 0 for a player it can't beat.
+    pub fn exploitability(&self) -> f64 {
+        (self.result.a_win_rate() - 0.5).max(0.0)
+    }
+}
+
+/// Tunes a counter player against `player` from the default weights, and plays `eval_games`
+/// fresh games between them. The fresh games are seeded with the bitwise complement of
+/// `options.seed`, so they don't overlap with the tuning games of nearby seeds.
+pub fn exploitability(
+    player: &BoxedStrugglePlayer,
+    options: &BestResponseOptions,
+    eval_games: u32,
+) -> Exploitability {
+    let counter = best_response(player, HeuristicWeights::DEFAULT, options, |_| {});
+    let result = run_match_seeded(
+        &counter.player(options.depth),
+        player,
+        eval_games,
+        !options.seed,
+        |_| {},
+    );
+
+    Exploitability {
+        counter,
+        result,
+        interval: wilson_score(result.a_win_rate(), result.games as u64),
+    }
+}
+
+/// Scales every weight by a random factor in `1 ± step`.
+pub fn perturb(weights: &HeuristicWeights, step: f64, rng: &mut impl Rng) -> HeuristicWeights {
+    let mut perturbed = *weights;
+
+    for name in HeuristicWeights::NAMES {
+        let weight = perturbed.get_mut(name).unwrap();
+        *weight *= 1.0 + rng.gen_range(-step..=step);
+    }
+
+    perturbed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::struggle_player;
+
+    #[test]
+    fn the_kept_weights_never_get_worse_on_their_round() {
+        let target = struggle_player("score_move").unwrap();
+        let options = BestResponseOptions {
+            rounds: 2,
+            candidates: 2,
+            games: 50,
+            depth: 0,
+            ..Default::default()
+        };
+
+        let mut rounds = Vec::new();
+        let result = best_response(&target, HeuristicWeights::DEFAULT, &options, |round| {
+            rounds.push(round.clone())
+        });
+
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(result.win_rate, rounds[1].win_rate);
+        assert!(rounds.iter().all(|round| round.step <= options.step));
+    }
+
+    #[test]
+    fn random_play_is_exploitable() {
+        let player = struggle_player("random").unwrap();
+        let options = BestResponseOptions {
+            rounds: 1,
+            candidates: 1,
+            games: 50,
+            depth: 0,
+            ..Default::default()
+        };
+
+        let report = exploitability(&player, &options, 400);
+
+        assert_eq!(report.result.games, 400);
+        assert!(report.exploitability() > 0.2);
+    }
+
+    #[test]
+    fn perturbations_stay_within_the_step() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let perturbed = perturb(&HeuristicWeights::DEFAULT, 0.1, &mut rng);
+
+        let ratio = perturbed.base_piece_score / HeuristicWeights::DEFAULT.base_piece_score;
+        assert!((0.9..=1.1).contains(&ratio));
+    }
+}
@@ -0,0 +1,195 @@
+//! A live terminal dashboard for long tournaments, with the progress, win rates and pace of every
+//! matchup and the current standings.
+
+use std::{io, time::Duration};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    widgets::{Block, Gauge, Row, Table},
+    DefaultTerminal, Frame,
+};
+
+use crate::{analysis::wilson_score, tournament::Tournament};
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shows the dashboard until the tournament finishes. Pressing q or Esc cancels the tournament.
+pub fn run(tournament: &Tournament) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = draw_until_finished(&mut terminal, tournament);
+    ratatui::restore();
+
+    result
+}
+
+fn draw_until_finished(terminal: &mut DefaultTerminal, tournament: &Tournament) -> io::Result<()> {
+    while !tournament.is_finished() {
+        terminal.draw(|frame| draw(frame, tournament))?;
+
+        if !event::poll(REFRESH_INTERVAL)? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press
+                && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+            {
+                tournament.cancel();
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn draw(frame: &mut Frame, tournament: &Tournament) {
+    let matchup_rows = tournament.matchups().len() as u16 + 3;
+
+    let [progress, matchups, standings] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Max(matchup_rows),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+
+    draw_progress(frame, progress, tournament);
+    draw_matchups(frame, matchups, tournament);
+    draw_standings(frame, standings, tournament);
+}
+
+fn draw_progress(frame: &mut Frame, area: Rect, tournament: &Tournament) {
+    let played = tournament.games_played();
+    let total = tournament.total_games();
+    let elapsed = tournament.elapsed();
+    let rate = played as f64 / elapsed.as_secs_f64();
+
+    let eta = if rate > 0.0 {
+        format_duration(Duration::from_secs_f64((total - played) as f64 / rate))
+    } else {
+        "-".to_string()
+    };
+
+    let label = format!(
+        "{} / {} games, {:.0} games/s, {} elapsed, ETA {}",
+        played,
+        total,
+        rate,
+        format_duration(elapsed),
+        eta
+    );
+
+    let gauge = Gauge::default()
+        .block(Block::bordered().title(" Tournament (q to stop) "))
+        .ratio(played as f64 / total.max(1) as f64)
+        .label(label);
+
+    frame.render_widget(gauge, area);
+}
+
+fn draw_matchups(frame: &mut Frame, area: Rect, tournament: &Tournament) {
+    let names = tournament.names();
+
+    let rows = tournament.matchups().iter().map(|matchup| {
+        let result = matchup.result();
+
+        Row::new([
+            names[matchup.a].clone(),
+            names[matchup.b].clone(),
+            format!("{} / {}", result.games, matchup.total_games()),
+            format_win_rate(result.a_win_rate(), result.games),
+            format_interval(result.a_win_rate(), result.games),
+            format!("{:.0}", matchup.games_per_second()),
+            matchup.eta().map_or("-".to_string(), format_duration),
+        ])
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(17),
+            Constraint::Length(7),
+            Constraint::Length(15),
+            Constraint::Length(8),
+            Constraint::Length(8),
+        ],
+    )
+    .header(header([
+        "A", "B", "Games", "A wins", "95% CI", "Games/s", "ETA",
+    ]))
+    .block(Block::bordered().title(" Matchups "));
+
+    frame.render_widget(table, area);
+}
+
+fn draw_standings(frame: &mut Frame, area: Rect, tournament: &Tournament) {
+    let names = tournament.names();
+
+    let rows = tournament
+        .standings()
+        .into_iter()
+        .enumerate()
+        .map(|(rank, standing)| {
+            Row::new([
+                format!("{}.", rank + 1),
+                names[standing.player].clone(),
+                standing.games.to_string(),
+                standing.wins.to_string(),
+                format_win_rate(standing.win_rate(), standing.games),
+                format_interval(standing.win_rate(), standing.games),
+            ])
+        });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),
+            Constraint::Fill(1),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(15),
+        ],
+    )
+    .header(header([
+        "", "Player", "Games", "Wins", "Win rate", "95% CI",
+    ]))
+    .block(Block::bordered().title(" Standings "));
+
+    frame.render_widget(table, area);
+}
+
+fn header<const N: usize>(titles: [&'static str; N]) -> Row<'static> {
+    Row::new(titles).style(Style::new().add_modifier(Modifier::BOLD))
+}
+
+fn format_win_rate(win_rate: f64, games: u32) -> String {
+    if games == 0 {
+        return "-".to_string();
+    }
+
+    format!("{:.1}%", win_rate * 100.0)
+}
+
+fn format_interval(win_rate: f64, games: u32) -> String {
+    if games == 0 {
+        return "-".to_string();
+    }
+
+    let (low, high) = wilson_score(win_rate, games as u64);
+    format!("{:.1}–{:.1}%", low * 100.0, high * 100.0)
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m{:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
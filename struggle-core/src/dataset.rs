@@ -0,0 +1,469 @@
+//! Self-play datasets for training value and policy models offline.
+//!
+//! A dataset is a directory of gzip-compressed shards named `shard-00000.bin.gz` and so on. Every
+//! shard starts with a header of little-endian `u32`s after an 8-byte magic:
+//!
+//! | Bytes | Contents                                              |
+//! |-------|-------------------------------------------------------|
+//! | 8     | `STRGDSET`                                            |
+//! | 4     | `FORMAT_VERSION`                                      |
+//! | 4     | `encoding::ENCODING_VERSION`                          |
+//! | 4     | Values in an encoded position                         |
+//! | 4     | Actions in the action space                           |
+//!
+//! followed by fixed-size records of one decision each:
+//!
+//! | Bytes | Contents                                              |
+//! |-------|-------------------------------------------------------|
+//! | 10    | The encoded position, one bit per value               |
+//! | 5     | The legal actions, one bit per action                 |
+//! | 1     | The chosen action, see `encoding::struggle_action`    |
+//! | 1     | The roll                                              |
+//! | 1     | 1 if the player to move went on to win, 0 otherwise   |
+//!
+//! Bits are packed least significant first.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::{
+    encoding::{struggle_action, Encode, ENCODING_VERSION, STRUGGLE_ACTIONS},
+    game::{game_seed, RaceGame, TurnResult},
+    games::struggle::{
+        board::{Board, StruggleMove},
+        players::StrugglePlayer,
+        AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+};
+
+pub const FORMAT_VERSION: u32 = 1;
+
+const MAGIC: &[u8; 8] = b"STRGDSET";
+
+const STATE_BYTES: usize = <Board as Encode>::ENCODED_LEN.div_ceil(8);
+const MASK_BYTES: usize = STRUGGLE_ACTIONS.div_ceil(8);
+const RECORD_BYTES: usize = STATE_BYTES + MASK_BYTES + 3;
+
+// Games are simulated a batch at a time, so that memory use stays flat for huge runs
+const BATCH_SIZE: u64 = 1 << 14;
+
+/// One decision of a self-play game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    state: [u8; STATE_BYTES],
+    legal: [u8; MASK_BYTES],
+    pub action: u8,
+    pub dice: u8,
+    /// Whether the player to move went on to win
+    pub won: bool,
+}
+
+fn pack(values: impl Iterator<Item = bool>, out: &mut [u8]) {
+    for (index, value) in values.enumerate() {
+        out[index / 8] |= (value as u8) << (index % 8);
+    }
+}
+
+fn bit(bytes: &[u8], index: usize) -> bool {
+    (bytes[index / 8] >> (index % 8)) & 1 == 1
+}
+
+impl Sample {
+    pub fn new(
+        board: &Board,
+        to_move: PlayerColor,
+        dice: u8,
+        moves: &[StruggleMove],
+        chosen: &StruggleMove,
+        won: bool,
+    ) -> Self {
+        let mut state = [0; STATE_BYTES];
+        pack(
+            board.encode(to_move).into_iter().map(|value| value != 0.0),
+            &mut state,
+        );
+
+        let mut legal = [0; MASK_BYTES];
+
+        for mov in moves {
+            let action = struggle_action(mov, to_move);
+            legal[action / 8] |= 1 << (action % 8);
+        }
+
+        Self {
+            state,
+            legal,
+            action: struggle_action(chosen, to_move) as u8,
+            dice,
+            won,
+        }
+    }
+
+    /// The position, see `encoding`.
+    pub fn state(&self) -> Vec<f32> {
+        (0..Board::ENCODED_LEN)
+            .map(|index| bit(&self.state, index) as u8 as f32)
+            .collect()
+    }
+
+    pub fn is_legal(&self, action: usize) -> bool {
+        bit(&self.legal, action)
+    }
+
+    pub fn legal_actions(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..STRUGGLE_ACTIONS).filter(|action| self.is_legal(*action))
+    }
+
+    fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        out.write_all(&self.state)?;
+        out.write_all(&self.legal)?;
+        out.write_all(&[self.action, self.dice, self.won as u8])
+    }
+
+    fn read(record: &[u8; RECORD_BYTES]) -> Self {
+        let (state, rest) = record.split_at(STATE_BYTES);
+        let (legal, rest) = rest.split_at(MASK_BYTES);
+
+        Self {
+            state: state.try_into().unwrap(),
+            legal: legal.try_into().unwrap(),
+            action: rest[0],
+            dice: rest[1],
+            won: rest[2] == 1,
+        }
+    }
+}
+
+/// Plays one game, returning every decision made in it.
+pub fn play_sampled_game<A: StrugglePlayer, B: StrugglePlayer>(
+    red: &A,
+    yellow: &B,
+    rng: &mut SmallRng,
+) -> Vec<Sample> {
+    let mut game = StruggleGame::new(
+        AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+        AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+        false,
+    );
+
+    // Randomly select who starts, like play_game
+    if rng.gen() {
+        game.set_current_player(game.other_player());
+    }
+
+    let mut decisions = Vec::new();
+
+    let winner = loop {
+        let dice = game.throw_dice(rng);
+        let ctx = game.create_turn_context(dice);
+        let moves = game.get_moves(&ctx);
+        let mov = game.select_move(&ctx, &moves, rng).clone();
+
+        decisions.push((
+            ctx.current_player,
+            Sample::new(game.board(), ctx.current_player, dice, &moves, &mov, false),
+        ));
+
+        match game.apply_move(&ctx, &mov) {
+            TurnResult::PlayAgain => {}
+            TurnResult::PassTo(player) => game.set_current_player(player),
+            TurnResult::EndGame { winner } => break winner,
+        }
+    };
+
+    decisions
+        .into_iter()
+        .map(|(player, sample)| Sample {
+            won: player == winner,
+            ..sample
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sampling {
+    /// Every decision of every game
+    All,
+    /// Up to this many decisions picked at random from each game, so that samples are less
+    /// correlated with each other
+    PerGame(usize),
+    /// A uniform sample of this many decisions over the whole run. The sample is kept in memory
+    /// and written at the end.
+    Reservoir(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct DatasetOptions {
+    pub games: u64,
+    /// Game `n` is played with the seed `game_seed(seed, n)`
+    pub seed: u64,
+    pub sampling: Sampling,
+    pub records_per_shard: usize,
+    /// Leaves out decisions with only one legal move, which teach a policy nothing
+    pub skip_forced: bool,
+}
+
+impl Default for DatasetOptions {
+    fn default() -> Self {
+        Self {
+            games: 100_000,
+            seed: 0,
+            sampling: Sampling::All,
+            records_per_shard: 1_000_000,
+            skip_forced: false,
+        }
+    }
+}
+
+/// Plays `options.games` games between `red` and `yellow` in parallel and writes their decisions
+/// to a dataset in `dir`. `progress` is called with the number of games finished after every
+/// batch. Returns the number of samples written.
+pub fn generate<A: StrugglePlayer, B: StrugglePlayer>(
+    red: &A,
+    yellow: &B,
+    dir: impl AsRef<Path>,
+    options: &DatasetOptions,
+    progress: impl Fn(u64),
+) -> io::Result<u64> {
+    let mut writer = DatasetWriter::create(dir, options.records_per_shard)?;
+
+    let mut reservoir = Vec::new();
+    let mut reservoir_rng = SmallRng::seed_from_u64(options.seed);
+    let mut seen = 0u64;
+
+    for start in (0..options.games).step_by(BATCH_SIZE as usize) {
+        let end = (start + BATCH_SIZE).min(options.games);
+
+        let batch = (start..end)
+            .into_par_iter()
+            .map(|game| {
+                let mut rng = SmallRng::seed_from_u64(game_seed(options.seed, game));
+                let mut samples = play_sampled_game(red, yellow, &mut rng);
+
+                if options.skip_forced {
+                    samples.retain(|sample| sample.legal_actions().nth(1).is_some());
+                }
+
+                if let Sampling::PerGame(count) = options.sampling {
+                    samples.shuffle(&mut rng);
+                    samples.truncate(count);
+                }
+
+                samples
+            })
+            .collect::<Vec<_>>();
+
+        for sample in batch.into_iter().flatten() {
+            let Sampling::Reservoir(capacity) = options.sampling else {
+                writer.write(&sample)?;
+                continue;
+            };
+
+            // Algorithm R: the nth sample replaces a random one with probability capacity / n
+            seen += 1;
+
+            if reservoir.len() < capacity {
+                reservoir.push(sample);
+            } else {
+                let index = reservoir_rng.gen_range(0..seen);
+
+                if index < capacity as u64 {
+                    reservoir[index as usize] = sample;
+                }
+            }
+        }
+
+        progress(end - start);
+    }
+
+    for sample in &reservoir {
+        writer.write(sample)?;
+    }
+
+    writer.finish()
+}
+
+type Shard = GzEncoder<BufWriter<File>>;
+
+/// Writes samples into shards of at most `records_per_shard` samples each.
+pub struct DatasetWriter {
+    dir: PathBuf,
+    records_per_shard: usize,
+    shard: Option<Shard>,
+    shards: usize,
+    in_shard: usize,
+    written: u64,
+}
+
+impl DatasetWriter {
+    pub fn create(dir: impl AsRef<Path>, records_per_shard: usize) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            records_per_shard: records_per_shard.max(1),
+            shard: None,
+            shards: 0,
+            in_shard: 0,
+            written: 0,
+        })
+    }
+
+    pub fn write(&mut self, sample: &Sample) -> io::Result<()> {
+        if self.in_shard == self.records_per_shard {
+            if let Some(shard) = self.shard.take() {
+                shard.finish()?.flush()?;
+            }
+        }
+
+        if self.shard.is_none() {
+            let path = self.dir.join(format!("shard-{:05}.bin.gz", self.shards));
+            let mut shard =
+                GzEncoder::new(BufWriter::new(File::create(path)?), Compression::default());
+            write_header(&mut shard)?;
+
+            self.shard = Some(shard);
+            self.shards += 1;
+            self.in_shard = 0;
+        }
+
+        sample.write(self.shard.as_mut().unwrap())?;
+        self.in_shard += 1;
+        self.written += 1;
+
+        Ok(())
+    }
+
+    /// Finishes the last shard, returning the number of samples written.
+    pub fn finish(mut self) -> io::Result<u64> {
+        if let Some(shard) = self.shard.take() {
+            shard.finish()?.flush()?;
+        }
+
+        Ok(self.written)
+    }
+}
+
+fn write_header(out: &mut impl Write) -> io::Result<()> {
+    out.write_all(MAGIC)?;
+
+    for value in [
+        FORMAT_VERSION,
+        ENCODING_VERSION,
+        Board::ENCODED_LEN as u32,
+        STRUGGLE_ACTIONS as u32,
+    ] {
+        out.write_all(&value.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn read_header(input: &mut impl Read) -> io::Result<()> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+    let mut magic = [0; 8];
+    input.read_exact(&mut magic)?;
+
+    if &magic != MAGIC {
+        return Err(invalid("Not a Struggle dataset shard".to_string()));
+    }
+
+    let expected = [
+        ("format", FORMAT_VERSION),
+        ("encoding", ENCODING_VERSION),
+        ("position length", Board::ENCODED_LEN as u32),
+        ("action space", STRUGGLE_ACTIONS as u32),
+    ];
+
+    for (field, expected) in expected {
+        let mut bytes = [0; 4];
+        input.read_exact(&mut bytes)?;
+        let value = u32::from_le_bytes(bytes);
+
+        if value != expected {
+            return Err(invalid(format!(
+                "Unsupported {} {}, expected {}",
+                field, value, expected
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn read_shard(path: impl AsRef<Path>) -> io::Result<Vec<Sample>> {
+    let mut input = GzDecoder::new(BufReader::new(File::open(path)?));
+    read_header(&mut input)?;
+
+    let mut samples = Vec::new();
+    let mut record = [0; RECORD_BYTES];
+
+    loop {
+        match input.read_exact(&mut record) {
+            Ok(()) => samples.push(Sample::read(&record)),
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(samples)
+}
+
+/// Reads every shard in `dir`, in order.
+pub fn read_dataset(dir: impl AsRef<Path>) -> io::Result<Vec<Sample>> {
+    let mut shards = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    shards.retain(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("shard-") && name.ends_with(".bin.gz"))
+    });
+    shards.sort();
+
+    let mut samples = Vec::new();
+
+    for shard in shards {
+        samples.extend(read_shard(shard)?);
+    }
+
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::RandomPlayer;
+
+    #[test]
+    fn datasets_round_trip() {
+        let dir = std::env::temp_dir().join(format!("struggle-dataset-{}", std::process::id()));
+
+        let options = DatasetOptions {
+            games: 20,
+            records_per_shard: 100,
+            sampling: Sampling::Reservoir(250),
+            ..Default::default()
+        };
+
+        let written = generate(&RandomPlayer, &RandomPlayer, &dir, &options, |_| {}).unwrap();
+        let samples = read_dataset(&dir).unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(written, 250);
+        assert_eq!(samples.len(), 250);
+        assert!(samples
+            .iter()
+            .all(|sample| sample.is_legal(sample.action as usize)));
+    }
+}
@@ -0,0 +1,213 @@
+//! Flat `f32` encodings of positions for learned models, shared by everything that trains or
+//! runs them, so that a model trained on one tool's data can be used by another.
+//!
+//! Positions are encoded from the point of view of the player to move: tiles are numbered from
+//! the mover's start tile, and the mover's planes come before the opponent's. Every value is 0 or
+//! 1. The layout for Struggle, with offsets into the tensor:
+//!
+//! | Offset | Length | Contents                                          |
+//! |--------|--------|---------------------------------------------------|
+//! | 0      | 28     | Mover's pieces on the tiles                       |
+//! | 28     | 28     | Opponent's pieces on the tiles                    |
+//! | 56     | 4      | Mover's filled goal slots                         |
+//! | 60     | 4      | Opponent's filled goal slots                      |
+//! | 64     | 5      | Mover's pieces waiting at home, one-hot 0-4       |
+//! | 69     | 5      | Opponent's pieces waiting at home, one-hot 0-4    |
+//! | 74     | 4      | Mover's color, one-hot in `PlayerColor` order     |
+//!
+//! Twist has 32 tiles and 3 goal slots, and adds the board rotation before the mover's color:
+//!
+//! | Offset | Length | Contents                                          |
+//! |--------|--------|---------------------------------------------------|
+//! | 0      | 32     | Mover's pieces on the tiles                       |
+//! | 32     | 32     | Opponent's pieces on the tiles                    |
+//! | 64     | 3      | Mover's filled goal slots                         |
+//! | 67     | 3      | Opponent's filled goal slots                      |
+//! | 70     | 5      | Mover's pieces waiting at home, one-hot 0-4       |
+//! | 75     | 5      | Opponent's pieces waiting at home, one-hot 0-4    |
+//! | 80     | 4      | Rotation, one-hot in `TwistRotation` order        |
+//! | 84     | 4      | Mover's color, one-hot in `PlayerColor` order     |
+//!
+//! Struggle moves are also numbered in a fixed action space for policy models, see
+//! `struggle_action`.
+//!
+//! Any change to the layouts must bump `ENCODING_VERSION`, which datasets and models record.
+
+use crate::games::{
+    struggle::{
+        board::{Board, BoardCell, HomeBase, StruggleMove},
+        PlayerColor,
+    },
+    twist::board::TwistBoard,
+};
+
+pub const ENCODING_VERSION: u32 = 1;
+
+/// The size of the Struggle action space, see `struggle_action`.
+pub const STRUGGLE_ACTIONS: usize = 1 + Board::TILES + 4 + 1;
+
+pub trait Encode {
+    /// The number of values in an encoded position.
+    const ENCODED_LEN: usize;
+
+    /// Writes the position as seen by `to_move` into `out`, which must be `ENCODED_LEN` long.
+    fn encode_into(&self, to_move: PlayerColor, out: &mut [f32]);
+
+    fn encode(&self, to_move: PlayerColor) -> Vec<f32> {
+        let mut out = vec![0.0; Self::ENCODED_LEN];
+        self.encode_into(to_move, &mut out);
+        out
+    }
+}
+
+// The shared part of both layouts, returning the rest of `out`
+fn encode_common<'a>(
+    tiles: &[BoardCell],
+    start: u8,
+    goals: (&[BoardCell], &[BoardCell]),
+    home_bases: (&HomeBase, &HomeBase),
+    to_move: PlayerColor,
+    out: &'a mut [f32],
+) -> &'a mut [f32] {
+    out.fill(0.0);
+
+    let (tile_planes, rest) = out.split_at_mut(tiles.len() * 2);
+    let (own_tiles, enemy_tiles) = tile_planes.split_at_mut(tiles.len());
+
+    for (index, tile) in tiles.iter().enumerate() {
+        let relative = relative_tile(index, start, tiles.len());
+
+        match tile {
+            Some(color) if *color == to_move => own_tiles[relative] = 1.0,
+            Some(_) => enemy_tiles[relative] = 1.0,
+            None => {}
+        }
+    }
+
+    let (goal_planes, rest) = rest.split_at_mut(goals.0.len() + goals.1.len());
+    let (own_goal, enemy_goal) = goal_planes.split_at_mut(goals.0.len());
+
+    for (plane, goal) in [(own_goal, goals.0), (enemy_goal, goals.1)] {
+        for (value, slot) in plane.iter_mut().zip(goal) {
+            *value = slot.is_some() as u8 as f32;
+        }
+    }
+
+    let (home_planes, rest) = rest.split_at_mut(10);
+
+    for (plane, home_base) in home_planes.chunks_mut(5).zip([home_bases.0, home_bases.1]) {
+        plane[home_base.pieces_waiting.min(4) as usize] = 1.0;
+    }
+
+    rest
+}
+
+fn relative_tile(index: usize, start: u8, tiles: usize) -> usize {
+    (index + tiles - start as usize) % tiles
+}
+
+fn opponent(players: (PlayerColor, PlayerColor), to_move: PlayerColor) -> PlayerColor {
+    if players.0 == to_move {
+        players.1
+    } else {
+        players.0
+    }
+}
+
+impl Encode for Board {
+    const ENCODED_LEN: usize = Board::TILES * 2 + 4 * 2 + 5 * 2 + 4;
+
+    fn encode_into(&self, to_move: PlayerColor, out: &mut [f32]) {
+        assert_eq!(out.len(), Self::ENCODED_LEN);
+
+        let enemy = opponent(self.players(), to_move);
+
+        let color = encode_common(
+            &self.tiles,
+            Board::get_start(to_move),
+            (&self.goals[to_move as usize], &self.goals[enemy as usize]),
+            (
+                &self.home_bases[to_move as usize],
+                &self.home_bases[enemy as usize],
+            ),
+            to_move,
+            out,
+        );
+
+        color[to_move as usize] = 1.0;
+    }
+}
+
+impl Encode for TwistBoard {
+    const ENCODED_LEN: usize = TwistBoard::TILES * 2 + 3 * 2 + 5 * 2 + 4 + 4;
+
+    fn encode_into(&self, to_move: PlayerColor, out: &mut [f32]) {
+        assert_eq!(out.len(), Self::ENCODED_LEN);
+
+        let enemy = opponent(self.players(), to_move);
+
+        let rest = encode_common(
+            &self.tiles,
+            TwistBoard::get_start(to_move),
+            (&self.goals[to_move as usize], &self.goals[enemy as usize]),
+            (
+                &self.home_bases[to_move as usize],
+                &self.home_bases[enemy as usize],
+            ),
+            to_move,
+            out,
+        );
+
+        let (rotation, color) = rest.split_at_mut(4);
+        rotation[self.rotation as usize] = 1.0;
+        color[to_move as usize] = 1.0;
+    }
+}
+
+/// Numbers a move by the piece it moves: 0 brings in a new piece, 1-28 move the piece on a tile
+/// (numbered from the mover's start tile), 29-32 move the piece in a goal slot and 33 skips the
+/// turn. A piece can only move one way with a given roll, so together with the roll the action
+/// identifies the move.
+pub fn struggle_action(mov: &StruggleMove, to_move: PlayerColor) -> usize {
+    let start = Board::get_start(to_move);
+
+    match *mov {
+        StruggleMove::AddNewPiece { .. } => 0,
+        StruggleMove::MovePiece { from, .. }
+        | StruggleMove::MoveToGoal {
+            from_board: from, ..
+        } => 1 + relative_tile(from as usize, start, Board::TILES),
+        StruggleMove::MoveInGoal { from_goal, .. } => 1 + Board::TILES + from_goal as usize,
+        StruggleMove::SkipTurn => STRUGGLE_ACTIONS - 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_are_encoded_from_the_movers_point_of_view() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[Board::YELLOW_START as usize + 2] = Some(PlayerColor::Yellow);
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let yellow = board.encode(PlayerColor::Yellow);
+        let red = board.encode(PlayerColor::Red);
+
+        assert_eq!(yellow.len(), Board::ENCODED_LEN);
+        assert_eq!(yellow[2], 1.0);
+        assert_eq!(yellow[64 + 3], 1.0);
+        assert_eq!(yellow[69 + 4], 1.0);
+        assert_eq!(yellow[74 + PlayerColor::Yellow as usize], 1.0);
+
+        assert_eq!(red[28 + Board::YELLOW_START as usize + 2], 1.0);
+        assert_eq!(red[64 + 4], 1.0);
+        assert_eq!(red[69 + 3], 1.0);
+        assert_eq!(red.iter().sum::<f32>(), yellow.iter().sum::<f32>());
+
+        let twist = TwistBoard::new((PlayerColor::Red, PlayerColor::Yellow));
+        assert_eq!(twist.encode(PlayerColor::Red).len(), 88);
+    }
+}
@@ -0,0 +1,133 @@
+//! Per-game records of bulk simulations, written as Parquet for pandas, polars or DuckDB.
+//!
+//! Every game is played with its own seed, so any single game in a file can be replayed from its
+//! `game_id`, see `game::GameId`. The rows are `records::GameRecord`s.
+
+use std::{fs::File, path::Path, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, StringArray, UInt32Array, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use parquet::{
+    arrow::ArrowWriter, basic::Compression, errors::ParquetError,
+    file::properties::WriterProperties,
+};
+
+use crate::{json::color_name, records::GameRecord};
+
+fn schema() -> SchemaRef {
+    let field = |name: &str, data_type: DataType| Field::new(name, data_type, false);
+
+    Arc::new(Schema::new(vec![
+        field("game_id", DataType::Utf8),
+        field("game", DataType::UInt64),
+        field("seed", DataType::UInt64),
+        field("red", DataType::Utf8),
+        field("yellow", DataType::Utf8),
+        field("winner", DataType::Utf8),
+        field("turns", DataType::UInt32),
+        field("red_turns", DataType::UInt32),
+        field("yellow_turns", DataType::UInt32),
+        field("red_eats", DataType::UInt32),
+        field("yellow_eats", DataType::UInt32),
+        field("red_evaluations", DataType::UInt64),
+        field("yellow_evaluations", DataType::UInt64),
+    ]))
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Arrow(ArrowError),
+    Parquet(ParquetError),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "{}", err),
+            ExportError::Arrow(err) => write!(f, "{}", err),
+            ExportError::Parquet(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(err: std::io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<ArrowError> for ExportError {
+    fn from(err: ArrowError) -> Self {
+        ExportError::Arrow(err)
+    }
+}
+
+impl From<ParquetError> for ExportError {
+    fn from(err: ParquetError) -> Self {
+        ExportError::Parquet(err)
+    }
+}
+
+/// Writes game records to a Parquet file, one row group per `write_batch` call.
+pub struct ParquetExporter {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+}
+
+impl ParquetExporter {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, ExportError> {
+        let schema = schema();
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let file = File::create(path)?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(properties))?;
+
+        Ok(Self { writer, schema })
+    }
+
+    pub fn write_batch(&mut self, records: &[GameRecord]) -> Result<(), ExportError> {
+        fn column<'a, T>(records: &'a [GameRecord], f: impl Fn(&'a GameRecord) -> T) -> Vec<T> {
+            records.iter().map(f).collect()
+        }
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(column(records, |r| r.id.to_string()))),
+            Arc::new(UInt64Array::from(column(records, |r| r.id.index))),
+            Arc::new(UInt64Array::from(column(records, |r| r.id.game_seed()))),
+            Arc::new(StringArray::from(column(records, |r| r.red.as_str()))),
+            Arc::new(StringArray::from(column(records, |r| r.yellow.as_str()))),
+            Arc::new(StringArray::from(column(records, |r| color_name(r.winner)))),
+            Arc::new(UInt32Array::from(column(records, |r| r.turns))),
+            Arc::new(UInt32Array::from(column(records, |r| {
+                r.turns_per_player[0]
+            }))),
+            Arc::new(UInt32Array::from(column(records, |r| {
+                r.turns_per_player[1]
+            }))),
+            Arc::new(UInt32Array::from(column(records, |r| r.eats[0]))),
+            Arc::new(UInt32Array::from(column(records, |r| r.eats[1]))),
+            Arc::new(UInt64Array::from(column(records, |r| r.evaluations[0]))),
+            Arc::new(UInt64Array::from(column(records, |r| r.evaluations[1]))),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema.clone(), columns)?;
+        self.writer.write(&batch)?;
+
+        Ok(())
+    }
+
+    /// Writes the footer. The file isn't readable until this is called.
+    pub fn finish(self) -> Result<(), ExportError> {
+        self.writer.close()?;
+        Ok(())
+    }
+}
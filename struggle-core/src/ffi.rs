@@ -0,0 +1,324 @@
+//! A C API for embedding the Struggle engine in other languages and game engines.
+//!
+//! Games are opaque handles created with `struggle_game_new` and released with
+//! `struggle_game_free`. A turn is played by rolling with `struggle_game_roll`, inspecting the
+//! legal moves with `struggle_game_move_count` / `struggle_game_get_move`, and applying one of them
+//! by index with `struggle_game_apply_move`. The header is written to `include/struggle.h` when
+//! building with the `ffi` feature.
+
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    game::{GamePlayer, RaceGame, TurnResult},
+    games::struggle::{
+        board::{MoveVec, StruggleMove},
+        players::{default_heuristic, GameContext, GameTreePlayer, RandomPlayer},
+        AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+};
+
+/// Red plays against Yellow, and Red moves first.
+pub struct StruggleFfiGame {
+    game: StruggleGame<RandomPlayer, RandomPlayer>,
+    rng: SmallRng,
+    ctx: Option<GameContext>,
+    moves: MoveVec,
+    winner: Option<PlayerColor>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StruggleMoveKind {
+    AddNewPiece = 0,
+    MovePiece = 1,
+    MoveToGoal = 2,
+    MoveInGoal = 3,
+    SkipTurn = 4,
+}
+
+/// A move in C friendly form. `from` and `to` are board tiles or goal slots depending on `kind`,
+/// and are zero when they don't apply.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StruggleMoveInfo {
+    pub kind: StruggleMoveKind,
+    pub from: u8,
+    pub to: u8,
+    pub eats: bool,
+}
+
+impl From<&StruggleMove> for StruggleMoveInfo {
+    fn from(mov: &StruggleMove) -> Self {
+        let (kind, from, to, eats) = match *mov {
+            StruggleMove::AddNewPiece { eats } => (StruggleMoveKind::AddNewPiece, 0, 0, eats),
+            StruggleMove::MovePiece { from, to, eats } => {
+                (StruggleMoveKind::MovePiece, from, to, eats)
+            }
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => (StruggleMoveKind::MoveToGoal, from_board, to_goal, false),
+            StruggleMove::MoveInGoal { from_goal, to_goal } => {
+                (StruggleMoveKind::MoveInGoal, from_goal, to_goal, false)
+            }
+            StruggleMove::SkipTurn => (StruggleMoveKind::SkipTurn, 0, 0, false),
+        };
+
+        Self {
+            kind,
+            from,
+            to,
+            eats,
+        }
+    }
+}
+
+pub const STRUGGLE_OK: i32 = 0;
+pub const STRUGGLE_ERROR_NULL: i32 = -1;
+pub const STRUGGLE_ERROR_NOT_ROLLED: i32 = -2;
+pub const STRUGGLE_ERROR_INVALID_MOVE: i32 = -3;
+pub const STRUGGLE_ERROR_GAME_OVER: i32 = -4;
+
+pub const STRUGGLE_NO_PLAYER: i32 = -1;
+
+/// Creates a new game. The seed makes the dice reproducible.
+#[no_mangle]
+pub extern "C" fn struggle_game_new(seed: u64) -> *mut StruggleFfiGame {
+    let game = StruggleGame::new(
+        AiStrugglePlayer::new(PlayerColor::Red, RandomPlayer),
+        AiStrugglePlayer::new(PlayerColor::Yellow, RandomPlayer),
+        false,
+    );
+
+    Box::into_raw(Box::new(StruggleFfiGame {
+        game,
+        rng: SmallRng::seed_from_u64(seed),
+        ctx: None,
+        moves: MoveVec::new(),
+        winner: None,
+    }))
+}
+
+/// Frees a game created with `struggle_game_new`. Passing null is allowed.
+///
+/// # Safety
+/// `game` must be null or a pointer returned by `struggle_game_new` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_free(game: *mut StruggleFfiGame) {
+    if !game.is_null() {
+        drop(Box::from_raw(game));
+    }
+}
+
+/// Rolls the die for the current player and returns it, or 0 if the game is over.
+/// Rolling again before applying a move rerolls.
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_roll(game: *mut StruggleFfiGame) -> u8 {
+    let Some(game) = game.as_mut() else {
+        return 0;
+    };
+
+    if game.winner.is_some() {
+        return 0;
+    }
+
+    let dice = game.game.throw_dice(&mut game.rng);
+    let ctx = game.game.create_turn_context(dice);
+    game.moves = game.game.get_moves(&ctx);
+    game.ctx = Some(ctx);
+
+    dice
+}
+
+/// The number of legal moves for the latest roll. There is always at least one after rolling.
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_move_count(game: *const StruggleFfiGame) -> u32 {
+    game.as_ref().map_or(0, |game| game.moves.len() as u32)
+}
+
+/// Writes the move at `index` to `out`. Returns false if the index is out of range.
+///
+/// # Safety
+/// `game` must be null or a live game handle, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_get_move(
+    game: *const StruggleFfiGame,
+    index: u32,
+    out: *mut StruggleMoveInfo,
+) -> bool {
+    let (Some(game), false) = (game.as_ref(), out.is_null()) else {
+        return false;
+    };
+
+    match game.moves.get(index as usize) {
+        Some(mov) => {
+            out.write(mov.into());
+            true
+        }
+        None => false,
+    }
+}
+
+/// Applies the move at `index` and passes the turn on, unless a six was rolled.
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_apply_move(game: *mut StruggleFfiGame, index: u32) -> i32 {
+    let Some(game) = game.as_mut() else {
+        return STRUGGLE_ERROR_NULL;
+    };
+
+    if game.winner.is_some() {
+        return STRUGGLE_ERROR_GAME_OVER;
+    }
+
+    let Some(ctx) = game.ctx.take() else {
+        return STRUGGLE_ERROR_NOT_ROLLED;
+    };
+
+    let Some(mov) = game.moves.get(index as usize).cloned() else {
+        game.ctx = Some(ctx);
+        return STRUGGLE_ERROR_INVALID_MOVE;
+    };
+
+    match game.game.apply_move(&ctx, &mov) {
+        TurnResult::PlayAgain => {}
+        TurnResult::PassTo(player) => game.game.set_current_player(player),
+        TurnResult::EndGame { winner } => game.winner = Some(winner),
+    }
+
+    game.moves.clear();
+
+    STRUGGLE_OK
+}
+
+/// Picks a move for the latest roll with expectiminimax at the given depth and returns its index,
+/// or a negative error code.
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_suggest_move(game: *mut StruggleFfiGame, depth: u8) -> i32 {
+    let Some(game) = game.as_mut() else {
+        return STRUGGLE_ERROR_NULL;
+    };
+
+    let Some(ctx) = &game.ctx else {
+        return STRUGGLE_ERROR_NOT_ROLLED;
+    };
+
+    let mut player = GameTreePlayer::new(default_heuristic, depth, "FFI");
+    let mov = player.select_move(ctx, game.game.board(), &game.moves, &mut game.rng);
+
+    game.moves
+        .iter()
+        .position(|candidate| candidate == mov)
+        .map_or(STRUGGLE_ERROR_INVALID_MOVE, |index| index as i32)
+}
+
+/// The player whose turn it is, as a `PlayerColor` index (red 0, blue 1, yellow 2, green 3).
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_current_player(game: *const StruggleFfiGame) -> i32 {
+    game.as_ref()
+        .map_or(STRUGGLE_NO_PLAYER, |game| game.game.current_player() as i32)
+}
+
+/// The winner as a `PlayerColor` index, or -1 while the game is still going.
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_winner(game: *const StruggleFfiGame) -> i32 {
+    game.as_ref()
+        .and_then(|game| game.winner)
+        .map_or(STRUGGLE_NO_PLAYER, |winner| winner as i32)
+}
+
+/// The player occupying a board tile (0-27), or -1 if the tile is empty or out of range.
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_tile(game: *const StruggleFfiGame, tile: u8) -> i32 {
+    game.as_ref()
+        .and_then(|game| {
+            game.game
+                .board()
+                .tiles
+                .get(tile as usize)
+                .copied()
+                .flatten()
+        })
+        .map_or(STRUGGLE_NO_PLAYER, |player| player as i32)
+}
+
+/// How many pieces a player has in their goal (0-4).
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_pieces_in_goal(
+    game: *const StruggleFfiGame,
+    player: u8,
+) -> u8 {
+    match game.as_ref() {
+        Some(game) if player < 4 => game
+            .game
+            .board()
+            .pieces_in_goal(PlayerColor::from(player as usize)),
+        _ => 0,
+    }
+}
+
+/// How many pieces a player has waiting in their home base (0-4).
+///
+/// # Safety
+/// `game` must be null or a live game handle.
+#[no_mangle]
+pub unsafe extern "C" fn struggle_game_pieces_waiting(
+    game: *const StruggleFfiGame,
+    player: u8,
+) -> u8 {
+    match game.as_ref() {
+        Some(game) if player < 4 => game.game.board().home_bases[player as usize].pieces_waiting,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_a_full_game_through_the_c_api() {
+        unsafe {
+            let game = struggle_game_new(42);
+
+            while struggle_game_winner(game) == STRUGGLE_NO_PLAYER {
+                let dice = struggle_game_roll(game);
+                assert!((1..=6).contains(&dice));
+                assert!(struggle_game_move_count(game) > 0);
+
+                let index = struggle_game_suggest_move(game, 0);
+                assert!(index >= 0);
+                assert_eq!(struggle_game_apply_move(game, index as u32), STRUGGLE_OK);
+            }
+
+            let winner = struggle_game_winner(game) as u8;
+            assert_eq!(struggle_game_pieces_in_goal(game, winner), 4);
+            assert_eq!(struggle_game_apply_move(game, 0), STRUGGLE_ERROR_GAME_OVER);
+
+            struggle_game_free(game);
+        }
+    }
+}
@@ -0,0 +1,811 @@
+use alloc::{
+    borrow::Cow,
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use core::cell::RefCell;
+use core::{
+    fmt::{self, Debug, Display},
+    str::FromStr,
+};
+
+#[cfg(feature = "std")]
+use rand::SeedableRng;
+use rand::{prelude::SmallRng, Rng};
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Debug)]
+pub enum TurnResult<PlayerId> {
+    PlayAgain,
+    PassTo(PlayerId),
+    EndGame { winner: PlayerId },
+}
+
+// The SplitMix64 generator's step, which spreads nearby inputs over the whole range
+const fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// The seed of game `index` of a run seeded with `seed`. The two are mixed rather than added, so
+/// that runs with nearby seeds don't share games: with `seed + index`, game 1 of seed 42 would be
+/// game 0 of seed 43.
+pub fn game_seed(seed: u64, index: u64) -> u64 {
+    splitmix64(seed ^ splitmix64(index))
+}
+
+/// Identifies a game of a seeded run by the run's master seed and the game's index in it, so that
+/// a game found in aggregate output can be played again: game `index` is played with the seed
+/// `game_seed(seed, index)`. Written as `seed-index`, e.g. `42-1337`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GameId {
+    pub seed: u64,
+    pub index: u64,
+}
+
+impl GameId {
+    pub fn new(seed: u64, index: u64) -> Self {
+        Self { seed, index }
+    }
+
+    /// The seed of the game's own RNG.
+    pub fn game_seed(&self) -> u64 {
+        game_seed(self.seed, self.index)
+    }
+}
+
+impl Display for GameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.seed, self.index)
+    }
+}
+
+impl FromStr for GameId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |part: &str| {
+            part.parse::<u64>()
+                .map_err(|_| alloc::format!("Invalid game id {}, expected seed-index", s))
+        };
+
+        match s.split_once('-') {
+            Some((seed, index)) => Ok(Self::new(parse(seed)?, parse(index)?)),
+            None => Err(alloc::format!("Invalid game id {}, expected seed-index", s)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for GameId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for GameId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// How a piece's time on the board ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PieceFate {
+    Eaten,
+    ReachedGoal,
+    /// The game ended with the piece still on the board
+    OnBoard,
+}
+
+/// A piece's time on the board, from the turn it entered the board to the turn it left it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PieceLife {
+    pub entered: u16,
+    pub left: u16,
+    pub fate: PieceFate,
+}
+
+impl PieceLife {
+    pub fn lifetime(&self) -> u16 {
+        self.left - self.entered
+    }
+}
+
+/// Where a player's pieces are at some point of a game.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PieceCounts {
+    pub home: u8,
+    pub board: u8,
+    pub goal: u8,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GameStats<const MAX_MOVES: usize> {
+    /// Set for the games of seeded runs, see `ResetGame::set_game_id`
+    pub id: Option<GameId>,
+    #[cfg_attr(feature = "serde", serde(with = "move_distribution"))]
+    pub move_distribution: [[u16; MAX_MOVES]; 2],
+    pub pieces_eaten_by: [u16; 2],
+    pub turns: u16,
+    pub turns_per_player: [u16; 2],
+    pub expectiminimax_evals: [u64; 2],
+    /// Turns each player rolled a six on
+    pub sixes: [u16; 2],
+    /// Turns each player could have eaten a piece on
+    pub eat_opportunities: [u16; 2],
+    /// How many times each player rolled each number, from 1 to 6
+    pub dice: [[u16; 6]; 2],
+    /// How many times each player rolled each face of the action die, in the order of
+    /// `ActionDie`. Only collected in Twist.
+    pub action_dice: [[u16; 3]; 2],
+    /// Every piece each player had on the board, in the order they left it. Only recorded with
+    /// timelines on, and only in Struggle, since spins and rotations move the pieces of Twist
+    /// around wholesale.
+    pub pieces: [Vec<PieceLife>; 2],
+    /// The turn each of a player's goal slots was last filled on, from the entrance inwards.
+    /// Turns are counted from 0 like `turns`.
+    pub goal_slots: [[Option<u16>; 4]; 2],
+    /// Where the pieces of both players were after each turn. Only recorded with timelines on,
+    /// see `ResetGame::set_timelines`.
+    pub trajectory: Vec<[PieceCounts; 2]>,
+}
+
+// Serde implements arrays of fixed lengths, but not of const generic ones
+#[cfg(feature = "serde")]
+mod move_distribution {
+    use alloc::vec::Vec;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        distribution: &[[u16; N]; 2],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        [&distribution[0][..], &distribution[1][..]].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[[u16; N]; 2], D::Error> {
+        let convert = |counts: Vec<u16>| {
+            let len = counts.len();
+            counts
+                .try_into()
+                .map_err(|_| de::Error::invalid_length(len, &"a count for every number of moves"))
+        };
+
+        let [a, b] = <[Vec<u16>; 2]>::deserialize(deserializer)?;
+        Ok([convert(a)?, convert(b)?])
+    }
+}
+
+impl<const MAX_MOVES: usize> GameStats<MAX_MOVES> {
+    pub fn new() -> Self {
+        Self {
+            id: None,
+            move_distribution: [[0; MAX_MOVES]; 2],
+            pieces_eaten_by: [0; 2],
+            turns: 0,
+            turns_per_player: [0; 2],
+            expectiminimax_evals: [0; 2],
+            sixes: [0; 2],
+            eat_opportunities: [0; 2],
+            dice: [[0; 6]; 2],
+            action_dice: [[0; 3]; 2],
+            pieces: [Vec::new(), Vec::new()],
+            goal_slots: [[None; 4]; 2],
+            trajectory: Vec::new(),
+        }
+    }
+}
+
+impl<const MAX_MOVES: usize> Default for GameStats<MAX_MOVES> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub trait NamedPlayer {
+    fn name(&self) -> Cow<'static, str>;
+
+    /// The name and every parameter of the player, for reports and manifests. Players with
+    /// parameters that their name leaves out should override this.
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name())
+    }
+}
+
+/// A full description of a player, so that e.g. "Expectiminimax(2)" in a report says which
+/// search options it was played with.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerConfig {
+    pub name: String,
+    pub parameters: BTreeMap<String, String>,
+    /// The version of this crate, as players can change from one version to the next
+    pub version: String,
+}
+
+impl PlayerConfig {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            parameters: BTreeMap::new(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+
+    pub fn with_parameter(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.parameters.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// Adds the name and the parameters of a wrapped player, with their keys prefixed by
+    /// `prefix`, e.g. `player.depth`.
+    pub fn with_inner(mut self, prefix: &str, inner: PlayerConfig) -> Self {
+        for (key, value) in inner.parameters {
+            self.parameters
+                .insert(alloc::format!("{prefix}.{key}"), value);
+        }
+
+        self.with_parameter(prefix, inner.name)
+    }
+}
+
+/// The types that a game's players see. Every game has a marker type for them, so that players
+/// can be written against a game without depending on their opponents, unlike `RaceGame`.
+pub trait GameRules: 'static {
+    type Board;
+    type Move;
+    type TurnContext;
+}
+
+/// A player of any game, so that wrappers like `DilutedPlayer` and the boxed players of `registry`
+/// only have to be written once. `StrugglePlayer` and `TwistPlayer` are this trait for each game.
+pub trait GamePlayer<R: GameRules>: Clone + Send + Sync + NamedPlayer {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move;
+
+    fn reset(&mut self) {}
+
+    fn total_evaluations(&self) -> u64 {
+        0
+    }
+}
+
+/// A player that can score every move, higher being better, so that e.g. `SoftmaxPlayer` can
+/// choose among them in its own way.
+pub trait ScoringPlayer<R: GameRules>: GamePlayer<R> {
+    /// The scores of `moves`, in the same order.
+    fn score_moves(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &[R::Move],
+        rng: &mut SmallRng,
+    ) -> Vec<f64>;
+}
+
+pub trait RaceGame {
+    type Board;
+    type PlayerId: Debug + Send + Sync + Clone + Eq + PartialEq;
+
+    type Move: Debug;
+    type MoveVector;
+
+    type TurnContext;
+    type DiceState: Clone + Debug;
+
+    /// What the players of this game see, see `GamePlayer`.
+    type Rules: GameRules<Board = Self::Board, Move = Self::Move, TurnContext = Self::TurnContext>;
+
+    const MAX_MOVES: usize;
+
+    fn board(&self) -> &Self::Board;
+    fn current_player(&self) -> Self::PlayerId;
+    fn other_player(&self) -> Self::PlayerId;
+    fn set_current_player(&mut self, player: Self::PlayerId);
+
+    fn throw_dice(&self, rng: &mut SmallRng) -> Self::DiceState;
+
+    fn create_turn_context(&self, dice: Self::DiceState) -> Self::TurnContext;
+
+    fn get_moves(&self, ctx: &Self::TurnContext) -> Self::MoveVector;
+
+    fn apply_move(
+        &mut self,
+        ctx: &Self::TurnContext,
+        mov: &Self::Move,
+    ) -> TurnResult<Self::PlayerId>;
+
+    fn select_move<'a>(
+        &mut self,
+        ctx: &Self::TurnContext,
+        moves: &'a Self::MoveVector,
+        rng: &mut SmallRng,
+    ) -> &'a Self::Move;
+
+    fn play_turn(&mut self, rng: &mut SmallRng) -> (Self::DiceState, TurnResult<Self::PlayerId>)
+    where
+        Self::Move: Debug,
+    {
+        let dice = self.throw_dice(rng);
+        (dice.clone(), self.play_turn_with_die(dice, rng))
+    }
+
+    fn play_turn_with_die(
+        &mut self,
+        dice: Self::DiceState,
+        rng: &mut SmallRng,
+    ) -> TurnResult<Self::PlayerId>
+    where
+        Self::Move: Debug,
+    {
+        let ctx = self.create_turn_context(dice.clone());
+        let moves = self.get_moves(&ctx);
+        let mov = self.select_move(&ctx, &moves, rng);
+
+        self.apply_move(&ctx, mov)
+    }
+}
+
+pub trait CreateGame: RaceGame {
+    type PlayerA: NamedPlayer + Clone + Send + Sync;
+    type PlayerB: NamedPlayer + Clone + Send + Sync;
+
+    fn create_game(
+        player_a: (Self::PlayerId, Self::PlayerA),
+        player_b: (Self::PlayerId, Self::PlayerB),
+        collect_stats: bool,
+    ) -> Self;
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    // Reused by every game on the thread, instead of seeding a new SmallRng for each one
+    static RNG: RefCell<SmallRng> = RefCell::new(SmallRng::from_rng(rand::thread_rng()).unwrap());
+}
+
+/// Plays a game with this thread's RNG.
+#[cfg(feature = "std")]
+pub fn play_game<G: RaceGame>(game: &mut G) -> G::PlayerId {
+    RNG.with(|rng| play_game_with_rng(game, &mut rng.borrow_mut()))
+}
+
+/// Like `play_game`, but with the caller's RNG so that the game can be reproduced from a seed.
+pub fn play_game_with_rng<G: RaceGame>(game: &mut G, rng: &mut SmallRng) -> G::PlayerId {
+    play_game_with_turn_cap(game, rng, None).expect("Games without a turn cap always finish")
+}
+
+/// Like `play_game_with_rng`, but gives up after `max_turns` turns and returns `None`, so that a
+/// few endless games (e.g. between players that avoid winning) can't hold up a whole run.
+pub fn play_game_with_turn_cap<G: RaceGame>(
+    game: &mut G,
+    rng: &mut SmallRng,
+    max_turns: Option<u32>,
+) -> Option<G::PlayerId> {
+    // Randomly select who starts
+    if rng.gen() {
+        game.set_current_player(game.other_player());
+    }
+
+    let mut turns = 0;
+
+    loop {
+        if max_turns.is_some_and(|max_turns| turns >= max_turns) {
+            return None;
+        }
+
+        turns += 1;
+
+        match game.play_turn(rng).1 {
+            TurnResult::PlayAgain => {}
+            TurnResult::PassTo(player) => {
+                game.set_current_player(player);
+            }
+            TurnResult::EndGame { winner } => {
+                return Some(winner);
+            }
+        }
+    }
+}
+
+pub trait IntoGameStats<const MAX_MOVES: usize>: RaceGame {
+    fn into_stats(self) -> Option<GameStats<MAX_MOVES>>;
+
+    fn stats(&self) -> Option<&GameStats<MAX_MOVES>>;
+}
+
+/// Games that can be played again from the start, so that `simulate_batch` can reuse them.
+pub trait ResetGame: RaceGame {
+    /// Sets up the starting position and clears the stats, keeping the players.
+    fn reset(&mut self);
+
+    /// Records the id of the game about to be played in its stats, if it collects them.
+    fn set_game_id(&mut self, id: GameId);
+
+    /// Records the timelines of the games from now on, if it collects stats: the per-turn
+    /// `GameStats::trajectory` and the `GameStats::pieces`. Off by default, since they allocate
+    /// as the game goes on.
+    fn set_timelines(&mut self, timelines: bool);
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchConfig {
+    /// Seeds game `i` with `game_seed(seed, i)`, so that the batch can be reproduced
+    pub seed: Option<u64>,
+    /// Games a worker plays in a row with the same game, RNG and aggregate
+    pub chunk_size: u64,
+    /// Games still going after this many turns are abandoned, see `play_game_with_turn_cap`
+    pub max_turns: Option<u32>,
+    /// Records the timelines of every game, see `ResetGame::set_timelines`
+    pub timelines: bool,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            chunk_size: 256,
+            max_turns: None,
+            timelines: false,
+        }
+    }
+}
+
+/// Accumulates the results of `simulate_batch`. Every worker records its games into its own
+/// aggregate, and the aggregates are merged at the end.
+pub trait BatchAggregate<G: RaceGame>: Default + Send {
+    fn record(&mut self, game: &G, winner: &G::PlayerId);
+
+    /// Called instead of `record` for games that hit `BatchConfig::max_turns`.
+    fn record_truncated(&mut self, _game: &G) {}
+
+    fn merge(&mut self, other: Self);
+}
+
+/// The number of games, and the wins of every player that won any. Truncated games count as
+/// games without a winner.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WinCounts<Id> {
+    pub games: u64,
+    pub wins: Vec<(Id, u64)>,
+    pub truncated: u64,
+}
+
+impl<Id> Default for WinCounts<Id> {
+    fn default() -> Self {
+        Self {
+            games: 0,
+            wins: Vec::new(),
+            truncated: 0,
+        }
+    }
+}
+
+impl<Id: PartialEq> WinCounts<Id> {
+    pub fn wins(&self, player: &Id) -> u64 {
+        self.wins
+            .iter()
+            .find(|(id, _)| id == player)
+            .map_or(0, |(_, wins)| *wins)
+    }
+
+    fn add(&mut self, player: Id, wins: u64) {
+        match self.wins.iter_mut().find(|(id, _)| *id == player) {
+            Some((_, count)) => *count += wins,
+            None => self.wins.push((player, wins)),
+        }
+    }
+}
+
+impl<G: RaceGame> BatchAggregate<G> for WinCounts<G::PlayerId> {
+    fn record(&mut self, _game: &G, winner: &G::PlayerId) {
+        self.games += 1;
+        self.add(winner.clone(), 1);
+    }
+
+    fn record_truncated(&mut self, _game: &G) {
+        self.games += 1;
+        self.truncated += 1;
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.games += other.games;
+        self.truncated += other.truncated;
+
+        for (player, wins) in other.wins {
+            self.add(player, wins);
+        }
+    }
+}
+
+/// The winner and stats of every game, in no particular order.
+impl<const MAX_MOVES: usize, G: IntoGameStats<MAX_MOVES>> BatchAggregate<G>
+    for Vec<(G::PlayerId, GameStats<MAX_MOVES>)>
+{
+    fn record(&mut self, game: &G, winner: &G::PlayerId) {
+        if let Some(stats) = game.stats() {
+            self.push((winner.clone(), stats.clone()));
+        }
+    }
+
+    fn merge(&mut self, mut other: Self) {
+        self.append(&mut other);
+    }
+}
+
+/// Plays `n` games in parallel and aggregates their results. Each worker creates a game with
+/// `game_factory` once, and resets it between games, instead of building new players and games
+/// for every one.
+#[cfg(feature = "std")]
+pub fn simulate_batch<G, S>(game_factory: impl Fn() -> G + Sync, n: u64, config: &BatchConfig) -> S
+where
+    G: ResetGame + Send,
+    S: BatchAggregate<G>,
+{
+    simulate_batch_with_progress(game_factory, n, config, |_| {})
+}
+
+/// Like `simulate_batch`, calling `progress` with the number of games after every chunk.
+#[cfg(feature = "std")]
+pub fn simulate_batch_with_progress<G, S>(
+    game_factory: impl Fn() -> G + Sync,
+    n: u64,
+    config: &BatchConfig,
+    progress: impl Fn(u64) + Sync,
+) -> S
+where
+    G: ResetGame + Send,
+    S: BatchAggregate<G>,
+{
+    let chunk_size = config.chunk_size.max(1);
+
+    (0..n.div_ceil(chunk_size))
+        .into_par_iter()
+        .fold(
+            || {
+                let rng = SmallRng::from_rng(rand::thread_rng()).unwrap();
+                let mut game = game_factory();
+
+                // Games from the factory may have them on already
+                if config.timelines {
+                    game.set_timelines(true);
+                }
+
+                (game, rng, S::default())
+            },
+            |(mut game, mut rng, mut aggregate), chunk| {
+                let games = chunk * chunk_size..((chunk + 1) * chunk_size).min(n);
+                let count = games.end - games.start;
+
+                for index in games {
+                    game.reset();
+
+                    if let Some(seed) = config.seed {
+                        let id = GameId::new(seed, index);
+                        rng = SmallRng::seed_from_u64(id.game_seed());
+                        game.set_game_id(id);
+                    }
+
+                    match play_game_with_turn_cap(&mut game, &mut rng, config.max_turns) {
+                        Some(winner) => aggregate.record(&game, &winner),
+                        None => aggregate.record_truncated(&game),
+                    }
+                }
+
+                progress(count);
+                (game, rng, aggregate)
+            },
+        )
+        .map(|(_, _, aggregate)| aggregate)
+        .reduce(S::default, |mut a, b| {
+            a.merge(b);
+            a
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use super::*;
+    use crate::games::struggle::{
+        players::RandomPlayer, AiStrugglePlayer, PlayerColor, StruggleGame,
+    };
+
+    #[test]
+    fn seeded_batches_are_reproducible() {
+        let factory = || {
+            StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, RandomPlayer),
+                AiStrugglePlayer::new(PlayerColor::Yellow, RandomPlayer),
+                false,
+            )
+        };
+        let config = BatchConfig {
+            seed: Some(7),
+            chunk_size: 16,
+            ..Default::default()
+        };
+
+        let a: WinCounts<PlayerColor> = simulate_batch(factory, 1000, &config);
+        let b: WinCounts<PlayerColor> = simulate_batch(factory, 1000, &config);
+
+        assert_eq!(a.games, 1000);
+        assert_eq!(
+            a.wins(&PlayerColor::Red) + a.wins(&PlayerColor::Yellow),
+            1000
+        );
+        assert_eq!(a.wins(&PlayerColor::Red), b.wins(&PlayerColor::Red));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn stats_round_trip_through_json() {
+        let (_, game) = StruggleGame::builder()
+            .collect_stats(true)
+            .timelines(true)
+            .seed(5)
+            .play();
+        let stats = game.into_stats().unwrap();
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let parsed: GameStats<4> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.move_distribution, stats.move_distribution);
+        assert_eq!(parsed.trajectory, stats.trajectory);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+        // Move distributions are checked against the number of moves
+        let json = json.replacen("\"move_distribution\":[[", "\"move_distribution\":[[0,", 1);
+        assert!(serde_json::from_str::<GameStats<4>>(&json).is_err());
+    }
+
+    #[test]
+    fn turn_cap_truncates_long_games() {
+        let factory = || {
+            StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, RandomPlayer),
+                AiStrugglePlayer::new(PlayerColor::Yellow, RandomPlayer),
+                false,
+            )
+        };
+        let config = BatchConfig {
+            seed: Some(7),
+            max_turns: Some(10),
+            ..Default::default()
+        };
+
+        // Nobody can get four pieces into their goal in ten turns
+        let counts: WinCounts<PlayerColor> = simulate_batch(factory, 100, &config);
+
+        assert_eq!(counts.games, 100);
+        assert_eq!(counts.truncated, 100);
+        assert!(counts.wins.is_empty());
+    }
+
+    #[test]
+    fn tracks_every_piece_that_leaves_the_board() {
+        let (winner, game) = StruggleGame::builder()
+            .collect_stats(true)
+            .timelines(true)
+            .seed(3)
+            .play();
+        let stats = game.into_stats().unwrap();
+
+        let winner = if winner == PlayerColor::Red { 0 } else { 1 };
+        let count = |player: usize, fate| {
+            stats.pieces[player]
+                .iter()
+                .filter(|piece| piece.fate == fate)
+                .count() as u16
+        };
+
+        assert_eq!(count(winner, PieceFate::ReachedGoal), 4);
+        assert_eq!(count(0, PieceFate::Eaten), stats.pieces_eaten_by[1]);
+        assert_eq!(count(1, PieceFate::Eaten), stats.pieces_eaten_by[0]);
+        assert!(stats
+            .pieces
+            .iter()
+            .flatten()
+            .all(|piece| piece.entered <= piece.left && piece.left <= stats.turns));
+
+        assert_eq!(stats.trajectory.len(), stats.turns as usize);
+        assert_eq!(stats.trajectory.last().unwrap()[winner].goal, 4);
+    }
+
+    #[test]
+    fn seeded_builds_play_the_same_game() {
+        let play = || {
+            let mut game = StruggleGame::builder()
+                .collect_stats(true)
+                .timelines(true)
+                .seed(8)
+                .build();
+            let winner = game.play();
+
+            (winner, game.into_stats().unwrap().trajectory)
+        };
+
+        let (winner, trajectory) = play();
+        let (_, played) = StruggleGame::builder()
+            .collect_stats(true)
+            .timelines(true)
+            .seed(8)
+            .play();
+
+        assert_eq!(play(), (winner, trajectory.clone()));
+        assert_eq!(played.into_stats().unwrap().trajectory, trajectory);
+    }
+
+    #[test]
+    fn timelines_are_opt_in() {
+        let factory = || {
+            StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, RandomPlayer),
+                AiStrugglePlayer::new(PlayerColor::Yellow, RandomPlayer),
+                true,
+            )
+        };
+        let stats = |timelines| {
+            let config = BatchConfig {
+                seed: Some(11),
+                timelines,
+                ..Default::default()
+            };
+            let games: Vec<(PlayerColor, GameStats<4>)> = simulate_batch(factory, 10, &config);
+            games
+        };
+
+        assert!(stats(false).iter().all(
+            |(_, stats)| stats.trajectory.is_empty() && stats.pieces.iter().all(Vec::is_empty)
+        ));
+        assert!(stats(true)
+            .iter()
+            .all(|(_, stats)| stats.trajectory.len() == stats.turns as usize
+                && !stats.pieces.iter().all(Vec::is_empty)));
+    }
+
+    #[test]
+    fn game_ids_round_trip_as_strings() {
+        let id = GameId::new(42, 1337);
+
+        assert_eq!(id.to_string(), "42-1337");
+        assert_eq!("42-1337".parse(), Ok(id));
+        assert!("42".parse::<GameId>().is_err());
+        #[cfg(feature = "serde")]
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""42-1337""#);
+        assert_eq!(id.game_seed(), game_seed(42, 1337));
+    }
+
+    #[test]
+    fn adjacent_seeds_play_different_games() {
+        let seeds = |seed| (0..10_000).map(move |index| game_seed(seed, index));
+
+        let run: BTreeSet<u64> = seeds(42).collect();
+        assert_eq!(run.len(), 10_000);
+        assert!(seeds(43).all(|seed| !run.contains(&seed)));
+        assert!(seeds(41).all(|seed| !run.contains(&seed)));
+    }
+}
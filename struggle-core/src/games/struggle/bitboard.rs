@@ -0,0 +1,298 @@
+//! A packed representation of the Struggle board for deep searches.
+//!
+//! `Board` keeps a cell per tile and goal slot, and a sorted list of pieces to patch on every move.
+//! `BitBoard` keeps a 28-bit mask of the tiles of each color, a 4-bit mask of each goal and the
+//! number of waiting pieces, so it's `Copy` and 26 bytes. Occupancy and eating come from masking
+//! the colors' tiles, and there's no cache to rebuild.
+//!
+//! It has the same API as `Board` for playing moves (`new`, `get_moves`, `perform_move`,
+//! `with_move` and `get_winner`) and generates the same moves in the same order, so move
+//! generation and playouts can run on either. Convert with `BitBoard::from(&board)` and
+//! `BitBoard::to_board`.
+//!
+//! `GameTreePlayer` doesn't use it: the search and its heuristics read the cells and the piece
+//! cache of a `Board`, and key their tables by its Zobrist hash, so running the search on a
+//! `BitBoard` would take making all of them generic over the board. `benches/struggle.rs`
+//! compares the two boards' move generation until then.
+
+use super::{
+    board::{Board, Destination, MoveVec, StruggleMove, DESTINATIONS},
+    PlayerColor, COLORS,
+};
+
+const FULL_GOAL: u8 = 0b1111;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BitBoard {
+    // Bit `i` is set when the color has a piece on tile `i`
+    tiles: [u32; 4],
+    // Bit `i` is set when slot `i` of the color's goal is filled
+    goals: [u8; 4],
+    waiting: [u8; 4],
+    players: (PlayerColor, PlayerColor),
+}
+
+// Calls `f` with the index of every set bit of `mask`, lowest first
+fn for_each_bit(mut mask: u32, mut f: impl FnMut(u8)) {
+    while mask != 0 {
+        f(mask.trailing_zeros() as u8);
+        mask &= mask - 1;
+    }
+}
+
+impl BitBoard {
+    pub fn new(player_a: PlayerColor, player_b: PlayerColor) -> Self {
+        Self {
+            tiles: [0; 4],
+            goals: [0; 4],
+            waiting: [4; 4],
+            players: (player_a, player_b),
+        }
+    }
+
+    pub fn players(&self) -> (PlayerColor, PlayerColor) {
+        self.players
+    }
+
+    fn occupied(&self) -> u32 {
+        self.tiles[0] | self.tiles[1] | self.tiles[2] | self.tiles[3]
+    }
+
+    /// The color of the piece on `tile`, if any.
+    pub fn tile(&self, tile: u8) -> Option<PlayerColor> {
+        COLORS
+            .into_iter()
+            .find(|&color| self.tiles[color as usize] & 1 << tile != 0)
+    }
+
+    pub fn pieces_waiting(&self, player: PlayerColor) -> u8 {
+        self.waiting[player as usize]
+    }
+
+    pub fn pieces_in_goal(&self, player: PlayerColor) -> u8 {
+        self.goals[player as usize].count_ones() as u8
+    }
+
+    pub fn get_winner(&self) -> Option<PlayerColor> {
+        COLORS
+            .into_iter()
+            .find(|&color| self.goals[color as usize] == FULL_GOAL)
+    }
+
+    /// The same moves as `Board::get_moves`, in the same order.
+    pub fn get_moves(&self, dice: u8, player: PlayerColor, _enemy: PlayerColor) -> MoveVec {
+        let mut moves = MoveVec::new_const();
+
+        let own = self.tiles[player as usize];
+        let enemies = self.occupied() & !own;
+        let goal = self.goals[player as usize];
+        let start = 1 << Board::get_start(player);
+
+        if self.waiting[player as usize] > 0 && dice == 6 && own & start == 0 {
+            moves.push(StruggleMove::AddNewPiece {
+                eats: enemies & start != 0,
+            });
+        }
+
+        for_each_bit(own, |from| {
+            match DESTINATIONS[player as usize][from as usize][dice as usize - 1] {
+                Destination::Goal(to_goal) => {
+                    if to_goal < 4 && goal & 1 << to_goal == 0 {
+                        moves.push(StruggleMove::MoveToGoal {
+                            from_board: from,
+                            to_goal,
+                        });
+                    }
+                }
+                Destination::Board(to) => {
+                    if own & 1 << to == 0 {
+                        moves.push(StruggleMove::MovePiece {
+                            from,
+                            to,
+                            eats: enemies & 1 << to != 0,
+                        });
+                    }
+                }
+            }
+        });
+
+        for_each_bit(goal as u32, |from_goal| {
+            let to_goal = from_goal + dice;
+
+            if to_goal < 4 && goal & 1 << to_goal == 0 {
+                moves.push(StruggleMove::MoveInGoal { from_goal, to_goal });
+            }
+        });
+
+        if moves.is_empty() {
+            moves.push(StruggleMove::SkipTurn);
+        }
+
+        moves
+    }
+
+    // Sends the piece on `tile`, if any, back to its home base
+    fn eat(&mut self, tile: u8) {
+        if let Some(color) = self.tile(tile) {
+            self.tiles[color as usize] &= !(1 << tile);
+            self.waiting[color as usize] += 1;
+        }
+    }
+
+    pub fn perform_move(&mut self, player: PlayerColor, mov: &StruggleMove) {
+        match *mov {
+            StruggleMove::AddNewPiece { eats } => {
+                let start = Board::get_start(player);
+
+                if eats {
+                    self.eat(start);
+                }
+
+                self.tiles[player as usize] |= 1 << start;
+                self.waiting[player as usize] -= 1;
+            }
+            StruggleMove::MovePiece { from, to, eats } => {
+                self.tiles[player as usize] &= !(1 << from);
+
+                if eats {
+                    self.eat(to);
+                }
+
+                self.tiles[player as usize] |= 1 << to;
+            }
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => {
+                self.tiles[player as usize] &= !(1 << from_board);
+                self.goals[player as usize] |= 1 << to_goal;
+            }
+            StruggleMove::MoveInGoal { from_goal, to_goal } => {
+                self.goals[player as usize] ^= 1 << from_goal | 1 << to_goal;
+            }
+            StruggleMove::SkipTurn => {}
+        }
+    }
+
+    pub fn with_move(&self, player: PlayerColor, mov: &StruggleMove) -> Self {
+        let mut board = *self;
+        board.perform_move(player, mov);
+        board
+    }
+
+    pub fn to_board(&self) -> Board {
+        let mut board = Board::new(self.players.0, self.players.1);
+
+        for tile in 0..Board::TILES as u8 {
+            board.tiles[tile as usize] = self.tile(tile);
+        }
+
+        for color in COLORS {
+            for slot in 0..4 {
+                if self.goals[color as usize] & 1 << slot != 0 {
+                    board.goals[color as usize][slot] = Some(color);
+                }
+            }
+
+            board.home_bases[color as usize].pieces_waiting = self.waiting[color as usize];
+        }
+
+        board.update_piece_cache();
+        board
+    }
+}
+
+impl From<&Board> for BitBoard {
+    fn from(board: &Board) -> Self {
+        let mut bitboard = Self::new(board.players.0, board.players.1);
+
+        for (tile, cell) in board.tiles.iter().enumerate() {
+            if let Some(color) = cell {
+                bitboard.tiles[*color as usize] |= 1 << tile;
+            }
+        }
+
+        for color in COLORS {
+            for (slot, cell) in board.goals[color as usize].iter().enumerate() {
+                if cell.is_some() {
+                    bitboard.goals[color as usize] |= 1 << slot;
+                }
+            }
+
+            bitboard.waiting[color as usize] = board.home_bases[color as usize].pieces_waiting;
+        }
+
+        bitboard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn plays_like_board() {
+        let mut rng = SmallRng::seed_from_u64(5);
+        let (a, b) = (PlayerColor::Blue, PlayerColor::Green);
+
+        for _ in 0..20 {
+            let mut board = Board::new(a, b);
+            let mut bitboard = BitBoard::new(a, b);
+            let mut to_move = a;
+
+            while board.get_winner().is_none() {
+                let dice = rng.gen_range(1..=6);
+                let enemy = if to_move == a { b } else { a };
+                let moves = board.get_moves(dice, to_move, enemy);
+
+                assert_eq!(bitboard.get_moves(dice, to_move, enemy), moves);
+
+                let mov = &moves[rng.gen_range(0..moves.len())];
+                board.perform_move(to_move, mov);
+                bitboard.perform_move(to_move, mov);
+
+                assert!(bitboard.to_board() == board);
+                assert_eq!(BitBoard::from(&board), bitboard);
+
+                if dice != 6 {
+                    to_move = enemy;
+                }
+            }
+
+            assert_eq!(bitboard.get_winner(), board.get_winner());
+        }
+    }
+
+    #[test]
+    fn eats_any_other_color() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[Board::RED_START as usize] = Some(PlayerColor::Green);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.tiles[5] = Some(PlayerColor::Blue);
+        for color in COLORS {
+            board.home_bases[color as usize].pieces_waiting = 3;
+        }
+        board.update_piece_cache();
+
+        let mut bitboard = BitBoard::from(&board);
+        let moves = bitboard.get_moves(2, PlayerColor::Red, PlayerColor::Yellow);
+
+        assert_eq!(
+            moves,
+            board.get_moves(2, PlayerColor::Red, PlayerColor::Yellow)
+        );
+        assert!(moves.contains(&StruggleMove::MovePiece {
+            from: 3,
+            to: 5,
+            eats: true
+        }));
+
+        bitboard.perform_move(PlayerColor::Red, &StruggleMove::AddNewPiece { eats: true });
+
+        assert_eq!(bitboard.tile(Board::RED_START), Some(PlayerColor::Red));
+        assert_eq!(bitboard.pieces_waiting(PlayerColor::Green), 4);
+        assert_eq!(bitboard.pieces_waiting(PlayerColor::Red), 2);
+    }
+}
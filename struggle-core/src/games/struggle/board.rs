@@ -0,0 +1,850 @@
+use alloc::borrow::Cow;
+use core::hash::Hash;
+
+use arrayvec::ArrayVec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::game::PieceCounts;
+
+use super::{zobrist, PlayerColor, COLORS};
+
+pub type BoardCell = Option<PlayerColor>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PiecePosition {
+    Board(u8),
+    Goal(u8),
+}
+
+impl PiecePosition {
+    pub fn as_board_index(self) -> Option<u8> {
+        match self {
+            PiecePosition::Board(index) => Some(index),
+            _ => None,
+        }
+    }
+}
+
+/// Serialized without the piece cache and the Zobrist hash, which are rebuilt when deserializing.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "BoardFields"))]
+pub struct Board {
+    pub tiles: [BoardCell; 7 * 4],
+    pub goals: [Goal; 4],
+    pub home_bases: [HomeBase; 4],
+
+    pub players: (PlayerColor, PlayerColor),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub piece_cache: (PieceVec, PieceVec),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    zobrist: u64,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct BoardFields {
+    tiles: [BoardCell; 7 * 4],
+    goals: [Goal; 4],
+    home_bases: [HomeBase; 4],
+    players: (PlayerColor, PlayerColor),
+}
+
+#[cfg(feature = "serde")]
+impl From<BoardFields> for Board {
+    fn from(fields: BoardFields) -> Self {
+        let mut board = Board {
+            tiles: fields.tiles,
+            goals: fields.goals,
+            home_bases: fields.home_bases,
+            players: fields.players,
+            piece_cache: (PieceVec::new(), PieceVec::new()),
+            zobrist: 0,
+        };
+
+        board.update_piece_cache();
+        board
+    }
+}
+
+impl Hash for Board {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.home_bases.hash(state);
+        self.piece_cache.hash(state);
+    }
+}
+
+pub type MoveVec = ArrayVec<StruggleMove, 4>;
+pub type PieceVec = ArrayVec<PiecePosition, 4>;
+
+fn remove_piece(pieces: &mut PieceVec, position: PiecePosition) {
+    if let Some(index) = pieces.iter().position(|&piece| piece == position) {
+        pieces.remove(index);
+    }
+}
+
+/// Where a piece on the board ends up with a roll. `Goal` can be past the end of the goal, in
+/// which case the piece can't move.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Destination {
+    Board(u8),
+    Goal(u8),
+}
+
+/// `DESTINATIONS[player][position][dice - 1]`, so that `get_moves` doesn't have to wrap around
+/// the board or check whether a piece passes its goal entrance.
+pub(super) const DESTINATIONS: [[[Destination; 6]; Board::TILES]; 4] = destinations();
+
+const fn destinations() -> [[[Destination; 6]; Board::TILES]; 4] {
+    let starts = [
+        Board::RED_START,
+        Board::BLUE_START,
+        Board::YELLOW_START,
+        Board::GREEN_START,
+    ];
+
+    let mut table = [[[Destination::Board(0); 6]; Board::TILES]; 4];
+    let mut player = 0;
+
+    while player < 4 {
+        let start = starts[player];
+        let mut pos = 0;
+
+        while pos < Board::TILES as u8 {
+            let mut dice = 1;
+
+            while dice <= 6 {
+                let new_pos = (pos + dice) % Board::TILES as u8;
+
+                // Red's goal entrance is at the end of the board, so it enters when it wraps around
+                let passes_entrance = if start == 0 {
+                    new_pos < pos
+                } else {
+                    pos < start && new_pos >= start
+                };
+
+                table[player][pos as usize][dice as usize - 1] = if passes_entrance {
+                    Destination::Goal((new_pos + Board::TILES as u8 - start) % Board::TILES as u8)
+                } else {
+                    Destination::Board(new_pos)
+                };
+
+                dice += 1;
+            }
+
+            pos += 1;
+        }
+
+        player += 1;
+    }
+
+    table
+}
+
+impl Board {
+    pub const TILES: usize = 7 * 4;
+
+    pub const RED_START: u8 = 0;
+    pub const BLUE_START: u8 = Self::RED_START + 7;
+    pub const YELLOW_START: u8 = Self::BLUE_START + 7;
+    pub const GREEN_START: u8 = Self::YELLOW_START + 7;
+
+    pub fn new(player_a: PlayerColor, player_b: PlayerColor) -> Self {
+        let mut board = Board {
+            tiles: [None; 7 * 4],
+            goals: COLORS.map(|_| [None; 4]),
+            home_bases: COLORS.map(|_| HomeBase::new()),
+
+            players: (player_a, player_b),
+            piece_cache: (PieceVec::new(), PieceVec::new()),
+            zobrist: 0,
+        };
+
+        board.zobrist = zobrist::hash(&board);
+        board
+    }
+
+    pub fn get_start(player: PlayerColor) -> u8 {
+        match player {
+            PlayerColor::Red => Self::RED_START,
+            PlayerColor::Blue => Self::BLUE_START,
+            PlayerColor::Yellow => Self::YELLOW_START,
+            PlayerColor::Green => Self::GREEN_START,
+        }
+    }
+
+    pub fn get_winner(&self) -> Option<PlayerColor> {
+        self.goals.iter().find_map(|g| {
+            let all_filled = g.iter().all(|cell| cell.is_some());
+            if all_filled {
+                Some(g[0].unwrap())
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn get_pieces(&self, player: PlayerColor, _enemy: PlayerColor) -> (&PieceVec, &PieceVec) {
+        if player == self.players.0 {
+            (&self.piece_cache.0, &self.piece_cache.1)
+        } else {
+            (&self.piece_cache.1, &self.piece_cache.0)
+        }
+    }
+
+    pub fn players(&self) -> (PlayerColor, PlayerColor) {
+        self.players
+    }
+
+    fn get_pieces_internal(&self, player: PlayerColor, enemy: PlayerColor) -> (PieceVec, PieceVec) {
+        let mut player_positions = PieceVec::new_const();
+        let mut enemy_positions = PieceVec::new_const();
+
+        for (i, piece) in self.tiles.iter().enumerate() {
+            match piece {
+                Some(color) if *color == player => {
+                    player_positions.push(PiecePosition::Board(i as u8))
+                }
+                // Other colors only share the board in games of more than two players
+                Some(color) if *color == enemy => {
+                    enemy_positions.push(PiecePosition::Board(i as u8))
+                }
+                _ => {}
+            }
+        }
+
+        let player_goal = &self.goals[player as usize];
+
+        for (i, piece) in player_goal.iter().enumerate() {
+            if piece.is_some() {
+                player_positions.push(PiecePosition::Goal(i as u8))
+            }
+        }
+
+        let enemy_goal = &self.goals[enemy as usize];
+
+        for (i, piece) in enemy_goal.iter().enumerate() {
+            if piece.is_some() {
+                enemy_positions.push(PiecePosition::Goal(i as u8))
+            }
+        }
+
+        player_positions.sort();
+        enemy_positions.sort();
+
+        (player_positions, enemy_positions)
+    }
+
+    pub fn get_moves(&self, dice: u8, player: PlayerColor, enemy: PlayerColor) -> MoveVec {
+        let mut moves = MoveVec::new_const();
+
+        let home_base = &self.home_bases[player as usize];
+        let goal = &self.goals[player as usize];
+        let (pieces, _) = self.get_pieces(player, enemy);
+        let player_start = Self::get_start(player);
+
+        if home_base.pieces_waiting > 0 && dice == 6 {
+            match self.tiles[player_start as usize] {
+                Some(other_piece) if other_piece != player => {
+                    moves.push(StruggleMove::AddNewPiece { eats: true });
+                }
+                None => {
+                    moves.push(StruggleMove::AddNewPiece { eats: false });
+                }
+                _ => {}
+            }
+        }
+
+        for piece in pieces {
+            match piece {
+                PiecePosition::Board(current_pos) => {
+                    let current_pos = *current_pos;
+
+                    match DESTINATIONS[player as usize][current_pos as usize][dice as usize - 1] {
+                        Destination::Goal(pos) => {
+                            if let Some(None) = goal.get(pos as usize) {
+                                moves.push(StruggleMove::MoveToGoal {
+                                    from_board: current_pos,
+                                    to_goal: pos,
+                                });
+                            }
+                        }
+                        Destination::Board(new_pos) => match self.tiles[new_pos as usize] {
+                            None => {
+                                moves.push(StruggleMove::MovePiece {
+                                    from: current_pos,
+                                    to: new_pos,
+                                    eats: false,
+                                });
+                            }
+                            Some(other_piece) if other_piece != player => {
+                                moves.push(StruggleMove::MovePiece {
+                                    from: current_pos,
+                                    to: new_pos,
+                                    eats: true,
+                                });
+                            }
+                            _ => {}
+                        },
+                    }
+                }
+                PiecePosition::Goal(i) => {
+                    let new_pos = i + dice;
+
+                    if let Some(None) = goal.get(new_pos as usize) {
+                        moves.push(StruggleMove::MoveInGoal {
+                            from_goal: *i,
+                            to_goal: new_pos,
+                        });
+                    }
+                }
+            }
+        }
+
+        if moves.is_empty() {
+            moves.push(StruggleMove::SkipTurn);
+        }
+
+        moves
+    }
+
+    pub fn perform_move(&mut self, player: PlayerColor, mov: &StruggleMove) {
+        let before = self.zobrist_of_move_cells(player, mov);
+        let eaten = self.eaten_by(player, mov);
+
+        match mov {
+            StruggleMove::AddNewPiece { eats } => {
+                let start = Self::get_start(player);
+
+                if *eats {
+                    let other_player =
+                        self.tiles[start as usize].expect("expected enemy piece at start");
+                    self.home_bases[other_player as usize].add_piece();
+                }
+
+                self.tiles[start as usize] = Some(player);
+                self.home_bases[player as usize]
+                    .remove_piece()
+                    .expect("Player should have pieces left in home base");
+            }
+            StruggleMove::MovePiece { from, to, eats } => {
+                if *eats {
+                    let target_player = self.tiles[*to as usize]
+                        .expect("expecting eating move to have piece in target");
+                    self.home_bases[target_player as usize].add_piece();
+                }
+
+                self.tiles[*to as usize] = self.tiles[*from as usize];
+                self.tiles[*from as usize] = None;
+            }
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => {
+                self.goals[player as usize][*to_goal as usize] = self.tiles[*from_board as usize];
+                self.tiles[*from_board as usize] = None;
+            }
+            StruggleMove::MoveInGoal { from_goal, to_goal } => {
+                self.goals[player as usize][*to_goal as usize] =
+                    self.goals[player as usize][*from_goal as usize];
+                self.goals[player as usize][*from_goal as usize] = None;
+            }
+            StruggleMove::SkipTurn => {}
+        }
+
+        self.zobrist ^= before ^ self.zobrist_of_move_cells(player, mov);
+        self.patch_piece_cache(player, mov, eaten);
+    }
+
+    // The piece `mov` eats, if any
+    fn eaten_by(&self, player: PlayerColor, mov: &StruggleMove) -> BoardCell {
+        match *mov {
+            StruggleMove::AddNewPiece { eats: true } => {
+                self.tiles[Self::get_start(player) as usize]
+            }
+            StruggleMove::MovePiece { to, eats: true, .. } => self.tiles[to as usize],
+            _ => None,
+        }
+    }
+
+    // Moves the piece `mov` moved in the piece cache, and drops the piece it ate, instead of
+    // scanning the whole board again. Both lists stay sorted like `get_pieces_internal` sorts them.
+    fn patch_piece_cache(&mut self, player: PlayerColor, mov: &StruggleMove, eaten: BoardCell) {
+        let (own, enemy, enemy_color) = if player == self.players.0 {
+            (
+                &mut self.piece_cache.0,
+                &mut self.piece_cache.1,
+                self.players.1,
+            )
+        } else if player == self.players.1 {
+            (
+                &mut self.piece_cache.1,
+                &mut self.piece_cache.0,
+                self.players.0,
+            )
+        } else {
+            // A third color in a game of more than two players, whose pieces aren't cached
+            self.refresh_piece_cache();
+            return;
+        };
+
+        let (from, to) = match *mov {
+            StruggleMove::AddNewPiece { .. } => {
+                (None, PiecePosition::Board(Self::get_start(player)))
+            }
+            StruggleMove::MovePiece { from, to, .. } => {
+                (Some(PiecePosition::Board(from)), PiecePosition::Board(to))
+            }
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => (
+                Some(PiecePosition::Board(from_board)),
+                PiecePosition::Goal(to_goal),
+            ),
+            StruggleMove::MoveInGoal { from_goal, to_goal } => (
+                Some(PiecePosition::Goal(from_goal)),
+                PiecePosition::Goal(to_goal),
+            ),
+            StruggleMove::SkipTurn => return,
+        };
+
+        if let Some(from) = from {
+            remove_piece(own, from);
+        }
+
+        let index = own.partition_point(|&piece| piece < to);
+        own.insert(index, to);
+
+        if eaten == Some(enemy_color) {
+            remove_piece(enemy, to);
+        }
+    }
+
+    /// Rebuilds the piece cache and the Zobrist hash. Needed after changing the board's fields
+    /// directly; `perform_move` keeps both up to date by itself.
+    pub fn update_piece_cache(&mut self) {
+        self.refresh_piece_cache();
+        self.zobrist = zobrist::hash(self);
+    }
+
+    fn refresh_piece_cache(&mut self) {
+        self.piece_cache = self.get_pieces_internal(self.players.0, self.players.1);
+    }
+
+    /// The Zobrist hash of the position, which doesn't include the player to move. Kept up to date
+    /// by `perform_move`, so this is free; see `zobrist` for how it's computed.
+    pub fn zobrist_hash(&self) -> u64 {
+        self.zobrist
+    }
+
+    // The Zobrist keys of the cells `mov` can change: the tiles and goal slots it touches, and
+    // the home bases, since eaten pieces return to theirs
+    fn zobrist_of_move_cells(&self, player: PlayerColor, mov: &StruggleMove) -> u64 {
+        let tile = |index: u8| zobrist::tile_key(index, self.tiles[index as usize]);
+        let goal =
+            |slot: u8| zobrist::goal_key(player, slot, self.goals[player as usize][slot as usize]);
+
+        let cells = match *mov {
+            StruggleMove::AddNewPiece { .. } => tile(Self::get_start(player)),
+            StruggleMove::MovePiece { from, to, .. } => tile(from) ^ tile(to),
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => tile(from_board) ^ goal(to_goal),
+            StruggleMove::MoveInGoal { from_goal, to_goal } => goal(from_goal) ^ goal(to_goal),
+            StruggleMove::SkipTurn => 0,
+        };
+
+        COLORS.iter().fold(cells, |hash, &color| {
+            hash ^ zobrist::waiting_key(color, self.home_bases[color as usize].pieces_waiting)
+        })
+    }
+
+    /// Performs `mov` like `perform_move`, returning what `unmake_move` needs to take it back.
+    /// Lets a search walk the tree on a single board instead of copying it at every node.
+    pub fn make_move(&mut self, player: PlayerColor, mov: &StruggleMove) -> Undo {
+        let eaten = self.eaten_by(player, mov);
+
+        let undo = Undo {
+            player,
+            mov: mov.clone(),
+            eaten,
+            piece_cache: self.piece_cache.clone(),
+            zobrist: self.zobrist,
+        };
+
+        self.perform_move(player, mov);
+        undo
+    }
+
+    /// Takes back the latest move made with `make_move`.
+    pub fn unmake_move(&mut self, undo: Undo) {
+        let Undo {
+            player,
+            mov,
+            eaten,
+            piece_cache,
+            zobrist,
+        } = undo;
+
+        let goal = &mut self.goals[player as usize];
+
+        match mov {
+            StruggleMove::AddNewPiece { .. } => {
+                self.tiles[Self::get_start(player) as usize] = eaten;
+                self.home_bases[player as usize].add_piece();
+            }
+            StruggleMove::MovePiece { from, to, .. } => {
+                self.tiles[from as usize] = self.tiles[to as usize];
+                self.tiles[to as usize] = eaten;
+            }
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => {
+                self.tiles[from_board as usize] = goal[to_goal as usize].take();
+            }
+            StruggleMove::MoveInGoal { from_goal, to_goal } => {
+                goal[from_goal as usize] = goal[to_goal as usize].take();
+            }
+            StruggleMove::SkipTurn => {}
+        }
+
+        if let Some(color) = eaten {
+            self.home_bases[color as usize]
+                .remove_piece()
+                .expect("An eaten piece should be in its home base");
+        }
+
+        self.piece_cache = piece_cache;
+        self.zobrist = zobrist;
+    }
+
+    pub fn with_move(&self, player: PlayerColor, mov: &StruggleMove) -> Cow<'_, Self> {
+        match mov {
+            StruggleMove::SkipTurn => Cow::Borrowed(self),
+            otherwise => {
+                let mut board = self.clone();
+                board.perform_move(player, otherwise);
+                Cow::Owned(board)
+            }
+        }
+    }
+
+    pub fn clockwise_distance(&self, from: u8, to: u8) -> u8 {
+        if to >= from {
+            to - from
+        } else {
+            self.tiles.len() as u8 - from + to
+        }
+    }
+
+    /// Calculates to distance to the last slot on the board before the goal for a piece at a given board position.
+    pub fn distance_to_goal_entrance(&self, player: PlayerColor, pos: u8) -> u8 {
+        let goal = match player {
+            PlayerColor::Red => 27,
+            _ => Self::get_start(player) - 1,
+        };
+
+        self.clockwise_distance(pos, goal)
+    }
+
+    /// Computes the distance to a particular goal slot (0-3) for a piece at a given board position.
+    pub fn distance_to_goal_slot(&self, player: PlayerColor, board_pos: u8, goal_pos: u8) -> u8 {
+        assert!(goal_pos < 4, "goal_pos must be in range 0-3");
+        let distance_to_goal = self.distance_to_goal_entrance(player, board_pos);
+        distance_to_goal + goal_pos + 1
+    }
+
+    pub fn pieces_in_goal(&self, player: PlayerColor) -> u8 {
+        self.goals[player as usize].into_iter().flatten().count() as u8
+    }
+
+    pub fn piece_counts(&self, player: PlayerColor) -> PieceCounts {
+        PieceCounts {
+            home: self.home_bases[player as usize].pieces_waiting,
+            board: self
+                .tiles
+                .iter()
+                .filter(|&&tile| tile == Some(player))
+                .count() as u8,
+            goal: self.pieces_in_goal(player),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct HomeBase {
+    pub pieces_waiting: u8,
+}
+
+impl Default for HomeBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HomeBase {
+    pub fn new() -> HomeBase {
+        HomeBase { pieces_waiting: 4 }
+    }
+
+    pub fn remove_piece(&mut self) -> Option<()> {
+        if self.pieces_waiting > 0 {
+            self.pieces_waiting -= 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    pub fn add_piece(&mut self) {
+        self.pieces_waiting += 1;
+    }
+
+    pub fn can_add_piece(&self) -> bool {
+        self.pieces_waiting > 0
+    }
+}
+
+type Goal = [BoardCell; 4];
+
+/// A move made with `Board::make_move`, with what it changed.
+#[derive(Clone, Debug)]
+pub struct Undo {
+    player: PlayerColor,
+    mov: StruggleMove,
+    eaten: BoardCell,
+    piece_cache: (PieceVec, PieceVec),
+    zobrist: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StruggleMove {
+    AddNewPiece { eats: bool },
+    MovePiece { from: u8, to: u8, eats: bool },
+    MoveToGoal { from_board: u8, to_goal: u8 },
+    MoveInGoal { from_goal: u8, to_goal: u8 },
+    SkipTurn,
+}
+
+impl StruggleMove {
+    pub fn eats(&self) -> bool {
+        match self {
+            StruggleMove::AddNewPiece { eats } => *eats,
+            StruggleMove::MovePiece { eats, .. } => *eats,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn boards_round_trip_through_json() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Yellow);
+        board.goals[PlayerColor::Red as usize][2] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let json = serde_json::to_string(&board).unwrap();
+        let parsed: Board = serde_json::from_str(&json).unwrap();
+
+        // The piece cache isn't serialized, but it's rebuilt
+        assert!(parsed == board);
+        assert!(!json.contains("piece_cache"));
+    }
+
+    #[test]
+    fn unmake_move_restores_the_board() {
+        use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(3);
+        let (a, b) = (PlayerColor::Red, PlayerColor::Yellow);
+        let mut board = Board::new(a, b);
+        let mut to_move = a;
+
+        while board.get_winner().is_none() {
+            let dice = rng.gen_range(1..=6);
+            let enemy = if to_move == a { b } else { a };
+            let moves = board.get_moves(dice, to_move, enemy);
+
+            // Every move is taken back before one of them is played for real
+            for mov in &moves {
+                let before = board.clone();
+                let undo = board.make_move(to_move, mov);
+                board.unmake_move(undo);
+
+                assert!(board == before, "{:?}", mov);
+            }
+
+            board.perform_move(to_move, &moves[rng.gen_range(0..moves.len())]);
+
+            if dice != 6 {
+                to_move = enemy;
+            }
+        }
+    }
+
+    #[test]
+    fn patched_piece_cache_matches_a_rebuilt_one() {
+        use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+        let mut rng = SmallRng::seed_from_u64(8);
+        let (a, b) = (PlayerColor::Green, PlayerColor::Blue);
+
+        for _ in 0..20 {
+            let mut board = Board::new(a, b);
+            let mut to_move = a;
+
+            while board.get_winner().is_none() {
+                let dice = rng.gen_range(1..=6);
+                let enemy = if to_move == a { b } else { a };
+                let moves = board.get_moves(dice, to_move, enemy);
+
+                board.perform_move(to_move, &moves[rng.gen_range(0..moves.len())]);
+                assert_eq!(board.piece_cache, board.get_pieces_internal(a, b));
+
+                if dice != 6 {
+                    to_move = enemy;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn red_goal_move_1() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[27] = Some(PlayerColor::Red);
+        board.update_piece_cache();
+        let moves = board.get_moves(1, PlayerColor::Red, PlayerColor::Yellow);
+
+        assert_eq!(moves.len(), 1);
+        assert_eq!(
+            moves[0],
+            StruggleMove::MoveToGoal {
+                from_board: 27,
+                to_goal: 0
+            }
+        );
+
+        board.perform_move(PlayerColor::Red, &moves[0]);
+
+        assert_eq!(board.tiles[27], None);
+        assert_eq!(
+            board.goals[PlayerColor::Red as usize][0],
+            Some(PlayerColor::Red)
+        );
+    }
+
+    #[test]
+    fn red_goal_move_2() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[26] = Some(PlayerColor::Red);
+        board.update_piece_cache();
+        let moves = board.get_moves(2, PlayerColor::Red, PlayerColor::Yellow);
+
+        assert_eq!(moves.len(), 1);
+        assert_eq!(
+            moves[0],
+            StruggleMove::MoveToGoal {
+                from_board: 26,
+                to_goal: 0
+            }
+        );
+
+        board.perform_move(PlayerColor::Red, &moves[0]);
+
+        assert_eq!(board.tiles[27], None);
+        assert_eq!(
+            board.goals[PlayerColor::Red as usize][0],
+            Some(PlayerColor::Red)
+        );
+    }
+
+    #[test]
+    fn yellow_move_around_red_home() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[27] = Some(PlayerColor::Yellow);
+        board.update_piece_cache();
+        let moves = board.get_moves(1, PlayerColor::Yellow, PlayerColor::Red);
+
+        assert_eq!(moves.len(), 1);
+        assert_eq!(
+            moves[0],
+            StruggleMove::MovePiece {
+                from: 27,
+                to: 0,
+                eats: false
+            }
+        );
+
+        board.perform_move(PlayerColor::Yellow, &moves[0]);
+
+        assert_eq!(board.tiles[27], None);
+        assert_eq!(board.tiles[0], Some(PlayerColor::Yellow));
+    }
+
+    #[test]
+    fn yellow_distance_to_goal() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Yellow, 0), 13);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Yellow, 27), 14);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Yellow, 13), 0);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Yellow, 14), 27);
+    }
+
+    #[test]
+    fn red_distance_to_goal() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Red, 0), 27);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Red, 1), 26);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Red, 27), 0);
+        assert_eq!(board.distance_to_goal_entrance(PlayerColor::Red, 26), 1);
+    }
+
+    #[test]
+    fn clockwise_distance_1() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        assert_eq!(board.clockwise_distance(0, 1), 1);
+        assert_eq!(board.clockwise_distance(0, 10), 10);
+        assert_eq!(board.clockwise_distance(26, 27), 1);
+        assert_eq!(board.clockwise_distance(27, 0), 1);
+        assert_eq!(board.clockwise_distance(3, 0), 25);
+    }
+
+    #[test]
+    fn destinations_match_the_rules() {
+        for player in COLORS {
+            let start = Board::get_start(player);
+
+            for pos in 0..Board::TILES as u8 {
+                for dice in 1..=6 {
+                    let new_pos = (pos + dice) % Board::TILES as u8;
+
+                    let expected = if player == PlayerColor::Red && new_pos < pos {
+                        Destination::Goal(new_pos)
+                    } else if player != PlayerColor::Red && pos < start && new_pos >= start {
+                        Destination::Goal(new_pos - start)
+                    } else {
+                        Destination::Board(new_pos)
+                    };
+
+                    assert_eq!(
+                        DESTINATIONS[player as usize][pos as usize][dice as usize - 1],
+                        expected
+                    );
+                }
+            }
+        }
+    }
+}
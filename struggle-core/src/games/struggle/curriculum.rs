@@ -0,0 +1,161 @@
+//! Curriculum training of the learned linear evaluation.
+//!
+//! The learner starts out against `RandomPlayer`, and the opponent is promoted to the next
+//! stage once the learner wins often enough. Every round plays more games between the learner
+//! and the current opponent, and retrains the learner on every position seen so far.
+
+use crate::{
+    game::{game_seed, NamedPlayer},
+    registry::{struggle_player, BoxedStrugglePlayer},
+    session::run_match,
+};
+
+use super::{
+    features::FEATURES,
+    linear::{self_play_examples, Example, LearnedLinearHeuristic, TrainingOptions},
+};
+
+/// The opponents from weakest to strongest, as player specs.
+pub const DEFAULT_STAGES: &[&str] = &[
+    "random",
+    "random_eater",
+    "score_move",
+    "expectiminimax:1",
+    "expectiminimax:2",
+    "expectiminimax:3",
+];
+
+#[derive(Clone, Debug)]
+pub struct CurriculumOptions {
+    /// The win rate against the current opponent needed for promotion
+    pub target_win_rate: f64,
+    /// Training games against the current opponent per round
+    pub games_per_round: u64,
+    /// Evaluation games against the current opponent per round
+    pub eval_games: u32,
+    /// Rounds to spend on a stage before giving up on the rest of the curriculum
+    pub max_rounds: usize,
+    /// Search depth of the learner
+    pub depth: u8,
+    pub training: TrainingOptions,
+}
+
+impl Default for CurriculumOptions {
+    fn default() -> Self {
+        Self {
+            target_win_rate: 0.6,
+            games_per_round: 2_000,
+            eval_games: 2_000,
+            max_rounds: 5,
+            depth: 1,
+            training: TrainingOptions::default(),
+        }
+    }
+}
+
+/// The outcome of one round of a stage.
+#[derive(Clone, Debug)]
+pub struct RoundReport {
+    pub stage: usize,
+    pub opponent: String,
+    pub round: usize,
+    /// Training positions seen so far, over all stages
+    pub examples: usize,
+    pub win_rate: f64,
+    pub promoted: bool,
+}
+
+pub struct CurriculumResult {
+    pub heuristic: LearnedLinearHeuristic,
+    /// The number of stages the learner passed
+    pub stages_passed: usize,
+}
+
+/// Builds the opponents of a curriculum from player specs, see `registry`.
+pub fn stages(specs: &[&str]) -> Result<Vec<BoxedStrugglePlayer>, String> {
+    specs.iter().map(|spec| struggle_player(spec)).collect()
+}
+
+/// Trains a learner through `stages` in order, calling `log` after every round.
+pub fn run_curriculum(
+    stages: &[BoxedStrugglePlayer],
+    options: &CurriculumOptions,
+    mut log: impl FnMut(&RoundReport),
+) -> CurriculumResult {
+    let mut heuristic = LearnedLinearHeuristic {
+        weights: [0.0; FEATURES],
+    };
+    let mut examples: Vec<Example> = Vec::new();
+    let mut games_played = 0;
+
+    for (stage, opponent) in stages.iter().enumerate() {
+        let mut promoted = false;
+
+        for round in 0..options.max_rounds {
+            let learner: BoxedStrugglePlayer = Box::new(heuristic.clone().player(options.depth));
+
+            examples.extend(self_play_examples(
+                &learner,
+                opponent,
+                options.games_per_round,
+                game_seed(options.training.seed, games_played),
+            ));
+            games_played += options.games_per_round;
+
+            heuristic = LearnedLinearHeuristic::train(&examples, &options.training);
+
+            let learner: BoxedStrugglePlayer = Box::new(heuristic.clone().player(options.depth));
+            let win_rate = run_match(&learner, opponent, options.eval_games, |_| {}).a_win_rate();
+            promoted = win_rate >= options.target_win_rate;
+
+            log(&RoundReport {
+                stage,
+                opponent: opponent.name().into_owned(),
+                round,
+                examples: examples.len(),
+                win_rate,
+                promoted,
+            });
+
+            if promoted {
+                break;
+            }
+        }
+
+        if !promoted {
+            return CurriculumResult {
+                heuristic,
+                stages_passed: stage,
+            };
+        }
+    }
+
+    CurriculumResult {
+        heuristic,
+        stages_passed: stages.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn learner_passes_the_first_stage() {
+        let options = CurriculumOptions {
+            target_win_rate: 0.55,
+            games_per_round: 200,
+            eval_games: 400,
+            depth: 0,
+            ..Default::default()
+        };
+
+        let mut reports = Vec::new();
+        let result = run_curriculum(&stages(&["random"]).unwrap(), &options, |report| {
+            reports.push(report.clone())
+        });
+
+        assert_eq!(result.stages_passed, 1);
+        assert!(reports.last().unwrap().promoted);
+    }
+}
@@ -0,0 +1,306 @@
+//! Distillation of a deep expectiminimax search into a cheap linear policy.
+//!
+//! Positions visited by some player are labeled with the moves a deep search prefers, and a
+//! linear model over the `features` of the position after each move is trained to pick the same
+//! moves, with a softmax over the legal moves. The model plays without any search, so it's
+//! orders of magnitude cheaper per move than its teacher.
+
+use std::{borrow::Cow, time::Instant};
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{game_seed, GamePlayer, NamedPlayer, RaceGame, TurnResult};
+
+use super::{
+    board::{Board, MoveVec, StruggleMove},
+    features::{extract, Features, FEATURES},
+    players::{default_heuristic, GameContext, GameTreePlayer, HeuristicFunction, StrugglePlayer},
+    AiStrugglePlayer, PlayerColor, StruggleGame, StruggleRules,
+};
+
+/// A position where the player had a choice, with the teacher's scores for every move.
+#[derive(Clone)]
+pub struct LabeledPosition {
+    pub board: Board,
+    pub ctx: GameContext,
+    pub moves: MoveVec,
+    pub scores: Vec<f64>,
+}
+
+impl LabeledPosition {
+    /// The index of the teacher's favorite move.
+    pub fn best(&self) -> usize {
+        self.scores
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, _)| index)
+            .unwrap()
+    }
+
+    fn candidates(&self) -> Vec<Features> {
+        self.moves
+            .iter()
+            .map(|mov| after_move(&self.board, &self.ctx, mov))
+            .collect()
+    }
+}
+
+fn after_move(board: &Board, ctx: &GameContext, mov: &StruggleMove) -> Features {
+    extract(
+        &board.with_move(ctx.current_player, mov),
+        ctx.current_player,
+        ctx.other_player,
+    )
+}
+
+/// The default expectiminimax player, as a concrete type so that its move scores can be read.
+pub fn teacher(depth: u8) -> GameTreePlayer<HeuristicFunction> {
+    GameTreePlayer::new(
+        default_heuristic as HeuristicFunction,
+        depth,
+        "Expectiminimax",
+    )
+}
+
+/// Plays `games` seeded games between `explorer` and itself, and labels every position with a
+/// choice with the scores of `teacher`.
+pub fn label_positions<P: StrugglePlayer>(
+    explorer: &P,
+    teacher: &GameTreePlayer<HeuristicFunction>,
+    games: u64,
+    seed: u64,
+) -> Vec<LabeledPosition> {
+    (0..games)
+        .into_par_iter()
+        .flat_map_iter(|game| {
+            let mut rng = SmallRng::seed_from_u64(game_seed(seed, game));
+            let mut teacher = teacher.clone();
+            let mut state = StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, explorer.clone()),
+                AiStrugglePlayer::new(PlayerColor::Yellow, explorer.clone()),
+                false,
+            );
+
+            // Randomly select who starts, like play_game
+            if rng.gen() {
+                state.set_current_player(state.other_player());
+            }
+
+            let mut positions = Vec::new();
+
+            loop {
+                let ctx = state.create_turn_context(state.throw_dice(&mut rng));
+                let moves = state.get_moves(&ctx);
+
+                if moves.len() > 1 {
+                    positions.push(LabeledPosition {
+                        board: state.board().clone(),
+                        ctx,
+                        moves: moves.clone(),
+                        scores: teacher.evaluate_moves(&ctx, state.board(), &moves, &mut rng),
+                    });
+                }
+
+                let mov = state.select_move(&ctx, &moves, &mut rng).clone();
+
+                match state.apply_move(&ctx, &mov) {
+                    TurnResult::PlayAgain => {}
+                    TurnResult::PassTo(player) => state.set_current_player(player),
+                    TurnResult::EndGame { .. } => break,
+                }
+            }
+
+            positions
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct DistillOptions {
+    pub epochs: usize,
+    pub learning_rate: f64,
+    /// L2 regularization strength
+    pub l2: f64,
+    /// Seeds the order in which positions are visited
+    pub seed: u64,
+}
+
+impl Default for DistillOptions {
+    fn default() -> Self {
+        Self {
+            epochs: 10,
+            learning_rate: 0.05,
+            l2: 1e-4,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DistilledPolicy {
+    pub weights: Features,
+}
+
+impl DistilledPolicy {
+    /// Fits the weights to the teacher's choices with SGD on the softmax cross-entropy.
+    pub fn train(positions: &[LabeledPosition], options: &DistillOptions) -> Self {
+        let examples = positions
+            .iter()
+            .map(|position| (position.candidates(), position.best()))
+            .collect::<Vec<_>>();
+
+        // SGD runs on features scaled to unit size, like in `linear`
+        let candidates = examples.iter().flat_map(|(candidates, _)| candidates);
+        let count = candidates.clone().count().max(1) as f64;
+        let scale: Features = std::array::from_fn(|index| {
+            let mean_square = candidates.clone().map(|f| f[index].powi(2)).sum::<f64>() / count;
+
+            if mean_square > 0.0 {
+                1.0 / mean_square.sqrt()
+            } else {
+                0.0
+            }
+        });
+
+        let mut rng = SmallRng::seed_from_u64(options.seed);
+        let mut order = (0..examples.len()).collect::<Vec<_>>();
+        let mut weights = [0.0; FEATURES];
+
+        for _ in 0..options.epochs {
+            order.shuffle(&mut rng);
+
+            for &index in &order {
+                let (candidates, best) = &examples[index];
+
+                let scaled = candidates
+                    .iter()
+                    .map(|features| scale_features(features, &scale))
+                    .collect::<Vec<_>>();
+
+                let probabilities = softmax(&scaled, &weights);
+                let mut gradient = [0.0; FEATURES];
+
+                for (candidate, (features, p)) in scaled.iter().zip(probabilities).enumerate() {
+                    let error = p - (candidate == *best) as u8 as f64;
+
+                    for (g, x) in gradient.iter_mut().zip(features) {
+                        *g += error * x;
+                    }
+                }
+
+                for (weight, g) in weights.iter_mut().zip(gradient) {
+                    *weight -= options.learning_rate * (g + options.l2 * *weight);
+                }
+            }
+        }
+
+        Self {
+            weights: std::array::from_fn(|index| weights[index] * scale[index]),
+        }
+    }
+
+    pub fn score(&self, features: &Features) -> f64 {
+        self.weights.iter().zip(features).map(|(w, x)| w * x).sum()
+    }
+
+    /// The index of the move the policy prefers.
+    pub fn choose(&self, ctx: &GameContext, board: &Board, moves: &[StruggleMove]) -> usize {
+        moves
+            .iter()
+            .map(|mov| self.score(&after_move(board, ctx, mov)))
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(index, _)| index)
+            .unwrap()
+    }
+
+    /// The share of `positions` where the policy picks the teacher's favorite move.
+    pub fn agreement(&self, positions: &[LabeledPosition]) -> f64 {
+        let agreed = positions
+            .iter()
+            .filter(|p| self.choose(&p.ctx, &p.board, &p.moves) == p.best())
+            .count();
+
+        agreed as f64 / positions.len() as f64
+    }
+
+    pub fn player(self) -> DistilledPlayer {
+        DistilledPlayer { policy: self }
+    }
+}
+
+fn scale_features(features: &Features, scale: &Features) -> Features {
+    std::array::from_fn(|index| features[index] * scale[index])
+}
+
+fn softmax(candidates: &[Features], weights: &Features) -> Vec<f64> {
+    let logits = candidates
+        .iter()
+        .map(|features| {
+            weights
+                .iter()
+                .zip(features)
+                .map(|(w, x)| w * x)
+                .sum::<f64>()
+        })
+        .collect::<Vec<_>>();
+
+    let max = logits.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exps = logits.iter().map(|l| (l - max).exp()).collect::<Vec<_>>();
+    let total = exps.iter().sum::<f64>();
+
+    exps.into_iter().map(|e| e / total).collect()
+}
+
+#[derive(Clone)]
+pub struct DistilledPlayer {
+    pub policy: DistilledPolicy,
+}
+
+impl NamedPlayer for DistilledPlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("Distilled")
+    }
+}
+
+impl GamePlayer<StruggleRules> for DistilledPlayer {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &'a [StruggleMove],
+        _rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        &moves[self.policy.choose(ctx, board, moves)]
+    }
+}
+
+/// The average time in microseconds `player` takes to choose a move in `positions`.
+pub fn micros_per_move(player: &mut impl StrugglePlayer, positions: &[LabeledPosition]) -> f64 {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let start = Instant::now();
+
+    for position in positions {
+        player.select_move(&position.ctx, &position.board, &position.moves, &mut rng);
+    }
+
+    start.elapsed().as_secs_f64() * 1e6 / positions.len().max(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::RandomPlayer;
+
+    #[test]
+    fn distilled_policy_agrees_with_its_teacher() {
+        let positions = label_positions(&RandomPlayer, &teacher(0), 30, 5);
+        let policy = DistilledPolicy::train(&positions, &DistillOptions::default());
+
+        // Random play picks the teacher's move less than half of the time
+        assert!(policy.agreement(&positions) > 0.5);
+    }
+}
@@ -0,0 +1,241 @@
+//! A player backed by an external program speaking the engine protocol of `struggle-engine`,
+//! so that bots written in any language can play against the built-in ones.
+//!
+//! Every clone of the player runs its own copy of the program, started on the first move it has
+//! to choose. A program that crashes, hangs past the timeout or answers with an illegal move is
+//! killed and restarted for the next move, and a random legal move is played in its place.
+//! After too many failures in a row the player gives up on the program and plays randomly.
+
+use std::{
+    borrow::Cow,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::SmallRng, seq::SliceRandom};
+use tracing::warn;
+
+use crate::game::{GamePlayer, NamedPlayer, PlayerConfig};
+
+use super::{
+    board::{Board, StruggleMove},
+    notation::{format_position, parse_move},
+    players::GameContext,
+    StruggleRules,
+};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+const MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    // Filled by a reader thread, so that reads can time out
+    lines: Receiver<String>,
+}
+
+impl EngineProcess {
+    fn spawn(command: &[String], timeout: Duration) -> Result<Self, String> {
+        let (program, args) = command.split_first().ok_or("Empty engine command")?;
+
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|err| format!("Failed to start '{}': {}", program, err))?;
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut process = Self {
+            child,
+            stdin,
+            lines,
+        };
+
+        process.send("struggle")?;
+        process.wait_for(timeout, |line| (line == "struggleok").then_some(()))?;
+
+        Ok(process)
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.stdin, "{}", line)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|err| format!("Failed to write to the engine: {}", err))
+    }
+
+    /// Reads lines until `f` accepts one, skipping everything else.
+    fn wait_for<T>(
+        &mut self,
+        timeout: Duration,
+        mut f: impl FnMut(&str) -> Option<T>,
+    ) -> Result<T, String> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+
+            let line = self
+                .lines
+                .recv_timeout(remaining)
+                .map_err(|err| match err {
+                    mpsc::RecvTimeoutError::Timeout => "The engine timed out".to_string(),
+                    mpsc::RecvTimeoutError::Disconnected => "The engine exited".to_string(),
+                })?;
+
+            if let Some(value) = f(line.trim()) {
+                return Ok(value);
+            }
+        }
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+pub struct ExternalPlayer {
+    command: Arc<Vec<String>>,
+    timeout: Duration,
+    process: Mutex<Option<EngineProcess>>,
+    failures: u32,
+}
+
+impl ExternalPlayer {
+    /// `command` is the program followed by its arguments.
+    pub fn new(command: Vec<String>) -> Self {
+        Self {
+            command: Arc::new(command),
+            timeout: DEFAULT_TIMEOUT,
+            process: Mutex::new(None),
+            failures: 0,
+        }
+    }
+
+    /// Splits a command line on whitespace, e.g. `python3 bot.py --depth 2`.
+    pub fn from_command_line(command: &str) -> Self {
+        Self::new(command.split_whitespace().map(str::to_string).collect())
+    }
+
+    /// How long the program may think about one move.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn ask(&mut self, ctx: &GameContext, board: &Board) -> Result<StruggleMove, String> {
+        let process = self.process.get_mut().unwrap();
+
+        if process.is_none() {
+            *process = Some(EngineProcess::spawn(&self.command, self.timeout)?);
+        }
+
+        let engine = process.as_mut().unwrap();
+
+        engine.send(&format!(
+            "position {}",
+            format_position(board, ctx.current_player)
+        ))?;
+        engine.send(&format!("go dice {}", ctx.dice))?;
+
+        let answer = engine.wait_for(self.timeout, |line| {
+            line.strip_prefix("bestmove ").map(str::to_string)
+        })?;
+
+        parse_move(answer.trim())
+    }
+}
+
+impl Clone for ExternalPlayer {
+    // Clones start their own process when they first need it
+    fn clone(&self) -> Self {
+        Self {
+            command: self.command.clone(),
+            timeout: self.timeout,
+            process: Mutex::new(None),
+            failures: 0,
+        }
+    }
+}
+
+impl NamedPlayer for ExternalPlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from(format!("External({})", self.command.join(" ")))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name()).with_parameter("timeout_ms", self.timeout.as_millis())
+    }
+}
+
+impl GamePlayer<StruggleRules> for ExternalPlayer {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        if moves.len() == 1 || self.failures >= MAX_CONSECUTIVE_FAILURES {
+            return moves.choose(rng).unwrap();
+        }
+
+        let result = self.ask(ctx, board).and_then(|mov| {
+            moves
+                .iter()
+                .find(|legal| **legal == mov)
+                .ok_or_else(|| format!("The engine played an illegal move {:?}", mov))
+        });
+
+        match result {
+            Ok(mov) => {
+                self.failures = 0;
+                mov
+            }
+            Err(err) => {
+                self.failures += 1;
+                warn!(
+                    engine = %self.name(),
+                    %err,
+                    failures = self.failures,
+                    "restarting the engine"
+                );
+
+                // Dropping the process kills it, the next move starts a fresh one
+                *self.process.get_mut().unwrap() = None;
+
+                moves.choose(rng).unwrap()
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
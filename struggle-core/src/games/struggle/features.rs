@@ -0,0 +1,122 @@
+//! Hand-crafted features of a position, for heuristics with learned or tuned weights.
+//!
+//! Every feature is computed for both sides, and a position is described by the difference
+//! between the player's and the enemy's values, so that swapping the sides negates the features.
+
+use super::{
+    board::{Board, PiecePosition},
+    PlayerColor,
+};
+
+pub const FEATURES: usize = 9;
+
+pub const FEATURE_NAMES: [&str; FEATURES] = [
+    "pieces_on_board",
+    "pieces_in_goal",
+    "pieces_waiting",
+    "advancement",
+    "attacking",
+    "threatened",
+    "can_enter_goal",
+    "blocking_own_start",
+    "on_enemy_start",
+];
+
+pub type Features = [f64; FEATURES];
+
+/// The features of `player`'s side alone, in the order of `FEATURE_NAMES`.
+pub fn side_features(board: &Board, player: PlayerColor, enemy: PlayerColor) -> Features {
+    let (own_pieces, enemy_pieces) = board.get_pieces(player, enemy);
+
+    let own_start = Board::get_start(player);
+    let enemy_start = Board::get_start(enemy);
+    let own_waiting = board.home_bases[player as usize].pieces_waiting;
+    let enemy_waiting = board.home_bases[enemy as usize].pieces_waiting;
+
+    let enemy_tiles = enemy_pieces
+        .iter()
+        .copied()
+        .filter_map(PiecePosition::as_board_index)
+        .collect::<Vec<_>>();
+
+    let mut features = [0.0; FEATURES];
+    features[2] = own_waiting as f64;
+
+    for piece in own_pieces {
+        let tile = match *piece {
+            PiecePosition::Board(tile) => tile,
+            PiecePosition::Goal(_) => {
+                features[1] += 1.0;
+                continue;
+            }
+        };
+
+        features[0] += 1.0;
+        features[3] += 1.0 - board.distance_to_goal_entrance(player, tile) as f64 / 28.0;
+
+        let within_roll = |distance: u8| (1..=6).contains(&distance);
+
+        if enemy_tiles
+            .iter()
+            .any(|enemy| within_roll(board.clockwise_distance(tile, *enemy)))
+        {
+            features[4] += 1.0;
+        }
+
+        if enemy_tiles
+            .iter()
+            .any(|enemy| within_roll(board.clockwise_distance(*enemy, tile)))
+        {
+            features[5] += 1.0;
+        }
+
+        if (0..4u8).any(|slot| {
+            board.goals[player as usize][slot as usize].is_none()
+                && within_roll(board.distance_to_goal_slot(player, tile, slot))
+        }) {
+            features[6] += 1.0;
+        }
+
+        if tile == own_start && own_waiting > 0 {
+            features[7] += 1.0;
+        }
+
+        if tile == enemy_start && enemy_waiting > 0 {
+            features[8] += 1.0;
+        }
+    }
+
+    features
+}
+
+/// The features of the position for `player`: their side's features minus the enemy's.
+pub fn extract(board: &Board, player: PlayerColor, enemy: PlayerColor) -> Features {
+    let own = side_features(board, player, enemy);
+    let theirs = side_features(board, enemy, player);
+
+    std::array::from_fn(|index| own[index] - theirs[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn swapping_sides_negates_the_features() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.tiles[7] = Some(PlayerColor::Yellow);
+        board.goals[PlayerColor::Yellow as usize][3] = Some(PlayerColor::Yellow);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 2;
+        board.update_piece_cache();
+
+        let red = extract(&board, PlayerColor::Red, PlayerColor::Yellow);
+        let yellow = extract(&board, PlayerColor::Yellow, PlayerColor::Red);
+
+        assert_eq!(red, yellow.map(|value| -value));
+        assert_eq!(red[1], -1.0);
+        assert_eq!(red[4], 1.0);
+        assert_eq!(yellow[5], 1.0);
+    }
+}
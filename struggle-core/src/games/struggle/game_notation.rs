@@ -0,0 +1,463 @@
+//! A readable text format for complete games, in the spirit of chess PGN.
+//!
+//! ```text
+//! [Red "Expectiminimax(1)"]
+//! [Yellow "Random"]
+//! [Seed "1234"]
+//! [Rules "struggle"]
+//! [Result "red"]
+//! [Chain "5c0f2e4d9a1b3c78"]
+//!
+//! 1. R: 6 n
+//! 2. R: 3 0-3
+//! 3. Y: 6 nx (eats R)
+//! ```
+//!
+//! Headers are free-form `[Key "value"]` pairs. A `Start` header holds the starting position in
+//! `notation` form, and defaults to `notation::START_POSITION`. Every turn lists the player, the
+//! roll and the move in `notation` form, with an optional comment in parentheses.
+//!
+//! A `Chain` header holds a rolling hash of the position after every turn, see
+//! `NotatedGame::seal`. `NotatedGame::verify` replays the game and checks it, which catches rule
+//! changes that make stored games play out differently than they did when they were recorded.
+
+use std::fmt::{self, Write as _};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    game::{RaceGame, TurnResult},
+    json::color_name,
+};
+
+use super::{
+    board::{Board, StruggleMove},
+    notation::{
+        color_char, format_move, format_position, parse_color, parse_move, parse_position,
+        START_POSITION,
+    },
+    players::StrugglePlayer,
+    transposition_table::get_board_hash,
+    AiStrugglePlayer, PlayerColor, StruggleGame,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotatedTurn {
+    pub player: PlayerColor,
+    pub dice: u8,
+    pub mov: StruggleMove,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NotatedGame {
+    pub headers: Vec<(String, String)>,
+    pub turns: Vec<NotatedTurn>,
+}
+
+/// A game after replaying all of its turns.
+pub struct ReplayedGame {
+    pub board: Board,
+    pub to_move: PlayerColor,
+    pub winner: Option<PlayerColor>,
+    /// The hash chain of the positions after every turn, see `NotatedGame::seal`
+    pub chain: u64,
+}
+
+/// Folds the position after a turn into the hash chain of a game.
+fn chain(hash: u64, board: &Board, to_move: PlayerColor) -> u64 {
+    // The board hash only uses the low 48 bits
+    let position = get_board_hash(board, to_move).bits() ^ (to_move as u64) << 56;
+    (hash ^ position)
+        .wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        .rotate_left(29)
+}
+
+impl NotatedGame {
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets a header, replacing an earlier value with the same key.
+    pub fn set_header(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+
+        match self.headers.iter_mut().find(|(k, _)| k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.headers.push((key.to_string(), value)),
+        }
+    }
+
+    /// Plays a game between `red` and `yellow` with the seed `seed`, recording every turn. The
+    /// game is the same as `play_game_with_rng` plays with an RNG seeded with `seed`.
+    pub fn play<A: StrugglePlayer, B: StrugglePlayer>(red: &A, yellow: &B, seed: u64) -> Self {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut state = StruggleGame::new(
+            AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+            AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+            false,
+        );
+        let mut game = NotatedGame::default();
+
+        game.set_header("Red", red.name());
+        game.set_header("Yellow", yellow.name());
+        game.set_header("Seed", seed.to_string());
+        game.set_header("Rules", "struggle");
+
+        // The same steps as `play_game_with_rng`, so that the RNG is used in the same order
+        if rng.gen() {
+            state.set_current_player(state.other_player());
+            game.set_header(
+                "Start",
+                format_position(state.board(), state.current_player()),
+            );
+        }
+
+        loop {
+            let dice = state.throw_dice(&mut rng);
+            let ctx = state.create_turn_context(dice);
+            let moves = state.get_moves(&ctx);
+            let mov = state.select_move(&ctx, &moves, &mut rng).clone();
+
+            game.turns.push(NotatedTurn {
+                player: ctx.current_player,
+                dice,
+                mov: mov.clone(),
+            });
+
+            match state.apply_move(&ctx, &mov) {
+                TurnResult::PlayAgain => {}
+                TurnResult::PassTo(player) => state.set_current_player(player),
+                TurnResult::EndGame { winner } => {
+                    game.set_header("Result", color_name(winner));
+                    game.seal().expect("Played games replay");
+                    return game;
+                }
+            }
+        }
+    }
+
+    pub fn start_position(&self) -> Result<(Board, PlayerColor), String> {
+        parse_position(self.header("Start").unwrap_or(START_POSITION))
+    }
+
+    /// Plays the turns from the starting position, checking that each move was legal and that
+    /// players took turns correctly.
+    pub fn replay(&self) -> Result<ReplayedGame, String> {
+        self.replay_with(|_, _| {})
+    }
+
+    /// The starting position and the position after every turn, with the player to move, for
+    /// stepping through the game.
+    pub fn positions(&self) -> Result<Vec<(Board, PlayerColor)>, String> {
+        let mut positions = vec![self.start_position()?];
+        self.replay_with(|board, to_move| positions.push((board.clone(), to_move)))?;
+
+        Ok(positions)
+    }
+
+    // `replay`, calling `visit` with the position after every turn
+    fn replay_with(
+        &self,
+        mut visit: impl FnMut(&Board, PlayerColor),
+    ) -> Result<ReplayedGame, String> {
+        let (mut board, mut to_move) = self.start_position()?;
+        let (a, b) = board.players();
+        let mut hash = 0;
+
+        for (index, turn) in self.turns.iter().enumerate() {
+            let number = index + 1;
+
+            if board.get_winner().is_some() {
+                return Err(format!("Turn {} comes after the game is over", number));
+            }
+
+            if turn.player != to_move {
+                return Err(format!(
+                    "Turn {} is played by {:?}, but it's {:?}'s turn",
+                    number, turn.player, to_move
+                ));
+            }
+
+            if !(1..=6).contains(&turn.dice) {
+                return Err(format!("Turn {} has an invalid roll {}", number, turn.dice));
+            }
+
+            let enemy = if to_move == a { b } else { a };
+            let moves = board.get_moves(turn.dice, to_move, enemy);
+
+            if !moves.contains(&turn.mov) {
+                return Err(format!(
+                    "Turn {}: {} isn't a legal move for {:?} with a {}",
+                    number,
+                    format_move(&turn.mov),
+                    to_move,
+                    turn.dice
+                ));
+            }
+
+            board.perform_move(to_move, &turn.mov);
+
+            if turn.dice != 6 {
+                to_move = enemy;
+            }
+
+            hash = chain(hash, &board, to_move);
+            visit(&board, to_move);
+        }
+
+        let winner = board.get_winner();
+
+        Ok(ReplayedGame {
+            board,
+            to_move,
+            winner,
+            chain: hash,
+        })
+    }
+
+    /// Replays the game and records its hash chain in the `Chain` header, so that `verify` can
+    /// later check that the game still plays out the same way.
+    pub fn seal(&mut self) -> Result<(), String> {
+        let chain = self.replay()?.chain;
+        self.set_header("Chain", format!("{:016x}", chain));
+        Ok(())
+    }
+
+    /// Replays the game and checks it against its `Chain` header and its `Result`, if it has one.
+    pub fn verify(&self) -> Result<ReplayedGame, String> {
+        let replayed = self.replay()?;
+
+        let expected = self
+            .header("Chain")
+            .ok_or("The game has no Chain header to verify")?;
+        let actual = format!("{:016x}", replayed.chain);
+
+        if expected != actual {
+            return Err(format!(
+                "The positions don't match the ones recorded, the chain is {} instead of {}",
+                actual, expected
+            ));
+        }
+
+        if let Some(result) = self.header("Result") {
+            if replayed.winner.map(color_name) != Some(result) {
+                return Err(format!(
+                    "The game replays to {:?} instead of the recorded result {}",
+                    replayed.winner, result
+                ));
+            }
+        }
+
+        Ok(replayed)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut game = NotatedGame::default();
+
+        for (line_number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| format!("Line {}: {}", line_number + 1, message);
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                let (key, value) = header
+                    .strip_suffix(']')
+                    .and_then(|header| header.split_once(' '))
+                    .and_then(|(key, value)| {
+                        let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+                        Some((key, value))
+                    })
+                    .ok_or_else(|| error("Expected a header like [Key \"value\"]"))?;
+
+                game.headers
+                    .push((key.to_string(), value.replace("\\\"", "\"")));
+                continue;
+            }
+
+            game.turns
+                .push(parse_turn(line, game.turns.len() + 1).map_err(|err| error(&err))?);
+        }
+
+        Ok(game)
+    }
+}
+
+fn parse_turn(line: &str, expected_number: usize) -> Result<NotatedTurn, String> {
+    // Drop the comment, if any
+    let line = match line.split_once('(') {
+        Some((turn, comment)) if comment.trim_end().ends_with(')') => turn.trim_end(),
+        Some(_) => return Err("Unclosed comment".to_string()),
+        None => line,
+    };
+
+    let invalid = || format!("Expected a turn like '12. R: 6 n', got '{}'", line);
+
+    let (number, rest) = line.split_once('.').ok_or_else(invalid)?;
+    let (player, rest) = rest.trim_start().split_once(':').ok_or_else(invalid)?;
+    let mut fields = rest.split_whitespace();
+
+    let (Some(dice), Some(mov), None) = (fields.next(), fields.next(), fields.next()) else {
+        return Err(invalid());
+    };
+
+    if number.trim().parse::<usize>().ok() != Some(expected_number) {
+        return Err(format!("Expected turn number {}", expected_number));
+    }
+
+    let mut player_chars = player.chars();
+    let player = match (player_chars.next(), player_chars.next()) {
+        (Some(c), None) => parse_color(c.to_ascii_lowercase()).ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+
+    Ok(NotatedTurn {
+        player,
+        dice: dice.parse().map_err(|_| invalid())?,
+        mov: parse_move(mov)?,
+    })
+}
+
+impl fmt::Display for NotatedGame {
+    /// Writes the game, commenting on captures when the turns can be replayed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in &self.headers {
+            writeln!(f, "[{} \"{}\"]", key, value.replace('"', "\\\""))?;
+        }
+
+        if !self.headers.is_empty() {
+            writeln!(f)?;
+        }
+
+        let players = self.start_position().ok().map(|(board, _)| board.players());
+
+        for (index, turn) in self.turns.iter().enumerate() {
+            let mut line = format!(
+                "{}. {}: {} {}",
+                index + 1,
+                color_char(turn.player).to_ascii_uppercase(),
+                turn.dice,
+                format_move(&turn.mov)
+            );
+
+            if let (true, Some((a, b))) = (turn.mov.eats(), players) {
+                let victim = if turn.player == a { b } else { a };
+                write!(line, " (eats {})", color_char(victim).to_ascii_uppercase())?;
+            }
+
+            writeln!(f, "{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    use super::*;
+
+    // Plays random legal moves until someone wins
+    fn random_game(seed: u64) -> NotatedGame {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let (mut board, mut to_move) = parse_position(START_POSITION).unwrap();
+        let (a, b) = board.players();
+        let mut game = NotatedGame::default();
+
+        game.set_header("Red", "Random");
+        game.set_header("Yellow", "Random \"the second\"");
+        game.set_header("Seed", seed.to_string());
+
+        while board.get_winner().is_none() {
+            let dice = rng.gen_range(1..=6);
+            let enemy = if to_move == a { b } else { a };
+            let moves = board.get_moves(dice, to_move, enemy);
+            let mov = moves[rng.gen_range(0..moves.len())].clone();
+
+            board.perform_move(to_move, &mov);
+            game.turns.push(NotatedTurn {
+                player: to_move,
+                dice,
+                mov,
+            });
+
+            if dice != 6 {
+                to_move = enemy;
+            }
+        }
+
+        game
+    }
+
+    #[test]
+    fn games_round_trip_through_text() {
+        let game = random_game(99);
+        let text = game.to_string();
+        let parsed = NotatedGame::parse(&text).unwrap();
+
+        assert_eq!(parsed, game);
+        assert_eq!(parsed.header("Yellow"), Some("Random \"the second\""));
+        assert!(parsed.replay().unwrap().winner.is_some());
+    }
+
+    #[test]
+    fn positions_step_through_the_game() {
+        let game = random_game(5);
+        let positions = game.positions().unwrap();
+
+        assert_eq!(positions.len(), game.turns.len() + 1);
+        // Boards aren't Debug
+        assert!(positions[0].0 == parse_position(START_POSITION).unwrap().0);
+        assert!(positions.last().unwrap().0 == game.replay().unwrap().board);
+    }
+
+    #[test]
+    fn rejects_illegal_turns() {
+        let game = NotatedGame::parse("1. R: 5 n").unwrap();
+        assert!(game.replay().is_err());
+
+        let game = NotatedGame::parse("1. Y: 6 n").unwrap();
+        assert!(game.replay().is_err());
+
+        assert!(NotatedGame::parse("2. R: 6 n").is_err());
+        assert!(NotatedGame::parse("1. R: 6 n (eats").is_err());
+    }
+
+    #[test]
+    fn played_games_replay_to_their_result() {
+        use crate::games::struggle::players::{RandomPlayer, ScoreMovePlayer};
+
+        let game = NotatedGame::play(&ScoreMovePlayer, &RandomPlayer, 9);
+        let replayed = NotatedGame::parse(&game.to_string())
+            .unwrap()
+            .replay()
+            .unwrap();
+
+        assert_eq!(replayed.winner.map(color_name), game.header("Result"));
+    }
+
+    #[test]
+    fn verify_catches_changed_games() {
+        use crate::games::struggle::players::{RandomPlayer, ScoreMovePlayer};
+
+        let game = NotatedGame::play(&ScoreMovePlayer, &RandomPlayer, 4);
+        assert!(NotatedGame::parse(&game.to_string())
+            .unwrap()
+            .verify()
+            .is_ok());
+
+        let mut unsealed = game.clone();
+        unsealed.headers.retain(|(key, _)| key != "Chain");
+        assert!(unsealed.verify().is_err());
+
+        let mut tampered = game.clone();
+        tampered.set_header("Chain", "0000000000000000");
+        assert!(tampered.verify().is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! Audits of board hashes, so that the correctness of the transposition table isn't taken on
+//! faith. Positions are sampled from random games, and the audit counts distinct positions that
+//! share a hash and measures how evenly the hashes use their bits and the slots of a table.
+//!
+//! The audit works with any position type and hash, so that other hashes can be compared with
+//! `get_board_hash` on the same positions, see `struggle-hash-audit`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use super::{
+    board::Board,
+    transposition_table::{get_board_hash, home_slot},
+    PlayerColor,
+};
+
+#[derive(Clone, Debug)]
+pub struct HashAudit {
+    /// Positions audited, including repeats
+    pub positions: u64,
+    pub distinct: usize,
+    /// Distinct positions whose hash was already taken by a different position
+    pub collisions: usize,
+    /// The share of the distinct positions' hashes with each bit set
+    pub bit_frequencies: [f64; 64],
+    pub slots: usize,
+    /// How far the distribution of the distinct positions over `slots` home slots is from
+    /// uniform, as the z-score of its chi-squared statistic. Around ±3 is fine.
+    pub slot_z_score: f64,
+}
+
+impl HashAudit {
+    /// Audits `hash` over `positions`, with `slot` mapping a hash to its home slot of a table.
+    /// `slots` must be a power of two.
+    pub fn run<T: Eq + Hash + Clone>(
+        positions: impl IntoIterator<Item = T>,
+        hash: impl Fn(&T) -> u64,
+        slot: impl Fn(u64) -> usize,
+        slots: usize,
+    ) -> Self {
+        let mut seen = HashSet::new();
+        let mut owners = HashMap::new();
+        let mut bit_counts = [0u64; 64];
+        let mut slot_counts = vec![0u64; slots];
+        let mut total = 0;
+        let mut collisions = 0;
+
+        for position in positions {
+            total += 1;
+
+            if !seen.insert(position.clone()) {
+                continue;
+            }
+
+            let bits = hash(&position);
+
+            if owners.insert(bits, position).is_some() {
+                collisions += 1;
+            }
+
+            for (bit, count) in bit_counts.iter_mut().enumerate() {
+                *count += (bits >> bit) & 1;
+            }
+
+            slot_counts[slot(bits) & (slots - 1)] += 1;
+        }
+
+        let distinct = seen.len();
+        let expected = distinct as f64 / slots as f64;
+        let chi_squared = slot_counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum::<f64>();
+        let freedom = (slots - 1) as f64;
+
+        Self {
+            positions: total,
+            distinct,
+            collisions,
+            bit_frequencies: bit_counts.map(|count| count as f64 / distinct as f64),
+            slots,
+            slot_z_score: (chi_squared - freedom) / (2.0 * freedom).sqrt(),
+        }
+    }
+
+    pub fn collision_rate(&self) -> f64 {
+        self.collisions as f64 / self.distinct as f64
+    }
+
+    /// Bits that are set in every hash or in none, which carry no information.
+    pub fn constant_bits(&self) -> usize {
+        self.bit_frequencies
+            .iter()
+            .filter(|&&frequency| frequency == 0.0 || frequency == 1.0)
+            .count()
+    }
+}
+
+/// Every position after a move in `games` games of random moves between Red and Yellow.
+pub fn sample_boards(games: u64, seed: u64) -> impl Iterator<Item = Board> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+
+    (0..games).flat_map(move |_| {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let mut to_move = PlayerColor::Red;
+        let mut boards = Vec::new();
+
+        while board.get_winner().is_none() {
+            let dice = rng.gen_range(1..=6);
+            let (a, b) = board.players();
+            let enemy = if to_move == a { b } else { a };
+            let moves = board.get_moves(dice, to_move, enemy);
+
+            board.perform_move(to_move, &moves[rng.gen_range(0..moves.len())]);
+            boards.push(board.clone());
+
+            if dice != 6 {
+                to_move = enemy;
+            }
+        }
+
+        boards
+    })
+}
+
+/// Audits `get_board_hash` over `boards`. The hash leaves out the player to move, so every
+/// board is hashed with the same one.
+pub fn audit_board_hash(boards: impl IntoIterator<Item = Board>, slots: usize) -> HashAudit {
+    HashAudit::run(
+        boards,
+        |board| get_board_hash(board, board.players().0).bits(),
+        home_slot,
+        slots,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn board_hashes_dont_collide() {
+        let audit = audit_board_hash(sample_boards(200, 1), 1 << 10);
+
+        assert!(audit.distinct > 1000);
+        assert_eq!(audit.collisions, 0);
+    }
+
+    #[test]
+    fn counts_collisions_of_a_bad_hash() {
+        let audit = HashAudit::run(0..1000u64, |n| n % 10, |bits| bits as usize, 16);
+
+        assert_eq!(audit.distinct, 1000);
+        assert_eq!(audit.collisions, 990);
+        assert_eq!(audit.constant_bits(), 60);
+    }
+}
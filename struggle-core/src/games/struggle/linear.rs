@@ -0,0 +1,221 @@
+//! A linear evaluation learned from game outcomes.
+//!
+//! A logistic model `P(win) = sigmoid(w · features)` is fitted with SGD to positions from
+//! self-play, labeled with whether the player went on to win. The logit is then used as the
+//! heuristic of a `GameTreePlayer`, which only cares about the order of the scores.
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{game_seed, RaceGame, TurnResult};
+
+use super::{
+    board::Board,
+    features::{extract, Features, FEATURES, FEATURE_NAMES},
+    players::{GameTreePlayer, StrugglePlayer, WIN_SCORE},
+    AiStrugglePlayer, PlayerColor, StruggleGame,
+};
+
+/// A position from the point of view of the player who just moved.
+#[derive(Clone, Copy, Debug)]
+pub struct Example {
+    pub features: Features,
+    pub won: bool,
+}
+
+/// Plays `games` seeded games between `red` and `yellow` in parallel, and returns an example of
+/// every position after a move.
+pub fn self_play_examples<A: StrugglePlayer, B: StrugglePlayer>(
+    red: &A,
+    yellow: &B,
+    games: u64,
+    seed: u64,
+) -> Vec<Example> {
+    (0..games)
+        .into_par_iter()
+        .flat_map_iter(|game| {
+            let mut rng = SmallRng::seed_from_u64(game_seed(seed, game));
+            let mut state = StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+                AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+                false,
+            );
+
+            // Randomly select who starts, like play_game
+            if rng.gen() {
+                state.set_current_player(state.other_player());
+            }
+
+            let mut positions = Vec::new();
+
+            let winner = loop {
+                let ctx = state.create_turn_context(state.throw_dice(&mut rng));
+                let moves = state.get_moves(&ctx);
+                let mov = state.select_move(&ctx, &moves, &mut rng).clone();
+                let result = state.apply_move(&ctx, &mov);
+
+                positions.push((
+                    ctx.current_player,
+                    extract(state.board(), ctx.current_player, ctx.other_player),
+                ));
+
+                match result {
+                    TurnResult::PlayAgain => {}
+                    TurnResult::PassTo(player) => state.set_current_player(player),
+                    TurnResult::EndGame { winner } => break winner,
+                }
+            };
+
+            positions
+                .into_iter()
+                .map(move |(player, features)| Example {
+                    features,
+                    won: player == winner,
+                })
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct TrainingOptions {
+    pub epochs: usize,
+    pub learning_rate: f64,
+    /// L2 regularization strength
+    pub l2: f64,
+    /// Seeds the order in which examples are visited
+    pub seed: u64,
+}
+
+impl Default for TrainingOptions {
+    fn default() -> Self {
+        Self {
+            epochs: 10,
+            learning_rate: 0.01,
+            l2: 1e-4,
+            seed: 0,
+        }
+    }
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LearnedLinearHeuristic {
+    pub weights: Features,
+}
+
+impl LearnedLinearHeuristic {
+    /// Fits the weights to `examples` with stochastic gradient descent on the log loss.
+    pub fn train(examples: &[Example], options: &TrainingOptions) -> Self {
+        // The features have very different ranges, so SGD runs on features scaled to unit
+        // variance, and the scale is folded back into the weights at the end. They're centered
+        // on zero already, since every position is seen from both sides.
+        let scale: Features = std::array::from_fn(|index| {
+            let mean_square = examples
+                .iter()
+                .map(|example| example.features[index].powi(2))
+                .sum::<f64>()
+                / examples.len().max(1) as f64;
+
+            if mean_square > 0.0 {
+                1.0 / mean_square.sqrt()
+            } else {
+                0.0
+            }
+        });
+
+        let mut rng = SmallRng::seed_from_u64(options.seed);
+        let mut order = (0..examples.len()).collect::<Vec<_>>();
+        let mut weights = [0.0; FEATURES];
+
+        for _ in 0..options.epochs {
+            order.shuffle(&mut rng);
+
+            for &index in &order {
+                let example = &examples[index];
+                let x: Features = std::array::from_fn(|i| example.features[i] * scale[i]);
+
+                let logit = weights.iter().zip(&x).map(|(w, x)| w * x).sum::<f64>();
+                let error = sigmoid(logit) - example.won as u8 as f64;
+
+                for (weight, x) in weights.iter_mut().zip(&x) {
+                    *weight -= options.learning_rate * (error * x + options.l2 * *weight);
+                }
+            }
+        }
+
+        Self {
+            weights: std::array::from_fn(|index| weights[index] * scale[index]),
+        }
+    }
+
+    /// The log-odds of `player` winning, or the usual win scores in finished games.
+    pub fn evaluate(&self, board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+        match board.get_winner() {
+            Some(winner) if winner == player => return WIN_SCORE,
+            Some(_) => return -WIN_SCORE,
+            None => {}
+        }
+
+        self.logit(&extract(board, player, enemy))
+    }
+
+    pub fn logit(&self, features: &Features) -> f64 {
+        self.weights.iter().zip(features).map(|(w, x)| w * x).sum()
+    }
+
+    pub fn win_probability(&self, board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+        sigmoid(self.logit(&extract(board, player, enemy)))
+    }
+
+    /// The mean log loss of the model on `examples`, 0.693 for a coin flip.
+    pub fn log_loss(&self, examples: &[Example]) -> f64 {
+        let total = examples
+            .iter()
+            .map(|example| {
+                let p = sigmoid(self.logit(&example.features)).clamp(1e-12, 1.0 - 1e-12);
+                if example.won {
+                    -p.ln()
+                } else {
+                    -(1.0 - p).ln()
+                }
+            })
+            .sum::<f64>();
+
+        total / examples.len() as f64
+    }
+
+    /// The weights by feature name.
+    pub fn named_weights(&self) -> impl Iterator<Item = (&'static str, f64)> + '_ {
+        FEATURE_NAMES.into_iter().zip(self.weights)
+    }
+
+    /// An expectiminimax player that searches `depth` moves ahead with this evaluation.
+    pub fn player(self, depth: u8) -> impl StrugglePlayer {
+        GameTreePlayer::new(
+            move |board: &Board, player, enemy| self.evaluate(board, player, enemy),
+            depth,
+            "LearnedLinear",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::{RandomPlayer, ScoreMovePlayer};
+
+    #[test]
+    fn training_beats_a_coin_flip() {
+        let examples = self_play_examples(&RandomPlayer, &ScoreMovePlayer, 200, 1);
+        let heuristic = LearnedLinearHeuristic::train(&examples, &TrainingOptions::default());
+
+        assert!(heuristic.log_loss(&examples) < std::f64::consts::LN_2);
+
+        // Having more pieces in the goal than the enemy is good
+        assert!(heuristic.weights[1] > 0.0);
+    }
+}
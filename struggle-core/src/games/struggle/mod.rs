@@ -0,0 +1,591 @@
+use alloc::vec::Vec;
+
+use arrayvec::ArrayVec;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use crate::game::play_game;
+use crate::game::{
+    play_game_with_rng, CreateGame, GameId, GameRules, GameStats, IntoGameStats, PieceFate,
+    PieceLife, RaceGame, ResetGame, TurnResult,
+};
+
+use self::{
+    board::{Board, StruggleMove},
+    players::{RandomPlayer, StrugglePlayer},
+};
+
+pub mod bitboard;
+pub mod board;
+#[cfg(feature = "std")]
+pub mod curriculum;
+#[cfg(feature = "std")]
+pub mod distill;
+#[cfg(feature = "std")]
+pub mod external;
+#[cfg(feature = "std")]
+pub mod features;
+#[cfg(feature = "std")]
+pub mod game_notation;
+#[cfg(feature = "std")]
+pub mod hash_audit;
+#[cfg(feature = "std")]
+pub mod linear;
+pub mod multi;
+#[cfg(feature = "std")]
+pub mod notation;
+pub mod players;
+#[cfg(feature = "std")]
+pub mod puzzles;
+#[cfg(feature = "std")]
+pub mod rollout;
+#[cfg(feature = "std")]
+pub mod transposition_table;
+#[cfg(feature = "std")]
+pub mod turn_log;
+#[cfg(feature = "std")]
+pub mod turn_table;
+#[cfg(feature = "std")]
+pub mod win_probability;
+pub mod zobrist;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PlayerColor {
+    Red = 0,
+    Blue,
+    Yellow,
+    Green,
+}
+
+impl From<usize> for PlayerColor {
+    fn from(value: usize) -> Self {
+        match value {
+            0 => Self::Red,
+            1 => Self::Blue,
+            2 => Self::Yellow,
+            3 => Self::Green,
+            _ => panic!("Invalid player color"),
+        }
+    }
+}
+
+pub const COLORS: [PlayerColor; 4] = [
+    PlayerColor::Red,
+    PlayerColor::Blue,
+    PlayerColor::Yellow,
+    PlayerColor::Green,
+];
+
+/// The types that Struggle players see, see `GamePlayer`.
+pub struct StruggleRules;
+
+impl GameRules for StruggleRules {
+    type Board = Board;
+    type Move = StruggleMove;
+    type TurnContext = players::GameContext;
+}
+
+#[derive(Clone)]
+pub struct AiStrugglePlayer<T> {
+    pub color: PlayerColor,
+    pub player: T,
+}
+
+impl<T> AiStrugglePlayer<T> {
+    pub fn new(color: PlayerColor, player: T) -> Self {
+        Self { color, player }
+    }
+
+    pub fn color(&self) -> PlayerColor {
+        self.color
+    }
+}
+
+pub type StruggleGameStats = GameStats<4>;
+
+#[derive(Clone)]
+pub struct StruggleGame<A: players::StrugglePlayer, B: players::StrugglePlayer> {
+    board: Board,
+    player_a: AiStrugglePlayer<A>,
+    player_b: AiStrugglePlayer<B>,
+
+    current_player: PlayerColor,
+
+    stats: Option<StruggleGameStats>,
+    // Whether the stats record the trajectory and piece lives, see `ResetGame::set_timelines`
+    timelines: bool,
+    // The RNG of `play`, if the game was built with a seed
+    rng: Option<SmallRng>,
+    // The turn the piece on each tile entered the board on, for `GameStats::pieces`
+    entered: [u16; Board::TILES],
+    #[cfg(feature = "std")]
+    logger: Option<turn_log::TurnLogger>,
+}
+
+impl<A: players::StrugglePlayer, B: players::StrugglePlayer> StruggleGame<A, B> {
+    pub fn new(
+        player_a: AiStrugglePlayer<A>,
+        player_b: AiStrugglePlayer<B>,
+        collect_stats: bool,
+    ) -> Self {
+        let board = Board::new(player_a.color, player_b.color);
+
+        Self {
+            board,
+            current_player: player_a.color,
+            player_a,
+            player_b,
+            stats: collect_stats.then(StruggleGameStats::default),
+            timelines: false,
+            rng: None,
+            entered: [0; Board::TILES],
+            #[cfg(feature = "std")]
+            logger: None,
+        }
+    }
+
+    /// Logs every turn of this game, see `turn_log`.
+    #[cfg(feature = "std")]
+    pub fn with_logger(mut self, logger: turn_log::TurnLogger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Starts the game from `board` with `to_move` to move, instead of the starting position.
+    /// The board must be between the colors of the two players. The pieces already on the board
+    /// count as having entered it on the first turn.
+    pub fn with_position(mut self, board: Board, to_move: PlayerColor) -> Self {
+        debug_assert_eq!(board.players(), (self.player_a.color, self.player_b.color));
+
+        self.board = board;
+        self.current_player = to_move;
+        self.entered = [0; Board::TILES];
+        self
+    }
+
+    // Records the pieces that `mov` takes off the board, before it's performed
+    fn track_pieces(&mut self, player: PlayerColor, mov: &StruggleMove) {
+        let (Some(stats), true) = (&mut self.stats, self.timelines) else {
+            return;
+        };
+
+        let turn = stats.turns;
+        let (own, enemy) = if player == self.player_a.color {
+            (0, 1)
+        } else {
+            (1, 0)
+        };
+
+        let leave = |pieces: &mut Vec<PieceLife>, tile: u8, fate| {
+            pieces.push(PieceLife {
+                entered: self.entered[tile as usize],
+                left: turn,
+                fate,
+            })
+        };
+
+        match *mov {
+            StruggleMove::AddNewPiece { eats } => {
+                let start = Board::get_start(player);
+
+                if eats {
+                    leave(&mut stats.pieces[enemy], start, PieceFate::Eaten);
+                }
+
+                self.entered[start as usize] = turn;
+            }
+            StruggleMove::MovePiece { from, to, eats } => {
+                if eats {
+                    leave(&mut stats.pieces[enemy], to, PieceFate::Eaten);
+                }
+
+                self.entered[to as usize] = self.entered[from as usize];
+            }
+            StruggleMove::MoveToGoal { from_board, .. } => {
+                leave(&mut stats.pieces[own], from_board, PieceFate::ReachedGoal);
+            }
+            StruggleMove::MoveInGoal { .. } | StruggleMove::SkipTurn => {}
+        }
+    }
+
+    // Records the pieces left on the board when the game ends
+    fn track_remaining_pieces(&mut self) {
+        let (Some(stats), true) = (&mut self.stats, self.timelines) else {
+            return;
+        };
+
+        for (tile, piece) in self.board.tiles.iter().enumerate() {
+            if let Some(color) = *piece {
+                let index = if color == self.player_a.color { 0 } else { 1 };
+
+                stats.pieces[index].push(PieceLife {
+                    entered: self.entered[tile],
+                    left: stats.turns,
+                    fate: PieceFate::OnBoard,
+                });
+            }
+        }
+    }
+
+    /// Plays the game to the end with the RNG seeded by `StruggleGameBuilder::seed`, so that a game
+    /// built with a seed plays out the same every time. Games without a seed are played with this
+    /// thread's RNG, see `play_game` (or with an RNG seeded with 0 without `std`).
+    pub fn play(&mut self) -> PlayerColor {
+        match self.rng.take() {
+            Some(mut rng) => {
+                let winner = play_game_with_rng(self, &mut rng);
+                self.rng = Some(rng);
+                winner
+            }
+            #[cfg(feature = "std")]
+            None => play_game(self),
+            #[cfg(not(feature = "std"))]
+            None => play_game_with_rng(self, &mut SmallRng::seed_from_u64(0)),
+        }
+    }
+
+    pub fn total_evaluations(&self, player: PlayerColor) -> u64 {
+        if player == self.player_a.color {
+            self.player_a.player.total_evaluations()
+        } else {
+            self.player_b.player.total_evaluations()
+        }
+    }
+}
+
+impl StruggleGame<RandomPlayer, RandomPlayer> {
+    /// The easiest way to set up a game, e.g.
+    ///
+    /// ```
+    /// use struggle_core::games::struggle::{players::{expectiminimax, RandomPlayer}, StruggleGame};
+    ///
+    /// let (winner, game) = StruggleGame::builder()
+    ///     .red(expectiminimax(2))
+    ///     .yellow(RandomPlayer)
+    ///     .collect_stats(true)
+    ///     .seed(42)
+    ///     .play();
+    /// ```
+    ///
+    /// Both players are `RandomPlayer`s unless they're set.
+    pub fn builder() -> StruggleGameBuilder<RandomPlayer, RandomPlayer> {
+        StruggleGameBuilder {
+            red: RandomPlayer,
+            yellow: RandomPlayer,
+            collect_stats: false,
+            timelines: false,
+            seed: None,
+            #[cfg(feature = "std")]
+            logger: None,
+        }
+    }
+}
+
+/// Sets up a `StruggleGame` between Red and Yellow, see `StruggleGame::builder`. There's no
+/// option for the rules yet, as there is only one set of them: picking rules is left for when the
+/// variants are added.
+pub struct StruggleGameBuilder<A, B> {
+    red: A,
+    yellow: B,
+    collect_stats: bool,
+    timelines: bool,
+    seed: Option<u64>,
+    #[cfg(feature = "std")]
+    logger: Option<turn_log::TurnLogger>,
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> StruggleGameBuilder<A, B> {
+    pub fn red<P: StrugglePlayer>(self, red: P) -> StruggleGameBuilder<P, B> {
+        StruggleGameBuilder {
+            red,
+            yellow: self.yellow,
+            collect_stats: self.collect_stats,
+            timelines: self.timelines,
+            seed: self.seed,
+            #[cfg(feature = "std")]
+            logger: self.logger,
+        }
+    }
+
+    pub fn yellow<P: StrugglePlayer>(self, yellow: P) -> StruggleGameBuilder<A, P> {
+        StruggleGameBuilder {
+            red: self.red,
+            yellow,
+            collect_stats: self.collect_stats,
+            timelines: self.timelines,
+            seed: self.seed,
+            #[cfg(feature = "std")]
+            logger: self.logger,
+        }
+    }
+
+    pub fn collect_stats(mut self, collect_stats: bool) -> Self {
+        self.collect_stats = collect_stats;
+        self
+    }
+
+    /// Records the timelines of the game in its stats, see `ResetGame::set_timelines`.
+    pub fn timelines(mut self, timelines: bool) -> Self {
+        self.timelines = timelines;
+        self
+    }
+
+    /// Seeds the RNG the game is played with, by `StruggleGame::play` or with the RNG of
+    /// `build_with_rng`, so that the game can be reproduced.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Logs every turn, see `StruggleGame::with_logger`.
+    #[cfg(feature = "std")]
+    pub fn logger(mut self, logger: turn_log::TurnLogger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
+    pub fn build(self) -> StruggleGame<A, B> {
+        StruggleGame {
+            timelines: self.timelines,
+            rng: self.seed.map(SmallRng::seed_from_u64),
+            #[cfg(feature = "std")]
+            logger: self.logger,
+            ..StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, self.red),
+                AiStrugglePlayer::new(PlayerColor::Yellow, self.yellow),
+                self.collect_stats,
+            )
+        }
+    }
+
+    /// The game, and an RNG to play it with: seeded if a seed was given, random otherwise (or
+    /// seeded with 0 without `std`).
+    pub fn build_with_rng(self) -> (StruggleGame<A, B>, SmallRng) {
+        let rng = match self.seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            #[cfg(feature = "std")]
+            None => SmallRng::from_rng(rand::thread_rng()).unwrap(),
+            // Without std there's no entropy to seed from
+            #[cfg(not(feature = "std"))]
+            None => SmallRng::seed_from_u64(0),
+        };
+
+        (self.build(), rng)
+    }
+
+    /// Plays the game to the end, returning the winner and the finished game.
+    pub fn play(self) -> (PlayerColor, StruggleGame<A, B>) {
+        let mut game = self.build();
+        let winner = game.play();
+
+        (winner, game)
+    }
+}
+
+impl<A: players::StrugglePlayer, B: players::StrugglePlayer> RaceGame for StruggleGame<A, B> {
+    type Board = Board;
+    type PlayerId = PlayerColor;
+
+    type Move = StruggleMove;
+    type MoveVector = ArrayVec<StruggleMove, 4>;
+
+    type TurnContext = players::GameContext;
+    type DiceState = u8;
+
+    type Rules = StruggleRules;
+
+    const MAX_MOVES: usize = 4;
+
+    fn board(&self) -> &Board {
+        &self.board
+    }
+
+    fn current_player(&self) -> PlayerColor {
+        self.current_player
+    }
+
+    fn other_player(&self) -> PlayerColor {
+        if self.player_a.color == self.current_player {
+            self.player_b.color
+        } else {
+            self.player_a.color
+        }
+    }
+
+    fn set_current_player(&mut self, player: PlayerColor) {
+        self.current_player = player;
+    }
+
+    fn throw_dice(&self, rng: &mut SmallRng) -> u8 {
+        rng.gen_range(1..=6)
+    }
+
+    fn create_turn_context(&self, dice: u8) -> Self::TurnContext {
+        Self::TurnContext {
+            current_player: self.current_player,
+            other_player: self.other_player(),
+            dice,
+        }
+    }
+
+    fn get_moves(&self, ctx: &Self::TurnContext) -> Self::MoveVector {
+        self.board
+            .get_moves(ctx.dice, ctx.current_player, ctx.other_player)
+    }
+
+    fn select_move<'a>(
+        &mut self,
+        ctx: &Self::TurnContext,
+        moves: &'a Self::MoveVector,
+        rng: &mut SmallRng,
+    ) -> &'a Self::Move {
+        if let Some(stats) = &mut self.stats {
+            let index = if self.current_player == self.player_a.color {
+                0
+            } else {
+                1
+            };
+
+            stats.move_distribution[index][moves.len() - 1] += 1;
+            stats.sixes[index] += (ctx.dice == 6) as u16;
+            stats.dice[index][ctx.dice as usize - 1] += 1;
+            stats.eat_opportunities[index] += moves.iter().any(StruggleMove::eats) as u16;
+        }
+
+        if self.current_player == self.player_a.color {
+            self.player_a
+                .player
+                .select_move(ctx, &self.board, moves, rng)
+        } else {
+            self.player_b
+                .player
+                .select_move(ctx, &self.board, moves, rng)
+        }
+    }
+
+    fn apply_move(
+        &mut self,
+        ctx: &Self::TurnContext,
+        mov: &Self::Move,
+    ) -> TurnResult<Self::PlayerId> {
+        self.track_pieces(ctx.current_player, mov);
+
+        if let Some(stats) = &mut self.stats {
+            let add_eats = match mov {
+                StruggleMove::AddNewPiece { eats: true, .. }
+                | StruggleMove::MovePiece { eats: true, .. } => 1,
+                _ => 0,
+            };
+
+            let index = if self.current_player == self.player_a.color {
+                0
+            } else {
+                1
+            };
+
+            match *mov {
+                StruggleMove::MoveToGoal { to_goal, .. } => {
+                    stats.goal_slots[index][to_goal as usize] = Some(stats.turns);
+                }
+                StruggleMove::MoveInGoal { from_goal, to_goal } => {
+                    stats.goal_slots[index][from_goal as usize] = None;
+                    stats.goal_slots[index][to_goal as usize] = Some(stats.turns);
+                }
+                _ => {}
+            }
+
+            stats.pieces_eaten_by[index] += add_eats;
+            stats.turns += 1;
+            stats.turns_per_player[index] += 1;
+        }
+
+        self.board.perform_move(ctx.current_player, mov);
+
+        if let (Some(stats), true) = (&mut self.stats, self.timelines) {
+            stats.trajectory.push([
+                self.board.piece_counts(self.player_a.color),
+                self.board.piece_counts(self.player_b.color),
+            ]);
+        }
+
+        #[cfg(feature = "std")]
+        if let Some(logger) = &mut self.logger {
+            logger.log(
+                &self.board,
+                ctx.current_player,
+                ctx.other_player,
+                ctx.dice,
+                mov,
+            );
+        }
+
+        if let Some(winner) = self.board.get_winner() {
+            self.track_remaining_pieces();
+
+            if let Some(stats) = &mut self.stats {
+                stats.expectiminimax_evals = [
+                    self.player_a.player.total_evaluations(),
+                    self.player_b.player.total_evaluations(),
+                ];
+            }
+            TurnResult::EndGame { winner }
+        } else if ctx.dice == 6 {
+            TurnResult::PlayAgain
+        } else {
+            TurnResult::PassTo(self.other_player())
+        }
+    }
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> CreateGame for StruggleGame<A, B> {
+    type PlayerA = A;
+    type PlayerB = B;
+
+    fn create_game(
+        player_a: (PlayerColor, A),
+        player_b: (PlayerColor, B),
+        collect_stats: bool,
+    ) -> Self {
+        let player_a = AiStrugglePlayer::new(player_a.0, player_a.1);
+        let player_b = AiStrugglePlayer::new(player_b.0, player_b.1);
+
+        Self::new(player_a, player_b, collect_stats)
+    }
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> IntoGameStats<4> for StruggleGame<A, B> {
+    fn into_stats(self) -> Option<StruggleGameStats> {
+        self.stats
+    }
+
+    fn stats(&self) -> Option<&StruggleGameStats> {
+        self.stats.as_ref()
+    }
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> ResetGame for StruggleGame<A, B> {
+    fn reset(&mut self) {
+        self.board = Board::new(self.player_a.color, self.player_b.color);
+        self.current_player = self.player_a.color;
+        self.entered = [0; Board::TILES];
+        self.player_a.player.reset();
+        self.player_b.player.reset();
+
+        if let Some(stats) = &mut self.stats {
+            *stats = StruggleGameStats::default();
+        }
+    }
+
+    fn set_game_id(&mut self, id: GameId) {
+        if let Some(stats) = &mut self.stats {
+            stats.id = Some(id);
+        }
+    }
+
+    fn set_timelines(&mut self, timelines: bool) {
+        self.timelines = timelines;
+    }
+}
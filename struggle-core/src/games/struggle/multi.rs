@@ -0,0 +1,270 @@
+//! Struggle between two to four players, e.g. a four-way game of Red, Blue, Yellow and Green.
+//!
+//! The players take turns in the order of their colors around the board, and a six still gives
+//! another turn. The board and the players are the same as in two player games: before each
+//! turn the board is turned to the player to move, with the next player in turn as its
+//! `other_player`. Every opponent's pieces can be eaten, but the board's piece cache, and so the
+//! heuristics, only see the pieces of the player to move and of the next player, who plays the
+//! part of the enemy. A piece cache has room for four pieces per side, which all of the opponents
+//! together would overflow.
+
+use alloc::vec::Vec;
+
+use arrayvec::ArrayVec;
+use rand::{rngs::SmallRng, Rng};
+
+use crate::game::{RaceGame, TurnResult};
+
+use super::{
+    board::{Board, StruggleMove},
+    players::{GameContext, StrugglePlayer},
+    AiStrugglePlayer, PlayerColor, StruggleRules,
+};
+
+/// Per-seat fields are indexed in turn order, i.e. in the order of `MultiStruggleGame::seats`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MultiGameStats {
+    pub turns: u16,
+    pub turns_per_seat: [u16; 4],
+    pub pieces_eaten_by: [u16; 4],
+    /// Pieces of each seat that were eaten
+    pub pieces_lost: [u16; 4],
+    pub sixes: [u16; 4],
+}
+
+#[derive(Clone)]
+pub struct MultiStruggleGame<P: StrugglePlayer> {
+    board: Board,
+    seats: Vec<AiStrugglePlayer<P>>,
+    current: usize,
+    stats: Option<MultiGameStats>,
+}
+
+impl<P: StrugglePlayer> MultiStruggleGame<P> {
+    /// A game between `seats`, which must have 2 to 4 players of different colors. Mixed
+    /// players can be seated with `P = BoxedStrugglePlayer`.
+    pub fn new(mut seats: Vec<AiStrugglePlayer<P>>, collect_stats: bool) -> Self {
+        assert!(
+            (2..=4).contains(&seats.len()),
+            "A game needs 2 to 4 players, got {}",
+            seats.len()
+        );
+
+        seats.sort_by_key(|seat| seat.color as usize);
+
+        assert!(
+            seats.windows(2).all(|pair| pair[0].color != pair[1].color),
+            "Every player needs a different color"
+        );
+
+        let mut game = Self {
+            board: Board::new(seats[0].color, seats[1].color),
+            seats,
+            current: 0,
+            stats: collect_stats.then(MultiGameStats::default),
+        };
+
+        game.turn_board();
+        game
+    }
+
+    /// The players in turn order.
+    pub fn seats(&self) -> &[AiStrugglePlayer<P>] {
+        &self.seats
+    }
+
+    pub fn stats(&self) -> Option<&MultiGameStats> {
+        self.stats.as_ref()
+    }
+
+    pub fn into_stats(self) -> Option<MultiGameStats> {
+        self.stats
+    }
+
+    /// The index of `color` in `seats`.
+    pub fn seat_of(&self, color: PlayerColor) -> Option<usize> {
+        self.seats.iter().position(|seat| seat.color == color)
+    }
+
+    fn next_seat(&self) -> usize {
+        (self.current + 1) % self.seats.len()
+    }
+
+    // Makes the player to move the board's first player and the next one its second
+    fn turn_board(&mut self) {
+        self.board.players = (
+            self.seats[self.current].color,
+            self.seats[self.next_seat()].color,
+        );
+        self.board.update_piece_cache();
+    }
+
+    /// Plays the game to the end and returns the winner. Unlike `play_game_with_rng`, which
+    /// picks one of two players to start, any of the players may start.
+    pub fn play(&mut self, rng: &mut SmallRng) -> PlayerColor {
+        let starter = self.seats[rng.gen_range(0..self.seats.len())].color;
+        self.set_current_player(starter);
+
+        loop {
+            match self.play_turn(rng).1 {
+                TurnResult::PlayAgain => {}
+                TurnResult::PassTo(player) => self.set_current_player(player),
+                TurnResult::EndGame { winner } => return winner,
+            }
+        }
+    }
+}
+
+impl<P: StrugglePlayer> RaceGame for MultiStruggleGame<P> {
+    type Board = Board;
+    type PlayerId = PlayerColor;
+
+    type Move = StruggleMove;
+    type MoveVector = ArrayVec<StruggleMove, 4>;
+
+    type TurnContext = GameContext;
+    type DiceState = u8;
+
+    type Rules = StruggleRules;
+
+    const MAX_MOVES: usize = 4;
+
+    fn board(&self) -> &Board {
+        &self.board
+    }
+
+    fn current_player(&self) -> PlayerColor {
+        self.seats[self.current].color
+    }
+
+    /// The next player in turn order.
+    fn other_player(&self) -> PlayerColor {
+        self.seats[self.next_seat()].color
+    }
+
+    fn set_current_player(&mut self, player: PlayerColor) {
+        self.current = self
+            .seat_of(player)
+            .expect("The player to move should have a seat");
+        self.turn_board();
+    }
+
+    fn throw_dice(&self, rng: &mut SmallRng) -> u8 {
+        rng.gen_range(1..=6)
+    }
+
+    fn create_turn_context(&self, dice: u8) -> GameContext {
+        GameContext {
+            current_player: self.current_player(),
+            other_player: self.other_player(),
+            dice,
+        }
+    }
+
+    fn get_moves(&self, ctx: &GameContext) -> Self::MoveVector {
+        self.board
+            .get_moves(ctx.dice, ctx.current_player, ctx.other_player)
+    }
+
+    fn select_move<'a>(
+        &mut self,
+        ctx: &GameContext,
+        moves: &'a Self::MoveVector,
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        self.seats[self.current]
+            .player
+            .select_move(ctx, &self.board, moves, rng)
+    }
+
+    fn apply_move(&mut self, ctx: &GameContext, mov: &StruggleMove) -> TurnResult<PlayerColor> {
+        let eaten = match *mov {
+            StruggleMove::AddNewPiece { eats: true } => {
+                self.board.tiles[Board::get_start(ctx.current_player) as usize]
+            }
+            StruggleMove::MovePiece { to, eats: true, .. } => self.board.tiles[to as usize],
+            _ => None,
+        };
+        let eaten_seat = eaten.and_then(|color| self.seat_of(color));
+
+        if let Some(stats) = &mut self.stats {
+            stats.turns += 1;
+            stats.turns_per_seat[self.current] += 1;
+            stats.sixes[self.current] += (ctx.dice == 6) as u16;
+
+            if let Some(seat) = eaten_seat {
+                stats.pieces_eaten_by[self.current] += 1;
+                stats.pieces_lost[seat] += 1;
+            }
+        }
+
+        self.board.perform_move(ctx.current_player, mov);
+
+        if let Some(winner) = self.board.get_winner() {
+            TurnResult::EndGame { winner }
+        } else if ctx.dice == 6 {
+            TurnResult::PlayAgain
+        } else {
+            TurnResult::PassTo(self.other_player())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::games::struggle::{players::RandomPlayer, COLORS};
+
+    #[test]
+    fn four_players_take_turns_until_one_wins() {
+        let seats = COLORS
+            .into_iter()
+            .rev()
+            .map(|color| AiStrugglePlayer::new(color, RandomPlayer))
+            .collect();
+        let mut game = MultiStruggleGame::new(seats, true);
+        let mut rng = SmallRng::seed_from_u64(7);
+
+        // Seated in turn order around the board
+        assert_eq!(game.seats()[0].color, PlayerColor::Red);
+        assert_eq!(game.other_player(), PlayerColor::Blue);
+
+        let winner = game.play(&mut rng);
+        let stats = game.stats().unwrap();
+
+        assert_eq!(game.board().get_winner(), Some(winner));
+        assert_eq!(stats.turns, stats.turns_per_seat.iter().sum::<u16>());
+        assert!(stats.turns_per_seat.iter().all(|&turns| turns > 0));
+        assert_eq!(
+            stats.pieces_eaten_by.iter().sum::<u16>(),
+            stats.pieces_lost.iter().sum::<u16>()
+        );
+    }
+
+    #[test]
+    fn moves_see_every_opponent() {
+        let seats = [PlayerColor::Red, PlayerColor::Blue, PlayerColor::Green]
+            .into_iter()
+            .map(|color| AiStrugglePlayer::new(color, RandomPlayer))
+            .collect();
+        let mut game = MultiStruggleGame::new(seats, false);
+
+        // Green's piece on Red's start tile can be eaten by Red, though Blue moves next
+        game.board.tiles[Board::RED_START as usize] = Some(PlayerColor::Green);
+        game.board.home_bases[PlayerColor::Green as usize].pieces_waiting = 3;
+        game.set_current_player(PlayerColor::Red);
+
+        let ctx = game.create_turn_context(6);
+        assert!(game
+            .get_moves(&ctx)
+            .contains(&StruggleMove::AddNewPiece { eats: true }));
+
+        game.apply_move(&ctx, &StruggleMove::AddNewPiece { eats: true });
+        assert_eq!(
+            game.board.home_bases[PlayerColor::Green as usize].pieces_waiting,
+            4
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! A compact text notation for positions and moves, used by the engine protocol.
+//!
+//! A position has four space separated fields: the tiles, the two players and the player to move.
+//!
+//! ```text
+//! r6y20 r:3:---- y:2:x--- y
+//! ```
+//!
+//! Tiles are listed from 0 to 27, with `r`, `b`, `y` and `g` for pieces and a number for a run of
+//! empty tiles. Each player is `color:waiting:goal`, where the goal lists slots 0-3 as `x` for a
+//! piece and `-` for an empty slot.
+//!
+//! Moves are `n` for a new piece, `3-9` for moving on the board, `25-g1` for entering the goal,
+//! `g0-g2` for moving inside the goal and `pass` for skipping the turn. An `x` in place of the
+//! dash, or after `n`, marks eating an enemy piece.
+
+use super::{
+    board::{Board, StruggleMove},
+    PlayerColor, COLORS,
+};
+
+/// Red against Yellow before the first move.
+pub const START_POSITION: &str = "28 r:4:---- y:4:---- r";
+
+pub fn color_char(color: PlayerColor) -> char {
+    match color {
+        PlayerColor::Red => 'r',
+        PlayerColor::Blue => 'b',
+        PlayerColor::Yellow => 'y',
+        PlayerColor::Green => 'g',
+    }
+}
+
+pub fn parse_color(c: char) -> Option<PlayerColor> {
+    COLORS.into_iter().find(|&color| color_char(color) == c)
+}
+
+pub fn format_position(board: &Board, to_move: PlayerColor) -> String {
+    let mut tiles = String::new();
+    let mut empty = 0;
+
+    for cell in &board.tiles {
+        match cell {
+            Some(color) => {
+                if empty > 0 {
+                    tiles.push_str(&empty.to_string());
+                    empty = 0;
+                }
+
+                tiles.push(color_char(*color));
+            }
+            None => empty += 1,
+        }
+    }
+
+    if empty > 0 {
+        tiles.push_str(&empty.to_string());
+    }
+
+    let (a, b) = board.players();
+    let player = |color: PlayerColor| {
+        let goal: String = board.goals[color as usize]
+            .iter()
+            .map(|slot| if slot.is_some() { 'x' } else { '-' })
+            .collect();
+
+        format!(
+            "{}:{}:{}",
+            color_char(color),
+            board.home_bases[color as usize].pieces_waiting,
+            goal
+        )
+    };
+
+    format!(
+        "{} {} {} {}",
+        tiles,
+        player(a),
+        player(b),
+        color_char(to_move)
+    )
+}
+
+fn parse_tiles(text: &str) -> Result<Vec<Option<PlayerColor>>, String> {
+    let mut tiles = Vec::with_capacity(Board::TILES);
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some(digit) = c.to_digit(10) {
+            let mut run = digit as usize;
+
+            while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+                run = run * 10 + digit as usize;
+                chars.next();
+            }
+
+            tiles.extend(std::iter::repeat_n(None, run));
+        } else {
+            let color = parse_color(c).ok_or_else(|| format!("Invalid tile '{}'", c))?;
+            tiles.push(Some(color));
+        }
+
+        if tiles.len() > Board::TILES {
+            break;
+        }
+    }
+
+    if tiles.len() != Board::TILES {
+        return Err(format!(
+            "Expected {} tiles, got {}",
+            Board::TILES,
+            tiles.len()
+        ));
+    }
+
+    Ok(tiles)
+}
+
+fn parse_player(text: &str) -> Result<(PlayerColor, u8, [bool; 4]), String> {
+    let invalid = || format!("Invalid player '{}', expected e.g. r:4:----", text);
+
+    let mut parts = text.split(':');
+    let (Some(color), Some(waiting), Some(goal), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+
+    let mut color_chars = color.chars();
+    let color = match (color_chars.next(), color_chars.next()) {
+        (Some(c), None) => parse_color(c).ok_or_else(invalid)?,
+        _ => return Err(invalid()),
+    };
+
+    let waiting = waiting.parse().map_err(|_| invalid())?;
+
+    let goal = goal
+        .chars()
+        .map(|c| match c {
+            'x' => Ok(true),
+            '-' => Ok(false),
+            _ => Err(invalid()),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let goal: [bool; 4] = goal.try_into().map_err(|_| invalid())?;
+
+    Ok((color, waiting, goal))
+}
+
+/// Parses a position into a board and the player to move.
+pub fn parse_position(text: &str) -> Result<(Board, PlayerColor), String> {
+    let fields: Vec<&str> = text.split_whitespace().collect();
+    let &[tiles, a, b, to_move] = fields.as_slice() else {
+        return Err(format!(
+            "Expected 4 fields in position '{}', got {}",
+            text,
+            fields.len()
+        ));
+    };
+
+    let tiles = parse_tiles(tiles)?;
+    let a = parse_player(a)?;
+    let b = parse_player(b)?;
+
+    if a.0 == b.0 {
+        return Err("Both players have the same color".to_string());
+    }
+
+    let mut board = Board::new(a.0, b.0);
+
+    for (color, waiting, goal) in [a, b] {
+        let on_board = tiles.iter().filter(|&&cell| cell == Some(color)).count();
+        let in_goal = goal.iter().filter(|&&slot| slot).count();
+
+        if on_board + in_goal + waiting as usize != 4 {
+            return Err(format!(
+                "{:?} has {} pieces, expected 4",
+                color,
+                on_board + in_goal + waiting as usize
+            ));
+        }
+
+        board.home_bases[color as usize].pieces_waiting = waiting;
+        board.goals[color as usize] = goal.map(|filled| filled.then_some(color));
+    }
+
+    if let Some(color) = tiles
+        .iter()
+        .flatten()
+        .find(|&&color| color != a.0 && color != b.0)
+    {
+        return Err(format!("{:?} isn't playing in this position", color));
+    }
+
+    board.tiles.copy_from_slice(&tiles);
+    board.update_piece_cache();
+
+    let to_move = match to_move.chars().collect::<Vec<_>>().as_slice() {
+        &[c] => parse_color(c).filter(|&color| color == a.0 || color == b.0),
+        _ => None,
+    }
+    .ok_or_else(|| format!("Invalid player to move '{}'", to_move))?;
+
+    Ok((board, to_move))
+}
+
+pub fn format_move(mov: &StruggleMove) -> String {
+    let separator = |eats: bool| if eats { 'x' } else { '-' };
+
+    match *mov {
+        StruggleMove::AddNewPiece { eats: false } => "n".to_string(),
+        StruggleMove::AddNewPiece { eats: true } => "nx".to_string(),
+        StruggleMove::MovePiece { from, to, eats } => {
+            format!("{}{}{}", from, separator(eats), to)
+        }
+        StruggleMove::MoveToGoal {
+            from_board,
+            to_goal,
+        } => format!("{}-g{}", from_board, to_goal),
+        StruggleMove::MoveInGoal { from_goal, to_goal } => format!("g{}-g{}", from_goal, to_goal),
+        StruggleMove::SkipTurn => "pass".to_string(),
+    }
+}
+
+pub fn parse_move(text: &str) -> Result<StruggleMove, String> {
+    let invalid = || format!("Invalid move '{}'", text);
+
+    match text {
+        "n" => return Ok(StruggleMove::AddNewPiece { eats: false }),
+        "nx" => return Ok(StruggleMove::AddNewPiece { eats: true }),
+        "pass" => return Ok(StruggleMove::SkipTurn),
+        _ => {}
+    }
+
+    let (from, to, eats) = match (text.split_once('-'), text.split_once('x')) {
+        (Some((from, to)), None) => (from, to, false),
+        (None, Some((from, to))) => (from, to, true),
+        _ => return Err(invalid()),
+    };
+
+    let goal_slot = |text: &str| text.strip_prefix('g').map(str::parse::<u8>);
+
+    let mov = match (goal_slot(from), goal_slot(to)) {
+        (None, None) => StruggleMove::MovePiece {
+            from: from.parse().map_err(|_| invalid())?,
+            to: to.parse().map_err(|_| invalid())?,
+            eats,
+        },
+        (None, Some(to_goal)) if !eats => StruggleMove::MoveToGoal {
+            from_board: from.parse().map_err(|_| invalid())?,
+            to_goal: to_goal.map_err(|_| invalid())?,
+        },
+        (Some(from_goal), Some(to_goal)) if !eats => StruggleMove::MoveInGoal {
+            from_goal: from_goal.map_err(|_| invalid())?,
+            to_goal: to_goal.map_err(|_| invalid())?,
+        },
+        _ => return Err(invalid()),
+    };
+
+    Ok(mov)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_round_trip() {
+        let (board, to_move) = parse_position(START_POSITION).unwrap();
+        assert!(board == Board::new(PlayerColor::Red, PlayerColor::Yellow));
+        assert_eq!(format_position(&board, to_move), START_POSITION);
+
+        let position = "r6y3r16 r:1:-x-- y:2:x--- y";
+        let (board, to_move) = parse_position(position).unwrap();
+        assert_eq!(board.tiles[7], Some(PlayerColor::Yellow));
+        assert_eq!(board.pieces_in_goal(PlayerColor::Red), 1);
+        assert_eq!(format_position(&board, to_move), position);
+
+        assert!(parse_position("27 r:4:---- y:4:---- r").is_err());
+        assert!(parse_position("28 r:3:---- y:4:---- r").is_err());
+        assert!(parse_position("b27 r:4:---- y:4:---- r").is_err());
+        assert!(parse_position("28 r:4:---- y:4:---- g").is_err());
+    }
+
+    #[test]
+    fn moves_round_trip() {
+        for mov in [
+            StruggleMove::AddNewPiece { eats: false },
+            StruggleMove::AddNewPiece { eats: true },
+            StruggleMove::MovePiece {
+                from: 3,
+                to: 9,
+                eats: true,
+            },
+            StruggleMove::MoveToGoal {
+                from_board: 25,
+                to_goal: 1,
+            },
+            StruggleMove::MoveInGoal {
+                from_goal: 0,
+                to_goal: 2,
+            },
+            StruggleMove::SkipTurn,
+        ] {
+            assert_eq!(parse_move(&format_move(&mov)), Ok(mov));
+        }
+
+        assert!(parse_move("3x").is_err());
+        assert!(parse_move("g1xg2").is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use alloc::{borrow::Cow, vec::Vec};
+
+use itertools::Itertools;
+use ordered_float::OrderedFloat;
+use rand::{prelude::*, rngs::SmallRng};
+
+use crate::game::{GamePlayer, NamedPlayer, ScoringPlayer};
+
+use super::{
+    board::{Board, StruggleMove},
+    PlayerColor, StruggleRules,
+};
+
+#[cfg(feature = "std")]
+mod search;
+#[cfg(feature = "std")]
+mod search_tree;
+
+#[cfg(feature = "std")]
+pub use search::*;
+#[cfg(feature = "std")]
+pub use search_tree::*;
+
+/// A player of Struggle. Players implement `GamePlayer<StruggleRules>`, and this is implemented
+/// for all of them.
+pub trait StrugglePlayer: GamePlayer<StruggleRules> {}
+
+impl<P: GamePlayer<StruggleRules>> StrugglePlayer for P {}
+
+#[derive(Clone, Copy, Debug)]
+pub struct GameContext {
+    pub current_player: PlayerColor,
+    pub other_player: PlayerColor,
+    pub dice: u8,
+}
+
+// Randomly selects any legal move
+#[derive(Clone)]
+pub struct RandomPlayer;
+
+impl NamedPlayer for RandomPlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("Random")
+    }
+}
+
+impl GamePlayer<StruggleRules> for RandomPlayer {
+    fn select_move<'a>(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        moves.choose(rng).unwrap()
+    }
+}
+
+// Eat whenever possible, otherwise select move randomly
+#[derive(Clone)]
+pub struct RandomEaterPlayer;
+
+impl GamePlayer<StruggleRules> for RandomEaterPlayer {
+    fn select_move<'a>(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        let eating_moves = moves.iter().find(|mov| mov.eats());
+
+        match eating_moves {
+            Some(mov) => mov,
+            None => moves.choose(rng).unwrap(),
+        }
+    }
+}
+
+impl NamedPlayer for RandomEaterPlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("RandomEater")
+    }
+}
+
+// Avoid eating at all costs
+#[derive(Clone)]
+pub struct RandomDietPlayer;
+
+impl GamePlayer<StruggleRules> for RandomDietPlayer {
+    fn select_move<'a>(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        let diet_compatible = moves.iter().filter(|mov| !mov.eats()).collect_vec();
+
+        match diet_compatible.len() {
+            0 => moves.choose(rng).unwrap(),
+            _ => diet_compatible.choose(rng).unwrap(),
+        }
+    }
+}
+
+impl NamedPlayer for RandomDietPlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("RandomDiet")
+    }
+}
+
+fn score_move(rng: &mut SmallRng, mov: &StruggleMove) -> OrderedFloat<f64> {
+    let score = match mov {
+        StruggleMove::AddNewPiece { eats } => {
+            if *eats {
+                150.0
+            } else {
+                50.0
+            }
+        }
+        StruggleMove::MovePiece {
+            from: _,
+            to: _,
+            eats,
+        } => {
+            if *eats {
+                100.0
+            } else {
+                1.0
+            }
+        }
+        StruggleMove::MoveToGoal {
+            from_board: _,
+            to_goal: _,
+        } => 10.0,
+        StruggleMove::MoveInGoal {
+            from_goal: _,
+            to_goal: _,
+        } => 1.0,
+        StruggleMove::SkipTurn => 0.0,
+    };
+    OrderedFloat(score + rng.gen::<f64>())
+}
+
+// Selects the best move using a simple heuristic
+// The board state is not inspected, only the move type
+#[derive(Clone)]
+pub struct ScoreMovePlayer;
+
+impl GamePlayer<StruggleRules> for ScoreMovePlayer {
+    fn select_move<'a>(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        moves.iter().max_by_key(|mov| score_move(rng, mov)).unwrap()
+    }
+}
+
+impl ScoringPlayer<StruggleRules> for ScoreMovePlayer {
+    fn score_moves(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        moves: &[StruggleMove],
+        rng: &mut SmallRng,
+    ) -> Vec<f64> {
+        moves.iter().map(|mov| score_move(rng, mov).0).collect()
+    }
+}
+
+impl NamedPlayer for ScoreMovePlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("ScoreMove")
+    }
+}
+
+// Selects the worst move using the same heuristic as ScoreMovePlayer, but negated
+#[derive(Clone)]
+pub struct WorstScoreMovePlayer;
+
+impl GamePlayer<StruggleRules> for WorstScoreMovePlayer {
+    fn select_move<'a>(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        moves.iter().min_by_key(|mov| score_move(rng, mov)).unwrap()
+    }
+}
+
+impl NamedPlayer for WorstScoreMovePlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("WorstScoreMove")
+    }
+}
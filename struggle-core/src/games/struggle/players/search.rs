@@ -0,0 +1,1892 @@
+//! `GameTreePlayer`'s expectiminimax search, its heuristics, and the players built on top of it.
+//! The search needs threads, clocks and hash maps, so it's only available with `std`.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ::rand::{prelude::*, rngs::SmallRng};
+use ordered_float::OrderedFloat;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span, info, trace};
+
+use crate::{
+    game::{GamePlayer, NamedPlayer, PlayerConfig, ScoringPlayer},
+    games::struggle::{
+        board::{Board, MoveVec, PiecePosition, StruggleMove},
+        transposition_table::{BoardHash, TranspositionTable},
+        PlayerColor, StruggleRules,
+    },
+};
+
+use super::{score_move, GameContext, RandomPlayer, SearchTree, StrugglePlayer, TreeNodeKind};
+
+pub type HeuristicFunction = fn(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64;
+
+#[derive(Clone)]
+pub struct GameTreePlayer<F>
+where
+    F: Fn(&Board, PlayerColor, PlayerColor) -> f64,
+{
+    pub heuristic: F,
+    pub max_depth: u8,
+
+    name: &'static str,
+
+    pub evaluations: u64,
+
+    cache: Arc<TranspositionTable>,
+
+    arena: Option<SearchArena>,
+    // Searches on a single board with `Board::make_move` and `Board::unmake_move`
+    undo: bool,
+    move_cache: Option<MoveCache>,
+
+    stats: SearchStats,
+
+    /// Chance nodes shallower than this search their six branches in parallel
+    parallel_plies: u8,
+
+    table: TableUse,
+    // The scale of the random noise that breaks ties between equally scored moves
+    noise: f64,
+    move_ordering: bool,
+    time_budget: Option<Duration>,
+    verbose: bool,
+    // Records the nodes of the search, see `search_tree`
+    tree: Option<SearchTree>,
+}
+
+/// Which positions a `GameTreePlayer` stores in its transposition table.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableUse {
+    #[default]
+    Off,
+    /// Only the heuristic values at the search horizon
+    Leaves,
+    /// The leaves and the expected values of the chance nodes above them that were searched
+    /// without pruning
+    AllNodes,
+}
+
+/// Counters of a `GameTreePlayer`, over every search since the last reset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    pub move_cache_hits: u64,
+    pub move_cache_misses: u64,
+}
+
+impl SearchStats {
+    /// The share of move generations answered by the `MoveCache`, 0 without lookups.
+    pub fn move_cache_hit_rate(&self) -> f64 {
+        let lookups = self.move_cache_hits + self.move_cache_misses;
+
+        if lookups == 0 {
+            0.0
+        } else {
+            self.move_cache_hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// The moves of the positions seen during one `select_move`, keyed by the position, the player
+/// to move and the roll. Transpositions and the six rolls of every chance node reach the same
+/// positions many times, and their moves only need to be generated once. The board hash leaves
+/// out the player to move, and the same tiles can come up with either side to move, e.g. after
+/// a player without pieces on the board skips its turn.
+#[derive(Clone, Default)]
+pub struct MoveCache {
+    moves: HashMap<(BoardHash, PlayerColor, u8), MoveVec>,
+}
+
+impl MoveCache {
+    /// Forgets the previous search, keeping the memory.
+    fn clear(&mut self) {
+        self.moves.clear();
+    }
+}
+
+/// Scratch boards for `GameTreePlayer`, one per ply. Every root move reuses the same boards
+/// from the top, instead of copying each child position into a fresh stack frame.
+///
+/// Boards don't own any heap memory, so the arena only saves copies, not allocations, and the
+/// gain is small. The `expectiminimax depth` benches in `benches/struggle.rs` (midgame position,
+/// default heuristic) measured, without and with the arena:
+///
+/// | Depth | Without | With arena |
+/// |-------|---------|------------|
+/// | 1     | 5.9 µs  | 5.8 µs     |
+/// | 2     | 84 µs   | 79 µs      |
+/// | 3     | 825 µs  | 817 µs     |
+///
+/// That was a release build with rustc 1.95 on one vCPU of an Intel Xeon (family 6, model 207)
+/// VM. Only depth 2 is clearly outside the noise.
+#[derive(Clone, Default)]
+pub struct SearchArena {
+    boards: Vec<Board>,
+}
+
+impl SearchArena {
+    /// Room for a search of `max_depth` plies, starting over from the first board.
+    fn reset(&mut self, board: &Board, max_depth: u8) -> &mut [Board] {
+        self.boards.resize(max_depth as usize + 1, board.clone());
+        &mut self.boards
+    }
+}
+
+/// Calls `f` with `board` after `mov`, along with the scratch boards that are still free. With
+/// `undo` the move is made on `board` itself and taken back afterwards. Otherwise the child is
+/// written into the first scratch board if there's one left, or into a copy.
+fn with_child<R>(
+    board: &mut Board,
+    player: PlayerColor,
+    mov: &StruggleMove,
+    undo: bool,
+    scratch: &mut [Board],
+    f: impl FnOnce(&mut Board, &mut [Board]) -> R,
+) -> R {
+    if *mov == StruggleMove::SkipTurn {
+        return f(board, scratch);
+    }
+
+    if undo {
+        let record = board.make_move(player, mov);
+        let result = f(board, scratch);
+        board.unmake_move(record);
+        return result;
+    }
+
+    match scratch.split_first_mut() {
+        Some((slot, rest)) => {
+            slot.clone_from(board);
+            slot.perform_move(player, mov);
+            f(slot, rest)
+        }
+        None => {
+            let mut child = board.clone();
+            child.perform_move(player, mov);
+            f(&mut child, scratch)
+        }
+    }
+}
+
+/// A node of the search: whose turn it is, whom it's valued for, how deep it is and the
+/// alpha-beta window it's searched with.
+#[derive(Clone, Copy, Debug)]
+struct SearchNode {
+    current_player: PlayerColor,
+    maximizing_player: PlayerColor,
+    minimizing_player: PlayerColor,
+    max_depth: u8,
+    depth: u8,
+    // Alpha: minimum guaranteed score for the maximizing player
+    alpha: f64,
+    // Beta: maximum guaranteed score for the minimizing player
+    beta: f64,
+}
+
+impl SearchNode {
+    /// The node after a move, with `to_move` to move and the window of this node.
+    fn child(self, to_move: PlayerColor) -> Self {
+        Self {
+            current_player: to_move,
+            depth: self.depth + 1,
+            ..self
+        }
+    }
+}
+
+pub(crate) const WIN_SCORE: f64 = 1e10;
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync> GameTreePlayer<F> {
+    pub fn new(f: F, max_depth: u8, name: &'static str) -> Self {
+        GameTreePlayer {
+            heuristic: f,
+            max_depth,
+            name,
+            evaluations: 0,
+            cache: Default::default(),
+            arena: None,
+            undo: false,
+            move_cache: None,
+            stats: SearchStats::default(),
+            parallel_plies: 0,
+            table: TableUse::Off,
+            noise: 1.0,
+            move_ordering: true,
+            time_budget: None,
+            verbose: false,
+            tree: None,
+        }
+    }
+
+    /// Configures every option of the search, starting from the defaults of `new` with a depth of 1.
+    pub fn builder(heuristic: F, name: &'static str) -> GameTreePlayerBuilder<F> {
+        GameTreePlayerBuilder {
+            player: GameTreePlayer::new(heuristic, 1, name),
+            table_capacity: None,
+        }
+    }
+
+    pub fn search_stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    // The moves of `player` after `dice` in `board`, from the move cache if there is one
+    fn moves(
+        &mut self,
+        board: &Board,
+        hash: BoardHash,
+        dice: u8,
+        player: PlayerColor,
+        enemy: PlayerColor,
+    ) -> MoveVec {
+        let Some(cache) = &mut self.move_cache else {
+            return board.get_moves(dice, player, enemy);
+        };
+
+        if let Some(moves) = cache.moves.get(&(hash, player, dice)) {
+            self.stats.move_cache_hits += 1;
+            return moves.clone();
+        }
+
+        self.stats.move_cache_misses += 1;
+
+        let moves = board.get_moves(dice, player, enemy);
+        cache.moves.insert((hash, player, dice), moves.clone());
+        moves
+    }
+
+    fn expectiminimax(
+        &mut self,
+        board: &mut Board,
+        node: SearchNode,
+        rng: &mut SmallRng,
+        scratch: &mut [Board],
+    ) -> f64 {
+        let SearchNode {
+            current_player,
+            maximizing_player,
+            minimizing_player,
+            max_depth,
+            depth,
+            alpha,
+            beta,
+        } = node;
+        let hash = BoardHash::of(board);
+        let key = hash.with_turn(current_player, maximizing_player);
+        // The table only answers for searches at least as deep as the one that stored the value
+        let remaining_depth = max_depth - depth;
+
+        if self.table != TableUse::Off {
+            if let Some(value) = self.cache.get(key, remaining_depth) {
+                self.tree_leaf(|| TreeNodeKind::Cached, Some(value as f64));
+                return value as f64;
+            }
+        }
+
+        self.evaluations += 1;
+
+        if depth == max_depth {
+            let value = (self.heuristic)(board, maximizing_player, minimizing_player);
+
+            if self.table != TableUse::Off {
+                self.cache
+                    .insert_if_better(key, value as f32, remaining_depth);
+            }
+
+            self.tree_leaf(|| TreeNodeKind::Horizon, Some(value));
+            return value;
+        }
+
+        let tree_node = self.open_node(|| TreeNodeKind::Chance {
+            to_move: current_player,
+        });
+
+        let branches = if depth < self.parallel_plies {
+            self.parallel_dice_branches(board, hash, node, rng)
+        } else {
+            let mut branches = [0.0; 6];
+
+            for (dice_roll, branch) in (1..=6).zip(&mut branches) {
+                *branch = self.dice_branch(board, hash, dice_roll, node, rng, scratch);
+            }
+
+            branches
+        };
+
+        let mut expected_value = 0.0;
+
+        for (dice_roll, score) in (1..=6).zip(branches) {
+            let multiplier = match dice_roll {
+                6 => 1.0 / 6.0,
+                _ => 1.0,
+            };
+
+            expected_value += score * multiplier;
+        }
+
+        expected_value /= 6.0;
+
+        // With a narrower window, pruning below may have cut the value to a bound
+        let full_window = alpha == f64::NEG_INFINITY && beta == f64::INFINITY;
+
+        if self.table == TableUse::AllNodes && full_window {
+            self.cache
+                .insert_if_better(key, expected_value as f32, remaining_depth);
+        }
+
+        self.close_node(tree_node, expected_value);
+        expected_value
+    }
+
+    /// The value of `board` after `dice_roll`, with alpha and beta starting over from the
+    /// values of the chance node.
+    fn dice_branch(
+        &mut self,
+        board: &mut Board,
+        hash: BoardHash,
+        dice_roll: u8,
+        mut node: SearchNode,
+        rng: &mut SmallRng,
+        scratch: &mut [Board],
+    ) -> f64 {
+        let SearchNode {
+            current_player,
+            maximizing_player,
+            minimizing_player,
+            depth,
+            ..
+        } = node;
+
+        if current_player == maximizing_player {
+            let mut moves =
+                self.moves(board, hash, dice_roll, maximizing_player, minimizing_player);
+            if self.move_ordering {
+                moves.sort_by_key(|mov| OrderedFloat(-score_move(rng, mov)));
+            }
+
+            let dice_node = self.open_node(|| TreeNodeKind::Dice {
+                player: maximizing_player,
+                dice: dice_roll,
+            });
+
+            let mut max_score = f64::NEG_INFINITY;
+            let mut best_move = moves.first().unwrap();
+            let mut searched = 0;
+
+            for mov in &moves {
+                searched += 1;
+                let move_node = self.open_node(|| TreeNodeKind::Move(mov.clone()));
+                let (score, guaranteed_win) = with_child(
+                    board,
+                    maximizing_player,
+                    mov,
+                    self.undo,
+                    &mut *scratch,
+                    |board, scratch| match board.get_winner() {
+                        Some(player) if player == maximizing_player => (WIN_SCORE, true),
+                        Some(_) => {
+                            panic!("This should never happen: minimizing player won after maximizing player's move")
+                        }
+                        None => {
+                            let to_move = if dice_roll == 6 {
+                                maximizing_player
+                            } else {
+                                minimizing_player
+                            };
+
+                            (
+                                self.expectiminimax(board, node.child(to_move), rng, scratch),
+                                false,
+                            )
+                        }
+                    },
+                );
+
+                self.close_node(move_node, score);
+
+                if score > max_score {
+                    best_move = mov;
+                }
+
+                max_score = max_score.max(score);
+                node.alpha = node.alpha.max(score);
+
+                // The maximizing can guarantee a win with this move, no need to look further
+                if guaranteed_win {
+                    break;
+                }
+
+                // Alpha-beta pruning: minimizing player will never allow this move
+                if max_score >= node.beta {
+                    break;
+                }
+            }
+
+            trace!(
+                depth,
+                ?best_move,
+                score = max_score,
+                "maximizing player chose a move"
+            );
+
+            self.tree_pruned(&moves[searched..]);
+            self.close_node(dice_node, max_score);
+            max_score
+        } else {
+            let mut moves =
+                self.moves(board, hash, dice_roll, minimizing_player, maximizing_player);
+            if self.move_ordering {
+                moves.sort_by_key(|mov| OrderedFloat(-score_move(rng, mov)));
+            }
+
+            let dice_node = self.open_node(|| TreeNodeKind::Dice {
+                player: minimizing_player,
+                dice: dice_roll,
+            });
+
+            let mut min_score = f64::INFINITY;
+            let mut searched = 0;
+
+            for mov in &moves {
+                searched += 1;
+                let move_node = self.open_node(|| TreeNodeKind::Move(mov.clone()));
+                let (score, guaranteed_loss) = with_child(
+                    board,
+                    minimizing_player,
+                    mov,
+                    self.undo,
+                    &mut *scratch,
+                    |board, scratch| match board.get_winner() {
+                        Some(player) if player == minimizing_player => (-WIN_SCORE, true),
+                        Some(_) => {
+                            panic!("This should never happen: maximizing player won after minimizing player's move")
+                        }
+                        None => {
+                            let to_move = if dice_roll == 6 {
+                                minimizing_player
+                            } else {
+                                maximizing_player
+                            };
+
+                            (
+                                self.expectiminimax(board, node.child(to_move), rng, scratch),
+                                false,
+                            )
+                        }
+                    },
+                );
+
+                self.close_node(move_node, score);
+
+                min_score = min_score.min(score);
+                node.beta = node.beta.min(score);
+
+                // The minimizing player can guarantee a loss with this move, no need to look further
+                if guaranteed_loss {
+                    break;
+                }
+
+                // Alpha-beta pruning: maximizing player will never allow this move
+                if min_score <= node.alpha {
+                    break;
+                }
+            }
+
+            self.tree_pruned(&moves[searched..]);
+            self.close_node(dice_node, min_score);
+            min_score
+        }
+    }
+
+    /// Evaluates the six branches of a chance node in parallel, each with a fork of this player
+    /// and an RNG seeded from `rng`. The branches are independent, so the result is the same
+    /// as searching them one by one, up to the random move ordering.
+    fn parallel_dice_branches(
+        &mut self,
+        board: &Board,
+        hash: BoardHash,
+        node: SearchNode,
+        rng: &mut SmallRng,
+    ) -> [f64; 6] {
+        let seeds: [u64; 6] = std::array::from_fn(|_| rng.gen());
+
+        let branches = (0..6)
+            .into_par_iter()
+            .map(|index| {
+                let mut branch = self.fork();
+                let mut rng = SmallRng::seed_from_u64(seeds[index]);
+                let mut board = board.clone();
+
+                let score =
+                    branch.dice_branch(&mut board, hash, index as u8 + 1, node, &mut rng, &mut []);
+
+                (score, branch)
+            })
+            .collect::<Vec<_>>();
+
+        let mut scores = [0.0; 6];
+
+        for (index, (score, branch)) in branches.into_iter().enumerate() {
+            scores[index] = score;
+            self.evaluations += branch.evaluations;
+            self.stats.move_cache_hits += branch.stats.move_cache_hits;
+            self.stats.move_cache_misses += branch.stats.move_cache_misses;
+        }
+
+        scores
+    }
+
+    // A copy of this player for a parallel branch of the search, sharing the transposition table
+    // but with its own counters and an empty move cache
+    fn fork(&self) -> Self {
+        GameTreePlayer {
+            heuristic: self.heuristic.clone(),
+            max_depth: self.max_depth,
+            name: self.name,
+            evaluations: 0,
+            cache: self.cache.clone(),
+            arena: None,
+            undo: self.undo,
+            move_cache: self.move_cache.as_ref().map(|_| MoveCache::default()),
+            stats: SearchStats::default(),
+            parallel_plies: self.parallel_plies,
+            table: self.table,
+            noise: self.noise,
+            move_ordering: self.move_ordering,
+            time_budget: None,
+            verbose: false,
+            tree: None,
+        }
+    }
+
+    /// Searches like `select_move` without a time budget, and records the first `limit` nodes of
+    /// the search tree. The chance nodes are searched one by one, so that every branch is
+    /// recorded, even with `parallel_plies`.
+    pub fn search_tree(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &[StruggleMove],
+        limit: usize,
+        rng: &mut SmallRng,
+    ) -> SearchTree {
+        let parallel_plies = std::mem::replace(&mut self.parallel_plies, 0);
+        self.tree = Some(SearchTree::new(limit));
+
+        if self.table != TableUse::Off {
+            self.cache.new_search();
+        }
+
+        if let Some(cache) = &mut self.move_cache {
+            cache.clear();
+        }
+
+        let mut best = f64::NEG_INFINITY;
+
+        for mov in moves {
+            let node = self.open_node(|| TreeNodeKind::Move(mov.clone()));
+            let score = self.evaluate_move(ctx, board, mov, rng);
+            self.close_node(node, score);
+
+            best = best.max(score);
+        }
+
+        self.parallel_plies = parallel_plies;
+        let mut tree = self.tree.take().unwrap();
+
+        if !moves.is_empty() {
+            tree.nodes[0].score = Some(best);
+            tree.chosen = tree
+                .nodes
+                .iter()
+                .position(|node| node.parent == Some(0) && node.score == Some(best));
+        }
+
+        tree
+    }
+
+    // Adds a node to the recorded tree, if there is one. `kind` is only built when recording.
+    fn open_node(&mut self, kind: impl FnOnce() -> TreeNodeKind) -> Option<usize> {
+        self.tree.as_mut().and_then(|tree| tree.open(kind()))
+    }
+
+    fn close_node(&mut self, parent: Option<usize>, score: f64) {
+        if let Some(tree) = &mut self.tree {
+            tree.close(parent, score);
+        }
+    }
+
+    fn tree_leaf(&mut self, kind: impl FnOnce() -> TreeNodeKind, score: Option<f64>) {
+        if let Some(tree) = &mut self.tree {
+            tree.leaf(kind(), score);
+        }
+    }
+
+    fn tree_pruned(&mut self, moves: &[StruggleMove]) {
+        if let Some(tree) = &mut self.tree {
+            for mov in moves {
+                tree.leaf(TreeNodeKind::Pruned(mov.clone()), None);
+            }
+        }
+    }
+
+    /// Evaluates a single candidate move for the current player with expectiminimax.
+    pub fn evaluate_move(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        mov: &StruggleMove,
+        rng: &mut SmallRng,
+    ) -> f64 {
+        self.evaluate_move_to_depth(ctx, board, mov, self.max_depth, rng)
+    }
+
+    fn evaluate_move_to_depth(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        mov: &StruggleMove,
+        max_depth: u8,
+        rng: &mut SmallRng,
+    ) -> f64 {
+        let next_turn = match ctx.dice {
+            6 => ctx.current_player,
+            _ => ctx.other_player,
+        };
+
+        let mut arena = self.arena.take();
+        // The search makes its moves on this copy with `undo`, and takes them back
+        let mut board = board.clone();
+
+        let score = {
+            let scratch: &mut [Board] = match &mut arena {
+                Some(arena) if !self.undo => arena.reset(&board, max_depth),
+                _ => &mut [],
+            };
+
+            with_child(
+                &mut board,
+                ctx.current_player,
+                mov,
+                self.undo,
+                scratch,
+                |board, scratch| {
+                    let node = SearchNode {
+                        current_player: next_turn,
+                        maximizing_player: ctx.current_player,
+                        minimizing_player: ctx.other_player,
+                        max_depth,
+                        depth: 0,
+                        alpha: f64::NEG_INFINITY,
+                        beta: f64::INFINITY,
+                    };
+
+                    self.expectiminimax(board, node, rng, scratch)
+                },
+            )
+        };
+
+        self.arena = arena;
+        score
+    }
+
+    /// Scores every candidate move, in the same order as `moves`. Unlike `select_move`, no tie-breaking
+    /// noise is added, so the scores can be shown to a human to explain the choice.
+    pub fn evaluate_moves(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &[StruggleMove],
+        rng: &mut SmallRng,
+    ) -> Vec<f64> {
+        moves
+            .iter()
+            .map(|mov| self.evaluate_move(ctx, board, mov, rng))
+            .collect()
+    }
+}
+
+/// Sets up a `GameTreePlayer`, see `GameTreePlayer::builder`.
+///
+/// ```
+/// use std::time::Duration;
+/// use struggle_core::games::struggle::players::{default_heuristic, GameTreePlayer, TableUse};
+///
+/// let player = GameTreePlayer::builder(default_heuristic, "Tuned")
+///     .depth(3)
+///     .time_budget(Duration::from_millis(50))
+///     .transposition_table(TableUse::Leaves)
+///     .table_capacity(1 << 20)
+///     .noise(0.0)
+///     .build();
+/// ```
+pub struct GameTreePlayerBuilder<F>
+where
+    F: Fn(&Board, PlayerColor, PlayerColor) -> f64,
+{
+    player: GameTreePlayer<F>,
+    table_capacity: Option<usize>,
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync>
+    GameTreePlayerBuilder<F>
+{
+    /// The number of plies to search, counting both players' moves.
+    pub fn depth(mut self, max_depth: u8) -> Self {
+        self.player.max_depth = max_depth;
+        self
+    }
+
+    /// Searches one ply deeper at a time, and doesn't start a deeper search after `budget` has
+    /// passed. The search that is running at the deadline is finished, so a move can take longer.
+    pub fn time_budget(mut self, budget: Duration) -> Self {
+        self.player.time_budget = Some(budget);
+        self
+    }
+
+    /// Off by default.
+    pub fn transposition_table(mut self, table: TableUse) -> Self {
+        self.player.table = table;
+        self
+    }
+
+    /// The number of entries in the transposition table, rounded up to a power of two.
+    pub fn table_capacity(mut self, capacity: usize) -> Self {
+        self.table_capacity = Some(capacity);
+        self
+    }
+
+    /// The scale of the random noise added to the scores of the moves to break ties, 1 by default.
+    /// Without noise, the first of the best moves is chosen.
+    pub fn noise(mut self, noise: f64) -> Self {
+        self.player.noise = noise;
+        self
+    }
+
+    /// Whether to search the likely best moves first, which makes alpha-beta pruning more
+    /// effective. On by default.
+    pub fn move_ordering(mut self, move_ordering: bool) -> Self {
+        self.player.move_ordering = move_ordering;
+        self
+    }
+
+    /// Logs the scores of the moves at the `info` level instead of `debug`, to follow a single
+    /// player without the noise of the others.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.player.verbose = verbose;
+        self
+    }
+
+    /// Caches the moves of every position during a search, see `MoveCache`.
+    pub fn move_cache(mut self) -> Self {
+        self.player.move_cache = Some(MoveCache::default());
+        self
+    }
+
+    /// Searches with a `SearchArena` of scratch boards.
+    pub fn arena(mut self) -> Self {
+        self.player.arena = Some(SearchArena::default());
+        self
+    }
+
+    /// Searches on a single board, making each move on the way down and taking it back on the
+    /// way up, instead of copying the board at every node. Takes precedence over the arena.
+    pub fn undo(mut self) -> Self {
+        self.player.undo = true;
+        self
+    }
+
+    /// Searches the dice branches of the chance nodes in the top `plies` plies in parallel. This
+    /// speeds up even the analysis of a single position, at the cost of a fork of the player per
+    /// branch, so one or two plies are usually enough.
+    pub fn parallel_plies(mut self, plies: u8) -> Self {
+        self.player.parallel_plies = plies;
+        self
+    }
+
+    pub fn build(self) -> GameTreePlayer<F> {
+        let mut player = self.player;
+
+        if let Some(capacity) = self.table_capacity {
+            player.cache = Arc::new(TranspositionTable::with_capacity(capacity));
+        }
+
+        player
+    }
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync> GamePlayer<StruggleRules>
+    for GameTreePlayer<F>
+{
+    fn select_move<'a>(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        if moves.len() == 1 {
+            return moves.first().unwrap();
+        }
+
+        let _span = debug_span!(
+            "select_move",
+            player = %self.name(),
+            depth = self.max_depth,
+            dice = ctx.dice
+        )
+        .entered();
+        let evaluations = self.evaluations;
+
+        // With a time budget, every depth up to `max_depth` is searched in turn, and the result
+        // of the deepest one that was started before the deadline is kept
+        let (first_depth, deadline) = match self.time_budget {
+            Some(budget) => (1.min(self.max_depth), Some(Instant::now() + budget)),
+            None => (self.max_depth, None),
+        };
+
+        let mut chosen = &moves[0];
+
+        for depth in first_depth..=self.max_depth {
+            if deadline.is_some_and(|deadline| depth > first_depth && Instant::now() >= deadline) {
+                break;
+            }
+
+            if self.table != TableUse::Off {
+                self.cache.new_search();
+            }
+
+            if let Some(cache) = &mut self.move_cache {
+                cache.clear();
+            }
+
+            chosen = moves
+                .iter()
+                .max_by_key(|mov| {
+                    let score = self.evaluate_move_to_depth(ctx, board, mov, depth, rng);
+
+                    if self.verbose {
+                        info!(depth, ?mov, score, "scored a move");
+                    } else {
+                        debug!(depth, ?mov, score, "scored a move");
+                    }
+
+                    // Add a bit of random noise to break ties
+                    OrderedFloat(score + self.noise * rng.gen::<f64>())
+                })
+                .unwrap();
+        }
+
+        let nodes = self.evaluations - evaluations;
+
+        if self.verbose {
+            info!(?chosen, nodes, "chose a move");
+        } else {
+            debug!(?chosen, nodes, "chose a move");
+        }
+
+        chosen
+    }
+
+    fn reset(&mut self) {
+        self.evaluations = 0;
+        self.stats = SearchStats::default();
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.evaluations
+    }
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync>
+    ScoringPlayer<StruggleRules> for GameTreePlayer<F>
+{
+    fn score_moves(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &[StruggleMove],
+        rng: &mut SmallRng,
+    ) -> Vec<f64> {
+        self.evaluate_moves(ctx, board, moves, rng)
+    }
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64> NamedPlayer for GameTreePlayer<F> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from(format!("{}({})", self.name, self.max_depth))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        let config = PlayerConfig::new(self.name())
+            .with_parameter("depth", self.max_depth)
+            .with_parameter("table", format!("{:?}", self.table))
+            .with_parameter("noise", self.noise)
+            .with_parameter("move_ordering", self.move_ordering)
+            .with_parameter("parallel_plies", self.parallel_plies);
+
+        match self.time_budget {
+            Some(budget) => config.with_parameter("time_budget_ms", budget.as_millis()),
+            None => config,
+        }
+    }
+}
+
+/// A `GameTreePlayer` that searches every move to a depth drawn from `depths` in proportion to
+/// their weights, like an opponent of inconsistent strength.
+#[derive(Clone)]
+pub struct RandomDepthPlayer<F>
+where
+    F: Fn(&Board, PlayerColor, PlayerColor) -> f64,
+{
+    pub player: GameTreePlayer<F>,
+    /// The depths and their weights
+    pub depths: Vec<(u8, f64)>,
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync> RandomDepthPlayer<F> {
+    /// Panics if there are no depths, or if the weights aren't positive.
+    pub fn new(player: GameTreePlayer<F>, depths: Vec<(u8, f64)>) -> Self {
+        assert!(
+            !depths.is_empty(),
+            "A random depth needs at least one depth"
+        );
+        assert!(
+            depths.iter().all(|(_, weight)| *weight > 0.0),
+            "The weights of the depths must be positive"
+        );
+
+        Self { player, depths }
+    }
+
+    /// Picks a depth from `min..=max` uniformly.
+    pub fn uniform(player: GameTreePlayer<F>, min: u8, max: u8) -> Self {
+        Self::new(player, (min..=max).map(|depth| (depth, 1.0)).collect())
+    }
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync> GamePlayer<StruggleRules>
+    for RandomDepthPlayer<F>
+{
+    fn select_move<'a>(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        let (depth, _) = self
+            .depths
+            .choose_weighted(rng, |(_, weight)| *weight)
+            .unwrap();
+
+        self.player.max_depth = *depth;
+        self.player.select_move(ctx, board, moves, rng)
+    }
+
+    fn reset(&mut self) {
+        self.player.reset()
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.player.total_evaluations()
+    }
+}
+
+impl<F: Fn(&Board, PlayerColor, PlayerColor) -> f64> NamedPlayer for RandomDepthPlayer<F> {
+    fn name(&self) -> Cow<'static, str> {
+        let total = self.depths.iter().map(|(_, weight)| weight).sum::<f64>();
+        let depths = self
+            .depths
+            .iter()
+            .map(|(depth, weight)| format!("{}: {:.0}%", depth, weight / total * 100.0))
+            .collect::<Vec<_>>();
+
+        Cow::from(format!("{}({})", self.player.name, depths.join(", ")))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name())
+            .with_inner("player", self.player.describe())
+            .with_parameter("depths", format!("{:?}", self.depths))
+    }
+}
+
+/// The terms of `default_heuristic`, so that they can be tuned.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HeuristicWeights {
+    pub base_piece_score: f64,
+    pub enemy_home_penalty: f64,
+    pub own_home_penalty: f64,
+    pub advance_piece_multiplier: f64,
+    pub at_eating_distance_bonus: f64,
+    pub base_piece_in_goal_score: f64,
+    pub advance_piece_in_goal_multiplier: f64,
+    pub relative_advancement_power: f64,
+    pub can_enter_goal_bonus: f64,
+}
+
+impl HeuristicWeights {
+    /// The hand-tuned weights of `default_heuristic`.
+    pub const DEFAULT: Self = Self {
+        base_piece_score: 550.0,
+        enemy_home_penalty: 100.0,
+        own_home_penalty: 100.0,
+        advance_piece_multiplier: 200.0,
+        at_eating_distance_bonus: 100.0,
+        base_piece_in_goal_score: 1000.0,
+        advance_piece_in_goal_multiplier: 10.0,
+        relative_advancement_power: 1.1,
+        can_enter_goal_bonus: 20.0,
+    };
+
+    pub const NAMES: [&'static str; 9] = [
+        "base_piece_score",
+        "enemy_home_penalty",
+        "own_home_penalty",
+        "advance_piece_multiplier",
+        "at_eating_distance_bonus",
+        "base_piece_in_goal_score",
+        "advance_piece_in_goal_multiplier",
+        "relative_advancement_power",
+        "can_enter_goal_bonus",
+    ];
+
+    /// The weight with one of the `NAMES`.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut f64> {
+        Some(match name {
+            "base_piece_score" => &mut self.base_piece_score,
+            "enemy_home_penalty" => &mut self.enemy_home_penalty,
+            "own_home_penalty" => &mut self.own_home_penalty,
+            "advance_piece_multiplier" => &mut self.advance_piece_multiplier,
+            "at_eating_distance_bonus" => &mut self.at_eating_distance_bonus,
+            "base_piece_in_goal_score" => &mut self.base_piece_in_goal_score,
+            "advance_piece_in_goal_multiplier" => &mut self.advance_piece_in_goal_multiplier,
+            "relative_advancement_power" => &mut self.relative_advancement_power,
+            "can_enter_goal_bonus" => &mut self.can_enter_goal_bonus,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for HeuristicWeights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn heuristic_evaluate_side(
+    board: &Board,
+    player: PlayerColor,
+    enemy: PlayerColor,
+    weights: &HeuristicWeights,
+) -> f64 {
+    let (own_pieces, enemy_pieces) = board.get_pieces(player, enemy);
+
+    let my_home = Board::get_start(player);
+    let enemy_home = Board::get_start(enemy);
+    let my_pieces_waiting = board.home_bases[player as usize].pieces_waiting;
+    let enemy_pieces_waiting = board.home_bases[enemy as usize].pieces_waiting;
+
+    let mut score = 0.0;
+
+    for piece in own_pieces {
+        match piece {
+            PiecePosition::Board(i) => {
+                score += weights.base_piece_score;
+
+                let distance_to_goal = board.distance_to_goal_entrance(player, *i);
+                let relative_advancement = 1.0 - distance_to_goal as f64 / 28.0;
+
+                // Encourage moving pieces that are already close to the goal further
+                score += relative_advancement.powf(weights.relative_advancement_power)
+                    * weights.advance_piece_multiplier;
+
+                // Penalize for being in the enemy home, because it's risky (unless there are no pieces waiting)
+                if *i == enemy_home && enemy_pieces_waiting > 0 {
+                    score -= weights.enemy_home_penalty;
+                }
+
+                // Give a small penalty for being in your own home (if there are still pieces waiting)
+                // because it blocks mobilizing other pieces
+                if *i == my_home && my_pieces_waiting > 0 {
+                    score -= weights.own_home_penalty;
+                }
+
+                for enemy_i in enemy_pieces
+                    .iter()
+                    .copied()
+                    .filter_map(PiecePosition::as_board_index)
+                {
+                    let distance_to_enemy = board.clockwise_distance(*i, enemy_i);
+
+                    // Small bonus for being within eating distance
+                    if (1..=6).contains(&distance_to_enemy) {
+                        score += weights.at_eating_distance_bonus;
+                    }
+                }
+
+                // If the piece can enter a goal slot with a roll of 1-6, give a bonus for each slot
+                for goal_position in 0..4u8 {
+                    let is_free = board.goals[player as usize][goal_position as usize].is_none();
+                    if !is_free {
+                        continue;
+                    }
+                    let distance = board.distance_to_goal_slot(player, *i, goal_position);
+                    if (1..=6).contains(&distance) {
+                        score += weights.can_enter_goal_bonus;
+                    }
+                }
+            }
+            PiecePosition::Goal(n) => {
+                score += weights.base_piece_in_goal_score
+                    + (*n as f64 / 3.0) * weights.advance_piece_in_goal_multiplier;
+            }
+        }
+    }
+
+    score
+}
+
+pub fn default_heuristic(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    weighted_heuristic(board, player, enemy, &HeuristicWeights::DEFAULT)
+}
+
+/// `default_heuristic` with different weights.
+pub fn weighted_heuristic(
+    board: &Board,
+    player: PlayerColor,
+    enemy: PlayerColor,
+    weights: &HeuristicWeights,
+) -> f64 {
+    match board.get_winner() {
+        Some(winner) if winner == player => {
+            return WIN_SCORE;
+        }
+        Some(_) => {
+            return -WIN_SCORE;
+        }
+        None => {}
+    }
+
+    let my_score = heuristic_evaluate_side(board, player, enemy, weights);
+    let enemy_score = heuristic_evaluate_side(board, enemy, player, weights);
+    my_score - enemy_score
+}
+
+pub fn minimal_heuristic(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    match board.get_winner() {
+        Some(winner) if winner == player => {
+            return WIN_SCORE;
+        }
+        Some(_) => {
+            return -WIN_SCORE;
+        }
+        None => {}
+    }
+
+    let mut score = 0.0;
+
+    let (my_pieces, enemy_pieces) = board.get_pieces(player, enemy);
+
+    for piece in my_pieces {
+        match piece {
+            PiecePosition::Board(_) => {
+                score += 1.0;
+            }
+            PiecePosition::Goal(_) => {
+                score += 10.0;
+            }
+        }
+    }
+
+    for piece in enemy_pieces {
+        match piece {
+            PiecePosition::Board(_) => {
+                score -= 10.0;
+            }
+            PiecePosition::Goal(_) => {
+                score -= 100.0;
+            }
+        }
+    }
+
+    score
+}
+
+pub fn expectiminimax(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(default_heuristic, depth, "Expectiminimax")
+}
+
+/// Expectiminimax that searches every move to a depth between 1 and `max_depth`, see
+/// `RandomDepthPlayer`.
+pub fn random_depth_expectiminimax(max_depth: u8) -> impl StrugglePlayer {
+    RandomDepthPlayer::uniform(
+        GameTreePlayer::new(default_heuristic as HeuristicFunction, 1, "Expectiminimax"),
+        1,
+        max_depth.max(1),
+    )
+}
+
+/// Expectiminimax with `default_heuristic` using custom weights.
+pub fn weighted_expectiminimax(depth: u8, weights: HeuristicWeights) -> impl StrugglePlayer {
+    GameTreePlayer::new(
+        move |board: &Board, player, enemy| weighted_heuristic(board, player, enemy, &weights),
+        depth,
+        "WeightedExpectiminimax",
+    )
+}
+
+pub fn expectiminimax_mvp(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(minimal_heuristic, depth, "ExpectiminimaxBasic")
+}
+
+pub fn worst_expectiminimax(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(
+        |b: &Board, p1, p2| -default_heuristic(b, p1, p2),
+        depth,
+        "WorstExpectiminimax",
+    )
+}
+
+pub fn participation_trophy(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(
+        |board: &Board, player, _| -(board.home_bases[player as usize].pieces_waiting as f64),
+        depth,
+        "ParticipationTrophy",
+    )
+}
+
+pub fn one_at_a_time(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(
+        |board: &Board, player, _| board.home_bases[player as usize].pieces_waiting as f64,
+        depth,
+        "OneAtATime",
+    )
+}
+
+fn one_at_a_time_heuristic(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    let (own_pieces, enemy_pieces) = board.get_pieces(player, enemy);
+
+    let mut score = 0.0;
+
+    match board.get_winner() {
+        Some(winner) if winner == player => {
+            return 10000000.0;
+        }
+        Some(_) => {
+            return -10000000.0;
+        }
+        None => {}
+    }
+
+    let mut own_pieces_on_board = 0;
+
+    for piece in own_pieces {
+        match piece {
+            PiecePosition::Board(_) => {
+                own_pieces_on_board += 1;
+            }
+            PiecePosition::Goal(_) => {
+                score += 10000.0;
+            }
+        }
+    }
+
+    if own_pieces_on_board > 1 {
+        score -= (own_pieces_on_board - 1) as f64 * 100.0;
+    }
+
+    for piece in enemy_pieces {
+        match piece {
+            PiecePosition::Board(_) => {
+                score -= 2000.0;
+            }
+            PiecePosition::Goal(_) => {
+                score -= 10000.0;
+            }
+        }
+    }
+
+    score
+}
+
+pub fn one_at_a_time_deluxe(max_depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(one_at_a_time_heuristic, max_depth, "OneAtATimeDeluxe")
+}
+
+/// The probability that `enemy` can eat one of `player`'s pieces with its next roll.
+pub fn eat_probability(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    let threats = (1..=6)
+        .filter(|&dice| {
+            board
+                .get_moves(dice, enemy, player)
+                .iter()
+                .any(StruggleMove::eats)
+        })
+        .count();
+
+    threats as f64 / 6.0
+}
+
+fn defensive_evaluate_side(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    let (own_pieces, _) = board.get_pieces(player, enemy);
+
+    let enemy_home = Board::get_start(enemy);
+    let enemy_pieces_waiting = board.home_bases[enemy as usize].pieces_waiting;
+
+    let mut score = 0.0;
+
+    for piece in own_pieces {
+        match piece {
+            PiecePosition::Board(i) => {
+                let distance_to_goal = board.distance_to_goal_entrance(player, *i);
+                score += 500.0 + (1.0 - distance_to_goal as f64 / 28.0) * 200.0;
+
+                // The enemy's start is where pieces get eaten without the enemy having to aim
+                if *i == enemy_home && enemy_pieces_waiting > 0 {
+                    score -= 400.0;
+                }
+            }
+            PiecePosition::Goal(n) => {
+                score += 1000.0 + *n as f64 * 10.0;
+            }
+        }
+    }
+
+    // A piece that gets eaten loses all its progress, so exposure costs more than any single
+    // piece is worth
+    score - eat_probability(board, player, enemy) * 1500.0
+}
+
+fn defensive_heuristic(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    match board.get_winner() {
+        Some(winner) if winner == player => {
+            return WIN_SCORE;
+        }
+        Some(_) => {
+            return -WIN_SCORE;
+        }
+        None => {}
+    }
+
+    defensive_evaluate_side(board, player, enemy) - defensive_evaluate_side(board, enemy, player)
+}
+
+/// Expectiminimax that keeps its pieces out of the enemy's reach, and off the enemy's start,
+/// even at the cost of progress.
+pub fn defensive_expectiminimax(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(defensive_heuristic, depth, "Defensive")
+}
+
+// The pieces in goal and how far the most advanced piece on the board has come
+fn rusher_progress(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    let (own_pieces, _) = board.get_pieces(player, enemy);
+
+    let most_advanced = own_pieces
+        .iter()
+        .copied()
+        .filter_map(PiecePosition::as_board_index)
+        .map(|i| 1.0 - board.distance_to_goal_entrance(player, i) as f64 / 28.0)
+        .fold(0.0, f64::max);
+
+    board.pieces_in_goal(player) as f64 * 1000.0 + most_advanced * 500.0
+}
+
+fn rusher_heuristic(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    match board.get_winner() {
+        Some(winner) if winner == player => {
+            return WIN_SCORE;
+        }
+        Some(_) => {
+            return -WIN_SCORE;
+        }
+        None => {}
+    }
+
+    let (own_pieces, enemy_pieces) = board.get_pieces(player, enemy);
+    let enemy_tiles = enemy_pieces
+        .iter()
+        .copied()
+        .filter_map(PiecePosition::as_board_index);
+
+    let attacking = own_pieces
+        .iter()
+        .copied()
+        .filter_map(PiecePosition::as_board_index)
+        .flat_map(|i| {
+            enemy_tiles
+                .clone()
+                .filter(move |&enemy_i| (1..=6).contains(&board.clockwise_distance(i, enemy_i)))
+        })
+        .count();
+
+    // Being eaten only matters through the enemy's progress, which eating sets back
+    rusher_progress(board, player, enemy) + attacking as f64 * 100.0
+        - rusher_progress(board, enemy, player)
+}
+
+/// Expectiminimax that runs its most advanced piece home and eats whatever it can reach, with no
+/// regard for its own safety.
+pub fn rusher_expectiminimax(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(rusher_heuristic, depth, "Rusher")
+}
+
+fn count_moves_heuristic(board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+    (1..=6)
+        .map(|die| board.get_moves(die, player, enemy).len() as f64)
+        .sum::<f64>()
+        / 6.0
+}
+
+pub fn maximize_options(depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(count_moves_heuristic, depth, "MaximizeOptions")
+}
+
+pub fn minimize_options(max_depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(
+        |board: &Board, player, enemy| -count_moves_heuristic(board, enemy, player),
+        max_depth,
+        "MinimizeOptions",
+    )
+}
+
+fn maximize_length_heuristic(board: &Board) -> f64 {
+    if board.get_winner().is_some() {
+        return -1000000.0;
+    }
+
+    let players = board.players();
+    let (a_pieces, b_pieces) = board.get_pieces(players.0, players.1);
+    let mut score = 0.0;
+
+    score -= board.home_bases[players.0 as usize].pieces_waiting as f64 * 2.0;
+    score -= board.home_bases[players.1 as usize].pieces_waiting as f64 * 2.0;
+
+    for (player, pieces) in &[(players.0, a_pieces), (players.1, b_pieces)] {
+        for piece in pieces.iter() {
+            match piece {
+                PiecePosition::Board(pos) => {
+                    let distance_to_goal = board.distance_to_goal_entrance(*player, *pos);
+                    let relative_distance = 1.0 - distance_to_goal as f64 / 28.0;
+
+                    score -= relative_distance * 50.0;
+                }
+                PiecePosition::Goal(_) => {
+                    score -= 1000.0;
+                }
+            }
+        }
+    }
+
+    score
+}
+
+pub fn maximize_length_expectiminimax(max_depth: u8) -> impl StrugglePlayer {
+    GameTreePlayer::new(
+        |board: &Board, _player, _enemy| maximize_length_heuristic(board),
+        max_depth,
+        "MaximizeLength",
+    )
+}
+
+#[derive(Clone)]
+pub struct StatefulGetItOverWith {
+    supporting: Option<PlayerColor>,
+    max_depth: u8,
+}
+
+pub fn stateful_get_it_over_with(max_depth: u8) -> impl StrugglePlayer {
+    StatefulGetItOverWith {
+        supporting: None,
+        max_depth,
+    }
+}
+
+impl GamePlayer<StruggleRules> for StatefulGetItOverWith {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &GameContext,
+        board: &Board,
+        moves: &'a [StruggleMove],
+        rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        if self.supporting.is_none() {
+            let own_pieces_in_goal = board.pieces_in_goal(ctx.current_player);
+            let enemy_pieces_in_goal = board.pieces_in_goal(ctx.other_player);
+
+            self.supporting = if own_pieces_in_goal >= 1 {
+                Some(ctx.current_player)
+            } else if enemy_pieces_in_goal >= 1 {
+                Some(ctx.other_player)
+            } else {
+                None
+            };
+        }
+
+        if let Some(supporting) = self.supporting {
+            GameTreePlayer::new(
+                |board: &Board, player, enemy| {
+                    if player == supporting {
+                        default_heuristic(board, player, enemy)
+                    } else {
+                        default_heuristic(board, enemy, player)
+                    }
+                },
+                self.max_depth,
+                "GetItOverWithInternal",
+            )
+            .select_move(ctx, board, moves, rng)
+        } else {
+            RandomPlayer.select_move(ctx, board, moves, rng)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.supporting = None;
+    }
+}
+
+impl NamedPlayer for StatefulGetItOverWith {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from("GetItOverWith")
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name()).with_parameter("depth", self.max_depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eat_probability_counts_the_rolls_that_eat() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[10] = Some(PlayerColor::Red);
+        board.tiles[7] = Some(PlayerColor::Yellow);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        // Yellow eats with a 3
+        let red = eat_probability(&board, PlayerColor::Red, PlayerColor::Yellow);
+        assert!((red - 1.0 / 6.0).abs() < 1e-9);
+
+        // And with a 6 once Red sits on Yellow's start
+        board.tiles[Board::YELLOW_START as usize] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 2;
+        board.update_piece_cache();
+
+        let red = eat_probability(&board, PlayerColor::Red, PlayerColor::Yellow);
+        assert!((red - 2.0 / 6.0).abs() < 1e-9);
+        assert_eq!(
+            eat_probability(&board, PlayerColor::Yellow, PlayerColor::Red),
+            0.0
+        );
+    }
+
+    #[test]
+    fn random_depth_searches_to_every_depth() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        let mut player = RandomDepthPlayer::uniform(
+            GameTreePlayer::new(default_heuristic as HeuristicFunction, 1, "Expectiminimax"),
+            1,
+            2,
+        );
+        let mut depths = [false; 3];
+
+        for _ in 0..30 {
+            player.select_move(&ctx, &board, &moves, &mut rng);
+            depths[player.player.max_depth as usize] = true;
+        }
+
+        assert_eq!(depths, [false, true, true]);
+        assert_eq!(player.name(), "Expectiminimax(1: 50%, 2: 50%)");
+    }
+
+    #[test]
+    fn arena_doesnt_change_the_scores() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        let mut plain = GameTreePlayer::new(default_heuristic, 2, "Plain");
+        let mut arena = GameTreePlayer::builder(default_heuristic, "Arena")
+            .depth(2)
+            .arena()
+            .build();
+
+        let plain_scores =
+            plain.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+        let arena_scores =
+            arena.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(plain_scores, arena_scores);
+        assert_eq!(plain.evaluations, arena.evaluations);
+    }
+
+    #[test]
+    fn undo_doesnt_change_the_scores() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[4] = Some(PlayerColor::Red);
+        board.tiles[9] = Some(PlayerColor::Yellow);
+        board.tiles[25] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 2;
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 5,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        let mut plain = GameTreePlayer::new(default_heuristic, 2, "Plain");
+        let mut undo = GameTreePlayer::builder(default_heuristic, "Undo")
+            .depth(2)
+            .undo()
+            .build();
+
+        let plain_scores =
+            plain.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+        let undo_scores =
+            undo.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(plain_scores, undo_scores);
+        assert_eq!(plain.evaluations, undo.evaluations);
+    }
+
+    #[test]
+    fn parallel_chance_nodes_dont_change_the_scores() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.tiles[12] = Some(PlayerColor::Yellow);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        // At depth 1 nothing is pruned, so the move ordering can't change the scores
+        let mut sequential = GameTreePlayer::new(default_heuristic, 1, "Sequential");
+        let mut parallel = GameTreePlayer::builder(default_heuristic, "Parallel")
+            .depth(1)
+            .parallel_plies(1)
+            .build();
+
+        let sequential_scores =
+            sequential.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+        let parallel_scores =
+            parallel.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(sequential_scores, parallel_scores);
+        assert_eq!(sequential.evaluations, parallel.evaluations);
+    }
+
+    #[test]
+    fn move_cache_doesnt_change_the_choice() {
+        // Adding a piece and moving the other one with two sixes reach the same position in
+        // either order, so the search sees it twice
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        let mut plain = GameTreePlayer::new(default_heuristic, 2, "Plain");
+        let mut cached = GameTreePlayer::builder(default_heuristic, "Cached")
+            .depth(2)
+            .move_cache()
+            .build();
+
+        let plain_move = plain.select_move(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+        let cached_move = cached.select_move(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(plain_move, cached_move);
+        assert_eq!(plain.evaluations, cached.evaluations);
+
+        let stats = cached.search_stats();
+        assert!(stats.move_cache_hits > 0);
+        assert!(stats.move_cache_hit_rate() < 1.0);
+    }
+
+    #[test]
+    fn move_cache_tells_the_players_apart() {
+        // Yellow has every piece at home, so unless it rolls a six it skips its turn and hands
+        // Red the same tiles back. A cache that doesn't know who is to move answers Red with
+        // Yellow's moves, and the other way around.
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.tiles[9] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 2;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 2,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        let mut plain = GameTreePlayer::new(default_heuristic, 3, "Plain");
+        let mut cached = GameTreePlayer::builder(default_heuristic, "Cached")
+            .depth(3)
+            .move_cache()
+            .build();
+
+        let plain_scores =
+            plain.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+        let cached_scores =
+            cached.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(plain_scores, cached_scores);
+        assert_eq!(plain.evaluations, cached.evaluations);
+        assert!(cached.search_stats().move_cache_hits > 0);
+    }
+
+    #[test]
+    fn spent_time_budget_stops_at_the_first_depth() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        let mut shallow = GameTreePlayer::builder(default_heuristic, "Shallow")
+            .noise(0.0)
+            .build();
+        let mut budgeted = GameTreePlayer::builder(default_heuristic, "Budgeted")
+            .depth(3)
+            .time_budget(Duration::ZERO)
+            .noise(0.0)
+            .build();
+
+        let shallow_move =
+            shallow.select_move(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+        let budgeted_move =
+            budgeted.select_move(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        assert_eq!(shallow_move, budgeted_move);
+        assert_eq!(shallow.evaluations, budgeted.evaluations);
+    }
+
+    #[test]
+    fn shallow_table_entries_dont_answer_deeper_searches() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[3] = Some(PlayerColor::Red);
+        board.tiles[12] = Some(PlayerColor::Yellow);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.home_bases[PlayerColor::Yellow as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+
+        let mut plain = GameTreePlayer::builder(default_heuristic, "Plain")
+            .depth(3)
+            .move_ordering(false)
+            .build();
+        let plain_scores =
+            plain.evaluate_moves(&ctx, &board, &moves, &mut SmallRng::seed_from_u64(0));
+
+        // Leaves only stores exact heuristic values, so the scores can only change if a value
+        // from a shallower horizon answers a deeper node
+        let mut tabled = GameTreePlayer::builder(default_heuristic, "Tabled")
+            .depth(3)
+            .transposition_table(TableUse::Leaves)
+            .move_ordering(false)
+            .build();
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        // Like the first iterations of iterative deepening, which fill the table with the values
+        // at their horizons
+        for depth in 1..3 {
+            for mov in &moves {
+                tabled.evaluate_move_to_depth(&ctx, &board, mov, depth, &mut rng);
+            }
+        }
+
+        let tabled_scores = tabled.evaluate_moves(&ctx, &board, &moves, &mut rng);
+
+        // The table stores f32s
+        for (plain, tabled) in plain_scores.iter().zip(&tabled_scores) {
+            assert!(
+                (plain - tabled).abs() <= 1e-5 * plain.abs().max(1.0),
+                "{} != {}",
+                plain,
+                tabled
+            );
+        }
+    }
+
+    #[test]
+    fn search_tree_records_a_decision() {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        board.tiles[2] = Some(PlayerColor::Red);
+        board.home_bases[PlayerColor::Red as usize].pieces_waiting = 3;
+        board.update_piece_cache();
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 6,
+        };
+        let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+        let mut player = GameTreePlayer::new(default_heuristic, 1, "Traced");
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        let tree = player.search_tree(&ctx, &board, &moves, 10_000, &mut rng);
+        let roots = tree.nodes.iter().filter(|node| node.parent == Some(0));
+
+        assert_eq!(roots.count(), moves.len());
+        assert!(tree.chosen.is_some());
+        assert_eq!(tree.omitted, 0);
+        assert!(tree.to_dot().starts_with("digraph"));
+
+        let small = player.search_tree(&ctx, &board, &moves, 5, &mut rng);
+        assert_eq!(small.nodes.len(), 5);
+        assert!(small.omitted > 0);
+    }
+}
@@ -0,0 +1,180 @@
+//! A record of the nodes a `GameTreePlayer` visits during a single decision, for debugging why
+//! the search prefers a surprising move, see `GameTreePlayer::search_tree`.
+
+use std::fmt::Write;
+
+use crate::games::struggle::{
+    board::StruggleMove,
+    notation::{color_char, format_move},
+    PlayerColor,
+};
+
+use super::search::WIN_SCORE;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TreeNodeKind {
+    /// The position the decision is made in
+    Root,
+    /// The expected value of a position over the next roll
+    Chance {
+        to_move: PlayerColor,
+    },
+    /// The best move of `player` after rolling `dice`
+    Dice {
+        player: PlayerColor,
+        dice: u8,
+    },
+    Move(StruggleMove),
+    /// A position at the search horizon, scored by the heuristic
+    Horizon,
+    /// A position whose value was found in the transposition table
+    Cached,
+    /// A move that wasn't searched, because of alpha-beta pruning or an earlier win
+    Pruned(StruggleMove),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeNode {
+    pub parent: Option<usize>,
+    pub kind: TreeNodeKind,
+    pub score: Option<f64>,
+}
+
+/// The nodes of a search, up to a limit. Nodes past the limit and everything below them are
+/// counted in `omitted` instead.
+#[derive(Clone, Debug)]
+pub struct SearchTree {
+    pub nodes: Vec<TreeNode>,
+    /// The index of the root's child that the search chose
+    pub chosen: Option<usize>,
+    pub omitted: usize,
+    limit: usize,
+    // The node new nodes are added under, or None below an omitted node
+    cursor: Option<usize>,
+}
+
+impl SearchTree {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            nodes: vec![TreeNode {
+                parent: None,
+                kind: TreeNodeKind::Root,
+                score: None,
+            }],
+            chosen: None,
+            omitted: 0,
+            limit: limit.max(1),
+            cursor: Some(0),
+        }
+    }
+
+    /// Adds a node under the current one and makes it current. Returns the previous node, to be
+    /// passed to `close`.
+    pub(super) fn open(&mut self, kind: TreeNodeKind) -> Option<usize> {
+        let parent = self.cursor;
+        self.cursor = self.push(parent, kind);
+        parent
+    }
+
+    /// Scores the current node and returns to `parent`.
+    pub(super) fn close(&mut self, parent: Option<usize>, score: f64) {
+        if let Some(node) = self.cursor {
+            self.nodes[node].score = Some(score);
+        }
+
+        self.cursor = parent;
+    }
+
+    /// Adds a node without children under the current one.
+    pub(super) fn leaf(&mut self, kind: TreeNodeKind, score: Option<f64>) {
+        if let Some(node) = self.push(self.cursor, kind) {
+            self.nodes[node].score = score;
+        }
+    }
+
+    fn push(&mut self, parent: Option<usize>, kind: TreeNodeKind) -> Option<usize> {
+        match parent {
+            Some(parent) if self.nodes.len() < self.limit => {
+                self.nodes.push(TreeNode {
+                    parent: Some(parent),
+                    kind,
+                    score: None,
+                });
+                Some(self.nodes.len() - 1)
+            }
+            _ => {
+                self.omitted += 1;
+                None
+            }
+        }
+    }
+
+    /// The tree in Graphviz DOT. Pruned moves are dashed and gray, and the chosen move is bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+
+        writeln!(dot, "digraph search {{").unwrap();
+        writeln!(dot, "  node [fontname=\"Source Sans Pro\", fontsize=10];").unwrap();
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let (label, shape) = match &node.kind {
+                TreeNodeKind::Root => ("root".to_string(), "doubleoctagon"),
+                TreeNodeKind::Chance { to_move } => {
+                    (format!("{} to move", color_char(*to_move)), "circle")
+                }
+                TreeNodeKind::Dice { player, dice } => {
+                    (format!("{} rolls {}", color_char(*player), dice), "diamond")
+                }
+                TreeNodeKind::Move(mov) | TreeNodeKind::Pruned(mov) => (format_move(mov), "box"),
+                TreeNodeKind::Horizon => ("heuristic".to_string(), "plaintext"),
+                TreeNodeKind::Cached => ("table".to_string(), "plaintext"),
+            };
+
+            let label = match node.score {
+                Some(score) => format!("{}\\n{}", label, format_score(score)),
+                None => label,
+            };
+
+            let style = if matches!(node.kind, TreeNodeKind::Pruned(_)) {
+                ", style=dashed, color=gray, fontcolor=gray"
+            } else if self.chosen == Some(index) {
+                ", style=bold, penwidth=2"
+            } else {
+                ""
+            };
+
+            writeln!(
+                dot,
+                "  n{} [label=\"{}\", shape={}{}];",
+                index, label, shape, style
+            )
+            .unwrap();
+
+            if let Some(parent) = node.parent {
+                writeln!(dot, "  n{} -> n{};", parent, index).unwrap();
+            }
+        }
+
+        if self.omitted > 0 {
+            writeln!(
+                dot,
+                "  omitted [label=\"{} nodes omitted\", shape=plaintext];",
+                self.omitted
+            )
+            .unwrap();
+        }
+
+        writeln!(dot, "}}").unwrap();
+        dot
+    }
+}
+
+fn format_score(score: f64) -> String {
+    if score >= WIN_SCORE {
+        "win".to_string()
+    } else if score <= -WIN_SCORE {
+        "loss".to_string()
+    } else {
+        format!("{:.1}", score)
+    }
+}
@@ -0,0 +1,298 @@
+//! Puzzle extraction: positions where a shallow and a deep expectiminimax search disagree.
+//!
+//! A position where searching deeper changes the best move is a "non-obvious" decision, which
+//! makes it a good test case for heuristics. The corpus is written as JSON lines, with positions
+//! and moves in `notation` form, see `struggle-puzzles`.
+//!
+//! Scoring a player over a suite of puzzles with `score_suite` measures how often it finds the
+//! deep search's move, and how much it loses by its choices according to the deep search. That's a
+//! far cheaper proxy for strength than a tournament, see `struggle-puzzle-score`.
+
+use std::io::{self, BufRead, Write};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{game_seed, GameId, RaceGame, TurnResult};
+
+use super::{
+    board::{Board, MoveVec},
+    distill::teacher,
+    notation::{format_move, format_position, parse_move, parse_position},
+    players::{GameContext, StrugglePlayer},
+    AiStrugglePlayer, PlayerColor, StruggleGame,
+};
+
+/// The choice of a search of some depth in a puzzle.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Verdict {
+    pub depth: u8,
+    /// The index of the chosen move in `Puzzle::moves`
+    pub best: usize,
+    /// The score of every move, in the order of `Puzzle::moves`
+    pub scores: Vec<f64>,
+}
+
+impl Verdict {
+    fn new(depth: u8, scores: Vec<f64>) -> Self {
+        let best = scores
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, _)| index)
+            .unwrap();
+
+        Self {
+            depth,
+            best,
+            scores,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub game: GameId,
+    /// The number of the turn in the game, from 1
+    pub turn: usize,
+    /// The position before the move, with the player to move
+    pub position: String,
+    pub dice: u8,
+    pub moves: Vec<String>,
+    pub shallow: Verdict,
+    pub deep: Verdict,
+}
+
+impl Puzzle {
+    /// How much worse the shallow choice is than the deep one, according to the deep search.
+    pub fn regret(&self) -> f64 {
+        self.loss(self.shallow.best)
+    }
+
+    /// How much worse the move at `index` is than the deep search's choice, according to the deep
+    /// search.
+    pub fn loss(&self, index: usize) -> f64 {
+        self.deep.scores[self.deep.best] - self.deep.scores[index]
+    }
+
+    /// The position, the turn and the candidate moves of the puzzle, in the order of `moves`.
+    pub fn setup(&self) -> Result<(Board, GameContext, MoveVec), String> {
+        let (board, current_player) = parse_position(&self.position)?;
+        let (a, b) = board.players();
+
+        let ctx = GameContext {
+            current_player,
+            other_player: if current_player == a { b } else { a },
+            dice: self.dice,
+        };
+
+        let moves = self
+            .moves
+            .iter()
+            .map(|mov| parse_move(mov))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if moves.len() != self.deep.scores.len() || moves.len() > 4 {
+            return Err(format!(
+                "Turn {} of {} has {} moves and {} scores",
+                self.turn,
+                self.game,
+                moves.len(),
+                self.deep.scores.len()
+            ));
+        }
+
+        Ok((board, ctx, moves.into_iter().collect()))
+    }
+}
+
+/// Plays `games` seeded games between `explorer` and itself, and collects the positions where
+/// expectiminimax at depth `shallow` chooses a different move than at depth `deep`.
+pub fn find_puzzles<P: StrugglePlayer>(
+    explorer: &P,
+    shallow: u8,
+    deep: u8,
+    games: u64,
+    seed: u64,
+) -> Vec<Puzzle> {
+    (0..games)
+        .into_par_iter()
+        .flat_map_iter(|index| {
+            let id = GameId::new(seed, index);
+            let mut rng = SmallRng::seed_from_u64(id.game_seed());
+            let mut shallow_search = teacher(shallow);
+            let mut deep_search = teacher(deep);
+            let mut state = StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, explorer.clone()),
+                AiStrugglePlayer::new(PlayerColor::Yellow, explorer.clone()),
+                false,
+            );
+
+            // Randomly select who starts, like play_game
+            if rng.gen() {
+                state.set_current_player(state.other_player());
+            }
+
+            let mut puzzles = Vec::new();
+
+            for turn in 1.. {
+                let ctx = state.create_turn_context(state.throw_dice(&mut rng));
+                let moves = state.get_moves(&ctx);
+
+                if moves.len() > 1 {
+                    let board = state.board();
+                    let shallow = Verdict::new(
+                        shallow,
+                        shallow_search.evaluate_moves(&ctx, board, &moves, &mut rng),
+                    );
+                    let deep = Verdict::new(
+                        deep,
+                        deep_search.evaluate_moves(&ctx, board, &moves, &mut rng),
+                    );
+
+                    if shallow.best != deep.best {
+                        puzzles.push(Puzzle {
+                            game: id,
+                            turn,
+                            position: format_position(board, ctx.current_player),
+                            dice: ctx.dice,
+                            moves: moves.iter().map(format_move).collect(),
+                            shallow,
+                            deep,
+                        });
+                    }
+                }
+
+                let mov = state.select_move(&ctx, &moves, &mut rng).clone();
+
+                match state.apply_move(&ctx, &mov) {
+                    TurnResult::PlayAgain => {}
+                    TurnResult::PassTo(player) => state.set_current_player(player),
+                    TurnResult::EndGame { .. } => break,
+                }
+            }
+
+            puzzles
+        })
+        .collect()
+}
+
+/// Writes `puzzles` as JSON lines, one puzzle per line.
+pub fn write_puzzles(writer: &mut impl Write, puzzles: &[Puzzle]) -> io::Result<()> {
+    for puzzle in puzzles {
+        serde_json::to_writer(&mut *writer, puzzle)?;
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+/// Reads puzzles written by `write_puzzles`.
+pub fn read_puzzles(reader: impl BufRead) -> Result<Vec<Puzzle>, String> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(index, line)| {
+            let line = line.map_err(|err| err.to_string())?;
+            serde_json::from_str(&line).map_err(|err| format!("Line {}: {}", index + 1, err))
+        })
+        .collect()
+}
+
+/// The result of running a player over a suite of puzzles.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SuiteScore {
+    pub puzzles: usize,
+    /// Puzzles where the player chose the deep search's move
+    pub solved: usize,
+    /// The sum of `Puzzle::loss` over the player's choices
+    pub total_loss: f64,
+}
+
+impl SuiteScore {
+    pub fn accuracy(&self) -> f64 {
+        self.solved as f64 / self.puzzles.max(1) as f64
+    }
+
+    pub fn average_loss(&self) -> f64 {
+        self.total_loss / self.puzzles.max(1) as f64
+    }
+}
+
+/// Lets `player` choose a move in every puzzle, and compares its choices to the deep search's.
+pub fn score_suite<P: StrugglePlayer>(
+    player: &P,
+    puzzles: &[Puzzle],
+    seed: u64,
+) -> Result<SuiteScore, String> {
+    puzzles
+        .par_iter()
+        .enumerate()
+        .map(|(index, puzzle)| {
+            let (board, ctx, moves) = puzzle.setup()?;
+            let mut player = player.clone();
+            let mut rng = SmallRng::seed_from_u64(game_seed(seed, index as u64));
+
+            let chosen = player.select_move(&ctx, &board, &moves, &mut rng);
+            let chosen = moves.iter().position(|mov| mov == chosen).unwrap();
+
+            Ok(SuiteScore {
+                puzzles: 1,
+                solved: (chosen == puzzle.deep.best) as usize,
+                total_loss: puzzle.loss(chosen),
+            })
+        })
+        .try_reduce(SuiteScore::default, |a, b| {
+            Ok(SuiteScore {
+                puzzles: a.puzzles + b.puzzles,
+                solved: a.solved + b.solved,
+                total_loss: a.total_loss + b.total_loss,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::{
+        default_heuristic, GameTreePlayer, HeuristicFunction, RandomPlayer,
+    };
+
+    #[test]
+    fn puzzles_are_disagreements() {
+        let puzzles = find_puzzles(&RandomPlayer, 0, 2, 20, 7);
+
+        assert!(!puzzles.is_empty());
+
+        for puzzle in &puzzles {
+            assert_ne!(puzzle.shallow.best, puzzle.deep.best);
+            assert!(puzzle.regret() >= 0.0);
+            assert!(parse_position(&puzzle.position).is_ok());
+        }
+    }
+
+    #[test]
+    fn the_deep_search_solves_its_own_puzzles() {
+        let puzzles = find_puzzles(&RandomPlayer, 0, 2, 10, 3);
+
+        let mut text = Vec::new();
+        write_puzzles(&mut text, &puzzles).unwrap();
+        let puzzles = read_puzzles(&text[..]).unwrap();
+
+        let search = |depth| {
+            GameTreePlayer::builder(default_heuristic as HeuristicFunction, "Search")
+                .depth(depth)
+                .noise(0.0)
+                .build()
+        };
+
+        let deep = score_suite(&search(2), &puzzles, 0).unwrap();
+        let shallow = score_suite(&search(0), &puzzles, 0).unwrap();
+
+        assert_eq!(deep.puzzles, puzzles.len());
+        assert!(deep.accuracy() > shallow.accuracy());
+        assert!(deep.average_loss() < shallow.average_loss());
+    }
+}
@@ -0,0 +1,167 @@
+//! Monte Carlo estimates of how a game goes on from an arbitrary position.
+//!
+//! The position is played out to the end many times by the given players, which gives the win
+//! probability of either player and the distribution of the remaining turns, e.g. for the
+//! `rollout` command of `struggle-engine`, or for heuristics that care about the length of the
+//! game like `maximize_length_expectiminimax`.
+
+use rand::{rngs::SmallRng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::game::{game_seed, IntoGameStats, RaceGame, TurnResult};
+
+use super::{board::Board, players::StrugglePlayer, AiStrugglePlayer, PlayerColor, StruggleGame};
+
+/// The outcomes of the rollouts of a position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rollouts {
+    /// The winner and the number of turns it took, for every game
+    pub outcomes: Vec<(PlayerColor, u32)>,
+}
+
+impl Rollouts {
+    pub fn games(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    pub fn win_probability(&self, player: PlayerColor) -> f64 {
+        let wins = self
+            .outcomes
+            .iter()
+            .filter(|(winner, _)| *winner == player)
+            .count();
+
+        wins as f64 / self.games().max(1) as f64
+    }
+
+    /// The mean number of turns until the game ends, counting the turns of both players.
+    pub fn mean_turns(&self) -> f64 {
+        mean(self.outcomes.iter().map(|(_, turns)| *turns))
+    }
+
+    /// The mean number of turns until `player` wins, over the games it wins, or `None` if it
+    /// never does.
+    pub fn mean_turns_to_win(&self, player: PlayerColor) -> Option<f64> {
+        let turns = self
+            .outcomes
+            .iter()
+            .filter(|(winner, _)| *winner == player)
+            .map(|(_, turns)| *turns);
+
+        (turns.clone().count() > 0).then(|| mean(turns))
+    }
+
+    /// The number of games that ended after each number of turns, from 0 to the longest game.
+    pub fn turn_histogram(&self) -> Vec<u32> {
+        let longest = self.outcomes.iter().map(|(_, turns)| *turns).max();
+        let mut histogram = vec![0; longest.map_or(0, |turns| turns as usize + 1)];
+
+        for (_, turns) in &self.outcomes {
+            histogram[*turns as usize] += 1;
+        }
+
+        histogram
+    }
+
+    /// The number of turns that a share `q` of the games ended within, e.g. 0.5 for the median.
+    pub fn turn_quantile(&self, q: f64) -> Option<u32> {
+        let mut turns = self
+            .outcomes
+            .iter()
+            .map(|(_, turns)| *turns)
+            .collect::<Vec<_>>();
+        turns.sort_unstable();
+
+        let index = ((turns.len() as f64 * q).ceil() as usize).clamp(1, turns.len().max(1)) - 1;
+        turns.get(index).copied()
+    }
+}
+
+fn mean(values: impl Iterator<Item = u32>) -> f64 {
+    let (count, sum) = values.fold((0, 0u64), |(count, sum), value| {
+        (count + 1, sum + value as u64)
+    });
+
+    sum as f64 / count.max(1) as f64
+}
+
+/// Plays `games` seeded games from `board` with `to_move` to move, in parallel. `a` plays the
+/// first of `board.players()` and `b` the second.
+pub fn rollouts<A: StrugglePlayer, B: StrugglePlayer>(
+    board: &Board,
+    to_move: PlayerColor,
+    a: &A,
+    b: &B,
+    games: u32,
+    seed: u64,
+) -> Rollouts {
+    let (color_a, color_b) = board.players();
+
+    if board.get_winner().is_some() {
+        return Rollouts {
+            outcomes: Vec::new(),
+        };
+    }
+
+    let outcomes = (0..games)
+        .into_par_iter()
+        .map(|game| {
+            let mut rng = SmallRng::seed_from_u64(game_seed(seed, game as u64));
+            let mut state = StruggleGame::new(
+                AiStrugglePlayer::new(color_a, a.clone()),
+                AiStrugglePlayer::new(color_b, b.clone()),
+                true,
+            )
+            .with_position(board.clone(), to_move);
+
+            let winner = play_from_position(&mut state, &mut rng);
+            let turns = state.stats().map_or(0, |stats| stats.turns as u32);
+
+            (winner, turns)
+        })
+        .collect();
+
+    Rollouts { outcomes }
+}
+
+// `play_game_with_rng` draws the starting player, which would ignore `to_move`
+fn play_from_position<A: StrugglePlayer, B: StrugglePlayer>(
+    state: &mut StruggleGame<A, B>,
+    rng: &mut SmallRng,
+) -> PlayerColor {
+    loop {
+        match state.play_turn(rng).1 {
+            TurnResult::PlayAgain => {}
+            TurnResult::PassTo(player) => state.set_current_player(player),
+            TurnResult::EndGame { winner } => return winner,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::{notation::parse_position, players::RandomPlayer};
+
+    #[test]
+    fn the_player_closer_to_the_goal_wins_more_often() {
+        // Red has three pieces in its goal and Yellow has all of its pieces waiting at home
+        let (board, to_move) = parse_position("r27 r:0:-xxx y:4:---- y").unwrap();
+        let rollouts = rollouts(&board, to_move, &RandomPlayer, &RandomPlayer, 400, 0);
+
+        assert_eq!(rollouts.games(), 400);
+        assert!(rollouts.win_probability(PlayerColor::Red) > 0.5);
+        assert!(
+            (rollouts.win_probability(PlayerColor::Red)
+                + rollouts.win_probability(PlayerColor::Yellow)
+                - 1.0)
+                .abs()
+                < 1e-9
+        );
+
+        let histogram = rollouts.turn_histogram();
+        assert_eq!(histogram.iter().sum::<u32>(), 400);
+        assert!(rollouts.turn_quantile(0.5).unwrap() as f64 <= rollouts.mean_turns() * 2.0);
+        assert!(rollouts.mean_turns_to_win(PlayerColor::Red).is_some());
+    }
+}
@@ -0,0 +1,357 @@
+//! A fixed-size transposition table for `GameTreePlayer`, shared by threads without locks.
+//!
+//! The table is an open-addressing array of slots, each two `AtomicU64`s: the entry's data
+//! (value, depth, age) and the board key XORed with the data. A reader accepts a slot only if
+//! the two words agree with its key, so a slot torn by a concurrent write reads as a miss
+//! instead of a wrong value. A key probes `PROBES` consecutive slots, and when all of them are
+//! taken, the entry from the oldest search or the shallowest depth is replaced.
+//!
+//! Depths are remaining depths: the plies that were searched below the entry's position, 0 at
+//! the search horizon. An entry only stands in for a search at least as deep as the one that
+//! produced it, so values from the horizon of a shallow search never answer a deeper one.
+
+use std::sync::{
+    atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering},
+    OnceLock,
+};
+
+use super::{
+    board::{Board, PiecePosition},
+    zobrist, PlayerColor,
+};
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BoardHash(u64);
+
+impl BoardHash {
+    /// The key of `board` in a table, its Zobrist hash. Unlike `get_board_hash` it covers every
+    /// color on the board, and costs nothing to get since `Board` keeps it up to date.
+    pub fn of(board: &Board) -> Self {
+        BoardHash(board.zobrist_hash())
+    }
+
+    /// The key of the position with `to_move` to move, valued for `scorer`. The same tiles are
+    /// worth something else with the other side to move, and searches value positions for
+    /// their own player, so table entries need both.
+    pub fn with_turn(self, to_move: PlayerColor, scorer: PlayerColor) -> Self {
+        BoardHash(self.0 ^ zobrist::turn_key(to_move, scorer))
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+}
+
+/// The first slot a key probes, before masking to the capacity of the table.
+pub fn home_slot(key: u64) -> usize {
+    // Fibonacci hashing spreads the packed positions, whose low bits are mostly alike
+    (key.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32) as usize
+}
+
+/// Slots a key may occupy, starting from its home slot.
+const PROBES: usize = 4;
+
+/// Slots in a table made with `new` or `default`, 1 MiB worth.
+pub const DEFAULT_CAPACITY: usize = 1 << 16;
+
+// Marks a slot as occupied, since every other bit of the data may be zero
+const OCCUPIED: u64 = 1 << 63;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TranspositionTableEntry {
+    value: f32,
+    // The remaining depth the value was searched to
+    depth: u8,
+    age: u8,
+}
+
+impl TranspositionTableEntry {
+    fn pack(self) -> u64 {
+        OCCUPIED | (self.age as u64) << 40 | (self.depth as u64) << 32 | self.value.to_bits() as u64
+    }
+
+    fn unpack(data: u64) -> Option<Self> {
+        (data & OCCUPIED != 0).then(|| Self {
+            value: f32::from_bits(data as u32),
+            depth: (data >> 32) as u8,
+            age: (data >> 40) as u8,
+        })
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    // The key XORed with the data
+    check: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    fn load(&self, key: u64) -> Option<TranspositionTableEntry> {
+        let data = self.data.load(Ordering::Relaxed);
+        let check = self.check.load(Ordering::Relaxed);
+
+        TranspositionTableEntry::unpack(data).filter(|_| check ^ data == key)
+    }
+
+    fn store(&self, key: u64, entry: TranspositionTableEntry) {
+        let data = entry.pack();
+        self.check.store(key ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+}
+
+// Entries across every table in the process, for monitoring
+static TOTAL_ENTRIES: AtomicUsize = AtomicUsize::new(0);
+
+pub struct TranspositionTable {
+    // Allocated on first use, since most players never use their table
+    slots: OnceLock<Box<[Slot]>>,
+    capacity: usize,
+    len: AtomicUsize,
+    age: AtomicU8,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A table with room for `capacity` entries, rounded up to a power of two.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            slots: OnceLock::new(),
+            capacity: capacity.max(PROBES).next_power_of_two(),
+            len: AtomicUsize::new(0),
+            age: AtomicU8::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Starts a new search, whose entries take precedence over the ones from earlier searches
+    /// when slots run out.
+    pub fn new_search(&self) {
+        self.age.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn slots(&self) -> &[Slot] {
+        self.slots
+            .get_or_init(|| (0..self.capacity).map(|_| Slot::default()).collect())
+    }
+
+    // The slots `key` may occupy
+    fn probe(&self, key: BoardHash) -> impl Iterator<Item = &Slot> {
+        let slots = self.slots();
+        let mask = self.capacity - 1;
+        let home = home_slot(key.0);
+
+        (0..PROBES).map(move |offset| &slots[(home + offset) & mask])
+    }
+
+    /// The value of the position, if it was searched at least `remaining_depth` plies deep.
+    pub fn get(&self, board_hash: BoardHash, remaining_depth: u8) -> Option<f32> {
+        self.probe(board_hash)
+            .find_map(|slot| slot.load(board_hash.0))
+            .filter(|entry| entry.depth >= remaining_depth)
+            .map(|entry| entry.value)
+    }
+
+    /// Stores the value of a position searched `remaining_depth` plies deep, unless the table
+    /// already has a deeper one.
+    pub fn insert_if_better(&self, board_hash: BoardHash, value: f32, remaining_depth: u8) {
+        let age = self.age.load(Ordering::Relaxed);
+        let depth = remaining_depth;
+        let entry = TranspositionTableEntry { value, depth, age };
+
+        let mut victim = None;
+        let mut victim_rank = (u8::MAX, u8::MAX);
+
+        for slot in self.probe(board_hash) {
+            if let Some(existing) = slot.load(board_hash.0) {
+                // Only a deeper search improves on the entry
+                if depth > existing.depth {
+                    slot.store(board_hash.0, entry);
+                }
+                return;
+            }
+
+            let data = slot.data.load(Ordering::Relaxed);
+
+            let Some(other) = TranspositionTableEntry::unpack(data) else {
+                slot.store(board_hash.0, entry);
+                self.len.fetch_add(1, Ordering::Relaxed);
+                TOTAL_ENTRIES.fetch_add(1, Ordering::Relaxed);
+                return;
+            };
+
+            // Entries from older searches go first, then the shallowest ones
+            let rank = (age.wrapping_sub(other.age) == 0) as u8;
+            let rank = (rank, other.depth);
+
+            if rank < victim_rank {
+                victim_rank = rank;
+                victim = Some(slot);
+            }
+        }
+
+        if let Some(slot) = victim {
+            slot.store(board_hash.0, entry);
+        }
+    }
+
+    /// The number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of entries in all live tables.
+    pub fn total_entries() -> usize {
+        TOTAL_ENTRIES.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for TranspositionTable {
+    fn drop(&mut self) {
+        TOTAL_ENTRIES.fetch_sub(self.len(), Ordering::Relaxed);
+    }
+}
+
+// We can pack the board state into a single 64-bit integer
+// There are 28 board slots, 4 goal slots, and 2 players with 4 pieces each
+// We can store the location of each piece with 5 bits
+// Additionally, we need one more bit indicating if the piece is on the board
+// bits 0-0:   player 0 piece 0 on board
+// bits 1-5:   player 0 piece 0 location
+// bits 6-6:   player 0 piece 1 on board
+// bits 7-11:  player 0 piece 1 location
+// bits 12-12: player 0 piece 2 on board
+// bits 13-17: player 0 piece 2 location
+// bits 18-18: player 0 piece 3 on board
+// bits 19-23: player 0 piece 3 location
+// bits 24-24: player 1 piece 0 on board
+// bits 25-29: player 1 piece 0 location
+// bits 30-30: player 1 piece 1 on board
+// bits 31-35: player 1 piece 1 location
+// bits 36-36: player 1 piece 2 on board
+// bits 37-41: player 1 piece 2 location
+// bits 42-42: player 1 piece 3 on board
+// bits 43-47: player 1 piece 3 location
+// bits 48-48: current player (0 or 1)
+// bits 49-63: unused
+
+const STORE_CURRENT_PLAYER_IN_KEY: bool = false;
+
+pub fn get_board_hash(board: &Board, current_player: PlayerColor) -> BoardHash {
+    let mut packed = 0u64;
+
+    const PLAYER_0_OFFSET: u64 = 0;
+
+    for (piece_index, piece) in board.piece_cache.0.iter().enumerate() {
+        let piece_offset = PLAYER_0_OFFSET + (piece_index as u64) * 6;
+        // Set the first bit to 1 if the piece is on the board
+        packed |= 1 << piece_offset;
+
+        match piece {
+            PiecePosition::Board(board_index) => {
+                debug_assert!(*board_index < 28);
+                packed |= (*board_index as u64) << (piece_offset + 1);
+            }
+            PiecePosition::Goal(goal_index) => {
+                debug_assert!(*goal_index < 4);
+                packed |= (28 + *goal_index as u64) << (piece_offset + 1);
+            }
+        }
+    }
+
+    const PLAYER_1_OFFSET: u64 = 24;
+
+    for (piece_index, piece) in board.piece_cache.1.iter().enumerate() {
+        let piece_offset = PLAYER_1_OFFSET + (piece_index as u64) * 6;
+        packed |= 1 << piece_offset;
+
+        match piece {
+            PiecePosition::Board(board_index) => {
+                debug_assert!(*board_index < 28);
+                packed |= (*board_index as u64) << (piece_offset + 1);
+            }
+            PiecePosition::Goal(goal_index) => {
+                debug_assert!(*goal_index < 4);
+                packed |= (28 + *goal_index as u64) << (piece_offset + 1);
+            }
+        }
+    }
+
+    // We don't need to store how many pieces are waiting, because it's implied by the board state
+
+    if STORE_CURRENT_PLAYER_IN_KEY {
+        let current_player_bit = if current_player == board.players.0 {
+            0
+        } else {
+            1
+        };
+
+        packed |= current_player_bit << 48;
+    }
+
+    BoardHash(packed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deeper_entries_win_and_full_probes_evict() {
+        let table = TranspositionTable::with_capacity(PROBES);
+        let key = BoardHash(42);
+
+        table.insert_if_better(key, 1.0, 2);
+        table.insert_if_better(key, 2.0, 1);
+        assert_eq!(table.get(key, 2), Some(1.0));
+        assert_eq!(table.get(key, 3), None);
+
+        table.insert_if_better(key, 3.0, 3);
+        assert_eq!(table.get(key, 3), Some(3.0));
+
+        // Every key probes all slots of a table this small
+        for other in 0..PROBES as u64 {
+            table.insert_if_better(BoardHash(1000 + other), 0.5, 5);
+        }
+
+        assert_eq!(table.len(), PROBES);
+        assert_eq!(table.get(key, 0), None);
+        assert_eq!(table.get(BoardHash(1000 + PROBES as u64 - 1), 5), Some(0.5));
+    }
+
+    #[test]
+    fn horizon_values_dont_answer_deeper_searches() {
+        let table = TranspositionTable::new();
+        let key = BoardHash(7);
+
+        // A heuristic value from the horizon of a search, with nothing searched below it
+        table.insert_if_better(key, 0.25, 0);
+
+        assert_eq!(table.get(key, 0), Some(0.25));
+        assert_eq!(table.get(key, 1), None);
+        assert_eq!(table.get(key, 3), None);
+
+        table.insert_if_better(key, 0.5, 2);
+
+        assert_eq!(table.get(key, 1), Some(0.5));
+        assert_eq!(table.get(key, 3), None);
+    }
+}
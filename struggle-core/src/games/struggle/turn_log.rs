@@ -0,0 +1,118 @@
+//! Structured per-turn logs as JSON lines, one object per turn:
+//!
+//! ```json
+//! {"game":"42-3","turn":17,"player":"red","dice":6,"move":{"kind":"add_new_piece","eats":true},"captures":true,"eval":12.5}
+//! ```
+//!
+//! Logging is enabled per game with `StruggleGame::with_logger`. One logger can be shared by
+//! games running in parallel, every line is written whole. Lines are tagged with the id of their
+//! game, see `game::GameId`.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+
+use crate::{
+    game::GameId,
+    json::{color_name, MoveJson},
+};
+
+use super::{
+    board::{Board, StruggleMove},
+    PlayerColor,
+};
+
+/// Scores the board for `player` against `enemy`, e.g. `players::default_heuristic`.
+pub type EvalFn = fn(&Board, PlayerColor, PlayerColor) -> f64;
+
+#[derive(Serialize)]
+struct TurnRecord {
+    game: GameId,
+    turn: u32,
+    player: &'static str,
+    dice: u8,
+    #[serde(rename = "move")]
+    mov: MoveJson,
+    captures: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    eval: Option<f64>,
+}
+
+#[derive(Clone)]
+pub struct TurnLogger {
+    out: Arc<Mutex<Box<dyn Write + Send>>>,
+    eval: Option<EvalFn>,
+    game: GameId,
+    turn: u32,
+}
+
+impl TurnLogger {
+    pub fn new(out: impl Write + Send + 'static) -> Self {
+        Self {
+            out: Arc::new(Mutex::new(Box::new(out))),
+            eval: None,
+            game: GameId::default(),
+            turn: 0,
+        }
+    }
+
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+
+    /// Adds the evaluation of the board after each move, from the mover's point of view.
+    pub fn with_eval(mut self, eval: EvalFn) -> Self {
+        self.eval = Some(eval);
+        self
+    }
+
+    /// A logger for a new game with the given id, writing to the same output.
+    pub fn for_game(&self, game: GameId) -> Self {
+        Self {
+            game,
+            turn: 0,
+            ..self.clone()
+        }
+    }
+
+    /// Logs a move that has already been applied to `board`.
+    pub fn log(
+        &mut self,
+        board: &Board,
+        player: PlayerColor,
+        enemy: PlayerColor,
+        dice: u8,
+        mov: &StruggleMove,
+    ) {
+        self.turn += 1;
+
+        let record = TurnRecord {
+            game: self.game,
+            turn: self.turn,
+            player: color_name(player),
+            dice,
+            mov: MoveJson::from(mov),
+            captures: mov.eats(),
+            eval: self.eval.map(|eval| eval(board, player, enemy)),
+        };
+
+        let mut line = serde_json::to_string(&record).unwrap();
+        line.push('\n');
+
+        // A closed pipe shouldn't take the simulation down with it
+        let _ = self.out.lock().unwrap().write_all(line.as_bytes());
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        self.out.lock().unwrap().flush()
+    }
+}
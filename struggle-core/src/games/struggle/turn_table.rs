@@ -0,0 +1,77 @@
+//! One row per turn of a single game, for inspecting how a heuristic sees a game in a spreadsheet,
+//! see `struggle-dump`.
+//!
+//! Every row has the position after the move in `notation` form, and the heuristic's evaluation
+//! of that position for both players.
+
+use serde::Serialize;
+
+use super::{
+    game_notation::NotatedGame,
+    notation::{color_char, format_move, format_position},
+    turn_log::EvalFn,
+};
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TurnRow {
+    pub turn: usize,
+    pub player: char,
+    pub dice: u8,
+    #[serde(rename = "move")]
+    pub mov: String,
+    /// The position after the move, with the player to move next
+    pub position: String,
+    /// The evaluation after the move from the point of view of each player of the game, in the
+    /// order of `Board::players`
+    pub eval_a: f64,
+    pub eval_b: f64,
+}
+
+/// Replays `game` and evaluates the position after every turn with `eval`.
+pub fn turn_rows(game: &NotatedGame, eval: EvalFn) -> Result<Vec<TurnRow>, String> {
+    // Checks that the moves are legal, so the loop below doesn't have to
+    game.replay()?;
+
+    let (mut board, _) = game.start_position()?;
+    let (a, b) = board.players();
+
+    let rows = game
+        .turns
+        .iter()
+        .enumerate()
+        .map(|(index, turn)| {
+            let enemy = if turn.player == a { b } else { a };
+            board.perform_move(turn.player, &turn.mov);
+
+            let to_move = if turn.dice == 6 { turn.player } else { enemy };
+
+            TurnRow {
+                turn: index + 1,
+                player: color_char(turn.player),
+                dice: turn.dice,
+                mov: format_move(&turn.mov),
+                position: format_position(&board, to_move),
+                eval_a: eval(&board, a, b),
+                eval_b: eval(&board, b, a),
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::{default_heuristic, RandomPlayer, ScoreMovePlayer};
+
+    #[test]
+    fn a_row_per_turn() {
+        let game = NotatedGame::play(&ScoreMovePlayer, &RandomPlayer, 3);
+        let rows = turn_rows(&game, default_heuristic).unwrap();
+
+        assert_eq!(rows.len(), game.turns.len());
+        assert_eq!(rows[0].turn, 1);
+        assert!(rows.iter().all(|row| (1..=6).contains(&row.dice)));
+    }
+}
@@ -0,0 +1,321 @@
+//! A calibrated model of the chance of winning from a position.
+//!
+//! `P(win) = sigmoid(w · features + t · side)`, where `side` is 1 for the player to move and -1
+//! for the other player. Every position is used from both points of view, so the probabilities
+//! of the two players always add up to one. The model is fitted to the outcomes of simulated
+//! games by logistic regression, which keeps it calibrated, see `calibration`.
+//!
+//! It serves both as an analysis function, e.g. for an evaluation bar or the win-probability
+//! curve of a recorded game, and as a heuristic for `GameTreePlayer`.
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{game_seed, RaceGame, TurnResult};
+
+use super::{
+    board::Board,
+    features::{extract, Features, FEATURES},
+    game_notation::NotatedGame,
+    linear::TrainingOptions,
+    players::{GameTreePlayer, StrugglePlayer, WIN_SCORE},
+    AiStrugglePlayer, PlayerColor, StruggleGame,
+};
+
+/// A position before a roll, from the point of view of one of the players.
+#[derive(Clone, Copy, Debug)]
+pub struct Example {
+    pub features: Features,
+    pub to_move: bool,
+    pub won: bool,
+}
+
+/// Plays `games` seeded games between `red` and `yellow` in parallel, and returns two examples
+/// of every position before a roll, one for each player.
+pub fn simulate_examples<A: StrugglePlayer, B: StrugglePlayer>(
+    red: &A,
+    yellow: &B,
+    games: u64,
+    seed: u64,
+) -> Vec<Example> {
+    (0..games)
+        .into_par_iter()
+        .flat_map_iter(|game| {
+            let mut rng = SmallRng::seed_from_u64(game_seed(seed, game));
+            let mut state = StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+                AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+                false,
+            );
+
+            // Randomly select who starts, like play_game
+            if rng.gen() {
+                state.set_current_player(state.other_player());
+            }
+
+            let mut positions = Vec::new();
+
+            let winner = loop {
+                let ctx = state.create_turn_context(state.throw_dice(&mut rng));
+
+                positions.push((
+                    ctx.current_player,
+                    extract(state.board(), ctx.current_player, ctx.other_player),
+                ));
+
+                let moves = state.get_moves(&ctx);
+                let mov = state.select_move(&ctx, &moves, &mut rng).clone();
+
+                match state.apply_move(&ctx, &mov) {
+                    TurnResult::PlayAgain => {}
+                    TurnResult::PassTo(player) => state.set_current_player(player),
+                    TurnResult::EndGame { winner } => break winner,
+                }
+            };
+
+            positions.into_iter().flat_map(move |(player, features)| {
+                let won = player == winner;
+
+                [
+                    Example {
+                        features,
+                        to_move: true,
+                        won,
+                    },
+                    Example {
+                        features: features.map(|value| -value),
+                        to_move: false,
+                        won: !won,
+                    },
+                ]
+            })
+        })
+        .collect()
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn side(to_move: bool) -> f64 {
+    if to_move {
+        1.0
+    } else {
+        -1.0
+    }
+}
+
+/// One bucket of predictions, see `WinProbabilityModel::calibration`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CalibrationBin {
+    /// Mean predicted probability
+    pub predicted: f64,
+    /// Share of the examples that were won
+    pub observed: f64,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WinProbabilityModel {
+    pub weights: Features,
+    /// The advantage of being the player to move, in log-odds
+    pub tempo: f64,
+}
+
+impl WinProbabilityModel {
+    /// Fits the model to `examples` with stochastic gradient descent on the log loss, like
+    /// `LearnedLinearHeuristic::train`.
+    pub fn train(examples: &[Example], options: &TrainingOptions) -> Self {
+        let scale: Features = std::array::from_fn(|index| {
+            let mean_square = examples
+                .iter()
+                .map(|example| example.features[index].powi(2))
+                .sum::<f64>()
+                / examples.len().max(1) as f64;
+
+            if mean_square > 0.0 {
+                1.0 / mean_square.sqrt()
+            } else {
+                0.0
+            }
+        });
+
+        let mut rng = SmallRng::seed_from_u64(options.seed);
+        let mut order = (0..examples.len()).collect::<Vec<_>>();
+        let mut weights = [0.0; FEATURES];
+        let mut tempo = 0.0;
+
+        for _ in 0..options.epochs {
+            order.shuffle(&mut rng);
+
+            for &index in &order {
+                let example = &examples[index];
+                let x: Features = std::array::from_fn(|i| example.features[i] * scale[i]);
+                let side = side(example.to_move);
+
+                let logit = weights.iter().zip(&x).map(|(w, x)| w * x).sum::<f64>() + tempo * side;
+                let error = sigmoid(logit) - example.won as u8 as f64;
+
+                for (weight, x) in weights.iter_mut().zip(&x) {
+                    *weight -= options.learning_rate * (error * x + options.l2 * *weight);
+                }
+
+                tempo -= options.learning_rate * error * side;
+            }
+        }
+
+        Self {
+            weights: std::array::from_fn(|index| weights[index] * scale[index]),
+            tempo,
+        }
+    }
+
+    /// The log-odds of winning, without the tempo.
+    pub fn score(&self, features: &Features) -> f64 {
+        self.weights.iter().zip(features).map(|(w, x)| w * x).sum()
+    }
+
+    pub fn logit(&self, features: &Features, to_move: bool) -> f64 {
+        self.score(features) + self.tempo * side(to_move)
+    }
+
+    pub fn predict(&self, features: &Features, to_move: bool) -> f64 {
+        sigmoid(self.logit(features, to_move))
+    }
+
+    /// The chance of `player` winning the game before the roll of `to_move`.
+    pub fn win_probability(&self, board: &Board, player: PlayerColor, to_move: PlayerColor) -> f64 {
+        let (a, b) = board.players();
+        let enemy = if player == a { b } else { a };
+
+        match board.get_winner() {
+            Some(winner) => (winner == player) as u8 as f64,
+            None => self.predict(&extract(board, player, enemy), player == to_move),
+        }
+    }
+
+    /// The chance of the first player of `game` winning at the start and after every turn.
+    pub fn win_probability_curve(&self, game: &NotatedGame) -> Result<Vec<f64>, String> {
+        // Checks the legality of the moves
+        game.replay()?;
+
+        let (mut board, mut to_move) = game.start_position()?;
+        let (a, b) = board.players();
+
+        let mut curve = vec![self.win_probability(&board, a, to_move)];
+
+        for turn in &game.turns {
+            board.perform_move(turn.player, &turn.mov);
+
+            if turn.dice != 6 {
+                to_move = if to_move == a { b } else { a };
+            }
+
+            curve.push(self.win_probability(&board, a, to_move));
+        }
+
+        Ok(curve)
+    }
+
+    /// The chance of `player` winning, or the usual win scores in finished games. The side to
+    /// move isn't known to the heuristic, so the tempo is left out.
+    pub fn evaluate(&self, board: &Board, player: PlayerColor, enemy: PlayerColor) -> f64 {
+        match board.get_winner() {
+            Some(winner) if winner == player => return WIN_SCORE,
+            Some(_) => return -WIN_SCORE,
+            None => {}
+        }
+
+        sigmoid(self.score(&extract(board, player, enemy)))
+    }
+
+    /// The mean log loss of the model on `examples`, 0.693 for a coin flip.
+    pub fn log_loss(&self, examples: &[Example]) -> f64 {
+        let total = examples
+            .iter()
+            .map(|example| {
+                let p = self
+                    .predict(&example.features, example.to_move)
+                    .clamp(1e-12, 1.0 - 1e-12);
+
+                if example.won {
+                    -p.ln()
+                } else {
+                    -(1.0 - p).ln()
+                }
+            })
+            .sum::<f64>();
+
+        total / examples.len() as f64
+    }
+
+    /// The mean squared error of the predicted probabilities, 0.25 for a coin flip.
+    pub fn brier_score(&self, examples: &[Example]) -> f64 {
+        let total = examples
+            .iter()
+            .map(|example| {
+                (self.predict(&example.features, example.to_move) - example.won as u8 as f64)
+                    .powi(2)
+            })
+            .sum::<f64>();
+
+        total / examples.len() as f64
+    }
+
+    /// Groups the predictions for `examples` into `bins` equal ranges of probability, and
+    /// compares them to the observed outcomes. A calibrated model has `predicted` close to
+    /// `observed` in every bin. Empty bins are left out.
+    pub fn calibration(&self, examples: &[Example], bins: usize) -> Vec<CalibrationBin> {
+        let mut sums = vec![(0.0, 0usize, 0usize); bins];
+
+        for example in examples {
+            let p = self.predict(&example.features, example.to_move);
+            let bin = ((p * bins as f64) as usize).min(bins - 1);
+
+            sums[bin].0 += p;
+            sums[bin].1 += example.won as usize;
+            sums[bin].2 += 1;
+        }
+
+        sums.into_iter()
+            .filter(|(_, _, count)| *count > 0)
+            .map(|(predicted, won, count)| CalibrationBin {
+                predicted: predicted / count as f64,
+                observed: won as f64 / count as f64,
+                count,
+            })
+            .collect()
+    }
+
+    /// An expectiminimax player that maximizes its expected chance of winning.
+    pub fn player(self, depth: u8) -> impl StrugglePlayer {
+        GameTreePlayer::new(
+            move |board: &Board, player, enemy| self.evaluate(board, player, enemy),
+            depth,
+            "WinProbability",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::{RandomPlayer, ScoreMovePlayer};
+
+    #[test]
+    fn the_players_chances_add_up_to_one() {
+        let examples = simulate_examples(&RandomPlayer, &ScoreMovePlayer, 200, 3);
+        let model = WinProbabilityModel::train(&examples, &TrainingOptions::default());
+
+        assert!(model.log_loss(&examples) < std::f64::consts::LN_2);
+        assert!(model.brier_score(&examples) < 0.25);
+
+        for pair in examples.chunks(2).take(50) {
+            let total = model.predict(&pair[0].features, pair[0].to_move)
+                + model.predict(&pair[1].features, pair[1].to_move);
+
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Zobrist hashing of Struggle positions.
+//!
+//! Every piece of every color on every tile and goal slot, and every number of waiting pieces,
+//! has a random key, and the hash of a position is the XOR of the keys of its contents. A move
+//! only changes a few of them, so `Board` keeps its hash up to date in `perform_move` by XORing
+//! the changed ones out and back in, and `Board::zobrist_hash` is free. Like
+//! `get_board_hash`, the hash leaves out the player to move; `turn_key` covers it for the keys
+//! that need it.
+
+use super::{
+    board::{Board, BoardCell},
+    PlayerColor,
+};
+
+struct Keys {
+    tiles: [[u64; Board::TILES]; 4],
+    goals: [[u64; 4]; 4],
+    // By the number of waiting pieces, from 0 to 4
+    waiting: [[u64; 5]; 4],
+    // By the player to move and the player the position is scored for
+    turns: [[u64; 4]; 4],
+}
+
+// The `n`th output of SplitMix64 seeded with `seed`, so that the keys can be generated at
+// compile time
+const fn splitmix(seed: u64, n: u64) -> u64 {
+    let mut z = seed.wrapping_add((n + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const fn generate_keys(seed: u64) -> Keys {
+    let mut n = 0;
+    let mut keys = Keys {
+        tiles: [[0; Board::TILES]; 4],
+        goals: [[0; 4]; 4],
+        waiting: [[0; 5]; 4],
+        turns: [[0; 4]; 4],
+    };
+
+    let mut color = 0;
+
+    while color < 4 {
+        let mut i = 0;
+
+        while i < Board::TILES {
+            keys.tiles[color][i] = splitmix(seed, n);
+            n += 1;
+            i += 1;
+        }
+
+        i = 0;
+
+        while i < 4 {
+            keys.goals[color][i] = splitmix(seed, n);
+            n += 1;
+            i += 1;
+        }
+
+        i = 0;
+
+        while i < 5 {
+            keys.waiting[color][i] = splitmix(seed, n);
+            n += 1;
+            i += 1;
+        }
+
+        color += 1;
+    }
+
+    color = 0;
+
+    while color < 4 {
+        let mut scorer = 0;
+
+        while scorer < 4 {
+            keys.turns[color][scorer] = splitmix(seed, n);
+            n += 1;
+            scorer += 1;
+        }
+
+        color += 1;
+    }
+
+    keys
+}
+
+static KEYS: Keys = generate_keys(0x5354_5255_4747_4C45);
+
+pub fn tile_key(tile: u8, cell: BoardCell) -> u64 {
+    cell.map_or(0, |color| KEYS.tiles[color as usize][tile as usize])
+}
+
+pub fn goal_key(player: PlayerColor, slot: u8, cell: BoardCell) -> u64 {
+    cell.map_or(0, |_| KEYS.goals[player as usize][slot as usize])
+}
+
+pub fn waiting_key(player: PlayerColor, waiting: u8) -> u64 {
+    KEYS.waiting[player as usize][waiting.min(4) as usize]
+}
+
+/// Tells apart the same position with another player to move, or scored for another player.
+pub fn turn_key(to_move: PlayerColor, scorer: PlayerColor) -> u64 {
+    KEYS.turns[to_move as usize][scorer as usize]
+}
+
+/// The hash of `board` from scratch. `Board::zobrist_hash` returns the same without the work.
+pub fn hash(board: &Board) -> u64 {
+    let mut hash = 0;
+
+    for (tile, &cell) in board.tiles.iter().enumerate() {
+        hash ^= tile_key(tile as u8, cell);
+    }
+
+    for (player, goal) in board.goals.iter().enumerate() {
+        for (slot, &cell) in goal.iter().enumerate() {
+            hash ^= goal_key(PlayerColor::from(player), slot as u8, cell);
+        }
+    }
+
+    for (player, home_base) in board.home_bases.iter().enumerate() {
+        hash ^= waiting_key(PlayerColor::from(player), home_base.pieces_waiting);
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn incremental_hashes_match_full_ones() {
+        let mut rng = SmallRng::seed_from_u64(11);
+        let (a, b) = (PlayerColor::Red, PlayerColor::Yellow);
+        let mut board = Board::new(a, b);
+        let mut to_move = a;
+        let start = board.zobrist_hash();
+
+        while board.get_winner().is_none() {
+            let dice = rng.gen_range(1..=6);
+            let enemy = if to_move == a { b } else { a };
+            let moves = board.get_moves(dice, to_move, enemy);
+
+            board.perform_move(to_move, &moves[rng.gen_range(0..moves.len())]);
+            assert_eq!(board.zobrist_hash(), hash(&board));
+
+            if dice != 6 {
+                to_move = enemy;
+            }
+        }
+
+        assert_ne!(board.zobrist_hash(), start);
+    }
+
+    #[test]
+    fn hashes_tell_colors_and_goals_apart() {
+        let mut red = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        red.tiles[5] = Some(PlayerColor::Red);
+        red.update_piece_cache();
+
+        let mut yellow = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        yellow.tiles[5] = Some(PlayerColor::Yellow);
+        yellow.update_piece_cache();
+
+        assert_ne!(red.zobrist_hash(), yellow.zobrist_hash());
+        assert_ne!(
+            goal_key(PlayerColor::Red, 0, Some(PlayerColor::Red)),
+            goal_key(PlayerColor::Yellow, 0, Some(PlayerColor::Yellow))
+        );
+    }
+}
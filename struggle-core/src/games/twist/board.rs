@@ -1,17 +1,23 @@
-use std::ops::Range;
+use core::ops::Range;
 
 use arrayvec::ArrayVec;
 use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use tinyvec::TinyVec;
 
-use crate::games::struggle::{
-    board::{BoardCell, HomeBase, PiecePosition},
-    PlayerColor, COLORS,
+use crate::{
+    game::PieceCounts,
+    games::struggle::{
+        board::{BoardCell, HomeBase, PiecePosition},
+        PlayerColor, COLORS,
+    },
 };
 
-type TwistGoal = [BoardCell; 3];
+pub type TwistGoal = [BoardCell; 3];
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TwistRotation {
     Initial = 0,
     Ccw90,
@@ -40,6 +46,7 @@ impl TwistRotation {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SpinSection {
     RedToBlue,
     BlueToYellow,
@@ -58,17 +65,85 @@ impl SpinSection {
 
 pub type TwistPieceVec = ArrayVec<PiecePosition, 4>;
 
+/// A cell as a nibble: 0 when empty, otherwise the color plus one.
+const fn cell_nibble(cell: BoardCell) -> u128 {
+    match cell {
+        None => 0,
+        Some(color) => color as u128 + 1,
+    }
+}
+
+/// Packs up to 32 cells into nibbles, the first cell in the lowest bits.
+fn pack_cells(cells: &[BoardCell]) -> u128 {
+    cells
+        .iter()
+        .rev()
+        .fold(0, |packed, &cell| packed << 4 | cell_nibble(cell))
+}
+
+/// Serialized without the caches, which are rebuilt when deserializing.
+///
+/// The tiles and goals are only public to read, since the caches have to follow every change:
+/// edit them with `set_tile` and `set_goal`, or `update` within the crate.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "TwistBoardFields"))]
 pub struct TwistBoard {
-    pub tiles: [BoardCell; TwistBoard::TILES],
-    pub goals: [TwistGoal; 4],
+    pub(crate) tiles: [BoardCell; TwistBoard::TILES],
+    pub(crate) goals: [TwistGoal; 4],
     pub home_bases: [HomeBase; 4],
     pub rotation: TwistRotation,
 
     players: (PlayerColor, PlayerColor),
+    #[cfg_attr(feature = "serde", serde(skip))]
     piece_cache: (TwistPieceVec, TwistPieceVec),
+    /// The tiles and goals packed into nibbles, see `pack_cells`. Like the piece cache, these are
+    /// updated by `update_piece_cache`.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    packed_tiles: u128,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    packed_goals: u128,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct TwistBoardFields {
+    tiles: [BoardCell; TwistBoard::TILES],
+    goals: [TwistGoal; 4],
+    home_bases: [HomeBase; 4],
+    rotation: TwistRotation,
+    players: (PlayerColor, PlayerColor),
 }
 
+#[cfg(feature = "serde")]
+impl From<TwistBoardFields> for TwistBoard {
+    fn from(fields: TwistBoardFields) -> Self {
+        let mut board = TwistBoard::new(fields.players);
+
+        board.update(|board| {
+            board.tiles = fields.tiles;
+            board.goals = fields.goals;
+            board.home_bases = fields.home_bases;
+            board.rotation = fields.rotation;
+        });
+
+        board
+    }
+}
+
+/// Compares the packed cells instead of the cells one by one.
+impl PartialEq for TwistBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.packed_tiles == other.packed_tiles
+            && self.packed_goals == other.packed_goals
+            && self.home_bases == other.home_bases
+            && self.rotation == other.rotation
+            && self.players == other.players
+    }
+}
+
+impl Eq for TwistBoard {}
+
 impl TwistBoard {
     pub const TILES: usize = 8 * 4;
 
@@ -92,16 +167,16 @@ impl TwistBoard {
     ];
 
     pub fn new(players: (PlayerColor, PlayerColor)) -> Self {
-        let board = Self {
+        Self {
             tiles: [None; Self::TILES],
             goals: [[None; 3]; 4],
             home_bases: COLORS.map(|_| HomeBase::new()),
             rotation: TwistRotation::Initial,
             players,
             piece_cache: (TwistPieceVec::new(), TwistPieceVec::new()),
-        };
-
-        board
+            packed_tiles: 0,
+            packed_goals: 0,
+        }
     }
 
     const fn internal_get_goal_entry(rotation: TwistRotation, color: PlayerColor) -> u8 {
@@ -152,18 +227,31 @@ impl TwistBoard {
     }
 
     pub fn get_winner(&self) -> Option<PlayerColor> {
-        for (i, goal) in self.goals.iter().enumerate() {
-            let player = PlayerColor::from(i);
+        COLORS.into_iter().find(|&player| {
+            let nibble = cell_nibble(Some(player));
             let goal_entry_pos = Self::get_goal_entrance(self.rotation, player);
 
-            if self.tiles[goal_entry_pos as usize] == Some(player)
-                && goal.iter().all(|&x| x == Some(player))
-            {
-                return Some(player);
-            }
-        }
+            // The whole goal and its entrance must be filled with the player's pieces
+            let goal = (self.packed_goals >> (player as usize * 12)) & 0xfff;
+            let entrance = (self.packed_tiles >> (goal_entry_pos * 4)) & 0xf;
+
+            goal == nibble * 0x111 && entrance == nibble
+        })
+    }
 
-        None
+    /// The tiles packed into nibbles, the first tile in the lowest bits.
+    pub fn packed_tiles(&self) -> u128 {
+        self.packed_tiles
+    }
+
+    /// Whether spinning the section would leave it as it is, i.e. it reads the same both ways.
+    pub fn spin_is_nop(&self, spin_section: SpinSection) -> bool {
+        let range = Self::get_spin_section_range(spin_section);
+        let section = (self.packed_tiles >> (range.start * 4)) as u32 & 0xf_ffff;
+
+        let nibble = |index: u32| (section >> (index * 4)) & 0xf;
+
+        nibble(0) == nibble(4) && nibble(1) == nibble(3)
     }
 
     pub const fn get_spin_section_range(spin_section: SpinSection) -> Range<usize> {
@@ -277,6 +365,12 @@ impl TwistBoard {
 
     pub(crate) fn update_piece_cache(&mut self) {
         self.piece_cache = self.get_pieces_internal(self.players.0, self.players.1);
+        self.packed_tiles = pack_cells(&self.tiles);
+        self.packed_goals = pack_cells(self.goals.as_flattened());
+    }
+
+    pub fn players(&self) -> (PlayerColor, PlayerColor) {
+        self.players
     }
 
     pub fn get_pieces(&self, player: PlayerColor) -> (&TwistPieceVec, &TwistPieceVec) {
@@ -287,6 +381,17 @@ impl TwistBoard {
         }
     }
 
+    pub fn piece_counts(&self, player: PlayerColor) -> PieceCounts {
+        let count =
+            |cells: &[BoardCell]| cells.iter().filter(|&&cell| cell == Some(player)).count() as u8;
+
+        PieceCounts {
+            home: self.home_bases[player as usize].pieces_waiting,
+            board: count(&self.tiles),
+            goal: count(&self.goals[player as usize]),
+        }
+    }
+
     pub fn clockwise_distance(from: u8, to: u8) -> u8 {
         if to >= from {
             to - from
@@ -300,6 +405,22 @@ impl TwistBoard {
         Self::clockwise_distance(pos, goal)
     }
 
+    pub fn tiles(&self) -> &[BoardCell; Self::TILES] {
+        &self.tiles
+    }
+
+    pub fn goals(&self) -> &[TwistGoal; 4] {
+        &self.goals
+    }
+
+    pub fn set_tile(&mut self, tile: u8, cell: BoardCell) {
+        self.update(|board| board.tiles[tile as usize] = cell);
+    }
+
+    pub fn set_goal(&mut self, player: PlayerColor, slot: u8, cell: BoardCell) {
+        self.update(|board| board.goals[player as usize][slot as usize] = cell);
+    }
+
     pub fn update(&mut self, updater: impl FnOnce(&mut TwistBoard)) {
         updater(self);
         self.update_piece_cache();
@@ -307,6 +428,7 @@ impl TwistBoard {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActionDie {
     #[default]
     DoNothing,
@@ -317,7 +439,7 @@ pub enum ActionDie {
 impl ActionDie {
     pub fn get_random(rng: &mut impl Rng) -> Self {
         match rng.gen_range(0..6) {
-            0 | 1 | 2 => Self::DoNothing,
+            0..=2 => Self::DoNothing,
             3 | 4 => Self::SpinSection,
             5 => Self::RotateBoard,
             _ => unreachable!(),
@@ -325,33 +447,38 @@ impl ActionDie {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DieResult {
     pub number: u8,
     pub action: ActionDie,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MoveFrom {
     Home,
     Board(u8),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NumberDieMove {
     DoNothing,
     MovePiece { from: MoveFrom, to: u8, eats: bool },
     MoveToGoal { from_board: u8, to_goal: u8 },
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ActionDieMove {
     DoNothing,
     SpinSection(SpinSection),
     RotateBoard,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TwistMove(pub NumberDieMove, pub ActionDieMove);
 
 impl Default for TwistMove {
@@ -360,8 +487,10 @@ impl Default for TwistMove {
     }
 }
 
-// Store up to 4 moves inline
-pub type TwistMoveVec = TinyVec<[TwistMove; 8]>;
+/// There are at most 5 moves for the number die (a new piece, 3 pieces on the board and doing
+/// nothing) and 5 for the action die (4 spins and doing nothing), so every combination of them
+/// fits inline.
+pub type TwistMoveVec = TinyVec<[TwistMove; 25]>;
 
 #[cfg(test)]
 mod tests {
@@ -453,4 +582,23 @@ mod tests {
 
         assert_eq!(board.get_winner(), Some(PlayerColor::Red));
     }
+
+    #[test]
+    fn boards_compare_by_their_cells() {
+        let mut a = TwistBoard::new((P1, P2));
+        let mut b = TwistBoard::new((P1, P2));
+        assert!(a == b);
+
+        a.update(|board| board.tiles[3] = Some(P1));
+        assert!(a != b);
+
+        b.update(|board| board.tiles[3] = Some(P2));
+        assert!(a != b);
+
+        b.update(|board| board.tiles[3] = Some(P1));
+        assert!(a == b);
+
+        a.update(|board| board.goals[2][1] = Some(P2));
+        assert!(a != b);
+    }
 }
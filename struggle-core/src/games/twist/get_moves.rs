@@ -7,15 +7,6 @@ use super::board::{
     TwistMove, TwistMoveVec,
 };
 
-fn spin_is_nop(board: &TwistBoard, spin_section: SpinSection) -> bool {
-    let section = board.get_spin_section(spin_section);
-
-    let mut rev_section = section.clone();
-    rev_section.reverse();
-
-    section == &rev_section
-}
-
 fn create_move_to_pos(
     board: &TwistBoard,
     player: PlayerColor,
@@ -122,9 +113,7 @@ pub fn get_twist_moves(board: &TwistBoard, dice: DieResult, player: PlayerColor)
     match dice.action {
         ActionDie::SpinSection => {
             for section in SpinSection::ALL {
-                let spin_is_nop = spin_is_nop(board, section);
-
-                if !spin_is_nop {
+                if !board.spin_is_nop(section) {
                     action_die_moves.push(ActionDieMove::SpinSection(section));
                 }
             }
@@ -139,16 +128,14 @@ pub fn get_twist_moves(board: &TwistBoard, dice: DieResult, player: PlayerColor)
     action_die_moves.push(ActionDieMove::DoNothing);
 
     // Create a cartesian product of all possible moves
-
-    let mut moves = TwistMoveVec::new();
-
-    for number_die_move in number_die_moves {
-        for action_die_move in &action_die_moves {
-            moves.push(TwistMove(number_die_move.clone(), action_die_move.clone()))
-        }
-    }
-
-    moves
+    number_die_moves
+        .iter()
+        .flat_map(|&number_die_move| {
+            action_die_moves
+                .iter()
+                .map(move |&action_die_move| TwistMove(number_die_move, action_die_move))
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -529,7 +516,7 @@ mod get_moves_tests {
         let action_move = ActionDieMove::RotateBoard;
 
         let expected_moves = vec![
-            TwistMove(number_move.clone(), action_move.clone()),
+            TwistMove(number_move, action_move),
             TwistMove(number_move, ActionDieMove::DoNothing),
             TwistMove(NumberDieMove::DoNothing, action_move),
             TwistMove::default(),
@@ -570,7 +557,7 @@ mod get_moves_tests {
 
         // Since there are no pieces on spin sections, we not generate moves for it
         let expected_moves = vec![
-            TwistMove(number_move.clone(), ActionDieMove::DoNothing),
+            TwistMove(number_move, ActionDieMove::DoNothing),
             TwistMove::default(),
         ];
 
@@ -610,7 +597,7 @@ mod get_moves_tests {
         let action_move = ActionDieMove::SpinSection(SpinSection::RedToBlue);
 
         let expected_moves = vec![
-            TwistMove(number_move.clone(), action_move.clone()),
+            TwistMove(number_move, action_move),
             TwistMove(number_move, ActionDieMove::DoNothing),
             TwistMove(NumberDieMove::DoNothing, action_move),
             TwistMove::default(),
@@ -642,7 +629,7 @@ mod spin_is_nop_tests {
             spin_section.copy_from_slice(&initial);
         });
 
-        spin_is_nop(&board, SpinSection::RedToBlue)
+        board.spin_is_nop(SpinSection::RedToBlue)
     }
 
     #[test]
@@ -664,4 +651,41 @@ mod spin_is_nop_tests {
         assert!(!test_spin_section([Some(P1), None, Some(P2), None, None]));
         assert!(!test_spin_section([Some(P1), None, None, None, Some(P2)]));
     }
+
+    #[test]
+    fn moves_stay_inline() {
+        use rand::{rngs::SmallRng, SeedableRng};
+
+        use crate::{
+            game::{RaceGame, TurnResult},
+            games::{
+                struggle::AiStrugglePlayer,
+                twist::{players::TwistDoSomethingPlayer, TwistGame},
+            },
+        };
+
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        for _ in 0..100 {
+            let mut game = TwistGame::new(
+                AiStrugglePlayer::new(P1, TwistDoSomethingPlayer),
+                AiStrugglePlayer::new(P2, TwistDoSomethingPlayer),
+                false,
+            );
+
+            loop {
+                let ctx = game.create_turn_context(game.throw_dice(&mut rng));
+                let moves = game.get_moves(&ctx);
+                assert!(moves.is_inline());
+
+                let mov = *game.select_move(&ctx, &moves, &mut rng);
+
+                match game.apply_move(&ctx, &mov) {
+                    TurnResult::PlayAgain => {}
+                    TurnResult::PassTo(player) => game.set_current_player(player),
+                    TurnResult::EndGame { .. } => break,
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,366 @@
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+
+#[cfg(feature = "std")]
+use crate::game::play_game;
+use crate::game::{
+    play_game_with_rng, CreateGame, GameId, GameRules, GameStats, IntoGameStats, RaceGame,
+    ResetGame, TurnResult,
+};
+
+use self::{
+    board::{ActionDie, DieResult, NumberDieMove, TwistBoard, TwistMove, TwistMoveVec},
+    get_moves::get_twist_moves,
+    players::{GameContext, TwistPlayer, TwistRandomPlayer},
+};
+
+use super::struggle::{AiStrugglePlayer, PlayerColor};
+
+pub mod board;
+pub mod get_moves;
+pub mod players;
+
+pub type TwistGameStats = GameStats<25>;
+
+/// The types that Twist players see, see `GamePlayer`.
+pub struct TwistRules;
+
+impl GameRules for TwistRules {
+    type Board = TwistBoard;
+    type Move = TwistMove;
+    type TurnContext = GameContext;
+}
+
+pub struct TwistGame<A: TwistPlayer, B: TwistPlayer> {
+    board: TwistBoard,
+    player_a: AiStrugglePlayer<A>,
+    player_b: AiStrugglePlayer<B>,
+
+    current_player: PlayerColor,
+
+    stats: Option<TwistGameStats>,
+    // Whether the stats record the trajectory, see `ResetGame::set_timelines`
+    timelines: bool,
+    // The RNG of `play`, if the game was built with a seed
+    rng: Option<SmallRng>,
+}
+
+impl<A: TwistPlayer, B: TwistPlayer> TwistGame<A, B> {
+    pub fn new(
+        player_a: AiStrugglePlayer<A>,
+        player_b: AiStrugglePlayer<B>,
+        collect_stats: bool,
+    ) -> Self {
+        let board = TwistBoard::new((player_a.color, player_b.color));
+
+        Self {
+            board,
+            current_player: player_a.color,
+            player_a,
+            player_b,
+            stats: collect_stats.then(TwistGameStats::default),
+            timelines: false,
+            rng: None,
+        }
+    }
+
+    /// Plays the game to the end with the RNG seeded by `TwistGameBuilder::seed`, so that a game
+    /// built with a seed plays out the same every time. Games without a seed are played with this
+    /// thread's RNG, see `play_game` (or with an RNG seeded with 0 without `std`).
+    pub fn play(&mut self) -> PlayerColor {
+        match self.rng.take() {
+            Some(mut rng) => {
+                let winner = play_game_with_rng(self, &mut rng);
+                self.rng = Some(rng);
+                winner
+            }
+            #[cfg(feature = "std")]
+            None => play_game(self),
+            #[cfg(not(feature = "std"))]
+            None => play_game_with_rng(self, &mut SmallRng::seed_from_u64(0)),
+        }
+    }
+}
+
+impl TwistGame<TwistRandomPlayer, TwistRandomPlayer> {
+    /// Sets up a game like `StruggleGame::builder`. Both players are `TwistRandomPlayer`s unless
+    /// they're set.
+    pub fn builder() -> TwistGameBuilder<TwistRandomPlayer, TwistRandomPlayer> {
+        TwistGameBuilder {
+            red: TwistRandomPlayer,
+            yellow: TwistRandomPlayer,
+            collect_stats: false,
+            timelines: false,
+            seed: None,
+        }
+    }
+}
+
+/// Sets up a `TwistGame` between Red and Yellow, see `TwistGame::builder`. Like
+/// `StruggleGameBuilder`, it has no option for the rules until there are variants to pick from.
+pub struct TwistGameBuilder<A, B> {
+    red: A,
+    yellow: B,
+    collect_stats: bool,
+    timelines: bool,
+    seed: Option<u64>,
+}
+
+impl<A: TwistPlayer, B: TwistPlayer> TwistGameBuilder<A, B> {
+    pub fn red<P: TwistPlayer>(self, red: P) -> TwistGameBuilder<P, B> {
+        TwistGameBuilder {
+            red,
+            yellow: self.yellow,
+            collect_stats: self.collect_stats,
+            timelines: self.timelines,
+            seed: self.seed,
+        }
+    }
+
+    pub fn yellow<P: TwistPlayer>(self, yellow: P) -> TwistGameBuilder<A, P> {
+        TwistGameBuilder {
+            red: self.red,
+            yellow,
+            collect_stats: self.collect_stats,
+            timelines: self.timelines,
+            seed: self.seed,
+        }
+    }
+
+    pub fn collect_stats(mut self, collect_stats: bool) -> Self {
+        self.collect_stats = collect_stats;
+        self
+    }
+
+    /// Records the timelines of the game in its stats, see `ResetGame::set_timelines`.
+    pub fn timelines(mut self, timelines: bool) -> Self {
+        self.timelines = timelines;
+        self
+    }
+
+    /// Seeds the RNG the game is played with, by `TwistGame::play` or with the RNG of
+    /// `build_with_rng`, so that the game can be reproduced.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> TwistGame<A, B> {
+        TwistGame {
+            timelines: self.timelines,
+            rng: self.seed.map(SmallRng::seed_from_u64),
+            ..TwistGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, self.red),
+                AiStrugglePlayer::new(PlayerColor::Yellow, self.yellow),
+                self.collect_stats,
+            )
+        }
+    }
+
+    /// The game, and an RNG to play it with: seeded if a seed was given, random otherwise (or
+    /// seeded with 0 without `std`).
+    pub fn build_with_rng(self) -> (TwistGame<A, B>, SmallRng) {
+        let rng = match self.seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            #[cfg(feature = "std")]
+            None => SmallRng::from_rng(rand::thread_rng()).unwrap(),
+            // Without std there's no entropy to seed from
+            #[cfg(not(feature = "std"))]
+            None => SmallRng::seed_from_u64(0),
+        };
+
+        (self.build(), rng)
+    }
+
+    /// Plays the game to the end, returning the winner and the finished game.
+    pub fn play(self) -> (PlayerColor, TwistGame<A, B>) {
+        let mut game = self.build();
+        let winner = game.play();
+
+        (winner, game)
+    }
+}
+
+impl<A: TwistPlayer, B: TwistPlayer> RaceGame for TwistGame<A, B> {
+    type Board = TwistBoard;
+    type PlayerId = PlayerColor;
+
+    type Move = TwistMove;
+    type MoveVector = TwistMoveVec;
+
+    type TurnContext = players::GameContext;
+
+    type DiceState = DieResult;
+
+    type Rules = TwistRules;
+
+    const MAX_MOVES: usize = 25;
+
+    fn board(&self) -> &Self::Board {
+        &self.board
+    }
+
+    fn current_player(&self) -> Self::PlayerId {
+        self.current_player
+    }
+
+    fn other_player(&self) -> Self::PlayerId {
+        if self.player_a.color == self.current_player {
+            self.player_b.color
+        } else {
+            self.player_a.color
+        }
+    }
+
+    fn set_current_player(&mut self, player: Self::PlayerId) {
+        self.current_player = player;
+    }
+
+    fn throw_dice(&self, rng: &mut rand::rngs::SmallRng) -> Self::DiceState {
+        let number = rng.gen_range(1..=6);
+        let action = ActionDie::get_random(rng);
+        DieResult { number, action }
+    }
+
+    fn create_turn_context(&self, die: Self::DiceState) -> Self::TurnContext {
+        GameContext {
+            die,
+            current_player: self.current_player,
+            other_player: self.other_player(),
+        }
+    }
+
+    fn get_moves(&self, ctx: &Self::TurnContext) -> Self::MoveVector {
+        get_twist_moves(&self.board, ctx.die, self.current_player)
+    }
+
+    fn apply_move(
+        &mut self,
+        ctx: &Self::TurnContext,
+        mov: &Self::Move,
+    ) -> crate::game::TurnResult<Self::PlayerId> {
+        if let (Some(stats), NumberDieMove::MoveToGoal { to_goal, .. }) = (&mut self.stats, mov.0) {
+            let index = if self.current_player == self.player_a.color {
+                0
+            } else {
+                1
+            };
+
+            // `select_move` already counted this turn
+            stats.goal_slots[index][to_goal as usize] = Some(stats.turns - 1);
+        }
+
+        self.board.perform_move(self.current_player, mov);
+
+        if let (Some(stats), true) = (&mut self.stats, self.timelines) {
+            stats.trajectory.push([
+                self.board.piece_counts(self.player_a.color),
+                self.board.piece_counts(self.player_b.color),
+            ]);
+        }
+
+        if let Some(winner) = self.board.get_winner() {
+            TurnResult::EndGame { winner }
+        } else if ctx.die.number == 6 {
+            TurnResult::PlayAgain
+        } else {
+            TurnResult::PassTo(self.other_player())
+        }
+    }
+
+    fn select_move<'a>(
+        &mut self,
+        ctx: &Self::TurnContext,
+        moves: &'a Self::MoveVector,
+        rng: &mut rand::rngs::SmallRng,
+    ) -> &'a Self::Move {
+        if let Some(stats) = &mut self.stats {
+            let index = if self.current_player == self.player_a.color {
+                0
+            } else {
+                1
+            };
+
+            stats.turns += 1;
+            stats.move_distribution[index][moves.len() - 1] += 1;
+            stats.sixes[index] += (ctx.die.number == 6) as u16;
+            stats.dice[index][ctx.die.number as usize - 1] += 1;
+            stats.action_dice[index][ctx.die.action as usize] += 1;
+            stats.eat_opportunities[index] += moves
+                .iter()
+                .any(|mov| matches!(mov.0, NumberDieMove::MovePiece { eats: true, .. }))
+                as u16;
+        }
+
+        if self.current_player == self.player_a.color {
+            self.player_a
+                .player
+                .select_move(ctx, &self.board, moves, rng)
+        } else {
+            self.player_b
+                .player
+                .select_move(ctx, &self.board, moves, rng)
+        }
+    }
+
+    fn play_turn(
+        &mut self,
+        rng: &mut rand::rngs::SmallRng,
+    ) -> (Self::DiceState, TurnResult<Self::PlayerId>) {
+        let dice = self.throw_dice(rng);
+        let ctx = self.create_turn_context(dice);
+
+        let mut moves = self.get_moves(&ctx);
+        moves.shuffle(rng);
+
+        let mov = self.select_move(&ctx, &moves, rng);
+
+        (dice, self.apply_move(&ctx, mov))
+    }
+}
+
+impl<A: TwistPlayer, B: TwistPlayer> CreateGame for TwistGame<A, B> {
+    type PlayerA = A;
+    type PlayerB = B;
+
+    fn create_game(
+        player_a: (PlayerColor, A),
+        player_b: (PlayerColor, B),
+        collect_stats: bool,
+    ) -> Self {
+        let player_a = AiStrugglePlayer::new(player_a.0, player_a.1);
+        let player_b = AiStrugglePlayer::new(player_b.0, player_b.1);
+
+        Self::new(player_a, player_b, collect_stats)
+    }
+}
+
+impl<A: TwistPlayer, B: TwistPlayer> IntoGameStats<25> for TwistGame<A, B> {
+    fn into_stats(self) -> Option<TwistGameStats> {
+        self.stats
+    }
+
+    fn stats(&self) -> Option<&TwistGameStats> {
+        self.stats.as_ref()
+    }
+}
+
+impl<A: TwistPlayer, B: TwistPlayer> ResetGame for TwistGame<A, B> {
+    fn reset(&mut self) {
+        self.board = TwistBoard::new((self.player_a.color, self.player_b.color));
+        self.current_player = self.player_a.color;
+
+        if let Some(stats) = &mut self.stats {
+            *stats = TwistGameStats::default();
+        }
+    }
+
+    fn set_game_id(&mut self, id: GameId) {
+        if let Some(stats) = &mut self.stats {
+            stats.id = Some(id);
+        }
+    }
+
+    fn set_timelines(&mut self, timelines: bool) {
+        self.timelines = timelines;
+    }
+}
@@ -1,29 +1,29 @@
-use std::borrow::Cow;
+use alloc::{borrow::Cow, vec::Vec};
 
 use itertools::Itertools;
 use rand::{
     rngs::SmallRng,
     seq::{IteratorRandom, SliceRandom},
 };
+use tracing::{debug, trace};
 
 use crate::{
-    game::NamedPlayer,
+    game::{GamePlayer, NamedPlayer, ScoringPlayer},
     games::struggle::{board::PiecePosition, PlayerColor},
 };
 
-use super::board::{ActionDieMove, DieResult, MoveFrom, NumberDieMove, TwistBoard, TwistMove};
+use super::{
+    board::{ActionDieMove, DieResult, MoveFrom, NumberDieMove, TwistBoard, TwistMove},
+    TwistRules,
+};
 
-pub trait TwistPlayer: Clone + Send + Sync + NamedPlayer {
-    fn select_move<'a>(
-        &mut self,
-        ctx: &GameContext,
-        board: &TwistBoard,
-        moves: &'a [TwistMove],
-        rng: &mut SmallRng,
-    ) -> &'a TwistMove;
-}
+/// A player of Twist. Players implement `GamePlayer<TwistRules>`, and this is implemented for all
+/// of them.
+pub trait TwistPlayer: GamePlayer<TwistRules> {}
 
-#[derive(Clone)]
+impl<P: GamePlayer<TwistRules>> TwistPlayer for P {}
+
+#[derive(Clone, Copy)]
 pub struct GameContext {
     pub die: DieResult,
 
@@ -33,8 +33,8 @@ pub struct GameContext {
 
 impl GameContext {
     pub fn with_swapped_players(&self) -> Self {
-        let mut ctx = self.clone();
-        std::mem::swap(&mut ctx.current_player, &mut ctx.other_player);
+        let mut ctx = *self;
+        core::mem::swap(&mut ctx.current_player, &mut ctx.other_player);
         ctx
     }
 }
@@ -49,7 +49,7 @@ impl NamedPlayer for TwistRandomPlayer {
     }
 }
 
-impl TwistPlayer for TwistRandomPlayer {
+impl GamePlayer<TwistRules> for TwistRandomPlayer {
     fn select_move<'a>(
         &mut self,
         _ctx: &GameContext,
@@ -71,7 +71,7 @@ impl NamedPlayer for TwistDoNothingPlayer {
     }
 }
 
-impl TwistPlayer for TwistDoNothingPlayer {
+impl GamePlayer<TwistRules> for TwistDoNothingPlayer {
     fn select_move<'a>(
         &mut self,
         _ctx: &GameContext,
@@ -94,7 +94,7 @@ impl NamedPlayer for TwistDoSomethingPlayer {
     }
 }
 
-impl TwistPlayer for TwistDoSomethingPlayer {
+impl GamePlayer<TwistRules> for TwistDoSomethingPlayer {
     fn select_move<'a>(
         &mut self,
         _ctx: &GameContext,
@@ -135,7 +135,7 @@ fn score_move(mov: &TwistMove, board: &TwistBoard, ctx: &GameContext) -> i32 {
     let mut board_after_move = board.clone();
     board_after_move.perform_move(
         ctx.current_player,
-        &TwistMove(mov.0.clone(), ActionDieMove::DoNothing),
+        &TwistMove(mov.0, ActionDieMove::DoNothing),
     );
     score += match &mov.1 {
         ActionDieMove::SpinSection(section) => {
@@ -160,7 +160,7 @@ fn score_move(mov: &TwistMove, board: &TwistBoard, ctx: &GameContext) -> i32 {
             }
 
             let before = score_spin_section(ctx.current_player, section);
-            let mut rotated_section = section.clone();
+            let mut rotated_section = *section;
             rotated_section.reverse();
             let after = score_spin_section(ctx.current_player, &rotated_section);
 
@@ -183,7 +183,22 @@ impl NamedPlayer for TwistScoreMovePlayer {
     }
 }
 
-impl TwistPlayer for TwistScoreMovePlayer {
+impl ScoringPlayer<TwistRules> for TwistScoreMovePlayer {
+    fn score_moves(
+        &mut self,
+        ctx: &GameContext,
+        board: &TwistBoard,
+        moves: &[TwistMove],
+        _rng: &mut SmallRng,
+    ) -> Vec<f64> {
+        moves
+            .iter()
+            .map(|mov| score_move(mov, board, ctx) as f64)
+            .collect()
+    }
+}
+
+impl GamePlayer<TwistRules> for TwistScoreMovePlayer {
     fn select_move<'a>(
         &mut self,
         ctx: &GameContext,
@@ -211,7 +226,7 @@ impl NamedPlayer for TwistWorstScoreMovePlayer {
     }
 }
 
-impl TwistPlayer for TwistWorstScoreMovePlayer {
+impl GamePlayer<TwistRules> for TwistWorstScoreMovePlayer {
     fn select_move<'a>(
         &mut self,
         ctx: &GameContext,
@@ -300,7 +315,7 @@ impl NamedPlayer for TwistScoreBoardPlayer {
     }
 }
 
-impl TwistPlayer for TwistScoreBoardPlayer {
+impl GamePlayer<TwistRules> for TwistScoreBoardPlayer {
     fn select_move<'a>(
         &mut self,
         ctx: &GameContext,
@@ -308,8 +323,12 @@ impl TwistPlayer for TwistScoreBoardPlayer {
         moves: &'a [TwistMove],
         _rng: &mut SmallRng,
     ) -> &'a TwistMove {
-        /*println!("{:?}", ctx.die);
-        println!("{:?} scoring {} moves", ctx.current_player, moves.len());*/
+        trace!(
+            player = ?ctx.current_player,
+            die = ?ctx.die,
+            moves = moves.len(),
+            "scoring moves"
+        );
 
         let mov = moves
             .iter()
@@ -318,15 +337,14 @@ impl TwistPlayer for TwistScoreBoardPlayer {
                 board_after_move.perform_move(ctx.current_player, mov);
 
                 let score = score_board(&board_after_move, ctx);
-
-                //println!("{:?} {:?} -> {}", ctx.current_player, mov, score);
+                trace!(?mov, score, "scored a move");
 
                 -score
             })
             .next()
             .unwrap();
 
-        //println!("{:?} selected {:?}\n", ctx.current_player, mov);
+        debug!(player = ?ctx.current_player, ?mov, "chose a move");
 
         mov
     }
@@ -341,7 +359,7 @@ impl NamedPlayer for TwistScoreBoardPlayerWorst {
     }
 }
 
-impl TwistPlayer for TwistScoreBoardPlayerWorst {
+impl GamePlayer<TwistRules> for TwistScoreBoardPlayerWorst {
     fn select_move<'a>(
         &mut self,
         ctx: &GameContext,
@@ -355,9 +373,7 @@ impl TwistPlayer for TwistScoreBoardPlayerWorst {
                 let mut board_after_move = board.clone();
                 board_after_move.perform_move(ctx.current_player, mov);
 
-                let score = score_board(&board_after_move, ctx);
-
-                score
+                score_board(&board_after_move, ctx)
             })
             .next()
             .unwrap()
@@ -369,11 +385,8 @@ fn score_board_max_length(board: &TwistBoard, ctx: &GameContext) -> i32 {
     let mut score = 0;
 
     // Winning or losing is bad.
-    match board.get_winner() {
-        Some(_) => {
-            return -100_000;
-        }
-        _ => {}
+    if board.get_winner().is_some() {
+        return -100_000;
     }
 
     let (pieces, enemy_pieces) = board.get_pieces(ctx.current_player);
@@ -430,7 +443,7 @@ impl NamedPlayer for TwistScoreBoardPlayerMaximizeLength {
     }
 }
 
-impl TwistPlayer for TwistScoreBoardPlayerMaximizeLength {
+impl GamePlayer<TwistRules> for TwistScoreBoardPlayerMaximizeLength {
     fn select_move<'a>(
         &mut self,
         ctx: &GameContext,
@@ -0,0 +1,248 @@
+//! Handicap calibration: how much weaker a strong player has to be made to play a weak one evenly.
+//!
+//! The handicap is binary searched, assuming that the strong player's win rate falls as the
+//! handicap grows. Every step plays the same seeded games, so that the steps differ only by the
+//! handicap and not by the dice. The search stops at the first handicap whose win rate is within
+//! the tolerance of 50%, and otherwise reports the closest one it found.
+
+use core::{fmt, str::FromStr};
+
+use crate::{
+    analysis::wilson_score,
+    players::DilutedPlayer,
+    registry::{struggle_player, BoxedStrugglePlayer},
+    session::{run_match_seeded, MatchResult},
+};
+
+/// A way to weaken a player, by an amount.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Handicap {
+    /// The share of the player's moves that are uniformly random instead, from 0 to 1
+    Dilution,
+    /// How many plies shallower than the depth of its spec the player searches
+    DepthReduction,
+}
+
+impl Handicap {
+    /// The smallest and the largest amount of this handicap for the player with `spec`.
+    pub fn range(self, spec: &str) -> Result<(f64, f64), String> {
+        match self {
+            Handicap::Dilution => Ok((0.0, 1.0)),
+            Handicap::DepthReduction => Ok((0.0, split_depth(spec)?.1 as f64)),
+        }
+    }
+
+    /// Whether only whole amounts make sense.
+    pub fn is_discrete(self) -> bool {
+        self == Handicap::DepthReduction
+    }
+
+    /// The player with `spec`, weakened by `amount`.
+    pub fn apply(self, spec: &str, amount: f64) -> Result<BoxedStrugglePlayer, String> {
+        match self {
+            Handicap::Dilution => Ok(Box::new(DilutedPlayer(
+                struggle_player(spec)?,
+                1.0 - amount,
+            ))),
+            Handicap::DepthReduction => {
+                let (name, depth) = split_depth(spec)?;
+                let depth = depth.saturating_sub(amount.round() as u8);
+
+                struggle_player(&format!("{}:{}", name, depth))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Handicap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Handicap::Dilution => "dilution",
+            Handicap::DepthReduction => "depth",
+        })
+    }
+}
+
+impl FromStr for Handicap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dilution" => Ok(Handicap::Dilution),
+            "depth" => Ok(Handicap::DepthReduction),
+            _ => Err(format!(
+                "Unknown handicap '{}', expected dilution or depth",
+                s
+            )),
+        }
+    }
+}
+
+fn split_depth(spec: &str) -> Result<(&str, u8), String> {
+    spec.split_once(':')
+        .and_then(|(name, depth)| Some((name, depth.parse().ok()?)))
+        .ok_or_else(|| {
+            format!(
+                "A depth handicap needs a player with a depth, e.g. expectiminimax:2, not '{}'",
+                spec
+            )
+        })
+}
+
+#[derive(Clone, Debug)]
+pub struct CalibrationOptions {
+    /// Games per step
+    pub games: u32,
+    /// How close to 50% the strong player's win rate has to get
+    pub tolerance: f64,
+    pub max_steps: usize,
+    pub seed: u64,
+}
+
+impl Default for CalibrationOptions {
+    fn default() -> Self {
+        Self {
+            games: 4_000,
+            tolerance: 0.02,
+            max_steps: 12,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CalibrationStep {
+    pub handicap: f64,
+    /// The handicapped strong player as A against the weak player
+    pub result: MatchResult,
+    /// The 95% Wilson interval of the strong player's win rate
+    pub interval: (f64, f64),
+}
+
+impl CalibrationStep {
+    fn distance(&self) -> f64 {
+        (self.result.a_win_rate() - 0.5).abs()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Calibration {
+    pub handicap: Handicap,
+    pub steps: Vec<CalibrationStep>,
+    /// The index of the step whose win rate was closest to 50%
+    pub best: usize,
+    /// Whether the best step is within the tolerance
+    pub converged: bool,
+}
+
+impl Calibration {
+    pub fn best(&self) -> &CalibrationStep {
+        &self.steps[self.best]
+    }
+}
+
+/// Searches the amount of `handicap` that makes the player with the spec `strong` win half of
+/// its games against `weak`, calling `log` after every step.
+pub fn calibrate_handicap(
+    strong: &str,
+    weak: &BoxedStrugglePlayer,
+    handicap: Handicap,
+    options: &CalibrationOptions,
+    mut log: impl FnMut(&CalibrationStep),
+) -> Result<Calibration, String> {
+    let (mut low, mut high) = handicap.range(strong)?;
+    // Discrete searches step past the amounts they've tried
+    let step = if handicap.is_discrete() { 1.0 } else { 0.0 };
+
+    let mut steps = Vec::<CalibrationStep>::new();
+
+    while steps.len() < options.max_steps && low <= high {
+        let amount = if handicap.is_discrete() {
+            ((low + high) / 2.0).floor()
+        } else {
+            (low + high) / 2.0
+        };
+
+        let player = handicap.apply(strong, amount)?;
+        let result = run_match_seeded(&player, weak, options.games, options.seed, |_| {});
+        let calibration_step = CalibrationStep {
+            handicap: amount,
+            result,
+            interval: wilson_score(result.a_win_rate(), result.games as u64),
+        };
+
+        log(&calibration_step);
+
+        let win_rate = result.a_win_rate();
+        let done = calibration_step.distance() <= options.tolerance;
+        steps.push(calibration_step);
+
+        if done {
+            break;
+        }
+
+        if win_rate > 0.5 {
+            low = amount + step;
+        } else {
+            high = amount - step;
+        }
+    }
+
+    let best = (0..steps.len())
+        .min_by(|&a, &b| steps[a].distance().total_cmp(&steps[b].distance()))
+        .ok_or("The handicap has no amounts to try")?;
+
+    Ok(Calibration {
+        handicap,
+        converged: steps[best].distance() <= options.tolerance,
+        steps,
+        best,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::NamedPlayer;
+
+    #[test]
+    fn depth_reduction_needs_a_depth() {
+        assert_eq!(
+            Handicap::DepthReduction.range("expectiminimax:3"),
+            Ok((0.0, 3.0))
+        );
+        assert!(Handicap::DepthReduction.range("score_move").is_err());
+        assert_eq!("depth".parse(), Ok(Handicap::DepthReduction));
+
+        let player = Handicap::DepthReduction
+            .apply("expectiminimax:3", 2.0)
+            .unwrap();
+        assert_eq!(player.name(), "Expectiminimax(1)");
+    }
+
+    #[test]
+    fn reports_the_step_closest_to_even() {
+        let weak = struggle_player("random").unwrap();
+        let options = CalibrationOptions {
+            games: 200,
+            tolerance: 0.1,
+            max_steps: 4,
+            ..Default::default()
+        };
+
+        let mut logged = 0;
+        let calibration =
+            calibrate_handicap("score_move", &weak, Handicap::Dilution, &options, |_| {
+                logged += 1
+            })
+            .unwrap();
+
+        assert_eq!(logged, calibration.steps.len());
+        assert!(calibration.steps.len() <= 4);
+        assert!(calibration.best().handicap > 0.0 && calibration.best().handicap < 1.0);
+        assert!(calibration
+            .steps
+            .iter()
+            .all(|step| step.distance() >= calibration.best().distance()));
+    }
+}
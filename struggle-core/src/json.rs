@@ -0,0 +1,85 @@
+//! JSON views of game state, shared by the server and the live broadcaster.
+
+use serde::Serialize;
+
+use crate::games::struggle::{
+    board::{Board, StruggleMove},
+    PlayerColor,
+};
+
+#[derive(Serialize)]
+pub struct BoardJson {
+    pub tiles: Vec<Option<&'static str>>,
+    pub players: Vec<PlayerJson>,
+}
+
+#[derive(Serialize)]
+pub struct PlayerJson {
+    pub color: &'static str,
+    pub waiting: u8,
+    pub goal: Vec<bool>,
+}
+
+impl BoardJson {
+    pub fn new(board: &Board) -> Self {
+        let (a, b) = board.players();
+
+        Self {
+            tiles: board
+                .tiles
+                .iter()
+                .map(|cell| cell.map(color_name))
+                .collect(),
+            players: [a, b]
+                .iter()
+                .map(|&player| PlayerJson {
+                    color: color_name(player),
+                    waiting: board.home_bases[player as usize].pieces_waiting,
+                    goal: board.goals[player as usize]
+                        .iter()
+                        .map(Option::is_some)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MoveJson {
+    AddNewPiece { eats: bool },
+    MovePiece { from: u8, to: u8, eats: bool },
+    MoveToGoal { from_board: u8, to_goal: u8 },
+    MoveInGoal { from_goal: u8, to_goal: u8 },
+    SkipTurn,
+}
+
+impl From<&StruggleMove> for MoveJson {
+    fn from(mov: &StruggleMove) -> Self {
+        match *mov {
+            StruggleMove::AddNewPiece { eats } => MoveJson::AddNewPiece { eats },
+            StruggleMove::MovePiece { from, to, eats } => MoveJson::MovePiece { from, to, eats },
+            StruggleMove::MoveToGoal {
+                from_board,
+                to_goal,
+            } => MoveJson::MoveToGoal {
+                from_board,
+                to_goal,
+            },
+            StruggleMove::MoveInGoal { from_goal, to_goal } => {
+                MoveJson::MoveInGoal { from_goal, to_goal }
+            }
+            StruggleMove::SkipTurn => MoveJson::SkipTurn,
+        }
+    }
+}
+
+pub fn color_name(color: PlayerColor) -> &'static str {
+    match color {
+        PlayerColor::Red => "red",
+        PlayerColor::Blue => "blue",
+        PlayerColor::Yellow => "yellow",
+        PlayerColor::Green => "green",
+    }
+}
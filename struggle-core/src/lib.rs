@@ -0,0 +1,63 @@
+//! The rules of Struggle and Twist, their AI players, and the tools for simulating and analysing
+//! games between them.
+//!
+//! Without the default `std` feature only the boards, move generation, the game loop and the
+//! players that don't search are available, for embedding the engine where there is only `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod best_response;
+#[cfg(feature = "tui")]
+pub mod dashboard;
+#[cfg(feature = "dataset")]
+pub mod dataset;
+#[cfg(feature = "std")]
+pub mod encoding;
+#[cfg(feature = "parquet")]
+pub mod export;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod game;
+pub mod games;
+#[cfg(feature = "std")]
+pub mod handicap;
+#[cfg(feature = "std")]
+pub mod json;
+#[cfg(feature = "logging")]
+pub mod logging;
+#[cfg(feature = "std")]
+pub mod manifest;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod mirror;
+#[cfg(feature = "std")]
+pub mod palette;
+pub mod players;
+#[cfg(feature = "std")]
+pub mod prelude;
+#[cfg(feature = "std")]
+pub mod records;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "sqlite")]
+pub mod results_db;
+#[cfg(feature = "std")]
+pub mod scaling;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "std")]
+pub mod sweep;
+#[cfg(feature = "std")]
+pub mod tournament;
+
+pub mod tinyvec_util;
@@ -0,0 +1,18 @@
+//! Logging for the tools, controlled at runtime with `RUST_LOG`.
+//!
+//! The library only emits `tracing` events, e.g. the search depth, node counts and chosen moves of
+//! `GameTreePlayer` at the `debug` and `trace` levels. `init` prints them to stderr, so that
+//! protocols on stdout like struggle-engine's aren't disturbed. For example
+//! `RUST_LOG=struggle_core::games::struggle::players=debug` logs every search.
+
+use tracing_subscriber::EnvFilter;
+
+/// Installs a subscriber that filters events by `RUST_LOG`, showing warnings by default.
+pub fn init() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
@@ -0,0 +1,169 @@
+//! Manifests that describe how a run was made, written next to its results so that the run can
+//! be reproduced: the command line, the players and the master seed of the games, and the build
+//! and the machine that made them.
+
+use std::{
+    io,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{analysis::DiceCheck, game::PlayerConfig};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RunManifest {
+    /// The binary that made the run, e.g. `struggle-tournament`
+    pub tool: String,
+    /// The version of this crate
+    pub version: String,
+    /// The git commit the crate was built from, if it was built from a checkout
+    #[serde(default)]
+    pub commit: Option<String>,
+    /// The command line, including the binary
+    pub args: Vec<String>,
+    /// The seed the seeds of the games were derived from
+    pub seed: u64,
+    /// The player specs, see `registry`
+    pub players: Vec<String>,
+    /// The full configurations of the players, see `NamedPlayer::describe`
+    #[serde(default)]
+    pub player_configs: Vec<PlayerConfig>,
+    /// The game the players played, e.g. `struggle`
+    #[serde(default = "default_rules")]
+    pub rules: String,
+    /// The games of the run, or of every matchup for tournaments
+    pub games: u64,
+    /// The turn cap after which games were abandoned, if there was one
+    #[serde(default)]
+    pub max_turns: Option<u32>,
+    #[serde(default)]
+    pub hardware: Hardware,
+    /// Seconds since the Unix epoch
+    pub started_at: u64,
+    /// Seconds from the start to the end of the run, if it finished
+    #[serde(default)]
+    pub elapsed_secs: Option<f64>,
+    /// The dice fairness checks of the run, for runs made with them, see `DiceTally`
+    #[serde(default)]
+    pub dice_checks: Vec<DiceCheck>,
+}
+
+fn default_rules() -> String {
+    "struggle".to_string()
+}
+
+/// The machine a run was made on, as the timings depend on it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Hardware {
+    pub os: String,
+    pub arch: String,
+    pub threads: usize,
+}
+
+impl Hardware {
+    pub fn current() -> Self {
+        Self {
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+}
+
+impl RunManifest {
+    /// A manifest for a run of `tool` starting now, with the current command line.
+    pub fn new(tool: &str, seed: u64) -> Self {
+        Self {
+            tool: tool.to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: option_env!("STRUGGLE_GIT_COMMIT").map(str::to_string),
+            args: std::env::args().collect(),
+            seed,
+            players: Vec::new(),
+            player_configs: Vec::new(),
+            rules: default_rules(),
+            games: 0,
+            max_turns: None,
+            hardware: Hardware::current(),
+            started_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            elapsed_secs: None,
+            dice_checks: Vec::new(),
+        }
+    }
+
+    pub fn with_players(mut self, players: impl IntoIterator<Item = String>) -> Self {
+        self.players = players.into_iter().collect();
+        self
+    }
+
+    pub fn with_player_configs(mut self, configs: impl IntoIterator<Item = PlayerConfig>) -> Self {
+        self.player_configs = configs.into_iter().collect();
+        self
+    }
+
+    pub fn with_games(mut self, games: u64) -> Self {
+        self.games = games;
+        self
+    }
+
+    pub fn with_rules(mut self, rules: &str) -> Self {
+        self.rules = rules.to_string();
+        self
+    }
+
+    pub fn with_max_turns(mut self, max_turns: Option<u32>) -> Self {
+        self.max_turns = max_turns;
+        self
+    }
+
+    /// Records how long the run took, once it has finished.
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed_secs = Some(elapsed.as_secs_f64());
+        self
+    }
+
+    pub fn with_dice_checks(mut self, checks: Vec<DiceCheck>) -> Self {
+        self.dice_checks = checks;
+        self
+    }
+
+    /// Writes the manifest as pretty-printed JSON.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_manifests_from_before_the_build_and_hardware_fields() {
+        let manifest: RunManifest = serde_json::from_str(
+            r#"{
+                "tool": "struggle-tournament",
+                "version": "0.1.0",
+                "args": ["struggle-tournament", "random", "score_move"],
+                "seed": 7,
+                "players": ["random", "score_move"],
+                "games": 100,
+                "started_at": 0
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.rules, "struggle");
+        assert_eq!(manifest.max_turns, None);
+        assert_eq!(manifest.elapsed_secs, None);
+        assert!(manifest.player_configs.is_empty());
+        assert!(manifest.dice_checks.is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Prometheus metrics for long-running servers and simulations.
+//!
+//! Everything is counted in one process-wide registry. Rates and ratios are left to Prometheus:
+//!
+//! - games per second: `rate(struggle_games_total[1m])`
+//! - win rate of a player: `struggle_player_wins_total / struggle_player_games_total`
+
+use std::{sync::OnceLock, thread::JoinHandle};
+
+use prometheus::{Encoder, IntCounter, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+use tiny_http::{Header, Response, Server};
+use tracing::warn;
+
+use crate::games::struggle::transposition_table::TranspositionTable;
+
+pub struct Metrics {
+    registry: Registry,
+    pub games: IntCounter,
+    pub player_games: IntCounterVec,
+    pub player_wins: IntCounterVec,
+    /// Interactive games held by the servers
+    pub active_games: IntGauge,
+    /// Games of batch matches that haven't finished yet
+    pub queued_games: IntGauge,
+    transposition_table_entries: IntGauge,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        let registry = Registry::new();
+
+        let games = IntCounter::new("struggle_games_total", "Games played to the end").unwrap();
+        let player_games = IntCounterVec::new(
+            Opts::new("struggle_player_games_total", "Games played by each player"),
+            &["player"],
+        )
+        .unwrap();
+        let player_wins = IntCounterVec::new(
+            Opts::new("struggle_player_wins_total", "Games won by each player"),
+            &["player"],
+        )
+        .unwrap();
+        let active_games =
+            IntGauge::new("struggle_active_games", "Interactive games in progress").unwrap();
+        let queued_games = IntGauge::new(
+            "struggle_queued_games",
+            "Games of batch matches waiting to be played",
+        )
+        .unwrap();
+        let transposition_table_entries = IntGauge::new(
+            "struggle_transposition_table_entries",
+            "Entries in all transposition tables",
+        )
+        .unwrap();
+
+        registry.register(Box::new(games.clone())).unwrap();
+        registry.register(Box::new(player_games.clone())).unwrap();
+        registry.register(Box::new(player_wins.clone())).unwrap();
+        registry.register(Box::new(active_games.clone())).unwrap();
+        registry.register(Box::new(queued_games.clone())).unwrap();
+        registry
+            .register(Box::new(transposition_table_entries.clone()))
+            .unwrap();
+
+        Self {
+            registry,
+            games,
+            player_games,
+            player_wins,
+            active_games,
+            queued_games,
+            transposition_table_entries,
+        }
+    }
+
+    /// Counts a finished game between two players, by display name.
+    pub fn record_game(&self, winner: &str, loser: &str) {
+        self.games.inc();
+        self.player_games.with_label_values(&[winner]).inc();
+        self.player_games.with_label_values(&[loser]).inc();
+        self.player_wins.with_label_values(&[winner]).inc();
+    }
+
+    /// All metrics in the Prometheus text format.
+    pub fn encode(&self) -> String {
+        self.transposition_table_entries
+            .set(TranspositionTable::total_entries() as i64);
+
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .unwrap();
+
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+/// A response with the metrics, for serving them from an existing `tiny_http` server.
+pub fn response() -> Response<std::io::Cursor<Vec<u8>>> {
+    let header = Header::from_bytes("Content-Type", TextEncoder::new().format_type()).unwrap();
+    Response::from_string(metrics().encode()).with_header(header)
+}
+
+/// Serves the metrics on their own port, at any path, from a background thread.
+pub fn serve(address: &str) -> std::io::Result<JoinHandle<()>> {
+    let server = Server::http(address).map_err(std::io::Error::other)?;
+
+    Ok(std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            if let Err(err) = request.respond(response()) {
+                warn!(%err, "failed to send metrics");
+            }
+        }
+    }))
+}
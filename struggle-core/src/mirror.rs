@@ -0,0 +1,65 @@
+//! Mirror matches: a player against itself, which Red should win half of the time.
+//!
+//! Both seats play the same way and the starting player is drawn at random, so a Red win rate
+//! away from 50% points at a color bias, either in the player or in the engine, e.g. in the goal
+//! paths, which differ between the colors. A player is flagged when its Red win rate is more than
+//! `z_limit` standard errors away from 50%.
+
+use crate::{
+    analysis::wilson_score,
+    game::NamedPlayer,
+    registry::BoxedStrugglePlayer,
+    session::{run_match_seeded, MatchResult},
+};
+
+/// Three standard errors, which a fair player exceeds in about 0.3% of the checks.
+pub const DEFAULT_Z_LIMIT: f64 = 3.0;
+
+#[derive(Clone, Debug)]
+pub struct MirrorCheck {
+    pub player: String,
+    /// The match with Red as A
+    pub result: MatchResult,
+    /// The 95% Wilson interval of Red's win rate
+    pub interval: (f64, f64),
+    /// How many standard errors Red's win rate is from 50%
+    pub z: f64,
+}
+
+impl MirrorCheck {
+    pub fn red_win_rate(&self) -> f64 {
+        self.result.a_win_rate()
+    }
+
+    pub fn biased(&self, z_limit: f64) -> bool {
+        self.z.abs() > z_limit
+    }
+}
+
+/// Plays `games` seeded games of `player` against itself.
+pub fn mirror_match(player: &BoxedStrugglePlayer, games: u32, seed: u64) -> MirrorCheck {
+    let result = run_match_seeded(player, player, games, seed, |_| {});
+    let red = result.a_win_rate();
+
+    MirrorCheck {
+        player: player.name().into_owned(),
+        result,
+        interval: wilson_score(red, result.games as u64),
+        z: (red - 0.5) / (0.25 / result.games as f64).sqrt(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::struggle_player;
+
+    #[test]
+    fn random_play_has_no_color_bias() {
+        let player = struggle_player("random").unwrap();
+        let check = mirror_match(&player, 4_000, 11);
+
+        assert_eq!(check.result.games, 4_000);
+        assert!(!check.biased(4.0), "z = {}", check.z);
+    }
+}
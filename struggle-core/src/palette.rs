@@ -0,0 +1,151 @@
+//! Colors shared by the visualizer, board images and plots.
+//!
+//! Besides the classic colors there are presets that stay distinguishable with color vision
+//! deficiencies. Those also mark pieces with a glyph, so players can be told apart by shape alone.
+
+use tracing::warn;
+
+use crate::{games::struggle::PlayerColor, render::Rgb};
+
+/// A pattern drawn on top of a piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Glyph {
+    None,
+    Dot,
+    Ring,
+    Cross,
+    Bar,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub name: &'static str,
+    /// Indexed by `PlayerColor`
+    pub players: [Rgb; 4],
+    pub glyphs: [Glyph; 4],
+    pub board: Rgb,
+    pub empty_tile: Rgb,
+    pub label: Rgb,
+    /// Fill color for single series charts
+    pub chart: Rgb,
+}
+
+impl Palette {
+    pub const CLASSIC: Palette = Palette {
+        name: "classic",
+        players: [
+            Rgb(230, 41, 55),
+            Rgb(0, 121, 241),
+            Rgb(253, 249, 0),
+            Rgb(0, 228, 48),
+        ],
+        glyphs: [Glyph::None; 4],
+        board: Rgb::GRAY,
+        empty_tile: Rgb::WHITE,
+        label: Rgb::BLACK,
+        chart: Rgb(68, 63, 212),
+    };
+
+    /// The Okabe-Ito colors, which are safe for the common kinds of color blindness.
+    pub const OKABE_ITO: Palette = Palette {
+        name: "okabe-ito",
+        players: [
+            Rgb(213, 94, 0),
+            Rgb(0, 114, 178),
+            Rgb(240, 228, 66),
+            Rgb(0, 158, 115),
+        ],
+        glyphs: [Glyph::Cross, Glyph::Dot, Glyph::Ring, Glyph::Bar],
+        board: Rgb::GRAY,
+        empty_tile: Rgb::WHITE,
+        label: Rgb::BLACK,
+        chart: Rgb(86, 180, 233),
+    };
+
+    /// Shades of gray, where only the glyphs tell the players apart reliably.
+    pub const MONOCHROME: Palette = Palette {
+        name: "monochrome",
+        players: [
+            Rgb(240, 240, 240),
+            Rgb(90, 90, 90),
+            Rgb(170, 170, 170),
+            Rgb(40, 40, 40),
+        ],
+        glyphs: [Glyph::Cross, Glyph::Dot, Glyph::Ring, Glyph::Bar],
+        board: Rgb(110, 110, 110),
+        empty_tile: Rgb::WHITE,
+        label: Rgb::BLACK,
+        chart: Rgb(90, 90, 90),
+    };
+
+    pub const PRESETS: [Palette; 3] = [Self::CLASSIC, Self::OKABE_ITO, Self::MONOCHROME];
+
+    pub fn player(&self, player: PlayerColor) -> Rgb {
+        self.players[player as usize]
+    }
+
+    pub fn glyph(&self, player: PlayerColor) -> Glyph {
+        self.glyphs[player as usize]
+    }
+
+    /// Parses either the name of a preset, or four comma separated hex colors for
+    /// red, blue, yellow and green, e.g. `#d55e00,#0072b2,#f0e442,#009e73`.
+    /// Custom colors use the glyphs of the color blind presets.
+    pub fn parse(spec: &str) -> Result<Palette, String> {
+        if let Some(preset) = Self::PRESETS.iter().find(|preset| preset.name == spec) {
+            return Ok(*preset);
+        }
+
+        let colors = spec
+            .split(',')
+            .map(|color| Rgb::parse_hex(color.trim()))
+            .collect::<Option<Vec<_>>>();
+
+        match colors.as_deref() {
+            Some(&[red, blue, yellow, green]) => Ok(Palette {
+                name: "custom",
+                players: [red, blue, yellow, green],
+                ..Self::OKABE_ITO
+            }),
+            _ => Err(format!(
+                "Invalid palette '{}', expected one of: {}, or four hex colors like #d55e00",
+                spec,
+                Self::PRESETS.map(|preset| preset.name).join(", ")
+            )),
+        }
+    }
+
+    /// The palette named by the `STRUGGLE_PALETTE` environment variable, or the classic one.
+    pub fn from_env() -> Palette {
+        match std::env::var("STRUGGLE_PALETTE") {
+            Ok(spec) => Self::parse(&spec).unwrap_or_else(|err| {
+                warn!(%err, "using the classic palette");
+                Self::CLASSIC
+            }),
+            Err(_) => Self::CLASSIC,
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::CLASSIC
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_presets_and_custom_colors() {
+        assert_eq!(Palette::parse("okabe-ito"), Ok(Palette::OKABE_ITO));
+
+        let custom = Palette::parse("#000000, #ff0000,#00ff00,#0000FF").unwrap();
+        assert_eq!(custom.player(PlayerColor::Blue), Rgb(255, 0, 0));
+        assert_eq!(custom.player(PlayerColor::Green), Rgb(0, 0, 255));
+
+        assert!(Palette::parse("#000000,#ff0000").is_err());
+        assert!(Palette::parse("sepia").is_err());
+    }
+}
@@ -0,0 +1,586 @@
+//! Players that wrap other players, for any game.
+
+use alloc::{borrow::Cow, format, string::String, sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng};
+
+use crate::game::{GamePlayer, GameRules, NamedPlayer, PlayerConfig};
+
+#[cfg(feature = "std")]
+use crate::game::ScoringPlayer;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+/// Plays the wrapped player's move with the given probability, and a uniformly random legal move
+/// otherwise.
+#[derive(Clone)]
+pub struct DilutedPlayer<P>(pub P, pub f64);
+
+impl<R: GameRules, P: GamePlayer<R>> GamePlayer<R> for DilutedPlayer<P> {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        if rng.gen::<f64>() < self.1 {
+            self.0.select_move(ctx, board, moves, rng)
+        } else {
+            moves.choose(rng).unwrap()
+        }
+    }
+
+    fn reset(&mut self) {
+        self.0.reset()
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.0.total_evaluations()
+    }
+}
+
+impl<P: NamedPlayer> NamedPlayer for DilutedPlayer<P> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from(format!("{} {:.0}%", self.0.name(), self.1 * 100.0))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name())
+            .with_inner("player", self.0.describe())
+            .with_parameter("dilution", self.1)
+    }
+}
+
+/// How the exploration rate of `EpsilonGreedy` changes over the moves it has made.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EpsilonSchedule {
+    Constant(f64),
+    /// Moves from `start` to `end` in a straight line over `steps` moves, then stays at `end`
+    Linear {
+        start: f64,
+        end: f64,
+        steps: u64,
+    },
+    /// Multiplies the rate by `decay` after every move, but never goes below `end`. Needs `std`
+    /// for the floating point power.
+    #[cfg(feature = "std")]
+    Exponential {
+        start: f64,
+        end: f64,
+        decay: f64,
+    },
+}
+
+impl EpsilonSchedule {
+    /// The exploration rate after `step` moves.
+    pub fn epsilon(&self, step: u64) -> f64 {
+        match *self {
+            EpsilonSchedule::Constant(epsilon) => epsilon,
+            EpsilonSchedule::Linear { start, end, steps } => {
+                let progress = (step as f64 / steps.max(1) as f64).min(1.0);
+                start + (end - start) * progress
+            }
+            #[cfg(feature = "std")]
+            EpsilonSchedule::Exponential { start, end, decay } => {
+                (start * decay.powf(step as f64)).max(end)
+            }
+        }
+    }
+}
+
+/// Plays a uniformly random legal move with probability ε, and the wrapped player's move
+/// otherwise. Unlike `DilutedPlayer`, the rate can follow a schedule, whose progress is shared
+/// by all clones of the player, so that it carries over from game to game.
+#[derive(Clone)]
+pub struct EpsilonGreedy<P> {
+    pub player: P,
+    pub schedule: EpsilonSchedule,
+    steps: Arc<AtomicU64>,
+}
+
+impl<P> EpsilonGreedy<P> {
+    pub fn new(player: P, schedule: EpsilonSchedule) -> Self {
+        Self {
+            player,
+            schedule,
+            steps: Default::default(),
+        }
+    }
+
+    pub fn constant(player: P, epsilon: f64) -> Self {
+        Self::new(player, EpsilonSchedule::Constant(epsilon))
+    }
+
+    /// The moves made so far by this player and its clones.
+    pub fn steps(&self) -> u64 {
+        self.steps.load(Ordering::Relaxed)
+    }
+
+    /// The current exploration rate.
+    pub fn epsilon(&self) -> f64 {
+        self.schedule.epsilon(self.steps())
+    }
+}
+
+impl<R: GameRules, P: GamePlayer<R>> GamePlayer<R> for EpsilonGreedy<P> {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        let step = self.steps.fetch_add(1, Ordering::Relaxed);
+
+        if rng.gen::<f64>() < self.schedule.epsilon(step) {
+            moves.choose(rng).unwrap()
+        } else {
+            self.player.select_move(ctx, board, moves, rng)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.player.reset()
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.player.total_evaluations()
+    }
+}
+
+impl<P: NamedPlayer> NamedPlayer for EpsilonGreedy<P> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from(format!("{} ε={:.2}", self.player.name(), self.epsilon()))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name())
+            .with_inner("player", self.player.describe())
+            .with_parameter("schedule", format!("{:?}", self.schedule))
+    }
+}
+
+/// When `PopulationPlayer` picks the member that plays.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resample {
+    PerGame,
+    PerMove,
+}
+
+/// Plays with one of several players, picked at random in proportion to their weights, for a
+/// whole game or for every move. An opponent tuned against it has to beat the mixture rather
+/// than a single fixed policy.
+#[derive(Clone)]
+pub struct PopulationPlayer<P> {
+    pub members: Vec<(P, f64)>,
+    pub resample: Resample,
+    // The member playing the current game, with `Resample::PerGame`
+    current: Option<usize>,
+}
+
+impl<P> PopulationPlayer<P> {
+    /// Panics if there are no members, or if the weights aren't positive.
+    pub fn new(members: Vec<(P, f64)>, resample: Resample) -> Self {
+        assert!(
+            !members.is_empty(),
+            "A population needs at least one member"
+        );
+        assert!(
+            members.iter().all(|(_, weight)| *weight > 0.0),
+            "The weights of a population must be positive"
+        );
+
+        Self {
+            members,
+            resample,
+            current: None,
+        }
+    }
+
+    fn pick(&self, rng: &mut SmallRng) -> usize {
+        let total = self.members.iter().map(|(_, weight)| weight).sum::<f64>();
+        let mut target = rng.gen::<f64>() * total;
+
+        for (index, (_, weight)) in self.members.iter().enumerate() {
+            if target < *weight {
+                return index;
+            }
+
+            target -= weight;
+        }
+
+        self.members.len() - 1
+    }
+}
+
+impl<R: GameRules, P: GamePlayer<R>> GamePlayer<R> for PopulationPlayer<P> {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        let index = match (self.resample, self.current) {
+            (Resample::PerGame, Some(index)) => index,
+            _ => self.pick(rng),
+        };
+
+        if self.resample == Resample::PerGame {
+            self.current = Some(index);
+        }
+
+        self.members[index].0.select_move(ctx, board, moves, rng)
+    }
+
+    fn reset(&mut self) {
+        self.current = None;
+
+        for (member, _) in &mut self.members {
+            member.reset();
+        }
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.members
+            .iter()
+            .map(|(member, _)| member.total_evaluations())
+            .sum()
+    }
+}
+
+impl<P: NamedPlayer> NamedPlayer for PopulationPlayer<P> {
+    fn name(&self) -> Cow<'static, str> {
+        let total = self.members.iter().map(|(_, weight)| weight).sum::<f64>();
+        let members = self
+            .members
+            .iter()
+            .map(|(member, weight)| format!("{} {:.0}%", member.name(), weight / total * 100.0))
+            .collect::<Vec<String>>();
+
+        Cow::from(format!("Population({})", members.join(", ")))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        self.members.iter().enumerate().fold(
+            PlayerConfig::new(self.name())
+                .with_parameter("resample", format!("{:?}", self.resample)),
+            |config, (index, (member, weight))| {
+                config
+                    .with_inner(&format!("member{}", index), member.describe())
+                    .with_parameter(format!("weight{}", index), weight)
+            },
+        )
+    }
+}
+
+/// Samples the moves from a softmax over the wrapped player's scores, with the probability of a
+/// move proportional to `exp(score / temperature)`. A temperature of 0 always plays the best
+/// move, and the higher it is, the closer the play is to uniformly random. The scale of the
+/// scores depends on the player, and so does a sensible temperature.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct SoftmaxPlayer<P> {
+    pub player: P,
+    pub temperature: f64,
+}
+
+#[cfg(feature = "std")]
+impl<R: GameRules, P: ScoringPlayer<R>> GamePlayer<R> for SoftmaxPlayer<P> {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        if moves.len() == 1 {
+            return &moves[0];
+        }
+
+        let scores = self.player.score_moves(ctx, board, moves, rng);
+        let best = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        if self.temperature <= 0.0 {
+            let index = scores.iter().position(|&score| score == best).unwrap();
+            return &moves[index];
+        }
+
+        // Relative to the best score, so that large scores don't overflow
+        let weights = scores
+            .iter()
+            .map(|score| ((score - best) / self.temperature).exp())
+            .collect::<Vec<_>>();
+
+        let mut target = rng.gen::<f64>() * weights.iter().sum::<f64>();
+
+        for (mov, weight) in moves.iter().zip(&weights) {
+            if target < *weight {
+                return mov;
+            }
+
+            target -= weight;
+        }
+
+        moves.last().unwrap()
+    }
+
+    fn reset(&mut self) {
+        self.player.reset()
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.player.total_evaluations()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: NamedPlayer> NamedPlayer for SoftmaxPlayer<P> {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::from(format!("{} T={}", self.player.name(), self.temperature))
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        PlayerConfig::new(self.name())
+            .with_inner("player", self.player.describe())
+            .with_parameter("temperature", self.temperature)
+    }
+}
+
+/// Measures the time the wrapped player takes to choose its moves. The totals are shared by all
+/// clones of the player, so they cover every game it plays in a batch.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct TimedPlayer<P> {
+    pub player: P,
+    moves: Arc<AtomicU64>,
+    nanos: Arc<AtomicU64>,
+}
+
+#[cfg(feature = "std")]
+impl<P> TimedPlayer<P> {
+    pub fn new(player: P) -> Self {
+        Self {
+            player,
+            moves: Default::default(),
+            nanos: Default::default(),
+        }
+    }
+
+    /// The moves chosen so far by this player and its clones.
+    pub fn moves(&self) -> u64 {
+        self.moves.load(Ordering::Relaxed)
+    }
+
+    /// The average time to choose a move, or zero before the first move.
+    pub fn average_move_time(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed) / self.moves().max(1))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: GameRules, P: GamePlayer<R>> GamePlayer<R> for TimedPlayer<P> {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        let start = Instant::now();
+        let chosen = self.player.select_move(ctx, board, moves, rng);
+
+        self.nanos
+            .fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        self.moves.fetch_add(1, Ordering::Relaxed);
+
+        chosen
+    }
+
+    fn reset(&mut self) {
+        self.player.reset()
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        self.player.total_evaluations()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<P: NamedPlayer> NamedPlayer for TimedPlayer<P> {
+    fn name(&self) -> Cow<'static, str> {
+        self.player.name()
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        self.player.describe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        game::RaceGame,
+        games::{
+            struggle::{
+                players::{expectiminimax, ScoreMovePlayer},
+                StruggleGame,
+            },
+            twist::{players::TwistScoreMovePlayer, TwistGame},
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    fn wraps_players_of_either_game() {
+        let (_, game) = StruggleGame::builder()
+            .red(DilutedPlayer(ScoreMovePlayer, 0.5))
+            .yellow(EpsilonGreedy::constant(ScoreMovePlayer, 0.1))
+            .seed(3)
+            .play();
+        assert!(game.board().get_winner().is_some());
+
+        let (_, game) = TwistGame::builder()
+            .red(DilutedPlayer(TwistScoreMovePlayer, 0.5))
+            .yellow(EpsilonGreedy::constant(TwistScoreMovePlayer, 0.1))
+            .seed(3)
+            .play();
+        assert!(game.board().get_winner().is_some());
+    }
+
+    #[test]
+    fn population_keeps_its_member_for_the_whole_game() {
+        use crate::games::struggle::{
+            board::{Board, StruggleMove},
+            players::GameContext,
+            PlayerColor, StruggleRules,
+        };
+        use rand::SeedableRng;
+
+        // Each member always plays the first or the last move
+        #[derive(Clone)]
+        struct Pick(bool);
+
+        impl GamePlayer<StruggleRules> for Pick {
+            fn select_move<'a>(
+                &mut self,
+                _ctx: &GameContext,
+                _board: &Board,
+                moves: &'a [StruggleMove],
+                _rng: &mut SmallRng,
+            ) -> &'a StruggleMove {
+                if self.0 {
+                    moves.first().unwrap()
+                } else {
+                    moves.last().unwrap()
+                }
+            }
+        }
+
+        impl NamedPlayer for Pick {
+            fn name(&self) -> Cow<'static, str> {
+                Cow::from(if self.0 { "First" } else { "Last" })
+            }
+        }
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 1,
+        };
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let moves = [
+            StruggleMove::SkipTurn,
+            StruggleMove::AddNewPiece { eats: false },
+        ];
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        let members = vec![(Pick(true), 1.0), (Pick(false), 1.0)];
+        let mut per_game = PopulationPlayer::new(members.clone(), Resample::PerGame);
+        let mut per_move = PopulationPlayer::new(members, Resample::PerMove);
+
+        let picks = |player: &mut PopulationPlayer<Pick>, rng: &mut SmallRng| {
+            (0..50)
+                .map(|_| {
+                    GamePlayer::<StruggleRules>::select_move(player, &ctx, &board, &moves, rng)
+                        .clone()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let game = picks(&mut per_game, &mut rng);
+        assert!(game.iter().all(|mov| *mov == game[0]));
+
+        let moves_per_move = picks(&mut per_move, &mut rng);
+        assert!(moves_per_move.iter().any(|mov| *mov != moves_per_move[0]));
+
+        assert_eq!(per_game.name(), "Population(First 50%, Last 50%)");
+    }
+
+    #[test]
+    fn softmax_interpolates_between_greedy_and_random() {
+        use crate::games::struggle::{
+            board::{Board, StruggleMove},
+            players::GameContext,
+            PlayerColor, StruggleRules,
+        };
+        use rand::SeedableRng;
+
+        let ctx = GameContext {
+            current_player: PlayerColor::Red,
+            other_player: PlayerColor::Yellow,
+            dice: 1,
+        };
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        // ScoreMovePlayer scores adding a piece 50 points higher than skipping
+        let moves = [
+            StruggleMove::SkipTurn,
+            StruggleMove::AddNewPiece { eats: false },
+        ];
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        let mut skips = |temperature| {
+            let mut player = SoftmaxPlayer {
+                player: ScoreMovePlayer,
+                temperature,
+            };
+
+            (0..1000)
+                .filter(|_| {
+                    GamePlayer::<StruggleRules>::select_move(
+                        &mut player,
+                        &ctx,
+                        &board,
+                        &moves,
+                        &mut rng,
+                    ) == &moves[0]
+                })
+                .count()
+        };
+
+        assert_eq!(skips(0.0), 0);
+        assert!((300..=700).contains(&skips(1000.0)));
+
+        let (_, game) = TwistGame::builder()
+            .red(SoftmaxPlayer {
+                player: TwistScoreMovePlayer,
+                temperature: 50.0,
+            })
+            .seed(3)
+            .play();
+        assert!(game.board().get_winner().is_some());
+    }
+
+    #[test]
+    fn describes_the_wrapped_player() {
+        let config = DilutedPlayer(expectiminimax(2), 0.5).describe();
+
+        assert_eq!(config.name, "Expectiminimax(2) 50%");
+        assert_eq!(config.parameters["player"], "Expectiminimax(2)");
+        assert_eq!(config.parameters["player.depth"], "2");
+        assert_eq!(config.parameters["dilution"], "0.5");
+    }
+}
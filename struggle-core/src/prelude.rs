@@ -0,0 +1,45 @@
+//! The types and functions that most programs using the engine need, in one import:
+//!
+//! ```
+//! use struggle_core::prelude::*;
+//!
+//! let (winner, _) = StruggleGame::builder()
+//!     .red(expectiminimax(1))
+//!     .yellow(RandomPlayer)
+//!     .seed(7)
+//!     .play();
+//!
+//! println!("{:?} won", winner);
+//! ```
+
+pub use rand::{rngs::SmallRng, SeedableRng};
+
+pub use crate::{
+    analysis::{wilson_score, Summary},
+    game::{
+        play_game, play_game_with_rng, simulate_batch, BatchConfig, GamePlayer, GameRules,
+        NamedPlayer, PlayerConfig, RaceGame, ResetGame, TurnResult,
+    },
+    games::{
+        struggle::{
+            board::{Board, StruggleMove},
+            players::{
+                default_heuristic, expectiminimax, GameContext, GameTreePlayer, RandomPlayer,
+                ScoreMovePlayer, StrugglePlayer, TableUse,
+            },
+            AiStrugglePlayer, PlayerColor, StruggleGame, StruggleRules,
+        },
+        twist::{
+            board::{TwistBoard, TwistMove},
+            players::{
+                GameContext as TwistGameContext, TwistPlayer, TwistRandomPlayer,
+                TwistScoreBoardPlayer,
+            },
+            TwistGame, TwistRules,
+        },
+    },
+    players::{DilutedPlayer, EpsilonGreedy},
+    registry::{struggle_player, twist_player, BoxedStrugglePlayer, BoxedTwistPlayer},
+    session::{run_match, MatchResult},
+    tournament::Tournament,
+};
@@ -0,0 +1,76 @@
+//! Per-game records of seeded simulations, shared by the result exporters.
+
+use rand::{rngs::SmallRng, SeedableRng};
+
+use crate::{
+    game::{play_game_with_rng, GameId, IntoGameStats, ResetGame},
+    games::struggle::{
+        players::StrugglePlayer, turn_log::TurnLogger, AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+};
+
+/// Per-player fields are indexed Red, Yellow.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GameRecord {
+    pub id: GameId,
+    pub red: String,
+    pub yellow: String,
+    pub winner: PlayerColor,
+    pub turns: u32,
+    pub turns_per_player: [u32; 2],
+    pub eats: [u32; 2],
+    pub evaluations: [u64; 2],
+    /// How many times each player rolled each number, see `GameStats::dice`
+    pub dice: [[u32; 6]; 2],
+}
+
+/// Plays the game `id` between `red` and `yellow`, logging its turns if given a logger.
+pub fn play_recorded_game<A: StrugglePlayer, B: StrugglePlayer>(
+    red: &A,
+    yellow: &B,
+    id: GameId,
+    logger: Option<&TurnLogger>,
+) -> GameRecord {
+    let mut rng = SmallRng::seed_from_u64(id.game_seed());
+    let mut state = StruggleGame::new(
+        AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+        AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+        true,
+    );
+
+    if let Some(logger) = logger {
+        state = state.with_logger(logger.for_game(id));
+    }
+
+    state.set_game_id(id);
+
+    let winner = play_game_with_rng(&mut state, &mut rng);
+    let stats = state.into_stats().unwrap();
+
+    GameRecord {
+        id,
+        red: red.name().into_owned(),
+        yellow: yellow.name().into_owned(),
+        winner,
+        turns: stats.turns as u32,
+        turns_per_player: stats.turns_per_player.map(u32::from),
+        eats: stats.pieces_eaten_by.map(u32::from),
+        evaluations: stats.expectiminimax_evals,
+        dice: stats.dice.map(|dice| dice.map(u32::from)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::struggle::players::RandomPlayer;
+
+    #[test]
+    fn seeded_games_are_reproducible() {
+        let a = play_recorded_game(&RandomPlayer, &RandomPlayer, GameId::new(1234, 0), None);
+        let b = play_recorded_game(&RandomPlayer, &RandomPlayer, GameId::new(1234, 0), None);
+
+        assert_eq!(a, b);
+        assert_eq!(a.turns, a.turns_per_player[0] + a.turns_per_player[1]);
+    }
+}
@@ -0,0 +1,436 @@
+//! Players that are picked at runtime, e.g. from the command line.
+//!
+//! A player spec is a name, optionally followed by a search depth in parentheses or after a
+//! colon: `random`, `expectiminimax(2)`, `expectiminimax:2`. Wrappers take other specs as
+//! arguments, e.g. `diluted(expectiminimax(1),0.5)`, see `WRAPPERS`. Struggle players can also
+//! be external programs, see `games::struggle::external`: `external:python3 bot.py`.
+
+use std::{borrow::Cow, fmt};
+
+use rand::rngs::SmallRng;
+
+use crate::{
+    game::{GamePlayer, GameRules, NamedPlayer, PlayerConfig},
+    games::{
+        struggle::{
+            external::ExternalPlayer,
+            players::{
+                defensive_expectiminimax, expectiminimax, expectiminimax_mvp,
+                maximize_length_expectiminimax, maximize_options, minimize_options, one_at_a_time,
+                one_at_a_time_deluxe, participation_trophy, random_depth_expectiminimax,
+                rusher_expectiminimax, stateful_get_it_over_with, worst_expectiminimax,
+                RandomDietPlayer, RandomEaterPlayer, RandomPlayer, ScoreMovePlayer,
+                WorstScoreMovePlayer,
+            },
+            StruggleRules,
+        },
+        twist::{
+            players::{
+                TwistDoNothingPlayer, TwistDoSomethingPlayer, TwistRandomPlayer,
+                TwistScoreBoardPlayer, TwistScoreBoardPlayerMaximizeLength,
+                TwistScoreBoardPlayerWorst, TwistScoreMovePlayer, TwistWorstScoreMovePlayer,
+            },
+            TwistRules,
+        },
+    },
+    players::DilutedPlayer,
+};
+
+/// Object-safe counterpart of `GamePlayer`. Every `GamePlayer` implements it, and `BoxedPlayer`
+/// implements `GamePlayer` in turn.
+pub trait DynPlayer<R: GameRules>: NamedPlayer + Send + Sync {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move;
+
+    fn reset(&mut self);
+
+    fn total_evaluations(&self) -> u64;
+
+    fn clone_box(&self) -> BoxedPlayer<R>;
+}
+
+pub type BoxedPlayer<R> = Box<dyn DynPlayer<R>>;
+pub type BoxedStrugglePlayer = BoxedPlayer<StruggleRules>;
+pub type BoxedTwistPlayer = BoxedPlayer<TwistRules>;
+
+impl<R: GameRules, P: GamePlayer<R> + 'static> DynPlayer<R> for P {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        GamePlayer::select_move(self, ctx, board, moves, rng)
+    }
+
+    fn reset(&mut self) {
+        GamePlayer::reset(self)
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        GamePlayer::total_evaluations(self)
+    }
+
+    fn clone_box(&self) -> BoxedPlayer<R> {
+        Box::new(self.clone())
+    }
+}
+
+impl<R: GameRules> Clone for BoxedPlayer<R> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+impl<R: GameRules> NamedPlayer for BoxedPlayer<R> {
+    fn name(&self) -> Cow<'static, str> {
+        (**self).name()
+    }
+
+    fn describe(&self) -> PlayerConfig {
+        (**self).describe()
+    }
+}
+
+impl<R: GameRules> GamePlayer<R> for BoxedPlayer<R> {
+    fn select_move<'a>(
+        &mut self,
+        ctx: &R::TurnContext,
+        board: &R::Board,
+        moves: &'a [R::Move],
+        rng: &mut SmallRng,
+    ) -> &'a R::Move {
+        DynPlayer::select_move(&mut **self, ctx, board, moves, rng)
+    }
+
+    fn reset(&mut self) {
+        DynPlayer::reset(&mut **self)
+    }
+
+    fn total_evaluations(&self) -> u64 {
+        DynPlayer::total_evaluations(&**self)
+    }
+}
+
+pub const STRUGGLE_PLAYERS: &[&str] = &[
+    "random",
+    "random_eater",
+    "random_diet",
+    "score_move",
+    "worst_score_move",
+    "expectiminimax",
+    "expectiminimax_basic",
+    "worst_expectiminimax",
+    "participation_trophy",
+    "one_at_a_time",
+    "one_at_a_time_deluxe",
+    "maximize_options",
+    "minimize_options",
+    "maximize_length",
+    "get_it_over_with",
+    "defensive",
+    "rusher",
+    "random_depth",
+];
+
+pub const TWIST_PLAYERS: &[&str] = &[
+    "random",
+    "do_nothing",
+    "do_something",
+    "score_move",
+    "worst_score_move",
+    "score_board",
+    "score_board_worst",
+    "maximize_length",
+];
+
+/// Players that wrap the player of another spec, in both games:
+/// - `diluted(player,p)` plays `player`'s move with probability `p`, and a random one otherwise
+pub const WRAPPERS: &[&str] = &["diluted"];
+
+/// A parsed player spec, a name with the specs in its parentheses. Numbers are specs without
+/// arguments, and `name:depth` is read as `name(depth)`.
+#[derive(Debug, PartialEq)]
+struct Spec<'a> {
+    name: &'a str,
+    args: Vec<Spec<'a>>,
+}
+
+impl<'a> Spec<'a> {
+    fn parse(spec: &'a str) -> Result<Self, String> {
+        match Self::parse_prefix(spec)? {
+            (parsed, "") => Ok(parsed),
+            (_, rest) => Err(format!(
+                "Unexpected '{}' at the end of player spec '{}'",
+                rest, spec
+            )),
+        }
+    }
+
+    // Parses a spec from the start of `input`, returning it with the rest of the input
+    fn parse_prefix(input: &'a str) -> Result<(Self, &'a str), String> {
+        let input = input.trim_start();
+        let end = input.find(['(', ')', ',', ':']).unwrap_or(input.len());
+        let (name, rest) = (input[..end].trim(), &input[end..]);
+
+        if name.is_empty() {
+            return Err(format!("Expected a player or a number at '{}'", input));
+        }
+
+        if let Some(depth) = rest.strip_prefix(':') {
+            let end = depth.find([')', ',']).unwrap_or(depth.len());
+            let depth = Spec {
+                name: depth[..end].trim(),
+                args: Vec::new(),
+            };
+
+            return Ok((
+                Spec {
+                    name,
+                    args: vec![depth],
+                },
+                &rest[1 + end..],
+            ));
+        }
+
+        let Some(mut rest) = rest.strip_prefix('(') else {
+            return Ok((
+                Spec {
+                    name,
+                    args: Vec::new(),
+                },
+                rest.trim_start(),
+            ));
+        };
+
+        let mut args = Vec::new();
+
+        loop {
+            let (arg, after) = Self::parse_prefix(rest)?;
+            args.push(arg);
+
+            if let Some(after) = after.strip_prefix(',') {
+                rest = after;
+            } else if let Some(after) = after.strip_prefix(')') {
+                return Ok((Spec { name, args }, after.trim_start()));
+            } else {
+                return Err(format!(
+                    "Expected ',' or ')' after '{}' in '{}'",
+                    name, input
+                ));
+            }
+        }
+    }
+
+    // The search depth of players that take one. Players that don't search ignore it.
+    fn depth(&self) -> Result<u8, String> {
+        match self.args.as_slice() {
+            [] => Ok(0),
+            [depth] if depth.args.is_empty() => depth
+                .name
+                .parse()
+                .map_err(|_| format!("Invalid search depth in player spec '{}'", self)),
+            _ => Err(format!("Expected at most a search depth in '{}'", self)),
+        }
+    }
+
+    fn number(&self) -> Result<f64, String> {
+        self.name
+            .parse()
+            .ok()
+            .filter(|_| self.args.is_empty())
+            .ok_or_else(|| format!("Expected a number, got '{}'", self))
+    }
+}
+
+impl fmt::Display for Spec<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+
+        if !self.args.is_empty() {
+            let args: Vec<_> = self.args.iter().map(Spec::to_string).collect();
+            write!(f, "({})", args.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Builds the wrapper named by `spec` around the player of its first argument, built by `build`
+fn wrapper<R: GameRules>(
+    spec: &Spec,
+    build: fn(&Spec) -> Result<BoxedPlayer<R>, String>,
+) -> Result<Option<BoxedPlayer<R>>, String> {
+    let player: BoxedPlayer<R> = match (spec.name, spec.args.as_slice()) {
+        ("diluted", [player, p]) => Box::new(DilutedPlayer(build(player)?, p.number()?)),
+        ("diluted", _) => {
+            return Err(format!(
+                "diluted takes a player and a probability, e.g. diluted(random,0.5), got '{}'",
+                spec
+            ))
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(player))
+}
+
+/// Builds a Struggle player from a spec such as `expectiminimax(2)`.
+pub fn struggle_player(spec: &str) -> Result<BoxedStrugglePlayer, String> {
+    if let Some(command) = spec.strip_prefix("external:") {
+        if command.trim().is_empty() {
+            return Err("An external player needs a command, e.g. external:./my-bot".to_string());
+        }
+
+        return Ok(Box::new(ExternalPlayer::from_command_line(command)));
+    }
+
+    build_struggle_player(&Spec::parse(spec)?)
+}
+
+fn build_struggle_player(spec: &Spec) -> Result<BoxedStrugglePlayer, String> {
+    if let Some(player) = wrapper(spec, build_struggle_player)? {
+        return Ok(player);
+    }
+
+    let (name, depth) = (spec.name, spec.depth()?);
+
+    let player: BoxedStrugglePlayer = match name {
+        "random" => Box::new(RandomPlayer),
+        "random_eater" => Box::new(RandomEaterPlayer),
+        "random_diet" => Box::new(RandomDietPlayer),
+        "score_move" => Box::new(ScoreMovePlayer),
+        "worst_score_move" => Box::new(WorstScoreMovePlayer),
+        "expectiminimax" => Box::new(expectiminimax(depth)),
+        "expectiminimax_basic" => Box::new(expectiminimax_mvp(depth)),
+        "worst_expectiminimax" => Box::new(worst_expectiminimax(depth)),
+        "participation_trophy" => Box::new(participation_trophy(depth)),
+        "one_at_a_time" => Box::new(one_at_a_time(depth)),
+        "one_at_a_time_deluxe" => Box::new(one_at_a_time_deluxe(depth)),
+        "maximize_options" => Box::new(maximize_options(depth)),
+        "minimize_options" => Box::new(minimize_options(depth)),
+        "maximize_length" => Box::new(maximize_length_expectiminimax(depth)),
+        "get_it_over_with" => Box::new(stateful_get_it_over_with(depth)),
+        "defensive" => Box::new(defensive_expectiminimax(depth)),
+        "rusher" => Box::new(rusher_expectiminimax(depth)),
+        "random_depth" => Box::new(random_depth_expectiminimax(depth)),
+        _ => {
+            return Err(format!(
+                "Unknown Struggle player '{}', expected one of: {}",
+                name,
+                [STRUGGLE_PLAYERS, WRAPPERS].concat().join(", ")
+            ))
+        }
+    };
+
+    Ok(player)
+}
+
+/// Builds a Twist player from a spec such as `score_board`.
+pub fn twist_player(spec: &str) -> Result<BoxedTwistPlayer, String> {
+    build_twist_player(&Spec::parse(spec)?)
+}
+
+fn build_twist_player(spec: &Spec) -> Result<BoxedTwistPlayer, String> {
+    if let Some(player) = wrapper(spec, build_twist_player)? {
+        return Ok(player);
+    }
+
+    let name = spec.name;
+    spec.depth()?;
+
+    let player: BoxedTwistPlayer = match name {
+        "random" => Box::new(TwistRandomPlayer),
+        "do_nothing" => Box::new(TwistDoNothingPlayer),
+        "do_something" => Box::new(TwistDoSomethingPlayer),
+        "score_move" => Box::new(TwistScoreMovePlayer),
+        "worst_score_move" => Box::new(TwistWorstScoreMovePlayer),
+        "score_board" => Box::new(TwistScoreBoardPlayer),
+        "score_board_worst" => Box::new(TwistScoreBoardPlayerWorst),
+        "maximize_length" => Box::new(TwistScoreBoardPlayerMaximizeLength),
+        _ => {
+            return Err(format!(
+                "Unknown Twist player '{}', expected one of: {}",
+                name,
+                [TWIST_PLAYERS, WRAPPERS].concat().join(", ")
+            ))
+        }
+    };
+
+    Ok(player)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::IntoGameStats,
+        games::{struggle::StruggleGame, twist::TwistGame},
+    };
+
+    #[test]
+    fn parses_every_listed_player() {
+        for name in STRUGGLE_PLAYERS {
+            assert!(struggle_player(&format!("{}:1", name)).is_ok(), "{}", name);
+        }
+
+        for name in TWIST_PLAYERS {
+            assert!(twist_player(name).is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_specs() {
+        assert!(struggle_player("nonexistent").is_err());
+        assert!(struggle_player("expectiminimax:deep").is_err());
+        assert!(twist_player("expectiminimax").is_err());
+        assert!(struggle_player("expectiminimax(1").is_err());
+        assert!(struggle_player("diluted(random)").is_err());
+        assert!(struggle_player("diluted(random,often)").is_err());
+        assert!(struggle_player("random)").is_err());
+    }
+
+    #[test]
+    fn parses_nested_specs() {
+        let spec = Spec::parse("diluted( expectiminimax:1 , 0.5)").unwrap();
+
+        assert_eq!(spec.to_string(), "diluted(expectiminimax(1),0.5)");
+        assert_eq!(spec.args[0].depth(), Ok(1));
+        assert_eq!(spec.args[1].number(), Ok(0.5));
+
+        let player = struggle_player("diluted(diluted(expectiminimax(1),0.5),0.9)").unwrap();
+        assert_eq!(player.name(), "Expectiminimax(1) 50% 90%");
+        assert!(twist_player("diluted(score_board,0.25)").is_ok());
+    }
+
+    #[test]
+    fn boxed_players_clone_and_play() {
+        let red = struggle_player("expectiminimax:1").unwrap();
+        let yellow = struggle_player("random").unwrap();
+        let (red, yellow) = (red.clone(), yellow.clone());
+
+        assert_eq!(red.name(), "Expectiminimax(1)");
+
+        let (_, game) = StruggleGame::builder()
+            .red(red)
+            .yellow(yellow)
+            .collect_stats(true)
+            .seed(1)
+            .play();
+        assert!(game.into_stats().unwrap().turns > 0);
+
+        let (_, game) = TwistGame::builder()
+            .red(twist_player("score_board").unwrap().clone())
+            .yellow(twist_player("random").unwrap().clone())
+            .collect_stats(true)
+            .seed(1)
+            .play();
+        assert!(game.into_stats().unwrap().turns > 0);
+    }
+}
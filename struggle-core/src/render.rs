@@ -0,0 +1,344 @@
+//! Board drawing that doesn't depend on a window. Boards are turned into a list of simple shapes,
+//! which can be drawn with macroquad by the visualizers, or written to SVG / PNG with plotters
+//! with the `plot` feature.
+
+use crate::{
+    games::{
+        struggle::{
+            board::{Board, BoardCell, HomeBase},
+            PlayerColor, COLORS,
+        },
+        twist::board::TwistBoard,
+    },
+    palette::{Glyph, Palette},
+};
+
+#[cfg(feature = "plot")]
+mod plot;
+#[cfg(feature = "plot")]
+pub use plot::{draw_shapes, save_png, to_plotters_color, to_svg};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const GRAY: Rgb = Rgb(130, 130, 130);
+
+    /// Parses `#rrggbb`, with or without the hash.
+    pub fn parse_hex(hex: &str) -> Option<Rgb> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Black or white, whichever is easier to read on top of this color.
+    pub fn contrasting(self) -> Rgb {
+        let luminance = 0.299 * self.0 as f32 + 0.587 * self.1 as f32 + 0.114 * self.2 as f32;
+
+        if luminance > 140.0 {
+            Rgb::BLACK
+        } else {
+            Rgb::WHITE
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Disc {
+        center: (f32, f32),
+        radius: f32,
+        color: Rgb,
+    },
+    Ring {
+        center: (f32, f32),
+        radius: f32,
+        thickness: f32,
+        color: Rgb,
+    },
+    Line {
+        from: (f32, f32),
+        to: (f32, f32),
+        thickness: f32,
+        color: Rgb,
+    },
+    // Position is the left end of the text baseline
+    Label {
+        position: (f32, f32),
+        text: String,
+        size: f32,
+        color: Rgb,
+    },
+}
+
+/// Where things are on a round board. All distances are given for a 1000x1000 board and multiplied
+/// by `scale`, so several boards can be drawn side by side.
+#[derive(Clone, Copy, Debug)]
+pub struct BoardLayout {
+    pub center: (f32, f32),
+    pub scale: f32,
+
+    tiles: usize,
+    tiles_per_side: usize,
+    // Rotates the board so that each player's side is in the same place in both games
+    tile_offset: usize,
+}
+
+impl BoardLayout {
+    pub const SIZE: f32 = 1000.0;
+    pub const OUTER_RADIUS: f32 = 500.0;
+    pub const INNER_RADIUS: f32 = 440.0;
+    pub const PIECE_RADIUS: f32 = 30.0;
+    pub const GOAL_SEPARATION: f32 = 70.0;
+    pub const HOME_BASE_DISTANCE: f32 = 45.0;
+    pub const HOME_BASE_PIECE_RADIUS: f32 = 8.0;
+
+    pub fn struggle(center: (f32, f32), scale: f32) -> Self {
+        Self {
+            center,
+            scale,
+            tiles: Board::TILES,
+            tiles_per_side: Board::TILES / 4,
+            tile_offset: 0,
+        }
+    }
+
+    pub fn twist(center: (f32, f32), scale: f32) -> Self {
+        Self {
+            center,
+            scale,
+            tiles: TwistBoard::TILES,
+            tiles_per_side: TwistBoard::TILES / 4,
+            tile_offset: TwistBoard::TILES / 4,
+        }
+    }
+
+    pub fn sector(&self) -> f32 {
+        (360.0 / self.tiles as f32).to_radians()
+    }
+
+    pub fn outer_radius(&self) -> f32 {
+        Self::OUTER_RADIUS * self.scale
+    }
+
+    pub fn piece_radius(&self) -> f32 {
+        Self::PIECE_RADIUS * self.scale
+    }
+
+    fn polar(&self, angle: f32, distance: f32) -> (f32, f32) {
+        let distance = distance * self.scale;
+
+        (
+            self.center.0 + distance * angle.cos(),
+            self.center.1 + distance * angle.sin(),
+        )
+    }
+
+    pub fn tile_position(&self, tile: u8) -> (f32, f32) {
+        let angle = (tile as usize + self.tile_offset) as f32 * self.sector();
+        self.polar(angle, Self::INNER_RADIUS)
+    }
+
+    // Goals and home bases are drawn halfway between the start tile and the tile before it
+    fn side_angle(&self, player: PlayerColor) -> f32 {
+        let start = player as usize * self.tiles_per_side + self.tile_offset;
+        start as f32 * self.sector() - self.sector() / 2.0
+    }
+
+    pub fn goal_position(&self, player: PlayerColor, slot: u8) -> (f32, f32) {
+        let distance = Self::INNER_RADIUS - ((slot + 1) as f32 * Self::GOAL_SEPARATION);
+        self.polar(self.side_angle(player), distance)
+    }
+
+    pub fn home_base_position(&self, player: PlayerColor, index: u8) -> (f32, f32) {
+        let angle = self.side_angle(player) + index as f32 * 5.0f32.to_radians();
+        self.polar(angle, Self::INNER_RADIUS + Self::HOME_BASE_DISTANCE)
+    }
+}
+
+pub trait RenderBoard {
+    fn layout(center: (f32, f32), scale: f32) -> BoardLayout;
+    fn shapes(&self, layout: &BoardLayout, palette: &Palette) -> Vec<Shape>;
+}
+
+impl RenderBoard for Board {
+    fn layout(center: (f32, f32), scale: f32) -> BoardLayout {
+        BoardLayout::struggle(center, scale)
+    }
+
+    fn shapes(&self, layout: &BoardLayout, palette: &Palette) -> Vec<Shape> {
+        let goals = [0, 1, 2, 3].map(|side| &self.goals[side][..]);
+        ring_shapes(layout, palette, &self.tiles, goals, &self.home_bases, 1)
+    }
+}
+
+impl RenderBoard for TwistBoard {
+    fn layout(center: (f32, f32), scale: f32) -> BoardLayout {
+        BoardLayout::twist(center, scale)
+    }
+
+    fn shapes(&self, layout: &BoardLayout, palette: &Palette) -> Vec<Shape> {
+        let goals = [0, 1, 2, 3].map(|side| &self.goals[side][..]);
+        // The goal entrance on the board counts as the first goal slot
+        ring_shapes(layout, palette, &self.tiles, goals, &self.home_bases, 2)
+    }
+}
+
+// A piece of the given player, with its glyph on top
+fn piece_shapes(
+    shapes: &mut Vec<Shape>,
+    palette: &Palette,
+    player: PlayerColor,
+    center: (f32, f32),
+    radius: f32,
+) {
+    let color = palette.player(player);
+
+    shapes.push(Shape::Disc {
+        center,
+        radius,
+        color,
+    });
+
+    let (x, y) = center;
+    let mark = color.contrasting();
+    let line = |from: (f32, f32), to: (f32, f32), thickness: f32| Shape::Line {
+        from: (x + from.0 * radius, y + from.1 * radius),
+        to: (x + to.0 * radius, y + to.1 * radius),
+        thickness: thickness * radius,
+        color: mark,
+    };
+
+    match palette.glyph(player) {
+        Glyph::None => {}
+        Glyph::Dot => shapes.push(Shape::Disc {
+            center,
+            radius: radius * 0.3,
+            color: mark,
+        }),
+        Glyph::Ring => shapes.push(Shape::Ring {
+            center,
+            radius: radius * 0.55,
+            thickness: radius * 0.15,
+            color: mark,
+        }),
+        Glyph::Cross => {
+            shapes.push(line((-0.5, -0.5), (0.5, 0.5), 0.15));
+            shapes.push(line((-0.5, 0.5), (0.5, -0.5), 0.15));
+        }
+        Glyph::Bar => shapes.push(line((-0.6, 0.0), (0.6, 0.0), 0.2)),
+    }
+}
+
+fn ring_shapes(
+    layout: &BoardLayout,
+    palette: &Palette,
+    tiles: &[BoardCell],
+    goals: [&[BoardCell]; 4],
+    home_bases: &[HomeBase; 4],
+    first_goal_label: usize,
+) -> Vec<Shape> {
+    let piece_radius = layout.piece_radius();
+    let line_width = 2.0 * layout.scale;
+
+    let mut shapes = vec![Shape::Disc {
+        center: layout.center,
+        radius: layout.outer_radius(),
+        color: palette.board,
+    }];
+
+    for (i, tile) in tiles.iter().enumerate() {
+        let center = layout.tile_position(i as u8);
+
+        let side = (i % layout.tiles_per_side == 0).then(|| COLORS[i / layout.tiles_per_side]);
+        let base_color = side.map_or(palette.empty_tile, |side| palette.player(side));
+
+        match tile {
+            None => shapes.push(Shape::Ring {
+                center,
+                radius: piece_radius,
+                thickness: line_width,
+                color: base_color,
+            }),
+            Some(player) => piece_shapes(&mut shapes, palette, *player, center, piece_radius),
+        }
+
+        let Some(side) = side else {
+            continue;
+        };
+
+        // goals
+        for (slot, cell) in goals[side as usize].iter().enumerate() {
+            let center = layout.goal_position(side, slot as u8);
+
+            match cell {
+                Some(_) => piece_shapes(&mut shapes, palette, side, center, piece_radius),
+                None => shapes.push(Shape::Ring {
+                    center,
+                    radius: piece_radius,
+                    thickness: line_width,
+                    color: base_color,
+                }),
+            }
+
+            shapes.push(Shape::Label {
+                position: center,
+                text: (slot + first_goal_label).to_string(),
+                size: 30.0 * layout.scale,
+                color: palette.label,
+            });
+        }
+
+        // home base
+        let home_base = &home_bases[side as usize];
+
+        for index in 0..4 {
+            let center = layout.home_base_position(side, index);
+            let radius = BoardLayout::HOME_BASE_PIECE_RADIUS * layout.scale;
+
+            shapes.push(if home_base.pieces_waiting > index {
+                Shape::Disc {
+                    center,
+                    radius,
+                    color: base_color,
+                }
+            } else {
+                Shape::Ring {
+                    center,
+                    radius,
+                    thickness: line_width,
+                    color: base_color,
+                }
+            });
+        }
+    }
+
+    shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn struggle_start_tiles_are_on_player_sides() {
+        let layout = BoardLayout::struggle((500.0, 500.0), 1.0);
+
+        // Red starts at angle 0, i.e. straight to the right of the center
+        let (x, y) = layout.tile_position(Board::RED_START);
+        assert!((x - 940.0).abs() < 0.01);
+        assert!((y - 500.0).abs() < 0.01);
+
+        // Yellow is on the opposite side
+        let (x, y) = layout.tile_position(Board::YELLOW_START);
+        assert!((x - 60.0).abs() < 0.01);
+        assert!((y - 500.0).abs() < 0.01);
+    }
+}
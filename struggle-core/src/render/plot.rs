@@ -0,0 +1,141 @@
+//! Drawing shapes with plotters, for SVG and PNG images of boards.
+
+use std::path::Path;
+
+use plotters::{
+    coord::Shift,
+    prelude::*,
+    style::text_anchor::{HPos, Pos, VPos},
+};
+
+use crate::palette::Palette;
+
+use super::{BoardLayout, RenderBoard, Rgb, Shape};
+
+pub fn to_plotters_color(color: Rgb) -> RGBColor {
+    RGBColor(color.0, color.1, color.2)
+}
+
+pub fn draw_shapes<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    shapes: &[Shape],
+) -> Result<(), DrawingAreaErrorKind<DB::ErrorType>> {
+    let point = |(x, y): (f32, f32)| (x.round() as i32, y.round() as i32);
+
+    for shape in shapes {
+        match shape {
+            Shape::Disc {
+                center,
+                radius,
+                color,
+            } => {
+                area.draw(&Circle::new(
+                    point(*center),
+                    radius.round() as i32,
+                    to_plotters_color(*color).filled(),
+                ))?;
+            }
+            Shape::Ring {
+                center,
+                radius,
+                thickness,
+                color,
+            } => {
+                area.draw(&Circle::new(
+                    point(*center),
+                    radius.round() as i32,
+                    to_plotters_color(*color).stroke_width(thickness.round().max(1.0) as u32),
+                ))?;
+            }
+            Shape::Line {
+                from,
+                to,
+                thickness,
+                color,
+            } => {
+                area.draw(&PathElement::new(
+                    vec![point(*from), point(*to)],
+                    to_plotters_color(*color).stroke_width(thickness.round().max(1.0) as u32),
+                ))?;
+            }
+            Shape::Label {
+                position,
+                text,
+                size,
+                color,
+            } => {
+                let style = ("sans-serif", *size as f64)
+                    .into_font()
+                    .color(&to_plotters_color(*color))
+                    .pos(Pos::new(HPos::Left, VPos::Bottom));
+
+                area.draw(&Text::new(text.clone(), point(*position), style))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn full_size_shapes<B: RenderBoard>(board: &B, palette: &Palette) -> Vec<Shape> {
+    let center = BoardLayout::SIZE / 2.0;
+    board.shapes(&B::layout((center, center), 1.0), palette)
+}
+
+const IMAGE_SIZE: (u32, u32) = (BoardLayout::SIZE as u32, BoardLayout::SIZE as u32);
+
+/// Renders a board as a standalone SVG document.
+pub fn to_svg<B: RenderBoard>(board: &B, palette: &Palette) -> String {
+    let mut svg = String::new();
+
+    {
+        let area = SVGBackend::with_string(&mut svg, IMAGE_SIZE).into_drawing_area();
+        area.fill(&BLACK).unwrap();
+        draw_shapes(&area, &full_size_shapes(board, palette)).unwrap();
+        area.present().unwrap();
+    }
+
+    svg
+}
+
+pub fn save_png<B: RenderBoard>(
+    board: &B,
+    palette: &Palette,
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let area = BitMapBackend::new(path, IMAGE_SIZE).into_drawing_area();
+    area.fill(&BLACK)?;
+    draw_shapes(&area, &full_size_shapes(board, palette))?;
+    area.present()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::games::struggle::{board::Board, PlayerColor};
+
+    use super::*;
+
+    #[test]
+    fn svg_contains_every_tile() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let svg = to_svg(&board, &Palette::CLASSIC);
+
+        // Background, tiles, goal slots and home base pieces
+        let circles = svg.matches("<circle").count();
+        assert_eq!(circles, 1 + Board::TILES + 4 * 4 + 4 * 4);
+    }
+
+    #[test]
+    fn png_is_written() {
+        let board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let path = std::env::temp_dir().join(format!("struggle-plot-{}.png", std::process::id()));
+
+        save_png(&board, &Palette::CLASSIC, &path).unwrap();
+        let png = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,483 @@
+//! Bounded experience replay buffers of encoded transitions, for players that learn online.
+//!
+//! `ReplayBuffer` samples uniformly, and `PrioritizedReplayBuffer` samples transitions in
+//! proportion to a priority, usually the size of their last TD error, as in prioritized
+//! experience replay. Both overwrite their oldest transitions once full.
+//!
+//! Buffers can be saved to and loaded from files, which start with a header of little-endian
+//! `u32`s after an 8-byte magic:
+//!
+//! | Bytes | Contents                                              |
+//! |-------|-------------------------------------------------------|
+//! | 8     | `STRGRPLY`                                            |
+//! | 4     | `FORMAT_VERSION`                                      |
+//! | 4     | `encoding::ENCODING_VERSION`                          |
+//! | 4     | Values in an encoded position                         |
+//! | 4     | Capacity of the buffer                                |
+//! | 4     | Number of transitions                                 |
+//!
+//! followed by the transitions from oldest to newest, each with its priority:
+//!
+//! | Bytes | Contents                                              |
+//! |-------|-------------------------------------------------------|
+//! | 4 × n | The encoded position as `f32`s                        |
+//! | 4     | The action, see `encoding::struggle_action`           |
+//! | 4     | The reward as an `f32`                                |
+//! | 4 × n | The encoded position after the action                 |
+//! | 1     | 1 if the game ended, 0 otherwise                      |
+//! | 8     | The master seed of the game's id, see `game::GameId`  |
+//! | 8     | The index of the game's id                            |
+//! | 8     | The priority as an `f64`, 1 in uniform buffers        |
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use rand::Rng;
+
+use crate::{encoding::ENCODING_VERSION, game::GameId};
+
+pub const FORMAT_VERSION: u32 = 2;
+
+const MAGIC: &[u8; 8] = b"STRGRPLY";
+
+/// A step of a game, with positions encoded from the mover's point of view, see `encoding`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub state: Vec<f32>,
+    pub action: u32,
+    pub reward: f32,
+    pub next_state: Vec<f32>,
+    pub done: bool,
+    /// The game the transition was played in, so that it can be replayed
+    pub game: GameId,
+}
+
+/// A ring of up to `capacity` transitions.
+#[derive(Clone, Debug)]
+struct Ring {
+    capacity: usize,
+    transitions: Vec<Transition>,
+    // Where the next transition goes once the ring is full
+    next: usize,
+}
+
+impl Ring {
+    fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "A replay buffer needs room for a transition");
+
+        Self {
+            capacity,
+            transitions: Vec::with_capacity(capacity),
+            next: 0,
+        }
+    }
+
+    /// Adds a transition and returns its slot.
+    fn push(&mut self, transition: Transition) -> usize {
+        if self.transitions.len() < self.capacity {
+            self.transitions.push(transition);
+            self.transitions.len() - 1
+        } else {
+            let slot = self.next;
+            self.transitions[slot] = transition;
+            self.next = (slot + 1) % self.capacity;
+            slot
+        }
+    }
+
+    /// Slots from the oldest transition to the newest.
+    fn slots(&self) -> impl Iterator<Item = usize> {
+        let len = self.transitions.len();
+        let start = if len < self.capacity { 0 } else { self.next };
+        (0..len).map(move |offset| (start + offset) % len)
+    }
+}
+
+pub struct ReplayBuffer {
+    ring: Ring,
+}
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ring: Ring::new(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ring.transitions.is_empty()
+    }
+
+    /// Adds a transition, replacing the oldest one if the buffer is full.
+    pub fn push(&mut self, transition: Transition) {
+        self.ring.push(transition);
+    }
+
+    /// `batch` transitions chosen uniformly with replacement, or none if the buffer is empty.
+    pub fn sample(&self, batch: usize, rng: &mut impl Rng) -> Vec<&Transition> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        (0..batch)
+            .map(|_| &self.ring.transitions[rng.gen_range(0..self.len())])
+            .collect()
+    }
+
+    /// The transitions from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Transition> {
+        self.ring.slots().map(|slot| &self.ring.transitions[slot])
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_buffer(path, &self.ring, |_| 1.0)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let (capacity, transitions) = read_buffer(path)?;
+        let mut buffer = Self::new(capacity);
+
+        for (transition, _) in transitions {
+            buffer.push(transition);
+        }
+
+        Ok(buffer)
+    }
+}
+
+/// A transition drawn from a `PrioritizedReplayBuffer`.
+pub struct PrioritizedSample<'a> {
+    /// Identifies the transition in `update_priority`
+    pub slot: usize,
+    pub transition: &'a Transition,
+    /// The importance-sampling weight that corrects for the bias of prioritized sampling,
+    /// normalized so that the largest possible weight is 1
+    pub weight: f64,
+}
+
+/// Proportional prioritized replay: a transition with priority `p` is sampled with probability
+/// `p^alpha / Σ p^alpha`.
+pub struct PrioritizedReplayBuffer {
+    ring: Ring,
+    /// How strongly the priorities skew the sampling, 0 is uniform
+    pub alpha: f64,
+    priorities: Vec<f64>,
+    // A sum tree of `p^alpha`, with the leaves at `leaves..2 * leaves`
+    tree: Vec<f64>,
+    leaves: usize,
+    max_priority: f64,
+}
+
+impl PrioritizedReplayBuffer {
+    pub fn new(capacity: usize, alpha: f64) -> Self {
+        let leaves = capacity.next_power_of_two();
+
+        Self {
+            ring: Ring::new(capacity),
+            alpha,
+            priorities: vec![0.0; capacity],
+            tree: vec![0.0; 2 * leaves],
+            leaves,
+            max_priority: 1.0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.ring.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ring.transitions.is_empty()
+    }
+
+    /// Adds a transition with the highest priority seen so far, so that it gets sampled at
+    /// least once before its priority is known.
+    pub fn push(&mut self, transition: Transition) {
+        let slot = self.ring.push(transition);
+        self.set_priority(slot, self.max_priority);
+    }
+
+    /// Sets the priority of a sampled transition, usually to `|td_error| + ε`.
+    pub fn update_priority(&mut self, slot: usize, priority: f64) {
+        assert!(priority > 0.0, "Priorities must be positive");
+
+        self.max_priority = self.max_priority.max(priority);
+        self.set_priority(slot, priority);
+    }
+
+    pub fn priority(&self, slot: usize) -> f64 {
+        self.priorities[slot]
+    }
+
+    fn set_priority(&mut self, slot: usize, priority: f64) {
+        self.priorities[slot] = priority;
+
+        let mut node = self.leaves + slot;
+        self.tree[node] = priority.powf(self.alpha);
+
+        while node > 1 {
+            node /= 2;
+            self.tree[node] = self.tree[2 * node] + self.tree[2 * node + 1];
+        }
+    }
+
+    // The slot whose range of the cumulative priorities contains `mass`
+    fn find(&self, mut mass: f64) -> usize {
+        let mut node = 1;
+
+        while node < self.leaves {
+            let left = 2 * node;
+
+            if mass < self.tree[left] || self.tree[left + 1] == 0.0 {
+                node = left;
+            } else {
+                mass -= self.tree[left];
+                node = left + 1;
+            }
+        }
+
+        (node - self.leaves).min(self.len() - 1)
+    }
+
+    /// `batch` transitions drawn in proportion to their priorities, one from each of `batch`
+    /// equal ranges of the total priority. `beta` sets how much of the sampling bias the
+    /// weights correct, from 0 to 1.
+    pub fn sample(
+        &self,
+        batch: usize,
+        beta: f64,
+        rng: &mut impl Rng,
+    ) -> Vec<PrioritizedSample<'_>> {
+        if self.is_empty() || batch == 0 {
+            return Vec::new();
+        }
+
+        let total = self.tree[1];
+        let segment = total / batch as f64;
+        let len = self.len() as f64;
+
+        let min_probability = self.tree[self.leaves..self.leaves + self.len()]
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
+            / total;
+        let max_weight = (len * min_probability).powf(-beta);
+
+        (0..batch)
+            .map(|index| {
+                let mass = segment * (index as f64 + rng.gen::<f64>());
+                let slot = self.find(mass.min(total));
+                let probability = self.tree[self.leaves + slot] / total;
+
+                PrioritizedSample {
+                    slot,
+                    transition: &self.ring.transitions[slot],
+                    weight: (len * probability).powf(-beta) / max_weight,
+                }
+            })
+            .collect()
+    }
+
+    /// The transitions from oldest to newest.
+    pub fn iter(&self) -> impl Iterator<Item = &Transition> {
+        self.ring.slots().map(|slot| &self.ring.transitions[slot])
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        write_buffer(path, &self.ring, |slot| self.priority(slot))
+    }
+
+    pub fn load(path: impl AsRef<Path>, alpha: f64) -> io::Result<Self> {
+        let (capacity, transitions) = read_buffer(path)?;
+        let mut buffer = Self::new(capacity, alpha);
+
+        for (transition, priority) in transitions {
+            buffer.push(transition);
+            buffer.update_priority(buffer.len() - 1, priority);
+        }
+
+        Ok(buffer)
+    }
+}
+
+fn write_buffer(
+    path: impl AsRef<Path>,
+    ring: &Ring,
+    priority: impl Fn(usize) -> f64,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let state_len = ring.transitions.first().map_or(0, |t| t.state.len());
+
+    out.write_all(MAGIC)?;
+
+    for value in [
+        FORMAT_VERSION,
+        ENCODING_VERSION,
+        state_len as u32,
+        ring.capacity as u32,
+        ring.transitions.len() as u32,
+    ] {
+        out.write_all(&value.to_le_bytes())?;
+    }
+
+    for slot in ring.slots() {
+        let transition = &ring.transitions[slot];
+
+        for value in &transition.state {
+            out.write_all(&value.to_le_bytes())?;
+        }
+        out.write_all(&transition.action.to_le_bytes())?;
+        out.write_all(&transition.reward.to_le_bytes())?;
+        for value in &transition.next_state {
+            out.write_all(&value.to_le_bytes())?;
+        }
+        out.write_all(&[transition.done as u8])?;
+        out.write_all(&transition.game.seed.to_le_bytes())?;
+        out.write_all(&transition.game.index.to_le_bytes())?;
+        out.write_all(&priority(slot).to_le_bytes())?;
+    }
+
+    out.flush()
+}
+
+fn read_u32(input: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    input.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_f32s(input: &mut impl Read, len: usize) -> io::Result<Vec<f32>> {
+    (0..len)
+        .map(|_| read_u32(input).map(f32::from_bits))
+        .collect()
+}
+
+fn read_buffer(path: impl AsRef<Path>) -> io::Result<(usize, Vec<(Transition, f64)>)> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut input = BufReader::new(File::open(path)?);
+
+    let mut magic = [0; 8];
+    input.read_exact(&mut magic)?;
+
+    if &magic != MAGIC {
+        return Err(invalid("Not a Struggle replay buffer".to_string()));
+    }
+
+    for (field, expected) in [("format", FORMAT_VERSION), ("encoding", ENCODING_VERSION)] {
+        let value = read_u32(&mut input)?;
+
+        if value != expected {
+            return Err(invalid(format!(
+                "Unsupported {} {}, expected {}",
+                field, value, expected
+            )));
+        }
+    }
+
+    let state_len = read_u32(&mut input)? as usize;
+    let capacity = read_u32(&mut input)? as usize;
+    let count = read_u32(&mut input)? as usize;
+
+    if capacity == 0 || count > capacity {
+        return Err(invalid(format!(
+            "{} transitions don't fit in a capacity of {}",
+            count, capacity
+        )));
+    }
+
+    let transitions = (0..count)
+        .map(|_| {
+            let state = read_f32s(&mut input, state_len)?;
+            let action = read_u32(&mut input)?;
+            let reward = f32::from_bits(read_u32(&mut input)?);
+            let next_state = read_f32s(&mut input, state_len)?;
+
+            let mut done = [0; 1];
+            input.read_exact(&mut done)?;
+
+            let mut game = [[0; 8]; 2];
+            input.read_exact(&mut game[0])?;
+            input.read_exact(&mut game[1])?;
+
+            let mut priority = [0; 8];
+            input.read_exact(&mut priority)?;
+
+            let transition = Transition {
+                state,
+                action,
+                reward,
+                next_state,
+                done: done[0] == 1,
+                game: GameId::new(u64::from_le_bytes(game[0]), u64::from_le_bytes(game[1])),
+            };
+
+            Ok((transition, f64::from_le_bytes(priority)))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    Ok((capacity, transitions))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    use super::*;
+
+    fn transition(action: u32) -> Transition {
+        Transition {
+            state: vec![action as f32, 0.5],
+            action,
+            reward: 1.0,
+            next_state: vec![0.0, action as f32],
+            done: action.is_multiple_of(2),
+            game: GameId::new(7, action as u64),
+        }
+    }
+
+    #[test]
+    fn prioritized_buffer_round_trips_and_prefers_high_priorities() {
+        let mut buffer = PrioritizedReplayBuffer::new(3, 1.0);
+
+        for action in 0..5 {
+            buffer.push(transition(action));
+        }
+
+        // The two oldest transitions were overwritten
+        let actions = buffer.iter().map(|t| t.action).collect::<Vec<_>>();
+        assert_eq!(actions, [2, 3, 4]);
+
+        let slot = (0..3)
+            .find(|slot| buffer.ring.transitions[*slot].action == 3)
+            .unwrap();
+        buffer.update_priority(slot, 1000.0);
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let samples = buffer.sample(100, 1.0, &mut rng);
+        let picked = samples.iter().filter(|s| s.transition.action == 3).count();
+        assert!(picked > 90);
+
+        let path = std::env::temp_dir().join("struggle-replay-test.bin");
+        buffer.save(&path).unwrap();
+        let loaded = PrioritizedReplayBuffer::load(&path, 1.0).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.iter().eq(buffer.iter()));
+        assert_eq!(loaded.capacity(), 3);
+    }
+}
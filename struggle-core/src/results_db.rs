@@ -0,0 +1,198 @@
+//! A SQLite database of match and game results that accumulates across runs.
+//!
+//! The schema is versioned with `PRAGMA user_version` and only ever extended, so that old
+//! databases keep working and queries written against them stay valid:
+//!
+//! ```sql
+//! SELECT red, yellow, SUM(games), SUM(red_wins) FROM matches GROUP BY red, yellow;
+//! ```
+
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use crate::{games::struggle::PlayerColor, json::color_name, records::GameRecord};
+
+const SCHEMA_VERSION: i32 = 1;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS matches (
+    id INTEGER PRIMARY KEY,
+    red TEXT NOT NULL,
+    yellow TEXT NOT NULL,
+    games INTEGER NOT NULL,
+    red_wins INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS games (
+    match_id INTEGER NOT NULL REFERENCES matches(id),
+    game INTEGER NOT NULL,
+    seed INTEGER NOT NULL,
+    winner TEXT NOT NULL,
+    turns INTEGER NOT NULL,
+    red_turns INTEGER NOT NULL,
+    yellow_turns INTEGER NOT NULL,
+    red_eats INTEGER NOT NULL,
+    yellow_eats INTEGER NOT NULL,
+    red_evaluations INTEGER NOT NULL,
+    yellow_evaluations INTEGER NOT NULL,
+    PRIMARY KEY (match_id, game)
+);
+
+CREATE INDEX IF NOT EXISTS matches_by_players ON matches (red, yellow);
+";
+
+/// Totals between two players over every match recorded, whichever color they played.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeadToHead {
+    pub games: u64,
+    pub a_wins: u64,
+}
+
+pub struct ResultsDb {
+    connection: Connection,
+}
+
+impl ResultsDb {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> rusqlite::Result<Self> {
+        let version: i32 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        connection.execute_batch(SCHEMA)?;
+
+        if version < SCHEMA_VERSION {
+            connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+        }
+
+        Ok(Self { connection })
+    }
+
+    /// Adds an empty match and returns its id. Games are added to it with `add_games`.
+    pub fn start_match(&mut self, red: &str, yellow: &str) -> rusqlite::Result<i64> {
+        self.connection.execute(
+            "INSERT INTO matches (red, yellow, games, red_wins) VALUES (?1, ?2, 0, 0)",
+            params![red, yellow],
+        )?;
+
+        Ok(self.connection.last_insert_rowid())
+    }
+
+    /// Stores games of a match and updates its totals, in one transaction.
+    pub fn add_games(&mut self, match_id: i64, games: &[GameRecord]) -> rusqlite::Result<()> {
+        let red_wins = games
+            .iter()
+            .filter(|game| game.winner == PlayerColor::Red)
+            .count();
+
+        let transaction = self.connection.transaction()?;
+
+        transaction.execute(
+            "UPDATE matches SET games = games + ?2, red_wins = red_wins + ?3 WHERE id = ?1",
+            params![match_id, games.len() as i64, red_wins as i64],
+        )?;
+
+        {
+            let mut insert = transaction.prepare(
+                "INSERT INTO games VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+
+            for game in games {
+                // SQLite integers are signed, large seeds wrap around but keep their bits
+                insert.execute(params![
+                    match_id,
+                    game.id.index as i64,
+                    game.id.game_seed() as i64,
+                    color_name(game.winner),
+                    game.turns,
+                    game.turns_per_player[0],
+                    game.turns_per_player[1],
+                    game.eats[0],
+                    game.eats[1],
+                    game.evaluations[0] as i64,
+                    game.evaluations[1] as i64,
+                ])?;
+            }
+        }
+
+        transaction.commit()
+    }
+
+    /// All recorded results between players `a` and `b`, by display name.
+    pub fn head_to_head(&self, a: &str, b: &str) -> rusqlite::Result<HeadToHead> {
+        // SUM over no rows is NULL
+        let (games, a_wins) = self.connection.query_row(
+            "SELECT
+                SUM(games),
+                SUM(CASE WHEN red = ?1 THEN red_wins ELSE games - red_wins END)
+            FROM matches
+            WHERE (red = ?1 AND yellow = ?2) OR (red = ?2 AND yellow = ?1)",
+            params![a, b],
+            |row| Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, Option<i64>>(1)?)),
+        )?;
+
+        Ok(HeadToHead {
+            games: games.unwrap_or(0) as u64,
+            a_wins: a_wins.unwrap_or(0) as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{GameId, NamedPlayer},
+        games::struggle::players::{RandomPlayer, ScoreMovePlayer},
+        records::play_recorded_game,
+    };
+
+    #[test]
+    fn head_to_head_spans_both_colors() {
+        let mut db = ResultsDb::open_in_memory().unwrap();
+        let (a, b) = (ScoreMovePlayer.name(), RandomPlayer.name());
+
+        let first: Vec<_> = (0..20)
+            .map(|game| {
+                play_recorded_game(&ScoreMovePlayer, &RandomPlayer, GameId::new(0, game), None)
+            })
+            .collect();
+        let second: Vec<_> = (0..10)
+            .map(|game| {
+                play_recorded_game(&RandomPlayer, &ScoreMovePlayer, GameId::new(0, game), None)
+            })
+            .collect();
+
+        let id = db.start_match(&a, &b).unwrap();
+        db.add_games(id, &first[..5]).unwrap();
+        db.add_games(id, &first[5..]).unwrap();
+
+        let id = db.start_match(&b, &a).unwrap();
+        db.add_games(id, &second).unwrap();
+
+        let a_wins = first
+            .iter()
+            .filter(|game| game.winner == PlayerColor::Red)
+            .count()
+            + second
+                .iter()
+                .filter(|game| game.winner == PlayerColor::Yellow)
+                .count();
+
+        let totals = db.head_to_head(&a, &b).unwrap();
+        assert_eq!(totals.games, 30);
+        assert_eq!(totals.a_wins, a_wins as u64);
+        assert_eq!(
+            db.head_to_head(&a, "Nobody").unwrap(),
+            HeadToHead::default()
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! How the strength and the cost of expectiminimax scale with its search depth.
+//!
+//! Every depth plays the same seeded games against a fixed baseline, and the study records the
+//! win rate with its 95% Wilson interval and the average time the searcher took per move, see
+//! `struggle-depth-study`.
+//!
+//! The cost of the search alone is measured by `search_cost`, on the same sampled positions for
+//! every depth and game phase, so that the effect of search improvements on the cost curve can be
+//! tracked from commit to commit, see `struggle-search-cost`.
+
+use std::{
+    fmt,
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+
+use crate::{
+    analysis::wilson_score,
+    game::GamePlayer,
+    games::struggle::{
+        board::{Board, MoveVec},
+        players::{expectiminimax, GameContext, GameTreePlayer, HeuristicFunction},
+        PlayerColor,
+    },
+    players::TimedPlayer,
+    registry::BoxedStrugglePlayer,
+    session::{run_match_seeded, MatchResult},
+};
+
+#[derive(Clone, Debug)]
+pub struct DepthPoint {
+    pub depth: u8,
+    /// The match of the searcher as A against the baseline
+    pub result: MatchResult,
+    /// The 95% Wilson interval of the searcher's win rate
+    pub interval: (f64, f64),
+    pub move_time: Duration,
+}
+
+impl DepthPoint {
+    pub fn win_rate(&self) -> f64 {
+        self.result.a_win_rate()
+    }
+}
+
+/// Plays `games` seeded games of `expectiminimax(depth)` against `baseline` for every depth in
+/// `depths`, calling `progress` after every depth.
+pub fn depth_scaling(
+    baseline: &BoxedStrugglePlayer,
+    depths: RangeInclusive<u8>,
+    games: u32,
+    seed: u64,
+    mut progress: impl FnMut(&DepthPoint),
+) -> Vec<DepthPoint> {
+    depths
+        .map(|depth| {
+            let searcher = TimedPlayer::new(expectiminimax(depth));
+            let boxed: BoxedStrugglePlayer = Box::new(searcher.clone());
+
+            let result = run_match_seeded(&boxed, baseline, games, seed, |_| {});
+            let point = DepthPoint {
+                depth,
+                result,
+                interval: wilson_score(result.a_win_rate(), result.games as u64),
+                move_time: searcher.average_move_time(),
+            };
+
+            progress(&point);
+            point
+        })
+        .collect()
+}
+
+/// How far a game has progressed, by where the pieces of both players are.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    /// Most pieces are still waiting at home
+    Opening,
+    Midgame,
+    /// Half of the pieces have reached their goals
+    Endgame,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Opening, Phase::Midgame, Phase::Endgame];
+
+    pub fn of(board: &Board) -> Phase {
+        let (a, b) = board.players();
+        let at_home = board.home_bases[a as usize].pieces_waiting
+            + board.home_bases[b as usize].pieces_waiting;
+        let in_goal = board.pieces_in_goal(a) + board.pieces_in_goal(b);
+
+        if in_goal >= 4 {
+            Phase::Endgame
+        } else if at_home >= 5 {
+            Phase::Opening
+        } else {
+            Phase::Midgame
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Phase::Opening => "opening",
+            Phase::Midgame => "midgame",
+            Phase::Endgame => "endgame",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// A position with a choice between moves, from random self-play.
+#[derive(Clone)]
+pub struct SearchPosition {
+    pub board: Board,
+    pub ctx: GameContext,
+    pub moves: MoveVec,
+    pub phase: Phase,
+}
+
+/// Plays random games until every phase has `per_phase` positions with more than one move.
+pub fn sample_positions(per_phase: usize, seed: u64) -> Vec<SearchPosition> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut positions = Vec::new();
+    let mut counts = [0; Phase::ALL.len()];
+
+    while counts.iter().any(|&count| count < per_phase) {
+        let mut board = Board::new(PlayerColor::Red, PlayerColor::Yellow);
+        let mut to_move = PlayerColor::Red;
+
+        while board.get_winner().is_none() {
+            let (a, b) = board.players();
+            let ctx = GameContext {
+                current_player: to_move,
+                other_player: if to_move == a { b } else { a },
+                dice: rng.gen_range(1..=6),
+            };
+            let moves = board.get_moves(ctx.dice, ctx.current_player, ctx.other_player);
+            let phase = Phase::of(&board);
+
+            if moves.len() > 1 && counts[phase as usize] < per_phase {
+                counts[phase as usize] += 1;
+                positions.push(SearchPosition {
+                    board: board.clone(),
+                    ctx,
+                    moves: moves.clone(),
+                    phase,
+                });
+            }
+
+            board.perform_move(to_move, &moves[rng.gen_range(0..moves.len())]);
+
+            if ctx.dice != 6 {
+                to_move = ctx.other_player;
+            }
+        }
+    }
+
+    positions
+}
+
+/// The cost of choosing a move at some depth in some phase.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostPoint {
+    pub depth: u8,
+    pub phase: Phase,
+    pub positions: usize,
+    pub micros_per_move: f64,
+    /// Heuristic evaluations per move
+    pub nodes_per_move: f64,
+}
+
+/// Times `select_move` of the searcher built by `searcher` for every depth in `depths`, over the
+/// positions of every phase. The moves are chosen one at a time on the calling thread, so that
+/// the times aren't skewed by other searches running at the same time.
+pub fn search_cost(
+    positions: &[SearchPosition],
+    depths: RangeInclusive<u8>,
+    searcher: impl Fn(u8) -> GameTreePlayer<HeuristicFunction>,
+) -> Vec<CostPoint> {
+    let mut points = Vec::new();
+
+    for depth in depths {
+        for phase in Phase::ALL {
+            let mut player = searcher(depth);
+            let mut rng = SmallRng::seed_from_u64(0);
+            let phase_positions = positions.iter().filter(|p| p.phase == phase);
+            let count = phase_positions.clone().count();
+
+            let start = Instant::now();
+
+            for position in phase_positions {
+                player.select_move(&position.ctx, &position.board, &position.moves, &mut rng);
+            }
+
+            let elapsed = start.elapsed();
+            let per_move = count.max(1) as f64;
+
+            points.push(CostPoint {
+                depth,
+                phase,
+                positions: count,
+                micros_per_move: elapsed.as_secs_f64() * 1e6 / per_move,
+                nodes_per_move: player.evaluations as f64 / per_move,
+            });
+        }
+    }
+
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::struggle_player;
+
+    #[test]
+    fn a_point_per_depth() {
+        let baseline = struggle_player("random").unwrap();
+        let points = depth_scaling(&baseline, 0..=1, 20, 1, |_| {});
+
+        assert_eq!(points.iter().map(|p| p.depth).collect::<Vec<_>>(), [0, 1]);
+
+        for point in &points {
+            assert_eq!(point.result.games, 20);
+            assert!(point.interval.0 <= point.win_rate() && point.win_rate() <= point.interval.1);
+            assert!(point.move_time > Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn deeper_searches_visit_more_nodes() {
+        let positions = sample_positions(5, 2);
+
+        for phase in Phase::ALL {
+            assert_eq!(positions.iter().filter(|p| p.phase == phase).count(), 5);
+        }
+
+        let points = search_cost(&positions, 0..=2, crate::games::struggle::distill::teacher);
+        assert_eq!(points.len(), 9);
+
+        for phase in Phase::ALL {
+            let nodes = points
+                .iter()
+                .filter(|p| p.phase == phase)
+                .map(|p| p.nodes_per_move)
+                .collect::<Vec<_>>();
+
+            assert!(nodes[0] < nodes[1] && nodes[1] < nodes[2]);
+        }
+    }
+}
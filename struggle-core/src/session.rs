@@ -0,0 +1,320 @@
+//! Interactive Struggle games driven one call at a time, for the network services.
+//!
+//! Unlike `play_game`, a session stops after every roll, so that a remote client can inspect the
+//! moves and pick one itself, or hand the decision to the seated AI player.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rand::{rngs::SmallRng, SeedableRng};
+use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    game::{game_seed, play_game_with_rng, NamedPlayer, RaceGame, ResetGame, TurnResult},
+    games::struggle::{
+        board::{Board, MoveVec, StruggleMove},
+        players::{GameContext, StrugglePlayer},
+        AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+    registry::{struggle_player, BoxedStrugglePlayer},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionError {
+    GameOver,
+    NotRolled,
+    InvalidMove(usize),
+    InvalidPlayer(String),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::GameOver => write!(f, "The game is already over"),
+            SessionError::NotRolled => write!(f, "Roll the die before moving"),
+            SessionError::InvalidMove(index) => write!(f, "No move with index {}", index),
+            SessionError::InvalidPlayer(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SessionError {}
+
+/// Red against Yellow, with Red moving first.
+pub struct GameSession {
+    game: StruggleGame<BoxedStrugglePlayer, BoxedStrugglePlayer>,
+    rng: SmallRng,
+    ctx: Option<GameContext>,
+    moves: MoveVec,
+    winner: Option<PlayerColor>,
+    turns: u32,
+    // Red, Yellow
+    names: [String; 2],
+}
+
+impl GameSession {
+    /// Creates a game between two player specs, see `registry`.
+    pub fn new(red: &str, yellow: &str, seed: Option<u64>) -> Result<Self, SessionError> {
+        let red = struggle_player(red).map_err(SessionError::InvalidPlayer)?;
+        let yellow = struggle_player(yellow).map_err(SessionError::InvalidPlayer)?;
+
+        let names = [red.name().into_owned(), yellow.name().into_owned()];
+
+        let rng = match seed {
+            Some(seed) => SmallRng::seed_from_u64(seed),
+            None => SmallRng::from_rng(rand::thread_rng()).unwrap(),
+        };
+
+        let session = Self {
+            game: StruggleGame::new(
+                AiStrugglePlayer::new(PlayerColor::Red, red),
+                AiStrugglePlayer::new(PlayerColor::Yellow, yellow),
+                false,
+            ),
+            rng,
+            ctx: None,
+            moves: MoveVec::new(),
+            winner: None,
+            turns: 0,
+            names,
+        };
+
+        #[cfg(feature = "metrics")]
+        crate::metrics::metrics().active_games.inc();
+
+        Ok(session)
+    }
+
+    pub fn board(&self) -> &Board {
+        self.game.board()
+    }
+
+    pub fn current_player(&self) -> PlayerColor {
+        self.game.current_player()
+    }
+
+    pub fn winner(&self) -> Option<PlayerColor> {
+        self.winner
+    }
+
+    pub fn turns(&self) -> u32 {
+        self.turns
+    }
+
+    pub fn player_name(&self, player: PlayerColor) -> &str {
+        match player {
+            PlayerColor::Red => &self.names[0],
+            _ => &self.names[1],
+        }
+    }
+
+    /// The latest roll, if the move for it hasn't been made yet.
+    pub fn dice(&self) -> Option<u8> {
+        self.ctx.as_ref().map(|ctx| ctx.dice)
+    }
+
+    /// The legal moves for the latest roll, or nothing before rolling.
+    pub fn moves(&self) -> &[StruggleMove] {
+        &self.moves
+    }
+
+    /// Rolls for the current player. Rolling again before moving rerolls.
+    pub fn roll(&mut self) -> Result<u8, SessionError> {
+        if self.winner.is_some() {
+            return Err(SessionError::GameOver);
+        }
+
+        let dice = self.game.throw_dice(&mut self.rng);
+        let ctx = self.game.create_turn_context(dice);
+        self.moves = self.game.get_moves(&ctx);
+        self.ctx = Some(ctx);
+
+        Ok(dice)
+    }
+
+    /// Applies one of the moves for the latest roll, by index.
+    pub fn apply(&mut self, index: usize) -> Result<(), SessionError> {
+        if self.winner.is_some() {
+            return Err(SessionError::GameOver);
+        }
+
+        let ctx = self.ctx.ok_or(SessionError::NotRolled)?;
+        let mov = self
+            .moves
+            .get(index)
+            .cloned()
+            .ok_or(SessionError::InvalidMove(index))?;
+
+        match self.game.apply_move(&ctx, &mov) {
+            TurnResult::PlayAgain => {}
+            TurnResult::PassTo(player) => self.game.set_current_player(player),
+            TurnResult::EndGame { winner } => {
+                self.winner = Some(winner);
+
+                #[cfg(feature = "metrics")]
+                crate::metrics::metrics().record_game(
+                    self.player_name(winner),
+                    self.player_name(self.game.other_player()),
+                );
+            }
+        }
+
+        self.ctx = None;
+        self.moves.clear();
+        self.turns += 1;
+
+        Ok(())
+    }
+
+    /// The index of the move the seated player would make for the latest roll.
+    pub fn seated_choice(&mut self) -> Result<usize, SessionError> {
+        let ctx = self.ctx.ok_or(SessionError::NotRolled)?;
+        let mov = self.game.select_move(&ctx, &self.moves, &mut self.rng);
+
+        Ok(self.moves.iter().position(|m| m == mov).unwrap())
+    }
+
+    /// The index of the move some other player would make for the latest roll.
+    pub fn suggestion(&mut self, player: &mut impl StrugglePlayer) -> Result<usize, SessionError> {
+        let ctx = self.ctx.ok_or(SessionError::NotRolled)?;
+        let mov = player.select_move(&ctx, self.game.board(), &self.moves, &mut self.rng);
+
+        Ok(self.moves.iter().position(|m| m == mov).unwrap())
+    }
+
+    /// Rolls if needed and lets the seated player move.
+    pub fn play_ai_turn(&mut self) -> Result<(), SessionError> {
+        if self.ctx.is_none() {
+            self.roll()?;
+        }
+
+        let index = self.seated_choice()?;
+        self.apply(index)
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for GameSession {
+    fn drop(&mut self) {
+        crate::metrics::metrics().active_games.dec();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MatchResult {
+    pub games: u32,
+    pub a_wins: u32,
+    /// Games abandoned at the turn cap, which neither player won
+    pub truncated: u32,
+}
+
+impl MatchResult {
+    pub fn b_wins(&self) -> u32 {
+        self.games - self.a_wins - self.truncated
+    }
+
+    /// The share of the games A won, with truncated games counting as draws: half a win each,
+    /// like in `analysis::elo_ratings`.
+    pub fn a_win_rate(&self) -> f64 {
+        (self.a_wins as f64 + self.truncated as f64 / 2.0) / self.games as f64
+    }
+}
+
+/// Plays `games` games in parallel, with `a` as Red. `progress` is called after every game
+/// with the results so far, from whichever thread finished it.
+pub fn run_match(
+    a: &BoxedStrugglePlayer,
+    b: &BoxedStrugglePlayer,
+    games: u32,
+    progress: impl Fn(MatchResult) + Sync,
+) -> MatchResult {
+    run_match_seeded(a, b, games, rand::random(), progress)
+}
+
+/// Like `run_match`, but game `n` is played with the seed `game_seed(seed, n)`, so that the result
+/// is the same on every run regardless of how the games are scheduled.
+pub fn run_match_seeded(
+    a: &BoxedStrugglePlayer,
+    b: &BoxedStrugglePlayer,
+    games: u32,
+    seed: u64,
+    progress: impl Fn(MatchResult) + Sync,
+) -> MatchResult {
+    let played = AtomicUsize::new(0);
+    let a_wins = AtomicUsize::new(0);
+
+    #[cfg(feature = "metrics")]
+    let names = (a.name(), b.name());
+    #[cfg(feature = "metrics")]
+    crate::metrics::metrics().queued_games.add(games as i64);
+
+    // Every rayon job builds its game once and resets it between games, instead of cloning the
+    // players for every game
+    let new_game = || {
+        StruggleGame::new(
+            AiStrugglePlayer::new(PlayerColor::Red, a.clone()),
+            AiStrugglePlayer::new(PlayerColor::Yellow, b.clone()),
+            false,
+        )
+    };
+
+    (0..games)
+        .into_par_iter()
+        .for_each_init(new_game, |game, index| {
+            let mut rng = SmallRng::seed_from_u64(game_seed(seed, index as u64));
+            game.reset();
+
+            let won = play_game_with_rng(game, &mut rng) == PlayerColor::Red;
+
+            #[cfg(feature = "metrics")]
+            {
+                let metrics = crate::metrics::metrics();
+                let (winner, loser) = if won {
+                    (&names.0, &names.1)
+                } else {
+                    (&names.1, &names.0)
+                };
+
+                metrics.record_game(winner, loser);
+                metrics.queued_games.dec();
+            }
+            let a_wins = a_wins.fetch_add(won as usize, Ordering::Relaxed) + won as usize;
+            let games = played.fetch_add(1, Ordering::Relaxed) + 1;
+
+            progress(MatchResult {
+                games: games as u32,
+                a_wins: a_wins as u32,
+                truncated: 0,
+            });
+        });
+
+    MatchResult {
+        games,
+        a_wins: a_wins.into_inner() as u32,
+        truncated: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_a_seeded_game_to_the_end() {
+        let mut session = GameSession::new("random", "score_move", Some(7)).unwrap();
+
+        assert_eq!(session.apply(0), Err(SessionError::NotRolled));
+
+        while session.winner().is_none() {
+            session.play_ai_turn().unwrap();
+        }
+
+        assert_eq!(session.roll(), Err(SessionError::GameOver));
+        assert!(session.turns() > 0);
+    }
+}
@@ -0,0 +1,248 @@
+//! Hyperparameter sweeps over Struggle players.
+//!
+//! A sweep builds a player for every configuration of a search space, and ranks them by their
+//! mean win rate against a gauntlet of fixed opponents. The parameters are:
+//!
+//! | Name                      | Meaning                                                        |
+//! |---------------------------|----------------------------------------------------------------|
+//! | `depth`                   | Search depth of the expectiminimax player, 1 by default        |
+//! | `strength`                | Chance of playing the searched move instead of a random one    |
+//! | any of `HeuristicWeights` | Overrides that term of `default_heuristic`                     |
+//!
+//! A dimension is written as `name=1,2,3` for a list of values or `name=300..800` for a range,
+//! which is sampled uniformly in random search and split into `steps` values in grid search.
+//!
+//! There's no MCTS player in the tree yet, so there's no iteration count to sweep either.
+
+use std::fmt;
+
+use rand::Rng;
+
+use crate::{
+    analysis::wilson_score,
+    games::struggle::players::{weighted_expectiminimax, HeuristicWeights},
+    players::DilutedPlayer,
+    registry::BoxedStrugglePlayer,
+    session::run_match,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Values {
+    List(Vec<f64>),
+    Range(f64, f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dimension {
+    pub name: String,
+    pub values: Values,
+}
+
+impl Dimension {
+    /// Parses `name=1,2,3` or `name=300..800`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let (name, values) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("Expected name=values, got {}", spec))?;
+
+        if !is_parameter(name) {
+            return Err(format!("Unknown parameter: {}", name));
+        }
+
+        let number = |value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid value for {}: {}", name, value))
+        };
+
+        let values = match values.split_once("..") {
+            Some((low, high)) => Values::Range(number(low)?, number(high)?),
+            None => Values::List(values.split(',').map(number).collect::<Result<_, _>>()?),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            values,
+        })
+    }
+
+    /// The values to try in a grid search. Ranges are split into `steps` evenly spaced values.
+    pub fn grid_values(&self, steps: usize) -> Vec<f64> {
+        match &self.values {
+            Values::List(values) => values.clone(),
+            Values::Range(low, high) if steps <= 1 => vec![(low + high) / 2.0],
+            Values::Range(low, high) => (0..steps)
+                .map(|step| low + (high - low) * step as f64 / (steps - 1) as f64)
+                .collect(),
+        }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> f64 {
+        match &self.values {
+            Values::List(values) => values[rng.gen_range(0..values.len())],
+            Values::Range(low, high) => rng.gen_range(*low..=*high),
+        }
+    }
+}
+
+fn is_parameter(name: &str) -> bool {
+    matches!(name, "depth" | "strength") || HeuristicWeights::NAMES.contains(&name)
+}
+
+/// A value for every dimension of a search space, in the same order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Configuration(pub Vec<(String, f64)>);
+
+impl Configuration {
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.0
+            .iter()
+            .find(|(parameter, _)| parameter == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// An expectiminimax player with these parameters.
+    pub fn player(&self) -> BoxedStrugglePlayer {
+        let mut weights = HeuristicWeights::DEFAULT;
+
+        for (name, value) in &self.0 {
+            if let Some(weight) = weights.get_mut(name) {
+                *weight = *value;
+            }
+        }
+
+        let depth = self.get("depth").unwrap_or(1.0).round() as u8;
+        let player = weighted_expectiminimax(depth, weights);
+
+        match self.get("strength") {
+            Some(strength) => Box::new(DilutedPlayer(player, strength)),
+            None => Box::new(player),
+        }
+    }
+}
+
+impl fmt::Display for Configuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SearchSpace {
+    pub dimensions: Vec<Dimension>,
+}
+
+impl SearchSpace {
+    /// Every combination of the dimensions' grid values.
+    pub fn grid(&self, steps: usize) -> Vec<Configuration> {
+        let mut configurations = vec![Configuration(Vec::new())];
+
+        for dimension in &self.dimensions {
+            configurations = configurations
+                .into_iter()
+                .flat_map(|configuration| {
+                    dimension.grid_values(steps).into_iter().map(move |value| {
+                        let mut values = configuration.0.clone();
+                        values.push((dimension.name.clone(), value));
+                        Configuration(values)
+                    })
+                })
+                .collect();
+        }
+
+        configurations
+    }
+
+    /// `count` configurations with independently sampled values.
+    pub fn random(&self, count: usize, rng: &mut impl Rng) -> Vec<Configuration> {
+        (0..count)
+            .map(|_| {
+                Configuration(
+                    self.dimensions
+                        .iter()
+                        .map(|dimension| (dimension.name.clone(), dimension.sample(rng)))
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SweepResult {
+    pub configuration: Configuration,
+    /// Win rate against every opponent of the gauntlet, in order
+    pub win_rates: Vec<f64>,
+    /// Mean of `win_rates`
+    pub win_rate: f64,
+    /// 95% confidence interval of `win_rate`
+    pub interval: (f64, f64),
+}
+
+/// Plays `games` games of every configuration against every opponent, and returns the results
+/// from best to worst. `progress` is called after every finished match.
+pub fn run_sweep(
+    configurations: Vec<Configuration>,
+    gauntlet: &[BoxedStrugglePlayer],
+    games: u32,
+    progress: impl Fn(),
+) -> Vec<SweepResult> {
+    let mut results = configurations
+        .into_iter()
+        .map(|configuration| {
+            let player = configuration.player();
+
+            let win_rates = gauntlet
+                .iter()
+                .map(|opponent| {
+                    let result = run_match(&player, opponent, games, |_| {});
+                    progress();
+                    result.a_win_rate()
+                })
+                .collect::<Vec<_>>();
+
+            let win_rate = win_rates.iter().sum::<f64>() / win_rates.len().max(1) as f64;
+            let samples = games as u64 * gauntlet.len() as u64;
+
+            SweepResult {
+                configuration,
+                win_rates,
+                win_rate,
+                interval: wilson_score(win_rate, samples),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    results.sort_by(|a, b| b.win_rate.total_cmp(&a.win_rate));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_covers_every_combination() {
+        let space = SearchSpace {
+            dimensions: vec![
+                Dimension::parse("depth=0,1").unwrap(),
+                Dimension::parse("base_piece_score=300..800").unwrap(),
+            ],
+        };
+
+        let grid = space.grid(3);
+
+        assert_eq!(grid.len(), 6);
+        assert_eq!(grid[0].to_string(), "depth=0 base_piece_score=300");
+        assert_eq!(grid[5].to_string(), "depth=1 base_piece_score=800");
+        assert!(Dimension::parse("iterations=100").is_err());
+    }
+}
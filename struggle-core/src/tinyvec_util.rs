@@ -1,3 +1,5 @@
+use alloc::vec::Vec;
+
 use tinyvec::{Array, TinyVec};
 
 pub trait TinyVecExt {
@@ -0,0 +1,547 @@
+//! Round-robin tournaments between players picked at runtime.
+//!
+//! Every pair of players plays the same number of games, taking turns moving first. The games of
+//! all matchups are interleaved, so that they all progress at the same pace, and the results can
+//! be read while the tournament is still running, e.g. by `dashboard`.
+//!
+//! Game `n` of the interleaved order is played with the seed `game_seed(seed, n)`, so a
+//! tournament with the same players and seed has the same results however the threads are
+//! scheduled.
+//!
+//! With a turn cap, games that run longer are abandoned and count as draws, so that a few
+//! pathological games can't dominate the running time. `Tournament::truncated` reports how many.
+//!
+//! `versus_field` instead plays one candidate against a weighted pool of opponents, for a single
+//! score to tune players against.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use rand::{
+    distributions::{Distribution, WeightedIndex},
+    rngs::SmallRng,
+    SeedableRng,
+};
+use rayon::prelude::*;
+
+use crate::{
+    analysis::{elo_ratings, nash_mixture, win_rate_matrix, EloAnchor},
+    game::{
+        game_seed, play_game_with_rng, play_game_with_turn_cap, NamedPlayer, PlayerConfig,
+        ResetGame,
+    },
+    games::struggle::{AiStrugglePlayer, PlayerColor, StruggleGame},
+    registry::BoxedStrugglePlayer,
+    session::MatchResult,
+};
+
+type TournamentGame = StruggleGame<BoxedStrugglePlayer, BoxedStrugglePlayer>;
+
+/// Games between two players, by their index in the tournament.
+pub struct Matchup {
+    pub a: usize,
+    pub b: usize,
+    total: u32,
+    games: AtomicU32,
+    a_wins: AtomicU32,
+    truncated: AtomicU32,
+    // When the first game started and the last one ended
+    started: OnceLock<Instant>,
+    finished: OnceLock<Instant>,
+}
+
+impl Matchup {
+    fn new(a: usize, b: usize, total: u32) -> Self {
+        Self {
+            a,
+            b,
+            total,
+            games: AtomicU32::new(0),
+            a_wins: AtomicU32::new(0),
+            truncated: AtomicU32::new(0),
+            started: OnceLock::new(),
+            finished: OnceLock::new(),
+        }
+    }
+
+    // A game with `a` as Red if `a_first`, otherwise as Yellow
+    fn new_game(&self, players: &[BoxedStrugglePlayer], a_first: bool) -> TournamentGame {
+        let (a, b) = (players[self.a].clone(), players[self.b].clone());
+        let (red, yellow) = if a_first { (a, b) } else { (b, a) };
+
+        StruggleGame::new(
+            AiStrugglePlayer::new(PlayerColor::Red, red),
+            AiStrugglePlayer::new(PlayerColor::Yellow, yellow),
+            false,
+        )
+    }
+
+    fn play(
+        &self,
+        game: &mut TournamentGame,
+        a_first: bool,
+        max_turns: Option<u32>,
+        rng: &mut SmallRng,
+    ) {
+        self.started.get_or_init(Instant::now);
+
+        let a_color = if a_first {
+            PlayerColor::Red
+        } else {
+            PlayerColor::Yellow
+        };
+
+        game.reset();
+
+        match play_game_with_turn_cap(game, rng, max_turns) {
+            Some(winner) => {
+                let won = winner == a_color;
+                self.a_wins.fetch_add(won as u32, Ordering::Relaxed);
+            }
+            None => {
+                self.truncated.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        if self.games.fetch_add(1, Ordering::Relaxed) + 1 == self.total {
+            let _ = self.finished.set(Instant::now());
+        }
+    }
+
+    pub fn total_games(&self) -> u32 {
+        self.total
+    }
+
+    /// The results so far.
+    pub fn result(&self) -> MatchResult {
+        MatchResult {
+            games: self.games.load(Ordering::Relaxed),
+            a_wins: self.a_wins.load(Ordering::Relaxed),
+            truncated: self.truncated.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.result().games == self.total
+    }
+
+    pub fn games_per_second(&self) -> f64 {
+        let Some(started) = self.started.get() else {
+            return 0.0;
+        };
+
+        let end = self.finished.get().copied().unwrap_or_else(Instant::now);
+        self.result().games as f64 / (end - *started).as_secs_f64()
+    }
+
+    /// The time left at the current pace, if any games have been played.
+    pub fn eta(&self) -> Option<Duration> {
+        let remaining = self.total - self.result().games;
+        let rate = self.games_per_second();
+
+        (rate > 0.0).then(|| Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+/// A player's results over all of their matchups.
+#[derive(Clone, Copy, Debug)]
+pub struct Standing {
+    pub player: usize,
+    pub games: u32,
+    pub wins: u32,
+    /// Games abandoned at the turn cap
+    pub draws: u32,
+}
+
+impl Standing {
+    /// The share of the games the player won, with draws counting as half a win.
+    pub fn win_rate(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games as f64
+    }
+}
+
+pub struct Tournament {
+    players: Vec<BoxedStrugglePlayer>,
+    names: Vec<String>,
+    matchups: Vec<Matchup>,
+    seed: u64,
+    max_turns: Option<u32>,
+    started: OnceLock<Instant>,
+    cancelled: AtomicBool,
+}
+
+impl Tournament {
+    /// A tournament where every pair of `players` plays `games_per_matchup` games, with a
+    /// random seed.
+    pub fn new(players: Vec<BoxedStrugglePlayer>, games_per_matchup: u32) -> Self {
+        let names = players
+            .iter()
+            .map(|player| player.name().into_owned())
+            .collect();
+
+        let mut matchups = Vec::new();
+
+        for a in 0..players.len() {
+            for b in (a + 1)..players.len() {
+                matchups.push(Matchup::new(a, b, games_per_matchup));
+            }
+        }
+
+        Self {
+            players,
+            names,
+            matchups,
+            seed: rand::random(),
+            max_turns: None,
+            started: OnceLock::new(),
+            cancelled: AtomicBool::new(false),
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The master seed that the seeds of the games are derived from.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Abandons games after `max_turns` turns, counting them as draws.
+    pub fn with_max_turns(mut self, max_turns: u32) -> Self {
+        self.max_turns = Some(max_turns);
+        self
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The full configurations of the players, in the same order as `names`.
+    pub fn player_configs(&self) -> Vec<PlayerConfig> {
+        self.players
+            .iter()
+            .map(|player| player.describe())
+            .collect()
+    }
+
+    pub fn matchups(&self) -> &[Matchup] {
+        &self.matchups
+    }
+
+    /// Plays all of the games in parallel, returning when they're done or the tournament is
+    /// cancelled.
+    pub fn run(&self) {
+        self.started.get_or_init(Instant::now);
+
+        let matchups = self.matchups.len() as u64;
+        let games = matchups * self.matchups.first().map_or(0, |m| m.total) as u64;
+
+        // Every rayon job keeps a game per matchup and side, and resets it between games,
+        // instead of cloning the players for every game
+        let games_per_job = || -> Vec<[Option<TournamentGame>; 2]> {
+            (0..matchups).map(|_| [None, None]).collect()
+        };
+
+        (0..games)
+            .into_par_iter()
+            .for_each_init(games_per_job, |job_games, index| {
+                if self.cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let slot = (index % matchups) as usize;
+                let matchup = &self.matchups[slot];
+                let a_first = (index / matchups).is_multiple_of(2);
+
+                let game = job_games[slot][a_first as usize]
+                    .get_or_insert_with(|| matchup.new_game(&self.players, a_first));
+
+                let mut rng = SmallRng::seed_from_u64(game_seed(self.seed, index));
+                matchup.play(game, a_first, self.max_turns, &mut rng);
+            });
+    }
+
+    /// Stops the tournament after the games in progress.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.matchups.iter().all(Matchup::is_finished)
+    }
+
+    pub fn games_played(&self) -> u32 {
+        self.matchups.iter().map(|m| m.result().games).sum()
+    }
+
+    pub fn total_games(&self) -> u32 {
+        self.matchups.iter().map(|m| m.total).sum()
+    }
+
+    /// Games abandoned at the turn cap so far.
+    pub fn truncated(&self) -> u32 {
+        self.matchups.iter().map(|m| m.result().truncated).sum()
+    }
+
+    /// Time since the tournament started running.
+    pub fn elapsed(&self) -> Duration {
+        self.started
+            .get()
+            .map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    /// The Elo ratings of the players from the results so far, in the same order as `names`, see
+    /// `analysis::elo_ratings`.
+    pub fn ratings(&self, anchor: Option<EloAnchor>) -> Vec<f64> {
+        elo_ratings(self.players.len(), &self.results(), anchor)
+    }
+
+    /// The weights of the players in the Nash equilibrium of picking a player, from the results
+    /// so far, in the same order as `names`, see `analysis::nash_mixture`.
+    pub fn nash_mixture(&self) -> Vec<f64> {
+        nash_mixture(&win_rate_matrix(self.players.len(), &self.results()))
+    }
+
+    fn results(&self) -> Vec<(usize, usize, MatchResult)> {
+        self.matchups
+            .iter()
+            .map(|matchup| (matchup.a, matchup.b, matchup.result()))
+            .collect()
+    }
+
+    /// The players by overall win rate, best first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings = (0..self.players.len())
+            .map(|player| Standing {
+                player,
+                games: 0,
+                wins: 0,
+                draws: 0,
+            })
+            .collect::<Vec<_>>();
+
+        for matchup in &self.matchups {
+            let result = matchup.result();
+
+            standings[matchup.a].games += result.games;
+            standings[matchup.a].wins += result.a_wins;
+            standings[matchup.a].draws += result.truncated;
+            standings[matchup.b].games += result.games;
+            standings[matchup.b].wins += result.b_wins();
+            standings[matchup.b].draws += result.truncated;
+        }
+
+        standings.sort_by(|a, b| b.win_rate().total_cmp(&a.win_rate()));
+        standings
+    }
+}
+
+/// The results of `versus_field`, from the candidate's point of view.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldResult {
+    /// The candidate's results against every opponent of the field, in the same order. The
+    /// candidate is player `a`.
+    pub opponents: Vec<MatchResult>,
+    /// The candidate's win rates against the opponents, weighted by the opponents' weights.
+    /// Opponents that were never drawn are left out.
+    pub score: f64,
+}
+
+impl FieldResult {
+    pub fn games(&self) -> u32 {
+        self.opponents.iter().map(|result| result.games).sum()
+    }
+
+    pub fn wins(&self) -> u32 {
+        self.opponents.iter().map(|result| result.a_wins).sum()
+    }
+}
+
+/// Plays `rounds` games between `candidate` and opponents drawn from `field` for every game, in
+/// proportion to their weights. Unlike a match against a single opponent, the score doesn't
+/// reward exploiting the quirks of one player, which makes it a better fitness function for
+/// tuning.
+pub fn versus_field(
+    candidate: &BoxedStrugglePlayer,
+    field: &[(BoxedStrugglePlayer, f64)],
+    rounds: u32,
+) -> FieldResult {
+    versus_field_seeded(candidate, field, rounds, rand::random())
+}
+
+/// Like `versus_field`, but game `n` is played (and its opponent drawn) with the seed
+/// `game_seed(seed, n)`.
+pub fn versus_field_seeded(
+    candidate: &BoxedStrugglePlayer,
+    field: &[(BoxedStrugglePlayer, f64)],
+    rounds: u32,
+    seed: u64,
+) -> FieldResult {
+    let weights = WeightedIndex::new(field.iter().map(|(_, weight)| *weight))
+        .expect("The field needs an opponent with a positive weight");
+
+    let no_results = || {
+        vec![
+            MatchResult {
+                games: 0,
+                a_wins: 0,
+                truncated: 0,
+            };
+            field.len()
+        ]
+    };
+
+    // Like in `Tournament::run`, every rayon job keeps a game per opponent and side
+    let games_per_job =
+        || -> Vec<[Option<TournamentGame>; 2]> { field.iter().map(|_| [None, None]).collect() };
+
+    let opponents = (0..rounds as u64)
+        .into_par_iter()
+        .fold(
+            || (games_per_job(), no_results()),
+            |(mut games, mut results), round| {
+                let mut rng = SmallRng::seed_from_u64(game_seed(seed, round));
+                let opponent = weights.sample(&mut rng);
+                let candidate_first = round % 2 == 0;
+
+                let game = games[opponent][candidate_first as usize].get_or_insert_with(|| {
+                    let (a, b) = (candidate.clone(), field[opponent].0.clone());
+                    let (red, yellow) = if candidate_first { (a, b) } else { (b, a) };
+
+                    StruggleGame::new(
+                        AiStrugglePlayer::new(PlayerColor::Red, red),
+                        AiStrugglePlayer::new(PlayerColor::Yellow, yellow),
+                        false,
+                    )
+                });
+
+                let candidate_color = if candidate_first {
+                    PlayerColor::Red
+                } else {
+                    PlayerColor::Yellow
+                };
+
+                game.reset();
+                let won = play_game_with_rng(game, &mut rng) == candidate_color;
+
+                results[opponent].games += 1;
+                results[opponent].a_wins += won as u32;
+
+                (games, results)
+            },
+        )
+        .map(|(_, results)| results)
+        .reduce(no_results, |mut a, b| {
+            for (a, b) in a.iter_mut().zip(b) {
+                a.games += b.games;
+                a.a_wins += b.a_wins;
+            }
+            a
+        });
+
+    let (weighted, total_weight) = opponents
+        .iter()
+        .zip(field)
+        .filter(|(result, _)| result.games > 0)
+        .fold((0.0, 0.0), |(weighted, total), (result, (_, weight))| {
+            (weighted + result.a_win_rate() * weight, total + weight)
+        });
+
+    FieldResult {
+        opponents,
+        score: weighted / total_weight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::struggle_player;
+
+    use super::*;
+
+    #[test]
+    fn every_pair_plays_every_game() {
+        let players = ["random", "score_move", "random_eater"]
+            .into_iter()
+            .map(|spec| struggle_player(spec).unwrap())
+            .collect();
+
+        let tournament = Tournament::new(players, 10);
+        tournament.run();
+
+        assert!(tournament.is_finished());
+        assert_eq!(tournament.matchups().len(), 3);
+        assert_eq!(tournament.games_played(), 30);
+
+        let standings = tournament.standings();
+        assert!(standings.iter().all(|standing| standing.games == 20));
+        assert_eq!(standings.iter().map(|s| s.wins).sum::<u32>(), 30);
+    }
+
+    #[test]
+    fn truncated_games_count_as_draws() {
+        let players = ["random", "score_move"]
+            .into_iter()
+            .map(|spec| struggle_player(spec).unwrap())
+            .collect();
+
+        // Nobody can get four pieces into their goal in five turns
+        let tournament = Tournament::new(players, 10).with_seed(3).with_max_turns(5);
+        tournament.run();
+
+        let result = tournament.matchups()[0].result();
+        assert_eq!(result.truncated, 10);
+        assert_eq!(result.a_win_rate(), 0.5);
+
+        for standing in tournament.standings() {
+            assert_eq!((standing.wins, standing.draws), (0, 10));
+            assert_eq!(standing.win_rate(), 0.5);
+        }
+
+        let half_drawn = MatchResult {
+            games: 4,
+            a_wins: 1,
+            truncated: 2,
+        };
+        assert_eq!(half_drawn.a_win_rate(), 0.5);
+    }
+
+    #[test]
+    fn seeded_tournaments_are_reproducible() {
+        let results = || {
+            let players = ["random", "score_move"]
+                .into_iter()
+                .map(|spec| struggle_player(spec).unwrap())
+                .collect();
+
+            let tournament = Tournament::new(players, 200).with_seed(11);
+            tournament.run();
+            tournament.matchups()[0].result()
+        };
+
+        assert_eq!(results().a_wins, results().a_wins);
+    }
+
+    #[test]
+    fn versus_field_samples_by_weight() {
+        let player = |spec| struggle_player(spec).unwrap();
+        let field = [(player("random"), 3.0), (player("score_move"), 1.0)];
+
+        let result = versus_field_seeded(&player("score_move"), &field, 400, 5);
+
+        assert_eq!(result.games(), 400);
+        assert!(result.opponents[0].games > result.opponents[1].games);
+        assert!((0.0..=1.0).contains(&result.score));
+        assert_eq!(
+            result,
+            versus_field_seeded(&player("score_move"), &field, 400, 5)
+        );
+    }
+}
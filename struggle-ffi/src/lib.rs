@@ -0,0 +1,5 @@
+//! The C API of `struggle_core::ffi`, built as `libstruggle.so` / `struggle.dll` and
+//! `libstruggle.a`. It's a crate of its own so that `struggle-core` stays an rlib, which
+//! `no_std` builds need. The header is written to `struggle-core/include/struggle.h`.
+
+pub use struggle_core::ffi::*;
@@ -0,0 +1,97 @@
+use std::sync::RwLock;
+
+use macroquad::prelude::*;
+use struggle_core::{
+    games::struggle::PlayerColor,
+    palette::Palette,
+    render::{Rgb, Shape},
+};
+
+// Chosen on the command line and switchable with a key, so it lives outside the game loops
+static PALETTE: RwLock<Palette> = RwLock::new(Palette::CLASSIC);
+
+pub fn palette() -> Palette {
+    *PALETTE.read().unwrap()
+}
+
+pub fn set_palette(palette: Palette) {
+    *PALETTE.write().unwrap() = palette;
+}
+
+/// Switches to the next preset palette when C is pressed.
+pub fn handle_palette_input() {
+    if !is_key_pressed(KeyCode::C) {
+        return;
+    }
+
+    let current = palette();
+    let index = Palette::PRESETS
+        .iter()
+        .position(|preset| *preset == current)
+        .map_or(0, |index| (index + 1) % Palette::PRESETS.len());
+
+    set_palette(Palette::PRESETS[index]);
+}
+
+pub fn to_color(color: Rgb) -> Color {
+    Color::from_rgba(color.0, color.1, color.2, 255)
+}
+
+pub fn player_to_color(player: PlayerColor) -> Color {
+    to_color(palette().player(player))
+}
+
+pub fn draw_shapes(shapes: &[Shape]) {
+    for shape in shapes {
+        match shape {
+            Shape::Disc {
+                center,
+                radius,
+                color,
+            } => {
+                draw_circle(center.0, center.1, *radius, to_color(*color));
+            }
+            Shape::Ring {
+                center,
+                radius,
+                thickness,
+                color,
+            } => {
+                draw_circle_lines(center.0, center.1, *radius, *thickness, to_color(*color));
+            }
+            Shape::Line {
+                from,
+                to,
+                thickness,
+                color,
+            } => {
+                draw_line(from.0, from.1, to.0, to.1, *thickness, to_color(*color));
+            }
+            Shape::Label {
+                position,
+                text,
+                size,
+                color,
+            } => {
+                draw_text(text, position.0, position.1, *size, to_color(*color));
+            }
+        }
+    }
+}
+
+// A box over the middle of the board, shown when a game has ended
+pub fn draw_summary(title: &str, color: Color, lines: &[String], keys: &str) {
+    let (left, top) = (150.0, 330.0);
+    let (width, height) = (700.0, 120.0 + lines.len() as f32 * 30.0);
+
+    draw_rectangle(left, top, width, height, Color::new(0.0, 0.0, 0.0, 0.85));
+    draw_rectangle_lines(left, top, width, height, 4.0, color);
+
+    draw_text(title, left + 20.0, top + 50.0, 40.0, color);
+
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, left + 20.0, top + 90.0 + i as f32 * 30.0, 24.0, WHITE);
+    }
+
+    draw_text(keys, left + 20.0, top + height - 15.0, 22.0, LIGHTGRAY);
+}
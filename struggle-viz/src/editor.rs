@@ -0,0 +1,279 @@
+use macroquad::prelude::*;
+use struggle_core::{
+    games::{
+        struggle::{
+            board::{Board, BoardCell, HomeBase},
+            PlayerColor,
+        },
+        twist::board::TwistBoard,
+    },
+    render::BoardLayout,
+};
+
+// Board access the editor needs, shared by Struggle and Twist boards
+pub trait EditableBoard {
+    const TILES: u8;
+    const GOAL_SLOTS: u8;
+
+    fn players(&self) -> (PlayerColor, PlayerColor);
+    fn tile(&self, tile: u8) -> BoardCell;
+    fn set_tile(&mut self, tile: u8, cell: BoardCell);
+    fn goal(&self, player: PlayerColor, slot: u8) -> BoardCell;
+    fn set_goal(&mut self, player: PlayerColor, slot: u8, cell: BoardCell);
+    fn home_base(&self, player: PlayerColor) -> &HomeBase;
+    fn home_base_mut(&mut self, player: PlayerColor) -> &mut HomeBase;
+    fn pieces(&self, player: PlayerColor) -> u8;
+
+    // Called after every edit, so that cached piece positions stay in sync with the tiles
+    fn refresh(&mut self);
+}
+
+impl EditableBoard for Board {
+    const TILES: u8 = Board::TILES as u8;
+    const GOAL_SLOTS: u8 = 4;
+
+    fn players(&self) -> (PlayerColor, PlayerColor) {
+        Board::players(self)
+    }
+
+    fn tile(&self, tile: u8) -> BoardCell {
+        self.tiles[tile as usize]
+    }
+
+    fn set_tile(&mut self, tile: u8, cell: BoardCell) {
+        self.tiles[tile as usize] = cell;
+    }
+
+    fn goal(&self, player: PlayerColor, slot: u8) -> BoardCell {
+        self.goals[player as usize][slot as usize]
+    }
+
+    fn set_goal(&mut self, player: PlayerColor, slot: u8, cell: BoardCell) {
+        self.goals[player as usize][slot as usize] = cell;
+    }
+
+    fn home_base(&self, player: PlayerColor) -> &HomeBase {
+        &self.home_bases[player as usize]
+    }
+
+    fn home_base_mut(&mut self, player: PlayerColor) -> &mut HomeBase {
+        &mut self.home_bases[player as usize]
+    }
+
+    fn pieces(&self, player: PlayerColor) -> u8 {
+        let on_board = self
+            .tiles
+            .iter()
+            .filter(|&&cell| cell == Some(player))
+            .count();
+        on_board as u8
+            + self.pieces_in_goal(player)
+            + self.home_bases[player as usize].pieces_waiting
+    }
+
+    fn refresh(&mut self) {
+        self.update_piece_cache();
+    }
+}
+
+impl EditableBoard for TwistBoard {
+    const TILES: u8 = TwistBoard::TILES as u8;
+    const GOAL_SLOTS: u8 = 3;
+
+    fn players(&self) -> (PlayerColor, PlayerColor) {
+        TwistBoard::players(self)
+    }
+
+    fn tile(&self, tile: u8) -> BoardCell {
+        self.tiles()[tile as usize]
+    }
+
+    fn set_tile(&mut self, tile: u8, cell: BoardCell) {
+        TwistBoard::set_tile(self, tile, cell);
+    }
+
+    fn goal(&self, player: PlayerColor, slot: u8) -> BoardCell {
+        self.goals()[player as usize][slot as usize]
+    }
+
+    fn set_goal(&mut self, player: PlayerColor, slot: u8, cell: BoardCell) {
+        TwistBoard::set_goal(self, player, slot, cell);
+    }
+
+    fn home_base(&self, player: PlayerColor) -> &HomeBase {
+        &self.home_bases[player as usize]
+    }
+
+    fn home_base_mut(&mut self, player: PlayerColor) -> &mut HomeBase {
+        &mut self.home_bases[player as usize]
+    }
+
+    fn pieces(&self, player: PlayerColor) -> u8 {
+        let on_board = self
+            .tiles()
+            .iter()
+            .filter(|&&cell| cell == Some(player))
+            .count();
+        let in_goal = self.goals()[player as usize]
+            .iter()
+            .filter(|&&cell| cell == Some(player))
+            .count();
+
+        (on_board + in_goal) as u8 + self.home_bases[player as usize].pieces_waiting
+    }
+
+    fn refresh(&mut self) {
+        self.update(|_| {});
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Target {
+    Tile(u8),
+    Goal(PlayerColor, u8),
+    HomeBase(PlayerColor, u8),
+}
+
+/// Lets the user build a position by clicking on the board.
+///
+/// Left clicking a tile cycles between empty and the two players, right clicking clears it.
+/// Pieces are taken from and returned to the home base, so each player keeps at most four.
+/// Clicking the n:th home base slot sets the number of waiting pieces to n.
+pub struct Editor<B: EditableBoard> {
+    pub board: B,
+    pub to_move: PlayerColor,
+}
+
+impl<B: EditableBoard> Editor<B> {
+    pub fn new(board: B, to_move: PlayerColor) -> Self {
+        Self { board, to_move }
+    }
+
+    pub fn other_player(&self) -> PlayerColor {
+        let (a, b) = self.board.players();
+
+        if self.to_move == a {
+            b
+        } else {
+            a
+        }
+    }
+
+    /// Applies this frame's clicks and key presses, returning whether anything changed.
+    pub fn handle_input(&mut self, layout: &BoardLayout) -> bool {
+        let mut changed = false;
+
+        if is_key_pressed(KeyCode::Tab) {
+            self.to_move = self.other_player();
+            changed = true;
+        }
+
+        let left = is_mouse_button_pressed(MouseButton::Left);
+        let right = is_mouse_button_pressed(MouseButton::Right);
+
+        if !left && !right {
+            return changed;
+        }
+
+        let Some(target) = self.target_at(layout, mouse_position()) else {
+            return changed;
+        };
+
+        match target {
+            Target::Tile(tile) => {
+                let cell = self.board.tile(tile);
+                let next = if right { None } else { self.next_owner(cell) };
+                self.replace(cell, next, |board, cell| board.set_tile(tile, cell));
+            }
+            Target::Goal(player, slot) => {
+                let cell = self.board.goal(player, slot);
+                let next = if right || cell.is_some() || !self.can_place(player) {
+                    None
+                } else {
+                    Some(player)
+                };
+                self.replace(cell, next, |board, cell| board.set_goal(player, slot, cell));
+            }
+            Target::HomeBase(player, index) => {
+                let waiting = self.board.home_base(player).pieces_waiting;
+                let elsewhere = self.board.pieces(player) - waiting;
+
+                // Clicking the last filled slot empties it, any other slot fills up to it
+                let wanted = if right || waiting == index + 1 {
+                    index
+                } else {
+                    index + 1
+                };
+
+                self.board.home_base_mut(player).pieces_waiting = wanted.min(4 - elsewhere);
+            }
+        }
+
+        self.board.refresh();
+
+        true
+    }
+
+    // The next player in the empty -> first player -> second player cycle who can still place a piece
+    fn next_owner(&self, cell: BoardCell) -> BoardCell {
+        let (a, b) = self.board.players();
+        let cycle = [None, Some(a), Some(b)];
+        let current = cycle.iter().position(|&owner| owner == cell).unwrap_or(0);
+
+        (1..cycle.len())
+            .map(|offset| cycle[(current + offset) % cycle.len()])
+            .find(|owner| match owner {
+                Some(player) => self.can_place(*player),
+                None => true,
+            })
+            .unwrap_or(None)
+    }
+
+    fn can_place(&self, player: PlayerColor) -> bool {
+        self.board.home_base(player).can_add_piece() || self.board.pieces(player) < 4
+    }
+
+    fn replace(&mut self, old: BoardCell, new: BoardCell, set: impl Fn(&mut B, BoardCell)) {
+        if let Some(player) = old {
+            self.board.home_base_mut(player).add_piece();
+        }
+
+        if let Some(player) = new {
+            // Without waiting pieces the player is short of a full set, so the piece is new
+            self.board.home_base_mut(player).remove_piece();
+        }
+
+        set(&mut self.board, new);
+    }
+
+    fn target_at(&self, layout: &BoardLayout, (x, y): (f32, f32)) -> Option<Target> {
+        let hits =
+            |position: (f32, f32), radius: f32| Vec2::from(position).distance(vec2(x, y)) <= radius;
+
+        let piece_radius = layout.piece_radius();
+        let home_radius = BoardLayout::HOME_BASE_PIECE_RADIUS * layout.scale * 2.0;
+        let (a, b) = self.board.players();
+
+        if let Some(tile) =
+            (0..B::TILES).find(|&tile| hits(layout.tile_position(tile), piece_radius))
+        {
+            return Some(Target::Tile(tile));
+        }
+
+        for player in [a, b] {
+            if let Some(slot) = (0..B::GOAL_SLOTS)
+                .find(|&slot| hits(layout.goal_position(player, slot), piece_radius))
+            {
+                return Some(Target::Goal(player, slot));
+            }
+
+            if let Some(index) =
+                (0..4).find(|&index| hits(layout.home_base_position(player, index), home_radius))
+            {
+                return Some(Target::HomeBase(player, index));
+            }
+        }
+
+        None
+    }
+}
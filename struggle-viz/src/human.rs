@@ -0,0 +1,102 @@
+use std::borrow::Cow;
+
+use ::rand::rngs::SmallRng;
+use macroquad::prelude::*;
+use struggle_core::{
+    game::{GamePlayer, NamedPlayer},
+    games::struggle::{
+        board::{Board, MoveVec, StruggleMove},
+        players::GameContext,
+        PlayerColor, StruggleRules,
+    },
+};
+
+use crate::{
+    draw::player_to_color,
+    struggle::{move_endpoints, PIECE_RADIUS},
+};
+
+/// The player spec that seats the user instead of an AI, e.g. `--red human`.
+pub const HUMAN: &str = "human";
+
+/// Stands in for the user in a seat. The user's moves are picked on the board by `HumanTurn`,
+/// so the game never asks this player for one; views that play by themselves, like the grid,
+/// are turned off while a human is seated.
+#[derive(Clone)]
+pub struct HumanPlayer;
+
+impl NamedPlayer for HumanPlayer {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("Human")
+    }
+}
+
+impl GamePlayer<StruggleRules> for HumanPlayer {
+    fn select_move<'a>(
+        &mut self,
+        _ctx: &GameContext,
+        _board: &Board,
+        _moves: &'a [StruggleMove],
+        _rng: &mut SmallRng,
+    ) -> &'a StruggleMove {
+        unreachable!("Human moves are picked on the board")
+    }
+}
+
+/// A roll of the user's that is waiting for them to pick a move.
+pub struct HumanTurn {
+    pub player: PlayerColor,
+    pub dice: u8,
+    pub moves: MoveVec,
+}
+
+impl HumanTurn {
+    // The move whose piece or target is under `point`. Pieces win over targets, since a
+    // piece can sit on the target of another move.
+    fn move_at(&self, point: Vec2) -> Option<&StruggleMove> {
+        let endpoints = || {
+            self.moves
+                .iter()
+                .filter_map(|mov| move_endpoints(self.player, mov).map(|ends| (mov, ends)))
+        };
+
+        endpoints()
+            .find(|(_, (from, _))| from.distance(point) <= PIECE_RADIUS)
+            .or_else(|| endpoints().find(|(_, (_, to))| to.distance(point) <= PIECE_RADIUS))
+            .map(|(mov, _)| mov)
+    }
+
+    /// The move the user clicked this frame, if any. A roll without legal moves only has
+    /// `SkipTurn`, which needs no click.
+    pub fn handle_input(&self) -> Option<StruggleMove> {
+        if let [StruggleMove::SkipTurn] = self.moves.as_slice() {
+            return Some(StruggleMove::SkipTurn);
+        }
+
+        if !is_mouse_button_pressed(MouseButton::Left) {
+            return None;
+        }
+
+        self.move_at(mouse_position().into()).cloned()
+    }
+
+    /// Rings every movable piece and its target, and draws the move under the mouse.
+    pub fn draw(&self) {
+        let color = player_to_color(self.player);
+        let hovered = self.move_at(mouse_position().into());
+
+        for mov in &self.moves {
+            let Some((from, to)) = move_endpoints(self.player, mov) else {
+                continue;
+            };
+
+            draw_circle_lines(from.x, from.y, PIECE_RADIUS + 4.0, 3.0, WHITE);
+            draw_circle_lines(to.x, to.y, PIECE_RADIUS + 6.0, 3.0, color);
+
+            if hovered == Some(mov) {
+                draw_line(from.x, from.y, to.x, to.y, 4.0, WHITE);
+                draw_circle_lines(to.x, to.y, PIECE_RADIUS + 10.0, 4.0, WHITE);
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::path::PathBuf;
+
+use ::rand::prelude::*;
+use clap::{Parser, ValueEnum};
+use macroquad::prelude::*;
+use struggle_core::{
+    games::struggle::game_notation::NotatedGame,
+    palette::Palette,
+    registry::{struggle_player, twist_player, BoxedStrugglePlayer},
+};
+
+mod draw;
+mod editor;
+mod human;
+mod measure;
+mod playback;
+mod recorder;
+mod replay;
+mod struggle;
+mod twist;
+
+use human::{HumanPlayer, HUMAN};
+use playback::{Playback, DEFAULT_TICK_INTERVAL};
+
+pub const WIDTH: usize = 1000;
+pub const HEIGHT: usize = 1000;
+pub const PANEL_WIDTH: usize = 320;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GameKind {
+    Struggle,
+    Twist,
+}
+
+/// Watch two AI players play Struggle or Twist, or play Struggle against one.
+#[derive(Parser)]
+struct Args {
+    #[arg(long, value_enum, default_value = "struggle")]
+    game: GameKind,
+
+    /// Red player, e.g. `expectiminimax:2`, or `human` to play Red yourself (Struggle only).
+    /// Defaults depend on the game.
+    #[arg(long)]
+    red: Option<String>,
+
+    /// Yellow player, e.g. `random`, or `human` to play Yellow yourself (Struggle only).
+    /// Defaults depend on the game.
+    #[arg(long)]
+    yellow: Option<String>,
+
+    /// Seed for the dice and the players, for reproducible games.
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Color palette: classic, okabe-ito, monochrome, or four hex colors for red, blue, yellow
+    /// and green. Defaults to $STRUGGLE_PALETTE.
+    #[arg(long)]
+    palette: Option<String>,
+
+    /// Seconds between turns.
+    #[arg(long, default_value_t = DEFAULT_TICK_INTERVAL)]
+    tick: f64,
+
+    /// Notation file that [L] loads into the board editor, e.g. one saved with [X]. Without one,
+    /// [L] loads from the clipboard. Struggle only.
+    #[arg(long)]
+    position: Option<PathBuf>,
+
+    /// A recorded game to step through instead of playing one, e.g. one exported with [E] to
+    /// out/replays. Struggle only.
+    #[arg(long)]
+    replay: Option<PathBuf>,
+
+    /// Plays games at full speed in the background and charts the win rate with the latest
+    /// final position, instead of showing one game turn by turn.
+    #[arg(long)]
+    measure: bool,
+}
+
+// Like `struggle_player`, but `human` seats the user
+fn struggle_seat(spec: &str) -> Result<BoxedStrugglePlayer, String> {
+    if spec == HUMAN {
+        Ok(Box::new(HumanPlayer))
+    } else {
+        struggle_player(spec)
+    }
+}
+
+fn window_conf() -> Conf {
+    Conf {
+        window_title: "Struggle!".to_string(),
+        window_resizable: false,
+        window_width: (WIDTH + PANEL_WIDTH) as i32,
+        window_height: HEIGHT as i32,
+        ..Default::default()
+    }
+}
+
+#[macroquad::main(window_conf)]
+async fn main() {
+    struggle_core::logging::init();
+
+    let args = Args::parse();
+
+    let seed = args.seed.unwrap_or_else(|| ::rand::thread_rng().gen());
+
+    let rng = match args.seed {
+        Some(seed) => SmallRng::seed_from_u64(seed),
+        None => SmallRng::from_rng(::rand::thread_rng()).unwrap(),
+    };
+
+    let playback = Playback::new(args.tick);
+
+    match args.palette.as_deref().map(Palette::parse) {
+        Some(Ok(palette)) => draw::set_palette(palette),
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+        None => draw::set_palette(Palette::from_env()),
+    }
+
+    if let Some(path) = &args.replay {
+        let game = std::fs::read_to_string(path)
+            .map_err(|err| format!("Could not read {}: {}", path.display(), err))
+            .and_then(|text| NotatedGame::parse(&text));
+
+        if let Err(err) = match game {
+            Ok(game) => replay::run(game, playback).await,
+            Err(err) => Err(err),
+        } {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+
+        return;
+    }
+
+    match args.game {
+        GameKind::Struggle => {
+            let red = args.red.as_deref().unwrap_or("expectiminimax:0");
+            let yellow = args.yellow.as_deref().unwrap_or("random");
+
+            let humans = [red == HUMAN, yellow == HUMAN];
+
+            if args.measure && humans.contains(&true) {
+                eprintln!("--measure plays games at full speed, which a human can't");
+                std::process::exit(2);
+            }
+
+            let (red, yellow) = match (struggle_seat(red), struggle_seat(yellow)) {
+                (Ok(red), Ok(yellow)) => (red, yellow),
+                (Err(err), _) | (_, Err(err)) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2);
+                }
+            };
+
+            if args.measure {
+                struggle::measure(red, yellow, seed).await
+            } else {
+                struggle::run(red, yellow, rng, playback, args.position, humans).await
+            }
+        }
+        GameKind::Twist => {
+            let red = args.red.as_deref().unwrap_or("score_move");
+            let yellow = args.yellow.as_deref().unwrap_or("score_move");
+
+            let (red, yellow) = match (twist_player(red), twist_player(yellow)) {
+                (Ok(red), Ok(yellow)) => (red, yellow),
+                (Err(err), _) | (_, Err(err)) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2);
+                }
+            };
+
+            if args.measure {
+                twist::measure(red, yellow, seed).await
+            } else {
+                twist::run(red, yellow, rng, playback).await
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use ::rand::prelude::*;
+use macroquad::prelude::*;
+use struggle_core::{
+    analysis::wilson_score, game::GameId, games::struggle::PlayerColor, render::RenderBoard,
+};
+
+use crate::{
+    draw::{draw_shapes, handle_palette_input, palette, player_to_color},
+    HEIGHT, PANEL_WIDTH, WIDTH,
+};
+
+// The chart keeps at most this many points, halving its resolution whenever it fills up
+const MAX_CHART_POINTS: usize = 512;
+
+const CHART_LEFT: f32 = 60.0;
+const CHART_TOP: f32 = 700.0;
+const CHART_WIDTH: f32 = (WIDTH + PANEL_WIDTH) as f32 - 2.0 * CHART_LEFT;
+const CHART_HEIGHT: f32 = HEIGHT as f32 - CHART_TOP - 50.0;
+
+// What the simulation thread has played so far
+struct Tally<B> {
+    games: u64,
+    a_wins: u64,
+    last_board: Option<B>,
+    last_winner: Option<PlayerColor>,
+}
+
+/// Plays games with `play` as fast as a background thread can, while drawing the win rate of
+/// Red (player A) with its 95% interval as it converges, and the final position of the latest
+/// game. Game `n` is played with the seed of `GameId::new(seed, n)`.
+pub async fn run<B, F>(names: (String, String), seed: u64, play: F)
+where
+    B: RenderBoard + Clone + Send + 'static,
+    F: Fn(&mut SmallRng) -> (PlayerColor, B) + Send + 'static,
+{
+    let tally = Arc::new(Mutex::new(Tally {
+        games: 0,
+        a_wins: 0,
+        last_board: None,
+        last_winner: None,
+    }));
+
+    {
+        let tally = tally.clone();
+
+        // Runs until the window is closed, which ends the process
+        thread::spawn(move || {
+            for index in 0.. {
+                let mut rng = SmallRng::seed_from_u64(GameId::new(seed, index).game_seed());
+                let (winner, board) = play(&mut rng);
+
+                let mut tally = tally.lock().unwrap();
+                tally.games += 1;
+                tally.a_wins += (winner == PlayerColor::Red) as u64;
+                tally.last_board = Some(board);
+                tally.last_winner = Some(winner);
+            }
+        });
+    }
+
+    // (games, A's wins) whenever the frame saw new games
+    let mut points: Vec<(u64, u64)> = Vec::new();
+    let mut stride = 1;
+    let started = get_time();
+
+    loop {
+        handle_palette_input();
+
+        let (games, a_wins, board, winner) = {
+            let tally = tally.lock().unwrap();
+            (
+                tally.games,
+                tally.a_wins,
+                tally.last_board.clone(),
+                tally.last_winner,
+            )
+        };
+
+        if points
+            .last()
+            .map_or(games > 0, |&(last, _)| games >= last + stride)
+        {
+            points.push((games, a_wins));
+
+            if points.len() > MAX_CHART_POINTS {
+                points = points.into_iter().step_by(2).collect();
+                stride *= 2;
+            }
+        }
+
+        clear_background(BLACK);
+
+        if let Some(board) = &board {
+            let layout = B::layout((WIDTH as f32 / 2.0, 340.0), 0.6);
+            draw_shapes(&board.shapes(&layout, &palette()));
+        }
+
+        draw_panel(&names, games, a_wins, winner, get_time() - started);
+        draw_chart(&points);
+
+        draw_text("measuring | [C]olors", 10.0, 20.0, 20.0, WHITE);
+
+        next_frame().await
+    }
+}
+
+fn draw_panel(
+    names: &(String, String),
+    games: u64,
+    a_wins: u64,
+    last_winner: Option<PlayerColor>,
+    elapsed: f64,
+) {
+    let left = WIDTH as f32 + 20.0;
+    let rate = if games == 0 {
+        0.5
+    } else {
+        a_wins as f64 / games as f64
+    };
+    let (low, high) = wilson_score(rate, games);
+
+    draw_text(
+        &names.0,
+        left,
+        60.0,
+        28.0,
+        player_to_color(PlayerColor::Red),
+    );
+    draw_text("vs", left, 90.0, 20.0, LIGHTGRAY);
+    draw_text(
+        &names.1,
+        left,
+        120.0,
+        28.0,
+        player_to_color(PlayerColor::Yellow),
+    );
+
+    let lines = [
+        format!("{} games", games),
+        format!("{:.0} games/s", games as f64 / elapsed.max(1e-3)),
+        format!("Red wins {:.2}%", rate * 100.0),
+        format!("95%: [{:.2}, {:.2}]", low * 100.0, high * 100.0),
+        match last_winner {
+            Some(winner) => format!("Last game: {:?}", winner),
+            None => String::new(),
+        },
+    ];
+
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, left, 170.0 + i as f32 * 26.0, 20.0, LIGHTGRAY);
+    }
+}
+
+// Red's win rate over the games played so far, with its 95% interval as a band
+fn draw_chart(points: &[(u64, u64)]) {
+    draw_rectangle_lines(CHART_LEFT, CHART_TOP, CHART_WIDTH, CHART_HEIGHT, 2.0, GRAY);
+
+    // 50% is the line to beat
+    let even = CHART_TOP + CHART_HEIGHT / 2.0;
+    draw_line(
+        CHART_LEFT,
+        even,
+        CHART_LEFT + CHART_WIDTH,
+        even,
+        1.0,
+        DARKGRAY,
+    );
+
+    draw_text("100%", 10.0, CHART_TOP + 5.0, 16.0, LIGHTGRAY);
+    draw_text("50%", 18.0, even + 5.0, 16.0, LIGHTGRAY);
+    draw_text("0%", 26.0, CHART_TOP + CHART_HEIGHT, 16.0, LIGHTGRAY);
+
+    let Some(&(total, _)) = points.last() else {
+        return;
+    };
+
+    draw_text(
+        &format!("{} games", total),
+        CHART_LEFT + CHART_WIDTH - 120.0,
+        CHART_TOP + CHART_HEIGHT + 24.0,
+        18.0,
+        LIGHTGRAY,
+    );
+
+    let to_screen = |games: u64, rate: f64| {
+        vec2(
+            CHART_LEFT + CHART_WIDTH * games as f32 / total.max(1) as f32,
+            CHART_TOP + CHART_HEIGHT * (1.0 - rate as f32),
+        )
+    };
+
+    let color = player_to_color(PlayerColor::Red);
+    let band = Color::new(color.r, color.g, color.b, 0.25);
+
+    let series = points
+        .iter()
+        .map(|&(games, a_wins)| {
+            let rate = a_wins as f64 / games as f64;
+            let (low, high) = wilson_score(rate, games);
+
+            (
+                to_screen(games, rate),
+                to_screen(games, low),
+                to_screen(games, high),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for pair in series.windows(2) {
+        let ((from, from_low, from_high), (to, to_low, to_high)) = (pair[0], pair[1]);
+
+        draw_triangle(from_low, from_high, to_high, band);
+        draw_triangle(from_low, to_high, to_low, band);
+        draw_line(from.x, from.y, to.x, to.y, 2.0, color);
+    }
+}
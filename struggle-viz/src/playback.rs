@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+
+pub const DEFAULT_TICK_INTERVAL: f64 = 0.2;
+pub const MIN_TICK_INTERVAL: f64 = 0.0125;
+pub const MAX_TICK_INTERVAL: f64 = 3.2;
+
+// How long turbo mode may simulate within a single frame before rendering again
+pub const TURBO_FRAME_BUDGET: f64 = 1.0 / 30.0;
+
+pub struct Playback {
+    pub tick_interval: f64,
+    pub paused: bool,
+    // Wait for the space key before every turn
+    pub step_mode: bool,
+    // Simulate as many turns as fit in a frame, skipping the move analysis
+    pub turbo: bool,
+}
+
+impl Playback {
+    pub fn new(tick_interval: f64) -> Self {
+        Self {
+            tick_interval: tick_interval.clamp(MIN_TICK_INTERVAL, MAX_TICK_INTERVAL),
+            paused: false,
+            step_mode: true,
+            turbo: false,
+        }
+    }
+
+    pub fn handle_input(&mut self) {
+        if is_key_pressed(KeyCode::P) {
+            self.paused = !self.paused;
+        }
+
+        if is_key_pressed(KeyCode::S) {
+            self.step_mode = !self.step_mode;
+        }
+
+        if is_key_pressed(KeyCode::T) {
+            self.turbo = !self.turbo;
+        }
+
+        if is_key_pressed(KeyCode::Up) {
+            self.tick_interval = (self.tick_interval / 2.0).max(MIN_TICK_INTERVAL);
+        }
+
+        if is_key_pressed(KeyCode::Down) {
+            self.tick_interval = (self.tick_interval * 2.0).min(MAX_TICK_INTERVAL);
+        }
+    }
+
+    /// `extra_keys` lists the shortcuts that only the current game supports.
+    pub fn status_text(&self, extra_keys: &str) -> String {
+        let mode = if self.paused {
+            "paused"
+        } else if self.turbo {
+            "turbo"
+        } else if self.step_mode {
+            "step"
+        } else {
+            "auto"
+        };
+
+        format!(
+            "{} | tick {:.3}s | [P]ause [S]tep [T]urbo [Up/Down] speed [R]estart [F] record [C]olors{}",
+            mode, self.tick_interval, extra_keys
+        )
+    }
+}
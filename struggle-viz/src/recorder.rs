@@ -0,0 +1,60 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use macroquad::prelude::*;
+
+// Writes every rendered turn to a numbered PNG, e.g. for `ffmpeg -i frame_%05d.png game.gif`
+pub struct Recorder {
+    pub directory: PathBuf,
+    pub frames: u32,
+    last_turn: Option<u32>,
+}
+
+impl Recorder {
+    pub fn start() -> Self {
+        let session = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let directory = PathBuf::from(format!("out/frames/{}", session));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        println!("Recording frames to {}", directory.display());
+
+        Self {
+            directory,
+            frames: 0,
+            last_turn: None,
+        }
+    }
+
+    pub fn capture(&mut self, turn: u32) {
+        if self.last_turn == Some(turn) {
+            return;
+        }
+
+        self.last_turn = Some(turn);
+
+        let mut image = get_screen_data();
+        flip_vertically(&mut image);
+
+        let path = self.directory.join(format!("frame_{:05}.png", self.frames));
+        image.export_png(path.to_str().unwrap());
+
+        self.frames += 1;
+    }
+}
+
+// The framebuffer is read bottom-up, while PNGs are stored top-down
+fn flip_vertically(image: &mut Image) {
+    let row_length = image.width as usize * 4;
+    let height = image.height as usize;
+
+    for y in 0..height / 2 {
+        let (top, bottom) = image.bytes.split_at_mut((height - y - 1) * row_length);
+        top[y * row_length..(y + 1) * row_length].swap_with_slice(&mut bottom[..row_length]);
+    }
+}
@@ -0,0 +1,148 @@
+use macroquad::prelude::*;
+use struggle_core::{
+    games::struggle::{
+        game_notation::NotatedGame,
+        notation::{color_char, format_move},
+    },
+    render::{BoardLayout, RenderBoard},
+};
+
+use crate::{
+    draw::{draw_shapes, handle_palette_input, palette, player_to_color},
+    playback::Playback,
+    struggle::{move_endpoints, PIECE_RADIUS},
+    HEIGHT, PANEL_WIDTH, WIDTH,
+};
+
+// How many turns before and after the current one are listed in the side panel
+const TURN_CONTEXT: usize = 12;
+
+fn format_turn(game: &NotatedGame, index: usize) -> String {
+    let turn = &game.turns[index];
+
+    format!(
+        "{}. {}: {} {}",
+        index + 1,
+        color_char(turn.player).to_ascii_uppercase(),
+        turn.dice,
+        format_move(&turn.mov)
+    )
+}
+
+fn draw_replay_panel(game: &NotatedGame, step: usize) {
+    let left = WIDTH as f32 + 20.0;
+
+    draw_rectangle(
+        WIDTH as f32,
+        0.0,
+        PANEL_WIDTH as f32,
+        HEIGHT as f32,
+        Color::from_rgba(24, 24, 24, 255),
+    );
+
+    draw_text(
+        &format!("Turn {}/{}", step, game.turns.len()),
+        left,
+        60.0,
+        32.0,
+        WHITE,
+    );
+
+    let mut y = 100.0;
+
+    for (key, value) in &game.headers {
+        draw_text(&format!("{}: {}", key, value), left, y, 18.0, LIGHTGRAY);
+        y += 22.0;
+    }
+
+    y += 20.0;
+
+    // `step` positions in, the latest turn played is `step - 1`
+    let first = step.saturating_sub(TURN_CONTEXT);
+    let last = (step + TURN_CONTEXT).min(game.turns.len());
+
+    for index in first..last {
+        let color = if index + 1 == step {
+            player_to_color(game.turns[index].player)
+        } else {
+            GRAY
+        };
+
+        draw_text(&format_turn(game, index), left, y, 20.0, color);
+        y += 24.0;
+    }
+}
+
+/// Steps through a recorded game, see `game_notation`. Plays forward on its own unless paused
+/// or in step mode.
+pub async fn run(game: NotatedGame, mut playback: Playback) -> Result<(), String> {
+    let positions = game.positions()?;
+    let layout = BoardLayout::struggle((WIDTH as f32 / 2.0, HEIGHT as f32 / 2.0), 1.0);
+
+    let mut step = 0;
+    let mut next_tick = 0.0;
+
+    loop {
+        let time = get_time();
+
+        playback.handle_input();
+        handle_palette_input();
+
+        let last = positions.len() - 1;
+
+        if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::Space) {
+            step = (step + 1).min(last);
+        }
+
+        if is_key_pressed(KeyCode::Left) {
+            step = step.saturating_sub(1);
+        }
+
+        if is_key_pressed(KeyCode::Home) || is_key_pressed(KeyCode::R) {
+            step = 0;
+        }
+
+        if is_key_pressed(KeyCode::End) {
+            step = last;
+        }
+
+        if !playback.paused && !playback.step_mode && step < last && time > next_tick {
+            step = if playback.turbo { last } else { step + 1 };
+            next_tick = time + playback.tick_interval;
+        }
+
+        clear_background(BLACK);
+
+        let (board, _) = &positions[step];
+        draw_shapes(&board.shapes(&layout, &palette()));
+
+        if let Some(turn) = step.checked_sub(1).map(|index| &game.turns[index]) {
+            let color = player_to_color(turn.player);
+
+            if let Some((from, to)) = move_endpoints(turn.player, &turn.mov) {
+                draw_line(from.x, from.y, to.x, to.y, 3.0, color);
+                draw_circle_lines(to.x, to.y, PIECE_RADIUS + 6.0, 4.0, WHITE);
+            }
+
+            draw_text(
+                &turn.dice.to_string(),
+                WIDTH as f32 / 2.0,
+                HEIGHT as f32 / 2.0,
+                40.0,
+                color,
+            );
+        }
+
+        draw_replay_panel(&game, step);
+
+        draw_text(
+            &playback.status_text(" [Left/Right] turn [Home/End]"),
+            10.0,
+            20.0,
+            20.0,
+            WHITE,
+        );
+
+        next_frame().await
+    }
+}
@@ -0,0 +1,1011 @@
+use std::{
+    collections::VecDeque,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ::rand::prelude::*;
+use macroquad::prelude::*;
+use struggle_core::{
+    game::{play_game_with_rng, NamedPlayer, RaceGame, TurnResult},
+    games::struggle::{
+        board::{Board, StruggleMove},
+        game_notation::{NotatedGame, NotatedTurn},
+        notation::{format_position, parse_position, START_POSITION},
+        players::{default_heuristic, GameContext, GameTreePlayer, StrugglePlayer},
+        AiStrugglePlayer, PlayerColor, StruggleGame,
+    },
+    json::color_name,
+    registry::BoxedStrugglePlayer,
+    render::{BoardLayout, RenderBoard},
+};
+
+use crate::{
+    draw::{draw_shapes, draw_summary, handle_palette_input, palette, player_to_color},
+    editor::Editor,
+    human::HumanTurn,
+    measure,
+    playback::{Playback, TURBO_FRAME_BUDGET},
+    recorder::Recorder,
+    HEIGHT, PANEL_WIDTH, WIDTH,
+};
+
+pub const PIECE_RADIUS: f32 = BoardLayout::PIECE_RADIUS;
+
+// How many of each player's latest dice rolls are listed in the side panel
+pub const DICE_HISTORY: usize = 12;
+
+// Search depth used to score Red's candidate moves for the overlay
+pub const ANALYSIS_DEPTH: u8 = 2;
+
+struct LastMove {
+    player: PlayerColor,
+    mov: StruggleMove,
+    captured: Option<PlayerColor>,
+    time: f64,
+}
+
+struct Seat {
+    color: PlayerColor,
+    name: String,
+    dice_history: VecDeque<u8>,
+    captures: u32,
+    sixes: u32,
+}
+
+impl Seat {
+    fn new(color: PlayerColor, name: String) -> Self {
+        Self {
+            color,
+            name,
+            dice_history: VecDeque::with_capacity(DICE_HISTORY),
+            captures: 0,
+            sixes: 0,
+        }
+    }
+}
+
+struct Match<A: StrugglePlayer, B: StrugglePlayer> {
+    game: StruggleGame<A, B>,
+    seats: [Seat; 2],
+    winner: Option<PlayerColor>,
+    last_die: u8,
+    last_die_player: PlayerColor,
+    last_move: Option<LastMove>,
+    turns: u32,
+    // Every move of the game, for exporting a replay
+    history: Vec<(PlayerColor, u8, StruggleMove)>,
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> Match<A, B> {
+    fn new(player_a: AiStrugglePlayer<A>, player_b: AiStrugglePlayer<B>) -> Self {
+        let seats = [
+            Seat::new(player_a.color, player_a.player.name().into_owned()),
+            Seat::new(player_b.color, player_b.player.name().into_owned()),
+        ];
+
+        Self {
+            game: StruggleGame::new(player_a, player_b, false),
+            seats,
+            winner: None,
+            last_die: 0,
+            last_die_player: PlayerColor::Red,
+            last_move: None,
+            turns: 0,
+            history: Vec::new(),
+        }
+    }
+
+    fn play_turn(&mut self, rng: &mut SmallRng) {
+        let dice = self.game.throw_dice(rng);
+        self.play_turn_with_die(dice, rng);
+    }
+
+    fn seat_mut(&mut self, color: PlayerColor) -> &mut Seat {
+        self.seats
+            .iter_mut()
+            .find(|seat| seat.color == color)
+            .expect("Every player should have a seat")
+    }
+
+    fn play_turn_with_die(&mut self, dice: u8, rng: &mut SmallRng) {
+        let ctx = self.game.create_turn_context(dice);
+        let moves = self.game.get_moves(&ctx);
+        let mov = self.game.select_move(&ctx, &moves, rng).clone();
+
+        self.play_turn_with_move(dice, mov);
+    }
+
+    // Plays a move that was picked outside of the game, e.g. by the user
+    fn play_turn_with_move(&mut self, dice: u8, mov: StruggleMove) {
+        let player = self.game.current_player();
+        let ctx = self.game.create_turn_context(dice);
+
+        let captured = match mov {
+            StruggleMove::AddNewPiece { eats: true } => {
+                self.game.board().tiles[Board::get_start(player) as usize]
+            }
+            StruggleMove::MovePiece { to, eats: true, .. } => self.game.board().tiles[to as usize],
+            _ => None,
+        };
+
+        let result = self.game.apply_move(&ctx, &mov);
+        self.last_die = dice;
+        self.last_die_player = player;
+        self.turns += 1;
+
+        let seat = self.seat_mut(player);
+
+        if seat.dice_history.len() == DICE_HISTORY {
+            seat.dice_history.pop_front();
+        }
+
+        seat.dice_history.push_back(dice);
+        seat.captures += captured.is_some() as u32;
+        seat.sixes += (dice == 6) as u32;
+
+        self.history.push((player, dice, mov.clone()));
+
+        self.last_move = Some(LastMove {
+            player,
+            mov,
+            captured,
+            time: get_time(),
+        });
+
+        match result {
+            TurnResult::PlayAgain => {}
+            TurnResult::PassTo(player) => {
+                self.game.set_current_player(player);
+            }
+            TurnResult::EndGame { winner } => {
+                self.winner = Some(winner);
+            }
+        }
+    }
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> Match<A, B> {
+    fn draw_summary(&self, winner: PlayerColor) {
+        let winner_name = &self
+            .seats
+            .iter()
+            .find(|seat| seat.color == winner)
+            .expect("The winner should have a seat")
+            .name;
+
+        let mut lines = vec![format!("{} turns", self.turns)];
+
+        for seat in &self.seats {
+            lines.push(format!(
+                "{:?} ({}): {} captures, {} sixes, {} evaluations",
+                seat.color,
+                seat.name,
+                seat.captures,
+                seat.sixes,
+                self.game.total_evaluations(seat.color)
+            ));
+        }
+
+        draw_summary(
+            &format!("{:?} ({}) wins!", winner, winner_name),
+            player_to_color(winner),
+            &lines,
+            "[R]estart  [W] swap seats  [E]xport replay",
+        );
+    }
+
+    // Writes the game in the text notation of `game_notation` to out/replays
+    fn export_replay(&self) -> std::io::Result<PathBuf> {
+        let mut replay = NotatedGame::default();
+
+        for seat in &self.seats {
+            replay.set_header(&format!("{:?}", seat.color), &seat.name);
+        }
+
+        replay.set_header("Rules", "struggle");
+
+        let first = self
+            .history
+            .first()
+            .map_or(self.seats[0].color, |(player, _, _)| *player);
+        let start = format_position(&Board::new(self.seats[0].color, self.seats[1].color), first);
+
+        if start != START_POSITION {
+            replay.set_header("Start", start);
+        }
+
+        if let Some(winner) = self.winner {
+            replay.set_header("Result", color_name(winner));
+        }
+
+        replay.turns = self
+            .history
+            .iter()
+            .map(|(player, dice, mov)| NotatedTurn {
+                player: *player,
+                dice: *dice,
+                mov: mov.clone(),
+            })
+            .collect();
+
+        replay
+            .seal()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        let session = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let directory = PathBuf::from("out/replays");
+        std::fs::create_dir_all(&directory)?;
+
+        let path = directory.join(format!("{}.txt", session));
+        std::fs::write(&path, replay.to_string())?;
+
+        Ok(path)
+    }
+}
+
+// Writes a position in the text notation of `notation` to out/positions and to the clipboard
+fn save_position(board: &Board, to_move: PlayerColor) -> std::io::Result<PathBuf> {
+    let position = format_position(board, to_move);
+    macroquad::miniquad::window::clipboard_set(&position);
+
+    let saved_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+
+    let directory = PathBuf::from("out/positions");
+    std::fs::create_dir_all(&directory)?;
+
+    let path = directory.join(format!("{}.txt", saved_at));
+    std::fs::write(&path, position + "\n")?;
+
+    Ok(path)
+}
+
+// Reads a position from `file`, or from the clipboard without one
+fn load_position(file: Option<&Path>) -> Result<(Board, PlayerColor), String> {
+    let text = match file {
+        Some(file) => std::fs::read_to_string(file)
+            .map_err(|err| format!("Could not read {}: {}", file.display(), err))?,
+        None => macroquad::miniquad::window::clipboard_get().ok_or("The clipboard is empty")?,
+    };
+
+    parse_position(text.trim())
+}
+
+fn draw_info_panel<A: StrugglePlayer, B: StrugglePlayer>(current: &Match<A, B>) {
+    let left = WIDTH as f32 + 20.0;
+    let board = current.game.board();
+
+    draw_rectangle(
+        WIDTH as f32,
+        0.0,
+        PANEL_WIDTH as f32,
+        HEIGHT as f32,
+        Color::from_rgba(24, 24, 24, 255),
+    );
+
+    draw_text(&format!("Turn {}", current.turns), left, 60.0, 32.0, WHITE);
+
+    for (i, seat) in current.seats.iter().enumerate() {
+        let top = 120.0 + i as f32 * 300.0;
+        let other = current.seats[1 - i].color;
+        let color = player_to_color(seat.color);
+
+        draw_circle(left + 10.0, top - 8.0, 10.0, color);
+        draw_text(&seat.name, left + 30.0, top, 28.0, WHITE);
+
+        let lines = [
+            format!(
+                "Heuristic: {:.0}",
+                default_heuristic(board, seat.color, other)
+            ),
+            format!("Captures: {}", seat.captures),
+            format!(
+                "Waiting: {}  In goal: {}",
+                board.home_bases[seat.color as usize].pieces_waiting,
+                board.pieces_in_goal(seat.color)
+            ),
+            "Latest rolls:".to_string(),
+        ];
+
+        for (line_index, line) in lines.iter().enumerate() {
+            let y = top + 35.0 + line_index as f32 * 26.0;
+            draw_text(line, left, y, 22.0, LIGHTGRAY);
+        }
+
+        // Newest roll first
+        for (roll_index, roll) in seat.dice_history.iter().rev().enumerate() {
+            let x = left + (roll_index % 6) as f32 * 44.0;
+            let y = top + 150.0 + (roll_index / 6) as f32 * 44.0;
+            let background = if *roll == 6 { color } else { DARKGRAY };
+
+            draw_rectangle(x, y, 36.0, 36.0, background);
+            draw_text(&roll.to_string(), x + 11.0, y + 27.0, 30.0, BLACK);
+        }
+    }
+}
+
+// Several games between the same players, simulated side by side
+struct Grid<A: StrugglePlayer, B: StrugglePlayer> {
+    size: usize,
+    matches: Vec<Match<A, B>>,
+}
+
+impl<A: StrugglePlayer, B: StrugglePlayer> Grid<A, B> {
+    fn new(size: usize, player_a: &AiStrugglePlayer<A>, player_b: &AiStrugglePlayer<B>) -> Self {
+        Self {
+            size,
+            matches: (0..size * size)
+                .map(|_| Match::new(player_a.clone(), player_b.clone()))
+                .collect(),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.matches.iter().all(|m| m.winner.is_some())
+    }
+
+    fn total_turns(&self) -> u32 {
+        self.matches.iter().map(|m| m.turns).sum()
+    }
+
+    fn play_turn(&mut self, rng: &mut SmallRng) {
+        for m in self.matches.iter_mut().filter(|m| m.winner.is_none()) {
+            m.play_turn(rng);
+        }
+    }
+
+    fn draw(&self) {
+        let cell = WIDTH as f32 / self.size as f32;
+        let scale = cell / BoardLayout::SIZE * 0.95;
+
+        for (i, m) in self.matches.iter().enumerate() {
+            let left = (i % self.size) as f32 * cell;
+            let top = (i / self.size) as f32 * cell;
+            let center = (left + cell / 2.0, top + cell / 2.0);
+
+            let layout = BoardLayout::struggle(center, scale);
+            draw_shapes(&m.game.board().shapes(&layout, &palette()));
+
+            if let Some(winner) = m.winner {
+                draw_rectangle_lines(left, top, cell, cell, 6.0, player_to_color(winner));
+            }
+
+            draw_text(
+                &m.turns.to_string(),
+                center.0 - 10.0,
+                center.1 + 8.0,
+                24.0,
+                WHITE,
+            );
+        }
+    }
+
+    fn draw_panel(&self) {
+        let left = WIDTH as f32 + 20.0;
+
+        draw_rectangle(
+            WIDTH as f32,
+            0.0,
+            PANEL_WIDTH as f32,
+            HEIGHT as f32,
+            Color::from_rgba(24, 24, 24, 255),
+        );
+
+        let finished = self.matches.iter().filter(|m| m.winner.is_some()).count();
+
+        draw_text(
+            &format!("Grid {}x{}", self.size, self.size),
+            left,
+            60.0,
+            32.0,
+            WHITE,
+        );
+        draw_text(
+            &format!("Finished: {}/{}", finished, self.matches.len()),
+            left,
+            95.0,
+            22.0,
+            LIGHTGRAY,
+        );
+
+        for (i, seat) in self.matches[0].seats.iter().enumerate() {
+            let top = 150.0 + i as f32 * 100.0;
+
+            let finished_games = self.matches.iter().filter(|m| m.winner == Some(seat.color));
+
+            let wins = finished_games.clone().count();
+            let average_turns = finished_games.map(|m| m.turns as f32).sum::<f32>() / wins as f32;
+
+            draw_circle(left + 10.0, top - 8.0, 10.0, player_to_color(seat.color));
+            draw_text(&seat.name, left + 30.0, top, 28.0, WHITE);
+            draw_text(
+                &format!("Wins: {}", wins),
+                left,
+                top + 30.0,
+                22.0,
+                LIGHTGRAY,
+            );
+
+            if wins > 0 {
+                draw_text(
+                    &format!("Average turns to win: {:.0}", average_turns),
+                    left,
+                    top + 56.0,
+                    22.0,
+                    LIGHTGRAY,
+                );
+            }
+        }
+    }
+}
+
+// Grid sizes cycled through with the G key; 0 shows a single game
+const GRID_SIZES: [usize; 3] = [0, 3, 4];
+
+fn layout() -> BoardLayout {
+    BoardLayout::struggle((WIDTH as f32 / 2.0, HEIGHT as f32 / 2.0), 1.0)
+}
+
+fn tile_position(tile: u8) -> Vec2 {
+    layout().tile_position(tile).into()
+}
+
+fn goal_position(player: PlayerColor, slot: u8) -> Vec2 {
+    layout().goal_position(player, slot).into()
+}
+
+fn home_base_position(player: PlayerColor, index: u8) -> Vec2 {
+    layout().home_base_position(player, index).into()
+}
+
+// How long captured pieces keep flashing after the capture
+const CAPTURE_FLASH_DURATION: f64 = 1.5;
+
+// Screen positions of where a move picks up a piece and where it puts it down
+pub fn move_endpoints(player: PlayerColor, mov: &StruggleMove) -> Option<(Vec2, Vec2)> {
+    match *mov {
+        StruggleMove::AddNewPiece { .. } => Some((
+            home_base_position(player, 0).lerp(home_base_position(player, 3), 0.5),
+            tile_position(Board::get_start(player)),
+        )),
+        StruggleMove::MovePiece { from, to, .. } => Some((tile_position(from), tile_position(to))),
+        StruggleMove::MoveToGoal {
+            from_board,
+            to_goal,
+        } => Some((tile_position(from_board), goal_position(player, to_goal))),
+        StruggleMove::MoveInGoal { from_goal, to_goal } => Some((
+            goal_position(player, from_goal),
+            goal_position(player, to_goal),
+        )),
+        StruggleMove::SkipTurn => None,
+    }
+}
+
+fn draw_last_move(last_move: &LastMove, time: f64) {
+    let player = last_move.player;
+
+    let Some((from, to)) = move_endpoints(player, &last_move.mov) else {
+        return;
+    };
+
+    let color = player_to_color(player);
+
+    draw_line(from.x, from.y, to.x, to.y, 3.0, color);
+    draw_circle_lines(from.x, from.y, PIECE_RADIUS + 4.0, 2.0, color);
+    draw_circle_lines(to.x, to.y, PIECE_RADIUS + 6.0, 4.0, WHITE);
+
+    if matches!(
+        last_move.mov,
+        StruggleMove::MoveToGoal { .. } | StruggleMove::MoveInGoal { .. }
+    ) {
+        // Mark the goal slot that was just filled
+        draw_circle_lines(to.x, to.y, PIECE_RADIUS + 12.0, 3.0, GOLD);
+    }
+
+    if let Some(captured) = last_move.captured {
+        let elapsed = time - last_move.time;
+
+        // Blink a few times per second, then leave a static marker
+        let visible =
+            elapsed > CAPTURE_FLASH_DURATION || ((elapsed * 6.0) as u32).is_multiple_of(2);
+
+        if visible {
+            draw_circle_lines(
+                to.x,
+                to.y,
+                PIECE_RADIUS + 14.0,
+                6.0,
+                player_to_color(captured),
+            );
+        }
+    }
+}
+
+fn format_score(score: f64) -> String {
+    // Wins and losses are scored with a huge constant, which would not fit on a tile
+    if score >= 1e9 {
+        "WIN".to_string()
+    } else if score <= -1e9 {
+        "LOSS".to_string()
+    } else {
+        format!("{:.0}", score)
+    }
+}
+
+fn draw_candidates(player: PlayerColor, candidates: &[(StruggleMove, f64)]) {
+    let best = candidates
+        .iter()
+        .map(|(_, score)| *score)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    for (mov, score) in candidates {
+        let Some((_, to)) = move_endpoints(player, mov) else {
+            continue;
+        };
+
+        let text = format_score(*score);
+        let color = if *score == best { GOLD } else { WHITE };
+        let dimensions = measure_text(&text, None, 22, 1.0);
+
+        draw_rectangle(
+            to.x - dimensions.width / 2.0 - 3.0,
+            to.y - PIECE_RADIUS - 22.0,
+            dimensions.width + 6.0,
+            22.0,
+            Color::new(0.0, 0.0, 0.0, 0.7),
+        );
+        draw_text(
+            &text,
+            to.x - dimensions.width / 2.0,
+            to.y - PIECE_RADIUS - 6.0,
+            22.0,
+            color,
+        );
+    }
+}
+
+const DIE_KEYS: [(KeyCode, u8); 6] = [
+    (KeyCode::Key1, 1),
+    (KeyCode::Key2, 2),
+    (KeyCode::Key3, 3),
+    (KeyCode::Key4, 4),
+    (KeyCode::Key5, 5),
+    (KeyCode::Key6, 6),
+];
+
+// What the analysis player and the side to move think of an edited position
+struct EditorAnalysis {
+    die: u8,
+    candidates: Vec<(StruggleMove, f64)>,
+    pick: StruggleMove,
+    picked_by: String,
+}
+
+fn analyze_position<F: Fn(&Board, PlayerColor, PlayerColor) -> f64 + Clone + Send + Sync>(
+    editor: &Editor<Board>,
+    die: u8,
+    analysis_player: &mut GameTreePlayer<F>,
+    player: Option<&BoxedStrugglePlayer>,
+    rng: &mut SmallRng,
+) -> EditorAnalysis {
+    let ctx = GameContext {
+        dice: die,
+        current_player: editor.to_move,
+        other_player: editor.other_player(),
+    };
+
+    let moves = editor
+        .board
+        .get_moves(die, ctx.current_player, ctx.other_player);
+    let scores = analysis_player.evaluate_moves(&ctx, &editor.board, &moves, rng);
+    let candidates: Vec<_> = moves.iter().cloned().zip(scores).collect();
+
+    // A human side to move has no pick of its own, so the analysis player's best move stands in
+    let (pick, picked_by) = match player {
+        Some(player) => {
+            // Clone the player so that its state in the running game is left alone
+            let mut player = player.clone();
+            let pick = player.select_move(&ctx, &editor.board, &moves, rng).clone();
+
+            (pick, player.name().into_owned())
+        }
+        None => {
+            let (best, _) = candidates
+                .iter()
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .expect("There is always at least one move");
+
+            (best.clone(), analysis_player.name().into_owned())
+        }
+    };
+
+    EditorAnalysis {
+        die,
+        candidates,
+        pick,
+        picked_by,
+    }
+}
+
+fn draw_editor_panel(editor: &Editor<Board>, analysis: &EditorAnalysis) {
+    let left = WIDTH as f32 + 20.0;
+
+    draw_rectangle(
+        WIDTH as f32,
+        0.0,
+        PANEL_WIDTH as f32,
+        HEIGHT as f32,
+        Color::from_rgba(24, 24, 24, 255),
+    );
+
+    draw_text("Board editor", left, 60.0, 32.0, WHITE);
+
+    let lines = [
+        format!("{:?} to move, rolled {}", editor.to_move, analysis.die),
+        format!("{} picks:", analysis.picked_by),
+        format!("{:?}", analysis.pick),
+        String::new(),
+        "Click: cycle piece".to_string(),
+        "Right click: remove piece".to_string(),
+        "Click home base: set waiting".to_string(),
+        "[1-6] die  [Tab] side to move".to_string(),
+        "[X] save  [L]oad position".to_string(),
+        "[B] back to the game".to_string(),
+    ];
+
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, left, 110.0 + i as f32 * 26.0, 20.0, LIGHTGRAY);
+    }
+}
+
+pub async fn run(
+    red: BoxedStrugglePlayer,
+    yellow: BoxedStrugglePlayer,
+    mut rng: SmallRng,
+    mut playback: Playback,
+    position_file: Option<PathBuf>,
+    // Whether the user plays Red and Yellow instead of `red` and `yellow`
+    mut humans: [bool; 2],
+) {
+    let center_x = WIDTH as f32 / 2.0;
+    let center_y = HEIGHT as f32 / 2.0;
+
+    let mut player_a = AiStrugglePlayer::new(PlayerColor::Red, red);
+    let mut analysis_player = GameTreePlayer::new(default_heuristic, ANALYSIS_DEPTH, "Analysis");
+    // Red's candidate moves for the latest roll, with their expectiminimax scores
+    let mut candidates: Vec<(StruggleMove, f64)> = Vec::new();
+
+    let mut player_b = AiStrugglePlayer::new(PlayerColor::Yellow, yellow);
+
+    let mut next_tick = 0.0;
+
+    let mut current = Match::new(player_a.clone(), player_b.clone());
+
+    let mut recorder: Option<Recorder> = None;
+    let mut step_requested = true;
+
+    let mut grid_index = 0;
+    let mut grid: Option<Grid<_, _>> = None;
+
+    let mut editor: Option<(Editor<Board>, EditorAnalysis)> = None;
+
+    // The user's roll, while they pick a move for it
+    let mut human_turn: Option<HumanTurn> = None;
+
+    loop {
+        let time = get_time();
+
+        playback.handle_input();
+        handle_palette_input();
+
+        if is_key_pressed(KeyCode::Space) {
+            step_requested = true;
+        }
+
+        // Seats in the same order as `humans`
+        let seat_colors = [player_a.color, player_b.color];
+        let is_human = |color: PlayerColor| {
+            seat_colors
+                .iter()
+                .zip(humans)
+                .any(|(&seat, human)| seat == color && human)
+        };
+
+        // The grid plays by itself, which the user can't
+        if is_key_pressed(KeyCode::G) && !humans.contains(&true) {
+            grid_index = (grid_index + 1) % GRID_SIZES.len();
+            grid = (GRID_SIZES[grid_index] > 0)
+                .then(|| Grid::new(GRID_SIZES[grid_index], &player_a, &player_b));
+        }
+
+        // The position to open in the board editor, if any
+        let mut opened = None;
+
+        if is_key_pressed(KeyCode::B) && grid.is_none() {
+            match editor {
+                Some(_) => editor = None,
+                None => {
+                    opened = Some((current.game.board().clone(), current.game.current_player()))
+                }
+            }
+        }
+
+        if is_key_pressed(KeyCode::L) && grid.is_none() {
+            match load_position(position_file.as_deref()) {
+                Ok(position) => opened = Some(position),
+                Err(err) => eprintln!("Could not load position: {}", err),
+            }
+        }
+
+        if let Some((board, to_move)) = opened {
+            let position = Editor::new(board, to_move);
+            let player = if is_human(position.to_move) {
+                None
+            } else if position.to_move == player_a.color {
+                Some(&player_a.player)
+            } else {
+                Some(&player_b.player)
+            };
+            let analysis = analyze_position(
+                &position,
+                current.last_die.max(1),
+                &mut analysis_player,
+                player,
+                &mut rng,
+            );
+
+            editor = Some((position, analysis));
+        }
+
+        if is_key_pressed(KeyCode::X) && grid.is_none() {
+            let saved = match &editor {
+                Some((position, _)) => save_position(&position.board, position.to_move),
+                None => save_position(current.game.board(), current.game.current_player()),
+            };
+
+            match saved {
+                Ok(path) => println!("Saved position to {} and the clipboard", path.display()),
+                Err(err) => eprintln!("Could not save position: {}", err),
+            }
+        }
+
+        if let Some((position, analysis)) = &mut editor {
+            let mut die = analysis.die;
+            let mut changed = position.handle_input(&layout());
+
+            for (key, value) in DIE_KEYS {
+                if is_key_pressed(key) {
+                    die = value;
+                    changed = true;
+                }
+            }
+
+            if changed {
+                let player = if is_human(position.to_move) {
+                    None
+                } else if position.to_move == player_a.color {
+                    Some(&player_a.player)
+                } else {
+                    Some(&player_b.player)
+                };
+
+                *analysis = analyze_position(position, die, &mut analysis_player, player, &mut rng);
+            }
+        } else if let Some(grid) = &mut grid {
+            if !grid.is_finished() && !playback.paused {
+                if playback.turbo {
+                    let deadline = time + TURBO_FRAME_BUDGET;
+
+                    while !grid.is_finished() && get_time() < deadline {
+                        grid.play_turn(&mut rng);
+                    }
+                } else if time > next_tick && (step_requested || !playback.step_mode) {
+                    step_requested = false;
+                    grid.play_turn(&mut rng);
+                    next_tick = time + playback.tick_interval;
+                }
+            }
+        } else if current.winner.is_none() && !playback.paused {
+            let player = current.game.current_player();
+
+            if is_human(player) {
+                // The user's turns don't wait for the step key, only for the tick after the
+                // previous move so that it can be seen
+                if let Some(turn) = &human_turn {
+                    if let Some(mov) = turn.handle_input() {
+                        current.play_turn_with_move(turn.dice, mov);
+                        human_turn = None;
+                        next_tick = time + playback.tick_interval;
+                    }
+                } else if time > next_tick {
+                    let dice = current.game.throw_dice(&mut rng);
+                    let ctx = current.game.create_turn_context(dice);
+
+                    candidates.clear();
+                    human_turn = Some(HumanTurn {
+                        player,
+                        dice,
+                        moves: current.game.get_moves(&ctx),
+                    });
+                }
+            } else if playback.turbo {
+                let deadline = time + TURBO_FRAME_BUDGET;
+
+                // Stops at the user's turn, whose move can't be simulated
+                while current.winner.is_none()
+                    && !is_human(current.game.current_player())
+                    && get_time() < deadline
+                {
+                    current.play_turn(&mut rng);
+                }
+            } else if time > next_tick && (step_requested || !playback.step_mode) {
+                step_requested = false;
+
+                let dice = current.game.throw_dice(&mut rng);
+
+                if current.game.current_player() == PlayerColor::Red {
+                    let ctx = GameContext {
+                        dice,
+                        current_player: PlayerColor::Red,
+                        other_player: PlayerColor::Yellow,
+                    };
+                    let moves = current.game.get_moves(&ctx);
+                    let scores = analysis_player.evaluate_moves(
+                        &ctx,
+                        current.game.board(),
+                        &moves,
+                        &mut rng,
+                    );
+
+                    candidates = moves.into_iter().zip(scores).collect();
+                } else {
+                    candidates.clear();
+                }
+
+                current.play_turn_with_die(dice, &mut rng);
+
+                next_tick = time + playback.tick_interval;
+            }
+        }
+
+        let game_over = grid.is_none() && current.winner.is_some();
+
+        if game_over && is_key_pressed(KeyCode::W) {
+            std::mem::swap(&mut player_a.player, &mut player_b.player);
+            humans.swap(0, 1);
+            current = Match::new(player_a.clone(), player_b.clone());
+            candidates.clear();
+            human_turn = None;
+        }
+
+        if game_over && is_key_pressed(KeyCode::E) {
+            match current.export_replay() {
+                Ok(path) => println!("Exported replay to {}", path.display()),
+                Err(err) => eprintln!("Could not export replay: {}", err),
+            }
+        }
+
+        if is_key_pressed(KeyCode::R) {
+            current = Match::new(player_a.clone(), player_b.clone());
+            candidates.clear();
+            human_turn = None;
+
+            if let Some(grid) = &mut grid {
+                *grid = Grid::new(grid.size, &player_a, &player_b);
+            }
+        }
+
+        clear_background(BLACK);
+
+        if let Some((position, analysis)) = &editor {
+            draw_shapes(&position.board.shapes(&layout(), &palette()));
+
+            draw_candidates(position.to_move, &analysis.candidates);
+
+            if let Some((from, to)) = move_endpoints(position.to_move, &analysis.pick) {
+                draw_line(from.x, from.y, to.x, to.y, 3.0, WHITE);
+                draw_circle_lines(to.x, to.y, PIECE_RADIUS + 6.0, 4.0, WHITE);
+            }
+
+            draw_editor_panel(position, analysis);
+        } else if let Some(grid) = &grid {
+            grid.draw();
+            grid.draw_panel();
+        } else {
+            draw_shapes(&current.game.board().shapes(&layout(), &palette()));
+
+            // The user's roll is shown as soon as it's thrown, before its move
+            let (die, die_player) = match &human_turn {
+                Some(turn) => (turn.dice, turn.player),
+                None => (current.last_die, current.last_die_player),
+            };
+
+            draw_text(
+                &die.to_string(),
+                center_x,
+                center_y,
+                40.0,
+                player_to_color(die_player),
+            );
+
+            if let Some(last_move) = &current.last_move {
+                draw_last_move(last_move, time);
+            }
+
+            if let Some(turn) = &human_turn {
+                turn.draw();
+                draw_text(
+                    "Your move: click a piece or its target",
+                    10.0,
+                    HEIGHT as f32 - 20.0,
+                    24.0,
+                    player_to_color(turn.player),
+                );
+            }
+
+            if !playback.turbo {
+                draw_candidates(PlayerColor::Red, &candidates);
+            }
+
+            draw_info_panel(&current);
+
+            if let Some(winner) = current.winner {
+                current.draw_summary(winner);
+            }
+        }
+
+        draw_text(
+            &playback.status_text(" [G]rid [B]oard editor [X] save [L]oad"),
+            10.0,
+            20.0,
+            20.0,
+            WHITE,
+        );
+
+        if is_key_pressed(KeyCode::F) {
+            recorder = match recorder {
+                Some(recorder) => {
+                    println!(
+                        "Recorded {} frames to {}",
+                        recorder.frames,
+                        recorder.directory.display()
+                    );
+                    None
+                }
+                None => Some(Recorder::start()),
+            };
+        }
+
+        if let Some(recorder) = &mut recorder {
+            let turns = grid.as_ref().map_or(current.turns, Grid::total_turns);
+            recorder.capture(turns);
+            draw_text("REC", WIDTH as f32 - 60.0, 20.0, 24.0, RED);
+        }
+
+        next_frame().await
+    }
+}
+
+/// Plays `red` against `yellow` at full speed while charting the win rate, see `measure::run`.
+pub async fn measure(red: BoxedStrugglePlayer, yellow: BoxedStrugglePlayer, seed: u64) {
+    let names = (red.name().into_owned(), yellow.name().into_owned());
+
+    measure::run(names, seed, move |rng| {
+        let mut game = StruggleGame::new(
+            AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+            AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+            false,
+        );
+        let winner = play_game_with_rng(&mut game, rng);
+
+        (winner, game.board().clone())
+    })
+    .await
+}
@@ -0,0 +1,362 @@
+use ::rand::prelude::*;
+use macroquad::prelude::*;
+use struggle_core::{
+    game::{play_game_with_rng, NamedPlayer, RaceGame, TurnResult},
+    games::{
+        struggle::{AiStrugglePlayer, PlayerColor},
+        twist::{
+            board::{ActionDie, DieResult, TwistBoard, TwistMove},
+            get_moves::get_twist_moves,
+            players::{GameContext, TwistPlayer},
+            TwistGame,
+        },
+    },
+    registry::BoxedTwistPlayer,
+    render::{BoardLayout, RenderBoard},
+};
+
+use crate::{
+    draw::{draw_shapes, draw_summary, handle_palette_input, palette, player_to_color},
+    editor::Editor,
+    measure,
+    playback::{Playback, TURBO_FRAME_BUDGET},
+    recorder::Recorder,
+    HEIGHT, PANEL_WIDTH, WIDTH,
+};
+
+const DIE_KEYS: [(KeyCode, u8); 6] = [
+    (KeyCode::Key1, 1),
+    (KeyCode::Key2, 2),
+    (KeyCode::Key3, 3),
+    (KeyCode::Key4, 4),
+    (KeyCode::Key5, 5),
+    (KeyCode::Key6, 6),
+];
+
+// The move the side to move would play in an edited position
+struct EditorAnalysis {
+    die: DieResult,
+    pick: TwistMove,
+    picked_by: String,
+}
+
+fn analyze_position(
+    editor: &Editor<TwistBoard>,
+    die: DieResult,
+    player: &BoxedTwistPlayer,
+    rng: &mut SmallRng,
+) -> EditorAnalysis {
+    let ctx = GameContext {
+        die,
+        current_player: editor.to_move,
+        other_player: editor.other_player(),
+    };
+
+    let moves = get_twist_moves(&editor.board, die, editor.to_move);
+
+    // Clone the player so that its state in the running game is left alone
+    let mut player = player.clone();
+    let pick = *player.select_move(&ctx, &editor.board, &moves, rng);
+
+    EditorAnalysis {
+        die,
+        pick,
+        picked_by: player.name().into_owned(),
+    }
+}
+
+// Rings around each player's start tile and current goal entrance. The entrances move with the
+// board's rotation, so these are recomputed from the board every frame.
+fn draw_entrance_markers(board: &TwistBoard, layout: &BoardLayout) {
+    let (a, b) = board.players();
+    let radius = layout.piece_radius() * 1.35;
+    let thickness = 3.0 * layout.scale;
+
+    for player in [a, b] {
+        let color = player_to_color(player);
+
+        let (x, y) = layout.tile_position(TwistBoard::get_start(player));
+        draw_circle_lines(x, y, radius, thickness, color);
+
+        // The entrance gets a doubled ring and a tick pointing at the board center
+        let (x, y) = layout.tile_position(TwistBoard::get_goal_entrance(board.rotation, player));
+        draw_circle_lines(x, y, radius, thickness, color);
+        draw_circle_lines(x, y, radius + 2.0 * thickness, thickness, color);
+
+        let (dx, dy) = (layout.center.0 - x, layout.center.1 - y);
+        let length = (dx * dx + dy * dy).sqrt();
+        let (dx, dy) = (dx / length, dy / length);
+        let tick = radius + 2.0 * thickness;
+
+        draw_line(
+            x + dx * tick,
+            y + dy * tick,
+            x + dx * (tick + 20.0 * layout.scale),
+            y + dy * (tick + 20.0 * layout.scale),
+            thickness,
+            color,
+        );
+    }
+}
+
+fn draw_editor_panel(editor: &Editor<TwistBoard>, analysis: &EditorAnalysis) {
+    let left = WIDTH as f32 + 20.0;
+
+    draw_rectangle(
+        WIDTH as f32,
+        0.0,
+        PANEL_WIDTH as f32,
+        HEIGHT as f32,
+        Color::from_rgba(24, 24, 24, 255),
+    );
+
+    draw_text("Board editor", left, 60.0, 32.0, WHITE);
+
+    let lines = [
+        format!("{:?} to move", editor.to_move),
+        format!(
+            "Rolled {} and {:?}",
+            analysis.die.number, analysis.die.action
+        ),
+        format!("Rotation: {:?}", editor.board.rotation),
+        format!("{} picks:", analysis.picked_by),
+        format!("{:?}", analysis.pick.0),
+        format!("{:?}", analysis.pick.1),
+        String::new(),
+        "Click: cycle piece".to_string(),
+        "Right click: remove piece".to_string(),
+        "Click home base: set waiting".to_string(),
+        "[1-6] die  [A]ction die".to_string(),
+        "[O] rotate  [Tab] side to move".to_string(),
+        "[B] back to the game".to_string(),
+    ];
+
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, left, 110.0 + i as f32 * 26.0, 20.0, LIGHTGRAY);
+    }
+}
+
+// Plays a single turn, returning the rolled die and who rolled it
+fn play_turn<A: TwistPlayer, B: TwistPlayer>(
+    game: &mut TwistGame<A, B>,
+    winner: &mut Option<PlayerColor>,
+    rng: &mut SmallRng,
+) -> (DieResult, PlayerColor) {
+    let (dice, result) = game.play_turn(rng);
+
+    let player = game.current_player();
+
+    match result {
+        TurnResult::PlayAgain => {}
+        TurnResult::PassTo(player) => {
+            game.set_current_player(player);
+        }
+        TurnResult::EndGame {
+            winner: game_winner,
+        } => {
+            *winner = Some(game_winner);
+        }
+    }
+
+    (dice, player)
+}
+
+pub async fn run(
+    red: BoxedTwistPlayer,
+    yellow: BoxedTwistPlayer,
+    mut rng: SmallRng,
+    mut playback: Playback,
+) {
+    let center_x = WIDTH as f32 / 2.0;
+    let center_y = HEIGHT as f32 / 2.0;
+
+    let player_a = AiStrugglePlayer::new(PlayerColor::Red, red);
+    let player_b = AiStrugglePlayer::new(PlayerColor::Yellow, yellow);
+
+    let mut next_tick = 0.0;
+
+    let mut winner = None;
+    let mut turns = 0;
+
+    let mut last_die = DieResult::default();
+    let mut last_die_player = PlayerColor::Red;
+
+    let mut game = TwistGame::new(player_a.clone(), player_b.clone(), false);
+
+    let mut recorder: Option<Recorder> = None;
+    let mut step_requested = true;
+
+    let mut editor: Option<(Editor<TwistBoard>, EditorAnalysis)> = None;
+
+    loop {
+        let time = get_time();
+
+        playback.handle_input();
+        handle_palette_input();
+
+        if is_key_pressed(KeyCode::Space) {
+            step_requested = true;
+        }
+
+        let layout = BoardLayout::twist((center_x, center_y), 1.0);
+
+        if is_key_pressed(KeyCode::B) {
+            editor = match editor {
+                Some(_) => None,
+                None => {
+                    let position = Editor::new(game.board().clone(), game.current_player());
+                    let player = if position.to_move == player_a.color {
+                        &player_a.player
+                    } else {
+                        &player_b.player
+                    };
+                    let die = DieResult {
+                        number: last_die.number.max(1),
+                        action: last_die.action,
+                    };
+                    let analysis = analyze_position(&position, die, player, &mut rng);
+
+                    Some((position, analysis))
+                }
+            };
+        }
+
+        if let Some((position, analysis)) = &mut editor {
+            let mut die = analysis.die;
+            let mut changed = position.handle_input(&layout);
+
+            for (key, value) in DIE_KEYS {
+                if is_key_pressed(key) {
+                    die.number = value;
+                    changed = true;
+                }
+            }
+
+            if is_key_pressed(KeyCode::A) {
+                die.action = match die.action {
+                    ActionDie::DoNothing => ActionDie::SpinSection,
+                    ActionDie::SpinSection => ActionDie::RotateBoard,
+                    ActionDie::RotateBoard => ActionDie::DoNothing,
+                };
+                changed = true;
+            }
+
+            if is_key_pressed(KeyCode::O) {
+                position.board.rotation = position.board.rotation.next();
+                changed = true;
+            }
+
+            if changed {
+                let player = if position.to_move == player_a.color {
+                    &player_a.player
+                } else {
+                    &player_b.player
+                };
+
+                *analysis = analyze_position(position, die, player, &mut rng);
+            }
+        } else if winner.is_none() && !playback.paused {
+            if playback.turbo {
+                let deadline = time + TURBO_FRAME_BUDGET;
+
+                while winner.is_none() && get_time() < deadline {
+                    (last_die, last_die_player) = play_turn(&mut game, &mut winner, &mut rng);
+                    turns += 1;
+                }
+            } else if time > next_tick && (step_requested || !playback.step_mode) {
+                step_requested = false;
+                (last_die, last_die_player) = play_turn(&mut game, &mut winner, &mut rng);
+                turns += 1;
+                next_tick = time + playback.tick_interval;
+            }
+        }
+
+        if is_key_pressed(KeyCode::R) {
+            game = TwistGame::new(player_a.clone(), player_b.clone(), false);
+            winner = None;
+            turns = 0;
+            last_die = DieResult::default();
+            last_die_player = PlayerColor::Red;
+        }
+
+        clear_background(BLACK);
+
+        if let Some((position, analysis)) = &editor {
+            draw_shapes(&position.board.shapes(&layout, &palette()));
+            draw_entrance_markers(&position.board, &layout);
+            draw_editor_panel(position, analysis);
+        } else {
+            draw_shapes(&game.board().shapes(&layout, &palette()));
+            draw_entrance_markers(game.board(), &layout);
+        }
+
+        draw_text(
+            &format!("{}\n{:?}", last_die.number, last_die.action),
+            center_x,
+            center_y,
+            40.0,
+            player_to_color(last_die_player),
+        );
+
+        if let (Some(winner), None) = (winner, &editor) {
+            let name = if winner == player_a.color {
+                player_a.player.name()
+            } else {
+                player_b.player.name()
+            };
+
+            draw_summary(
+                &format!("{:?} ({}) wins!", winner, name),
+                player_to_color(winner),
+                &[format!("{} turns", turns)],
+                "[R]estart",
+            );
+        }
+
+        draw_text(
+            &playback.status_text(" [B]oard editor"),
+            10.0,
+            20.0,
+            20.0,
+            WHITE,
+        );
+
+        if is_key_pressed(KeyCode::F) {
+            recorder = match recorder {
+                Some(recorder) => {
+                    println!(
+                        "Recorded {} frames to {}",
+                        recorder.frames,
+                        recorder.directory.display()
+                    );
+                    None
+                }
+                None => Some(Recorder::start()),
+            };
+        }
+
+        if let Some(recorder) = &mut recorder {
+            recorder.capture(turns);
+            draw_text("REC", WIDTH as f32 - 60.0, 20.0, 24.0, RED);
+        }
+
+        next_frame().await
+    }
+}
+
+/// Plays `red` against `yellow` at full speed while charting the win rate, see `measure::run`.
+pub async fn measure(red: BoxedTwistPlayer, yellow: BoxedTwistPlayer, seed: u64) {
+    let names = (red.name().into_owned(), yellow.name().into_owned());
+
+    measure::run(names, seed, move |rng| {
+        let mut game = TwistGame::new(
+            AiStrugglePlayer::new(PlayerColor::Red, red.clone()),
+            AiStrugglePlayer::new(PlayerColor::Yellow, yellow.clone()),
+            false,
+        );
+        let winner = play_game_with_rng(&mut game, rng);
+
+        (winner, game.board().clone())
+    })
+    .await
+}